@@ -0,0 +1,226 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Filters, groups and counts over the variables and data structures of the results.
+// `results.filter(|var| var.is_mutable())` selects records without copying them, and a
+// selection is aggregated into typed rows: `group_by` counts the mutable and immutable
+// variables per file, module, scope, basic type or mutability (and the data structures, when
+// grouping by file or module), and the `counts_by` functions count the records per key.
+// Groups and counts are listed largest first, ties by key. `--group-by` and `forest stats`
+// are built on these.
+
+use crate::{AnalysisResults, DataStructureInfo, VarInfo};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+// What records are grouped or counted by
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GroupKey {
+    File,
+    Module, // The module of the file the record is declared in
+    Scope,
+    BasicType,
+    Mutability,
+}
+
+impl GroupKey {
+    // Function to look up a key by its --group-by name, e.g. `basic_type`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "file" => Some(GroupKey::File),
+            "module" => Some(GroupKey::Module),
+            "scope" => Some(GroupKey::Scope),
+            "basic_type" => Some(GroupKey::BasicType),
+            "mutability" => Some(GroupKey::Mutability),
+            _ => None,
+        }
+    }
+}
+
+// Counts of one group
+#[derive(Default, Serialize)]
+pub struct Group {
+    pub key: String,
+    pub mutable_variables: usize,
+    pub immutable_variables: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data_structures: Option<usize>, // Only when grouping by file or module
+}
+
+impl Group {
+    pub fn variables(&self) -> usize {
+        self.mutable_variables + self.immutable_variables
+    }
+}
+
+// Number of records with the same key
+#[derive(Serialize)]
+pub struct Count {
+    pub key: String,
+    pub count: usize,
+}
+
+// Variables and data structures selected from the results
+#[derive(Clone)]
+pub struct Selection<'a> {
+    results: &'a AnalysisResults,
+    variables: Vec<&'a VarInfo>, // Mutable variables first, then immutable ones
+    data_structures: Vec<&'a DataStructureInfo>,
+}
+
+impl<'a> Selection<'a> {
+    pub fn variables(&self) -> &[&'a VarInfo] {
+        &self.variables
+    }
+
+    pub fn data_structures(&self) -> &[&'a DataStructureInfo] {
+        &self.data_structures
+    }
+
+    // Function to keep the variables for which `keep` is true
+    pub fn filter(mut self, keep: impl Fn(&VarInfo) -> bool) -> Self {
+        self.variables.retain(|var| keep(var));
+        self
+    }
+
+    // Function to keep the data structures for which `keep` is true
+    pub fn filter_data_structures(mut self, keep: impl Fn(&DataStructureInfo) -> bool) -> Self {
+        self.data_structures
+            .retain(|data_structure| keep(data_structure));
+        self
+    }
+
+    // Modules of the analysed files, by path
+    fn modules(&self) -> HashMap<&'a Path, &'a str> {
+        self.results
+            .file_lines
+            .iter()
+            .map(|file| (file.file_path.as_path(), file.module.as_str()))
+            .collect()
+    }
+
+    // Function to aggregate the variables, and the data structures when grouping by file or
+    // module, by the given key
+    pub fn group_by(&self, key: GroupKey) -> Vec<Group> {
+        let modules = self.modules();
+        let counts_structures = matches!(key, GroupKey::File | GroupKey::Module);
+
+        let mut groups: BTreeMap<String, Group> = BTreeMap::new();
+        for var in &self.variables {
+            let group = groups.entry(var_key(var, key, &modules)).or_default();
+            if var.mutable {
+                group.mutable_variables += 1;
+            } else {
+                group.immutable_variables += 1;
+            }
+        }
+        if counts_structures {
+            for data_structure in &self.data_structures {
+                let group = groups
+                    .entry(file_key(&data_structure.file_path, key, &modules))
+                    .or_default();
+                *group.data_structures.get_or_insert(0) += 1;
+            }
+        }
+
+        let mut groups: Vec<Group> = groups
+            .into_iter()
+            .map(|(key, mut group)| {
+                group.key = key;
+                if counts_structures {
+                    group.data_structures.get_or_insert(0);
+                }
+                group
+            })
+            .collect();
+        groups.sort_by(|a, b| {
+            let size = |group: &Group| group.variables() + group.data_structures.unwrap_or(0);
+            size(b).cmp(&size(a)).then_with(|| a.key.cmp(&b.key))
+        });
+        groups
+    }
+
+    // Function to count the variables by the given key
+    pub fn counts_by(&self, key: GroupKey) -> Vec<Count> {
+        let modules = self.modules();
+        counted(self.variables.iter().map(|var| var_key(var, key, &modules)))
+    }
+
+    pub fn counts_by_basic_type(&self) -> Vec<Count> {
+        self.counts_by(GroupKey::BasicType)
+    }
+
+    // Function to count the data structures by file or by module (other keys count by file)
+    pub fn data_structure_counts_by(&self, key: GroupKey) -> Vec<Count> {
+        let modules = self.modules();
+        counted(
+            self.data_structures
+                .iter()
+                .map(|data_structure| file_key(&data_structure.file_path, key, &modules)),
+        )
+    }
+}
+
+// Function to give the key of a file: its module when grouping by module, otherwise its path
+fn file_key(path: &Path, key: GroupKey, modules: &HashMap<&Path, &str>) -> String {
+    match modules.get(path) {
+        Some(module) if key == GroupKey::Module => module.to_string(),
+        _ => path.display().to_string(),
+    }
+}
+
+// Function to give the key of a variable
+fn var_key(var: &VarInfo, key: GroupKey, modules: &HashMap<&Path, &str>) -> String {
+    match key {
+        GroupKey::Scope => var.scope.to_string(),
+        GroupKey::BasicType => var.basic_type.to_string(),
+        GroupKey::Mutability => if var.mutable { "mutable" } else { "immutable" }.to_string(),
+        GroupKey::File | GroupKey::Module => file_key(&var.file_path, key, modules),
+    }
+}
+
+// Function to count equal keys, largest first
+fn counted(keys: impl Iterator<Item = String>) -> Vec<Count> {
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for key in keys {
+        *counts.entry(key).or_default() += 1;
+    }
+    let mut counts: Vec<Count> = counts
+        .into_iter()
+        .map(|(key, count)| Count { key, count })
+        .collect();
+    counts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.key.cmp(&b.key)));
+    counts
+}
+
+// Filters and aggregates over the whole results
+impl AnalysisResults {
+    // All the variables and data structures, to be filtered and aggregated
+    pub fn select(&self) -> Selection<'_> {
+        Selection {
+            results: self,
+            variables: self
+                .mutable_vars
+                .iter()
+                .chain(&self.immutable_vars)
+                .collect(),
+            data_structures: self.data_structures.iter().collect(),
+        }
+    }
+
+    // The variables for which `keep` is true, with all the data structures
+    pub fn filter(&self, keep: impl Fn(&VarInfo) -> bool) -> Selection<'_> {
+        self.select().filter(keep)
+    }
+
+    pub fn group_by(&self, key: GroupKey) -> Vec<Group> {
+        self.select().group_by(key)
+    }
+
+    pub fn group_by_scope(&self) -> Vec<Group> {
+        self.group_by(GroupKey::Scope)
+    }
+
+    pub fn counts_by_basic_type(&self) -> Vec<Count> {
+        self.select().counts_by_basic_type()
+    }
+}
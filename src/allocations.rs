@@ -0,0 +1,176 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Clone and allocation hotspots.
+// Calls that copy or allocate as a side effect — `.clone()`, `.to_owned()`, `.to_string()`,
+// `String::from`, `vec!` and `.collect()` — are listed with their enclosing function, and
+// functions are ranked by how many such calls they make to show where allocations cluster.
+
+use crate::collector::macro_args;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use syn::visit::{self, Visit};
+
+// Number of functions listed in the ranking
+const TOP_FUNCTIONS: usize = 10;
+
+// Methods whose result is a fresh copy or collection
+const ALLOCATING_METHODS: &[&str] = &["clone", "to_owned", "to_string", "collect"];
+
+// A single clone or allocation call
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Allocation {
+    pub kind: String, // "clone", "to_owned", "to_string", "String::from", "vec!" or "collect"
+    pub function: String, // Path of the enclosing function, or module for code outside one
+    #[serde(rename = "file")]
+    pub file_path: PathBuf,
+    #[serde(rename = "line")]
+    pub line_number: usize,
+}
+
+// A function with the allocation calls it makes
+pub struct Hotspot<'a> {
+    pub function: &'a str,
+    pub file_path: &'a Path,
+    pub first_line: usize,
+    pub count: usize,
+}
+
+// Visitor that finds the clone and allocation calls of a file
+struct AllocationVisitor<'a> {
+    file_path: &'a Path,
+    scope: String,
+    allocations: &'a mut Vec<Allocation>,
+}
+
+impl AllocationVisitor<'_> {
+    fn record(&mut self, kind: &str, line_number: usize) {
+        self.allocations.push(Allocation {
+            kind: kind.to_string(),
+            function: self.scope.clone(),
+            file_path: self.file_path.to_path_buf(),
+            line_number,
+        });
+    }
+
+    fn visit_in_scope(&mut self, name: &impl std::fmt::Display, visit: impl FnOnce(&mut Self)) {
+        let inner_scope = format!("{}::{}", self.scope, name);
+        let outer_scope = std::mem::replace(&mut self.scope, inner_scope);
+        visit(self);
+        self.scope = outer_scope;
+    }
+}
+
+impl<'ast> Visit<'ast> for AllocationVisitor<'_> {
+    fn visit_item_fn(&mut self, item_fn: &'ast syn::ItemFn) {
+        self.visit_in_scope(&item_fn.sig.ident, |visitor| {
+            visit::visit_item_fn(visitor, item_fn)
+        });
+    }
+
+    fn visit_impl_item_fn(&mut self, method: &'ast syn::ImplItemFn) {
+        self.visit_in_scope(&method.sig.ident, |visitor| {
+            visit::visit_impl_item_fn(visitor, method)
+        });
+    }
+
+    fn visit_trait_item_fn(&mut self, method: &'ast syn::TraitItemFn) {
+        self.visit_in_scope(&method.sig.ident, |visitor| {
+            visit::visit_trait_item_fn(visitor, method)
+        });
+    }
+
+    fn visit_item_impl(&mut self, item_impl: &'ast syn::ItemImpl) {
+        let name = crate::impl_blocks::type_name(&item_impl.self_ty);
+        self.visit_in_scope(&name, |visitor| visit::visit_item_impl(visitor, item_impl));
+    }
+
+    fn visit_item_trait(&mut self, item_trait: &'ast syn::ItemTrait) {
+        self.visit_in_scope(&item_trait.ident, |visitor| {
+            visit::visit_item_trait(visitor, item_trait)
+        });
+    }
+
+    fn visit_item_mod(&mut self, item_mod: &'ast syn::ItemMod) {
+        self.visit_in_scope(&item_mod.ident, |visitor| {
+            visit::visit_item_mod(visitor, item_mod)
+        });
+    }
+
+    fn visit_expr_method_call(&mut self, call: &'ast syn::ExprMethodCall) {
+        let method = call.method.to_string();
+        if ALLOCATING_METHODS.contains(&method.as_str()) {
+            self.record(&method, call.method.span().start().line);
+        }
+        visit::visit_expr_method_call(self, call);
+    }
+
+    fn visit_expr_call(&mut self, call: &'ast syn::ExprCall) {
+        if let syn::Expr::Path(ref expr_path) = *call.func {
+            let segments: Vec<String> = expr_path
+                .path
+                .segments
+                .iter()
+                .map(|segment| segment.ident.to_string())
+                .collect();
+            if segments.ends_with(&["String".to_string(), "from".to_string()]) {
+                let line = expr_path.path.segments[0].ident.span().start().line;
+                self.record("String::from", line);
+            }
+        }
+        visit::visit_expr_call(self, call);
+    }
+
+    // Macro arguments such as `println!("{}", name.clone())` are only tokens to syn
+    fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+        if mac.path.is_ident("vec") {
+            let line = mac.bang_token.span.start().line;
+            self.record("vec!", line);
+        }
+        if let Some(args) = macro_args(mac) {
+            for arg in &args {
+                self.visit_expr(arg);
+            }
+        }
+    }
+}
+
+// Function to collect the clone and allocation calls of a parsed file
+pub fn collect_allocations(
+    file_ast: &syn::File,
+    file_path: &Path,
+    module_path: &str,
+    allocations: &mut Vec<Allocation>,
+) {
+    let mut visitor = AllocationVisitor {
+        file_path,
+        scope: module_path.to_string(),
+        allocations,
+    };
+    visitor.visit_file(file_ast);
+}
+
+// Function to rank functions by the number of allocation calls they make, most first
+pub fn hotspots(allocations: &[Allocation]) -> Vec<Hotspot<'_>> {
+    let mut by_function: HashMap<(&str, &Path), Hotspot> = HashMap::new();
+    for allocation in allocations {
+        let hotspot = by_function
+            .entry((allocation.function.as_str(), allocation.file_path.as_path()))
+            .or_insert(Hotspot {
+                function: &allocation.function,
+                file_path: &allocation.file_path,
+                first_line: allocation.line_number,
+                count: 0,
+            });
+        hotspot.count += 1;
+        hotspot.first_line = hotspot.first_line.min(allocation.line_number);
+    }
+    let mut ranked: Vec<Hotspot> = by_function.into_values().collect();
+    ranked.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then_with(|| a.function.cmp(b.function))
+            .then_with(|| a.file_path.cmp(b.file_path))
+    });
+    ranked.truncate(TOP_FUNCTIONS);
+    ranked
+}
@@ -1,56 +0,0 @@
-// Copyright (c) 2025 Nicholas D. Crosbie
-// Function to extract data_structure information from a line of code
-pub fn extract_data_structure_info<'a>(
-    line: &'a str,
-    data_structure_type: &'a str,
-    line_number: usize,
-) -> Option<(&'a str, usize)> {
-    let rest = &line[line.find(data_structure_type)? + data_structure_type.len()..];
-    let name_end = rest.find(|c: char| !c.is_alphanumeric() && c != '_');
-
-    let name = match name_end {
-        Some(end) if end > 0 => &rest[..end],
-        None if !rest.is_empty() => rest,
-        _ => return None,
-    };
-
-    Some((name, line_number))
-}
-
-// Function to extract variable name and kind from a line of code
-pub fn extract_var_name_and_kind(line: &str, start_idx: usize) -> Option<(&str, &str)> {
-    let rest = &line[start_idx..];
-
-    // Handle pattern matching with destructuring
-    if rest.starts_with("(") || rest.starts_with("{") || rest.starts_with("[") {
-        // More detailed extraction for destructuring patterns
-        // Get first name in pattern
-        let pattern_end = match rest.starts_with("(") {
-            true => rest.find(')').unwrap_or(rest.len()),
-            false if rest.starts_with("{") => rest.find('}').unwrap_or(rest.len()),
-            false => rest.find(']').unwrap_or(rest.len()),
-        };
-
-        let pattern = &rest[0..pattern_end + 1];
-
-        // Try to find variable names in the pattern
-        let first_var = pattern
-            .split(|c| "()[]{},".contains(c))
-            .map(|s| s.trim())
-            .find(|s| !s.is_empty() && !s.starts_with(".."));
-
-        // Implementation would continue here
-        if let Some(name) = first_var {
-            return Some((name, "inferred"));
-        }
-    }
-
-    // Simple variable name extraction
-    // Implementation would go here
-    None
-}
-
-pub fn extract_name_from_for_loop(line: &str, start_idx: usize) -> Option<(&str, &str)> {
-    // Implementation would go here
-    None
-}
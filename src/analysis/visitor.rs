@@ -1,68 +0,0 @@
-// Copyright (c) 2025 Nicholas D. Crosbie
-use crate::models::{data_structureInfo, VarInfo};
-use quote::ToTokens;
-use std::path::PathBuf;
-use syn::visit::{self, Visit};
-use syn::{spanned::Spanned, Expr, Pat, Type};
-
-pub struct VariableVisitor<'ast> {
-    pub file_path: PathBuf,
-    pub file_content: String,
-    pub mutable_vars: Vec<VarInfo>,
-    pub immutable_vars: Vec<VarInfo>,
-    pub data_structures: Vec<data_structureInfo>,
-}
-
-impl<'ast> VariableVisitor<'ast> {
-    pub fn new(file_path: PathBuf, file_content: String) -> Self {
-        Self {
-            file_path,
-            file_content,
-            mutable_vars: Vec::new(),
-            immutable_vars: Vec::new(),
-            data_structures: Vec::new(),
-        }
-    }
-
-    // Helper method to find line numbers using span information
-    pub fn get_line_number(&self, code_snippet: &str) -> usize {
-        // Implementation would go here
-        1
-    }
-}
-
-impl<'ast> Visit<'ast> for VariableVisitor<'ast> {
-    // Visit struct items
-    fn visit_item_struct(&mut self, item_struct: &'ast syn::ItemStruct) {
-        // Get the line number for this node
-        let line_number = self.get_line_number(&item_struct.to_token_stream().to_string());
-
-        // Add struct to data_structures
-        self.data_structures.push(data_structureInfo {
-            name: item_struct.ident.to_string(),
-            data_structure_type: "struct".to_string(),
-            file_path: self.file_path.clone(),
-            line_number,
-        });
-
-        visit::visit_item_struct(self, item_struct);
-    }
-
-    // Visit enum items
-    fn visit_item_enum(&mut self, item_enum: &'ast syn::ItemEnum) {
-        // Get the line number for this node
-        let line_number = self.get_line_number(&item_enum.to_token_stream().to_string());
-
-        // Add enum to data_structures
-        self.data_structures.push(data_structureInfo {
-            name: item_enum.ident.to_string(),
-            data_structure_type: "enum".to_string(),
-            file_path: self.file_path.clone(),
-            line_number,
-        });
-
-        visit::visit_item_enum(self, item_enum);
-    }
-
-    // Additional visit methods would be implemented here
-}
@@ -0,0 +1,232 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// The library API: `Analyzer::new(options).analyze(path)`.
+// A path is analysed as the command line would with the same options: a project directory is asked
+// about its workspace with `cargo metadata` and walked honouring .gitignore, a single .rs
+// file is analysed on its own. Files that syn cannot parse fall back to the items that parse
+// on their own, and files that cannot be read are recorded in `errors()`; with `fail_fast` they
+// abort the analysis with a `ForestError` instead. The records of the results are read
+// through the accessors below.
+
+use crate::collector::Record;
+use crate::{
+    analyse_project, project_metadata, single_file, targets, AnalysisError, AnalysisMetadata,
+    AnalysisOptions, AnalysisResults, DataStructureInfo, ForestError, VarInfo,
+};
+use chrono::Local;
+use std::path::Path;
+
+// Analyses projects and files with a fixed set of options
+#[derive(Default)]
+pub struct Analyzer {
+    options: AnalysisOptions,
+}
+
+impl Analyzer {
+    pub fn new(options: AnalysisOptions) -> Self {
+        Analyzer { options }
+    }
+
+    // Function to analyse a project directory or a single .rs file
+    pub fn analyze(&self, path: impl AsRef<Path>) -> Result<AnalysisResults, ForestError> {
+        Ok(self.analyze_with_metadata(path)?.0)
+    }
+
+    // Function to analyse a path, also returning what is known about its project
+    pub fn analyze_with_metadata(
+        &self,
+        path: impl AsRef<Path>,
+    ) -> Result<(AnalysisResults, AnalysisMetadata), ForestError> {
+        let dir = path.as_ref().to_string_lossy();
+        let (policy, options) = (self.options.policy(), self.options.source());
+        let datetime = Local::now().to_string();
+        if single_file::is_single_source(&dir) {
+            if self.options.resolves_types() {
+                return Err(ForestError::Config(
+                    "--semantic needs a project directory".to_string(),
+                ));
+            }
+            let metadata = AnalysisMetadata {
+                project_name: single_file::project_name(&dir),
+                version: "unknown".to_string(),
+                datetime,
+                dependencies: Vec::new(),
+                cargo: None,
+            };
+            return Ok((single_file::analyse(&dir, policy, options)?, metadata));
+        }
+
+        let metadata = project_metadata(&dir, datetime)?;
+        let mut results = analyse_project(&dir, &self.options, metadata.cargo.as_ref())?;
+        if let Some(ref cargo_metadata) = metadata.cargo {
+            results.file_targets = targets::Targets::new(cargo_metadata).classify(
+                results
+                    .file_lines
+                    .iter()
+                    .map(|file_lines| file_lines.file_path.as_path()),
+            );
+        }
+        Ok((results, metadata))
+    }
+}
+
+// Read access to the results for library users
+impl AnalysisResults {
+    pub fn mutable_variables(&self) -> &[VarInfo] {
+        &self.mutable_vars
+    }
+
+    pub fn immutable_variables(&self) -> &[VarInfo] {
+        &self.immutable_vars
+    }
+
+    pub fn data_structures(&self) -> &[DataStructureInfo] {
+        &self.data_structures
+    }
+
+    // Files and directories that could not be analysed
+    pub fn errors(&self) -> &[AnalysisError] {
+        &self.errors
+    }
+
+    // Number of source files analysed
+    pub fn file_count(&self) -> usize {
+        self.file_lines.len()
+    }
+
+    // What the analysis did besides analysing every file, e.g. `Sampling 10 of 200 files`
+    pub fn notes(&self) -> &[String] {
+        &self.notes
+    }
+
+    // Problems that did not stop the analysis, e.g. a file skipped or a cache not written
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    // Records of the custom collectors, see `Collector`
+    pub fn collected(&self) -> &[Record] {
+        &self.collected
+    }
+
+    // Summary of a custom collector's records, if it gives one
+    pub fn collector_summary(&self, collector: &str) -> Option<&serde_json::Value> {
+        self.collector_summaries.get(collector)
+    }
+}
+
+impl VarInfo {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn is_mutable(&self) -> bool {
+        self.mutable
+    }
+
+    pub fn file_path(&self) -> &Path {
+        &self.file_path
+    }
+
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    // How the variable is declared, e.g. `let binding` or `function parameter: &str`
+    pub fn kind(&self) -> &str {
+        &self.var_kind
+    }
+
+    pub fn var_type(&self) -> &str {
+        &self.var_type
+    }
+
+    pub fn basic_type(&self) -> &str {
+        &self.basic_type
+    }
+
+    // The enclosing function, closure or module, e.g. `crate::parser::parse`
+    pub fn scope(&self) -> &str {
+        &self.scope
+    }
+
+    // "test", "example", "bench" or "production"
+    pub fn code_class(&self) -> &str {
+        &self.code_class
+    }
+
+    pub fn read_count(&self) -> usize {
+        self.read_count
+    }
+
+    pub fn write_count(&self) -> usize {
+        self.write_count
+    }
+}
+
+impl DataStructureInfo {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    // The kind of item, e.g. `struct`, `enum`, `function` or `trait`
+    pub fn kind(&self) -> &str {
+        &self.data_structure_type
+    }
+
+    pub fn file_path(&self) -> &Path {
+        &self.file_path
+    }
+
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    // The logical path, e.g. `crate::parser::Lexer`
+    pub fn item_path(&self) -> &str {
+        &self.item_path
+    }
+
+    // "pub", "pub(crate)", ... or "private" (empty when unknown)
+    pub fn visibility(&self) -> &str {
+        &self.visibility
+    }
+
+    // "test", "example", "bench" or "production"
+    pub fn code_class(&self) -> &str {
+        &self.code_class
+    }
+}
+
+impl AnalysisMetadata {
+    // Name of the package or workspace, or of the file when a single file is analysed
+    pub fn project_name(&self) -> &str {
+        &self.project_name
+    }
+
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    // When the analysis ran
+    pub fn datetime(&self) -> &str {
+        &self.datetime
+    }
+}
+
+impl AnalysisError {
+    pub fn file_path(&self) -> &Path {
+        &self.file_path
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
@@ -1,27 +1,263 @@
 // Copyright (c) 2025 Nicholas D. Crosbie
-use clap::{Arg, ArgAction, Command};
+use crate::csv_output::parse_delimiter;
+use crate::formatter::Formatters;
+use crate::links::parse_link_map;
+use crate::sampling::{parse_sample_spec, SampleSpec};
+use crate::thresholds::Thresholds;
+use crate::AnalysisOptions;
+use clap::builder::PossibleValuesParser;
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use std::path::PathBuf;
+
+// Subcommands that replace the default project analysis
+pub enum SubCommand {
+    Doctor,
+    Schema,
+    Precommit {
+        project_dir: Option<String>,
+        thresholds: Thresholds, // Limits the staged files must stay within
+    },
+    Diff {
+        baseline: String,
+        current: Option<String>, // A second report, or the project to analyse
+        format: String,
+    },
+    Stats {
+        project_dir: Option<String>,
+        top: usize, // Entries kept per ranking
+        format: String,
+    },
+    Batch {
+        project_dirs: Vec<String>,
+        manifest: Option<String>, // File listing more project directories, one per line
+        format: String,
+    },
+    Serve {
+        project_dir: Option<String>,
+        options: AnalysisOptions, // How files are selected and analysed
+    },
+}
+
+// Options of the analysis that `forest serve` accepts too, defined once on the main command
+const SERVE_ARGS: [&str; 11] = [
+    "allow_degraded",
+    "max_depth",
+    "no_ignore",
+    "follow_symlinks",
+    "exclude",
+    "scope_depth",
+    "targets",
+    "macro_bodies",
+    "analyse_doc_tests",
+    "exclude_tests",
+    "doc_coverage",
+];
 
 pub struct Args {
-    pub project_dir: String,
+    pub subcommand: Option<SubCommand>,
+    pub project_dir: Option<String>,
     pub output_file: Option<String>,
     pub format: String,
-    pub sort: bool,
+    pub graph: String, // Graph written by --format dot
+    pub delimiter: u8,
+    pub template: Option<String>,
+    pub csv_single_table: bool,
+    pub columns: Option<Vec<String>>, // Variable columns of the listings, in order
+    pub summary_only: bool,
+    pub sections: Option<Vec<String>>, // Console sections to print; None prints all
+    pub sort_by: Option<Vec<String>>,  // Keys the variables are sorted by; --sort is `name`
+    pub name_filter: Option<String>,   // Regex the names of listed records must match
+    pub type_filter: Option<String>,   // Regex the types of listed records must match
+    pub scope_filter: Option<String>,  // Regex the scopes of listed records must match
+    pub file_filter: Option<String>,   // Glob the files of listed records must match
+    pub mutability: Option<String>,    // Only list mutable or immutable variables
+    pub group_by: Option<String>,      // Aggregate counts by this key instead of listing records
     pub tree: bool,
     pub markdown_help: bool,
     pub link: bool, // New field for the link flag
+    pub link_map: Vec<(PathBuf, PathBuf)>,
+    pub editor: String,               // Editor the links open in
+    pub editor_url: Option<String>,   // URL template of --editor custom
+    pub context_lines: Option<usize>, // Lines shown around each variable declaration
+    pub redact: bool,                 // Hash identifiers and drop paths and code for sharing
+    pub paths: Option<String>,        // How file paths appear: relative, absolute or canonical
+    pub fail_fast: bool,
+    pub allow_degraded: bool,
+    pub strict: bool, // Fail the run when any file cannot be parsed by syn
+    pub status_file: Option<String>,
+    pub baseline: Option<String>, // JSON report the run is compared with
+    pub max_mutable_vars: Option<usize>, // Fail above this many mutable variables
+    pub max_unsafe_blocks: Option<usize>, // Fail above this many unsafe blocks
+    pub max_unused_mut: Option<usize>, // Fail above this many never-mutated `mut` variables
+    pub fail_on_increase: Option<Vec<String>>, // Fail when these metrics grew past the baseline
+    pub junit: Option<String>,    // JUnit XML report of thresholds and findings
+    pub sample: Option<SampleSpec>,
+    pub sample_seed: Option<u64>,
+    pub max_depth: Option<usize>,
+    pub no_ignore: bool,
+    pub follow_symlinks: bool,
+    pub exclude: Vec<String>,       // Globs of files and directories to skip
+    pub scope_depth: Option<usize>, // Only record variables at most this many blocks deep
+    pub targets: Option<Vec<String>>, // Only files of these cargo target kinds
+    pub changed: bool,
+    pub changed_since: Option<String>,
+    pub resume: bool,
+    pub stream: bool,        // Write JSON Lines records while files are analysed
+    pub jobs: Option<usize>, // Threads analysing files in parallel (default: one per CPU)
+    pub rustdoc_json: Option<String>,
+    pub macro_bodies: bool,
+    pub analyse_doc_tests: bool,
+    pub exclude_tests: bool,
+    pub doc_coverage: bool,
+    pub public_api: bool,
+    pub semantic: bool, // Resolve the types of let bindings with rust-analyzer
 }
 
 // Add this new function that returns the Command definition
+// Function to build the command line, offering the built-in output formats
 pub fn command() -> Command {
+    command_with_formats(&Formatters::builtin().names())
+}
+
+// Function to build the command line, offering the registered output formats for --format
+pub fn command_with_formats(formats: &[&str]) -> Command {
+    let command = main_command(formats);
+    let shared: Vec<Arg> = command
+        .get_arguments()
+        .filter(|arg| SERVE_ARGS.contains(&arg.get_id().as_str()))
+        .cloned()
+        .collect();
+    command.mut_subcommand("serve", |serve| serve.args(shared))
+}
+
+// Function to build the command line before its analysis options are shared with serve
+fn main_command(formats: &[&str]) -> Command {
     Command::new("forest")
         .about("Explore and summarise Rust projects")
         .version(env!("CARGO_PKG_VERSION"))
         .author(env!("CARGO_PKG_AUTHORS"))
         .after_help("Copyright (c) 2025 Nicholas D. Crosbie")
+        .args_conflicts_with_subcommands(true)
+        .subcommand(
+            Command::new("doctor")
+                .about("Run a self-check on an embedded fixture project and diagnose the environment"),
+        )
+        .subcommand(
+            Command::new("schema")
+                .about("Print the JSON Schema of the --format json output"),
+        )
+        .subcommand(
+            Command::new("precommit")
+                .about("Analyse only staged Rust files and exit non-zero on findings (for git hooks)")
+                .arg(
+                    Arg::new("project_dir")
+                        .help("The directory containing the Rust project (defaults to the cargo workspace root)")
+                        .index(1),
+                )
+                .arg(
+                    Arg::new("max_mutable_vars")
+                        .long("max-mutable-vars")
+                        .help("Fail when the staged files declare more mutable variables than this")
+                        .value_name("N")
+                        .value_parser(clap::value_parser!(usize)),
+                )
+                .arg(
+                    Arg::new("max_unsafe_blocks")
+                        .long("max-unsafe-blocks")
+                        .help("Fail when the staged files have more unsafe blocks than this")
+                        .value_name("N")
+                        .value_parser(clap::value_parser!(usize)),
+                )
+                .arg(
+                    Arg::new("max_unused_mut")
+                        .long("max-unused-mut")
+                        .help("Fail when the staged files declare more `mut` variables that are never mutated than this")
+                        .value_name("N")
+                        .value_parser(clap::value_parser!(usize)),
+                ),
+        )
+        .subcommand(
+            Command::new("diff")
+                .about("Report added, removed and changed variables and data structures between two analyses")
+                .arg(
+                    Arg::new("baseline")
+                        .help("JSON report written by --format json")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    Arg::new("current")
+                        .help("A second JSON report, or the directory of the project to analyse (defaults to the cargo workspace root)")
+                        .index(2),
+                )
+                .arg(
+                    Arg::new("format")
+                        .long("format")
+                        .help("Print the differences as text or as JSON")
+                        .value_parser(["text", "json"])
+                        .default_value("text"),
+                ),
+        )
+        .subcommand(
+            Command::new("stats")
+                .about("Rank the files and functions with the most mutable variables, data structures and lines")
+                .arg(
+                    Arg::new("project_dir")
+                        .help("The directory containing the Rust project (defaults to the cargo workspace root)")
+                        .index(1),
+                )
+                .arg(
+                    Arg::new("top")
+                        .long("top")
+                        .help("Number of entries in each ranking")
+                        .value_name("N")
+                        .value_parser(clap::value_parser!(usize))
+                        .default_value("10"),
+                )
+                .arg(
+                    Arg::new("format")
+                        .long("format")
+                        .help("Print the rankings as text or as JSON")
+                        .value_parser(["text", "json"])
+                        .default_value("text"),
+                ),
+        )
+        .subcommand(
+            Command::new("batch")
+                .about("Analyse several projects and report their metrics side by side")
+                .arg(
+                    Arg::new("project_dirs")
+                        .help("Directories of the projects to analyse")
+                        .num_args(1..)
+                        .required_unless_present("manifest")
+                        .index(1),
+                )
+                .arg(
+                    Arg::new("manifest")
+                        .long("manifest")
+                        .help("File listing project directories, one per line, relative to the file")
+                        .value_name("FILE"),
+                )
+                .arg(
+                    Arg::new("format")
+                        .long("format")
+                        .help("Print the combined report as text or as JSON, with each project's full results")
+                        .value_parser(["text", "json"])
+                        .default_value("text"),
+                ),
+        )
+        .subcommand(
+            Command::new("serve")
+                .about("Keep the analysis in memory and answer JSON-RPC requests on stdin, re-analysing changed files")
+                .arg(
+                    Arg::new("project_dir")
+                        .help("The directory containing the Rust project (defaults to the cargo workspace root)")
+                        .index(1),
+                ),
+        )
         .arg(
             Arg::new("project_dir")
-                .help("The directory containing the Rust project to analyse")
-                .required(true)
+                .help("The directory containing the Rust project to analyse, a single .rs file, or - to read source from stdin (defaults to the cargo workspace root)")
                 .index(1),
         )
         .arg(
@@ -33,18 +269,121 @@ pub fn command() -> Command {
         .arg(
             Arg::new("format")
                 .long("format")
-                .help("Output format (json, csv, or text)")
+                .help("Output format, one of the registered formats")
                 .value_name("FORMAT")
-                .value_parser(["json", "csv", "text"])
+                .value_parser(PossibleValuesParser::new(
+                    formats.iter().map(|format| format.to_string()),
+                ))
                 .default_value("text"),
         )
+        .arg(
+            Arg::new("graph")
+                .long("graph")
+                .help("Graph written by --format dot: the module tree, the call graph or type usage")
+                .value_name("GRAPH")
+                .value_parser(crate::graph::GRAPHS.to_vec())
+                .default_value("modules"),
+        )
+        .arg(
+            Arg::new("template")
+                .long("template")
+                .help("Render the results with a Tera template file instead of --format (the template sees the JSON output model)")
+                .value_name("FILE"),
+        )
+        .arg(
+            Arg::new("delimiter")
+                .long("delimiter")
+                .help("Field delimiter of --format csv, e.g. ';' or 'tab' for TSV")
+                .value_name("CHAR")
+                .value_parser(parse_delimiter)
+                .default_value(","),
+        )
+        .arg(
+            Arg::new("csv_single_table")
+                .long("csv-single-table")
+                .help("With --format csv, write variables and data structures as one table instead of one file per table")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("columns")
+                .long("columns")
+                .help("Choose and order the variable columns of the csv, text and console listings, e.g. name,file,line,type,scope")
+                .value_name("COLUMN,...")
+                .value_parser(crate::columns::COLUMNS.to_vec())
+                .value_delimiter(',')
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("summary_only")
+                .long("summary-only")
+                .help("Print only the summary counts, without the listings of each section")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("sections")
+                .long("sections")
+                .help("Print only these console sections, e.g. mutable,immutable,structures")
+                .value_name("SECTION,...")
+                .value_parser(crate::sections::SECTIONS.to_vec())
+                .value_delimiter(',')
+                .action(ArgAction::Append)
+                .conflicts_with("summary_only"),
+        )
         .arg(
             Arg::new("sort")
                 .short('s')
                 .long("sort")
-                .help("Sort variable names alphabetically")
+                .help("Sort variable names alphabetically (the same as --sort-by name)")
                 .action(ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new("sort_by")
+                .long("sort-by")
+                .help("Sort the variables by these keys, e.g. file,line or scope,name (results are otherwise ordered by file, line and name)")
+                .value_name("KEY,...")
+                .value_parser(crate::ordering::SORT_KEYS.to_vec())
+                .value_delimiter(',')
+                .action(ArgAction::Append)
+                .conflicts_with("sort"),
+        )
+        .arg(
+            Arg::new("name_filter")
+                .long("name")
+                .help("Only list variables and data structures whose name matches this regex")
+                .value_name("REGEX"),
+        )
+        .arg(
+            Arg::new("type_filter")
+                .long("type")
+                .help("Only list variables whose type, and data structures whose kind, matches this regex")
+                .value_name("REGEX"),
+        )
+        .arg(
+            Arg::new("scope_filter")
+                .long("scope")
+                .help("Only list variables whose scope, and data structures whose path, matches this regex")
+                .value_name("REGEX"),
+        )
+        .arg(
+            Arg::new("file_filter")
+                .long("file")
+                .help("Only list records from files matching this glob, relative to the project directory, e.g. src/parser/**")
+                .value_name("GLOB"),
+        )
+        .arg(
+            Arg::new("mutability")
+                .long("mutability")
+                .help("Only list mutable or immutable variables")
+                .value_parser(crate::filters::MUTABILITIES.to_vec()),
+        )
+        .arg(
+            Arg::new("group_by")
+                .long("group-by")
+                .help("Aggregate variable and data structure counts by this key instead of listing every record")
+                .value_name("KEY")
+                .value_parser(crate::grouping::GROUP_KEYS.to_vec())
+                .conflicts_with("columns"),
+        )
         .arg(
             Arg::new("tree")
                 .long("tree")
@@ -54,7 +393,272 @@ pub fn command() -> Command {
         .arg(
             Arg::new("link")
                 .long("link")
-                .help("Include VSCode-compatible link to the source in the output")
+                .help("Include editor links to the source in the output (VS Code unless --editor says otherwise)")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("link_map")
+                .long("link-map")
+                .help("Rewrite a path prefix in links, e.g. /container/src=/home/me/project (repeatable)")
+                .value_name("FROM=TO")
+                .value_parser(parse_link_map)
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("editor")
+                .long("editor")
+                .help("Editor that --link links open in")
+                .value_name("EDITOR")
+                .value_parser(crate::links::EDITORS.to_vec())
+                .default_value("vscode"),
+        )
+        .arg(
+            Arg::new("editor_url")
+                .long("editor-url")
+                .help("URL template of the links with {path}, {line} and {column} placeholders, e.g. 'idea://open?file={path}&line={line}' (implies --editor custom)")
+                .value_name("TEMPLATE")
+                .required_if_eq("editor", "custom"),
+        )
+        .arg(
+            Arg::new("context_lines")
+                .long("context-lines")
+                .help("Include N source lines before and after each variable declaration")
+                .value_name("N")
+                .value_parser(clap::value_parser!(usize)),
+        )
+        .arg(
+            Arg::new("redact")
+                .long("redact")
+                .help("Hash the project's identifiers, make paths relative and leave out source code, so the report can be shared outside the organisation")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(["context_lines", "paths"]),
+        )
+        .arg(
+            Arg::new("paths")
+                .long("paths")
+                .help("How file paths appear in all outputs and links: relative to the project directory, absolute or canonical (symlinks resolved)")
+                .value_name("MODE")
+                .value_parser(crate::paths::PATH_MODES.to_vec()),
+        )
+        .arg(
+            Arg::new("fail_fast")
+                .long("fail-fast")
+                .help("Stop on the first IO or parse error instead of collecting errors and continuing")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("allow_degraded")
+                .long("allow-degraded")
+                .help("Whether files that syn cannot parse may fall back to analysing the items that do parse")
+                .value_name("BOOL")
+                .value_parser(clap::value_parser!(bool))
+                .default_value("true"),
+        )
+        .arg(
+            Arg::new("strict")
+                .long("strict")
+                .help("Exit with an error when any file cannot be parsed by syn, even if the fallback parser analysed it")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("status_file")
+                .long("status-file")
+                .help("Always write a machine-readable JSON run outcome to the specified file, even on failure")
+                .value_name("FILE"),
+        )
+        .arg(
+            Arg::new("baseline")
+                .long("baseline")
+                .help("Compare the run with a JSON report written by --format json")
+                .value_name("FILE"),
+        )
+        .arg(
+            Arg::new("max_mutable_vars")
+                .long("max-mutable-vars")
+                .help("Exit non-zero when the project declares more mutable variables than this")
+                .value_name("N")
+                .value_parser(clap::value_parser!(usize)),
+        )
+        .arg(
+            Arg::new("max_unsafe_blocks")
+                .long("max-unsafe-blocks")
+                .help("Exit non-zero when the project has more unsafe blocks than this")
+                .value_name("N")
+                .value_parser(clap::value_parser!(usize)),
+        )
+        .arg(
+            Arg::new("max_unused_mut")
+                .long("max-unused-mut")
+                .help("Exit non-zero when the project declares more `mut` variables that are never mutated than this")
+                .value_name("N")
+                .value_parser(clap::value_parser!(usize)),
+        )
+        .arg(
+            Arg::new("fail_on_increase")
+                .long("fail-on-increase")
+                .help("Exit non-zero when these metrics increased since the --baseline")
+                .value_name("METRIC,...")
+                .value_parser(crate::thresholds::metric_names())
+                .value_delimiter(',')
+                .action(ArgAction::Append)
+                .requires("baseline"),
+        )
+        .arg(
+            Arg::new("junit")
+                .long("junit")
+                .help("Write thresholds and per-file findings as a JUnit XML report to the specified file")
+                .value_name("FILE"),
+        )
+        .arg(
+            Arg::new("sample")
+                .long("sample")
+                .help("Analyse a random sample of files (e.g. 10% or 200) and extrapolate estimated totals")
+                .value_name("PERCENT|N")
+                .value_parser(parse_sample_spec),
+        )
+        .arg(
+            Arg::new("sample_seed")
+                .long("sample-seed")
+                .help("Seed for --sample so the same files are selected again")
+                .value_name("SEED")
+                .value_parser(clap::value_parser!(u64))
+                .requires("sample"),
+        )
+        .arg(
+            Arg::new("max_depth")
+                .long("max-depth")
+                .help("Maximum directory depth to descend into below the project directory (0 = top level only)")
+                .value_name("N")
+                .value_parser(clap::value_parser!(usize)),
+        )
+        .arg(
+            Arg::new("no_ignore")
+                .long("no-ignore")
+                .help("Also analyse files excluded by .gitignore, .ignore or global git excludes, and hidden files")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("follow_symlinks")
+                .long("follow-symlinks")
+                .help("Follow symbolic links to files and directories while walking the project")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("exclude")
+                .long("exclude")
+                .value_name("GLOB")
+                .help("Skip files and directories matching this glob, relative to the project directory (repeatable), e.g. 'benches/**'")
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("scope_depth")
+                .long("scope-depth")
+                .help("Only record variables declared at most N blocks deep (1 = function bodies)")
+                .value_name("N")
+                .value_parser(clap::value_parser!(usize)),
+        )
+        .arg(
+            Arg::new("targets")
+                .long("targets")
+                .value_name("KIND,...")
+                .help("Only analyse files of these cargo target kinds, e.g. lib,bins")
+                .value_parser(crate::targets::TARGET_KINDS.to_vec())
+                .value_delimiter(',')
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("changed")
+                .long("changed")
+                .help("Only re-analyse .rs files modified according to git, reusing cached results for the rest")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("sample"),
+        )
+        .arg(
+            Arg::new("changed_since")
+                .long("changed-since")
+                .help("Like --changed, but compare against the given git revision instead of HEAD")
+                .value_name("REV")
+                .conflicts_with("sample"),
+        )
+        .arg(
+            Arg::new("resume")
+                .long("resume")
+                .help("Continue an interrupted analysis, reusing the files completed before its last checkpoint")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("stream")
+                .long("stream")
+                .help("Write variables, data structures and errors as JSON Lines while files are analysed, ending with a summary line, instead of the report")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all([
+                    "format",
+                    "template",
+                    "columns",
+                    "sections",
+                    "sort_by",
+                    "group_by",
+                    "context_lines",
+                    "redact",
+                    "baseline",
+                    "max_mutable_vars",
+                    "max_unsafe_blocks",
+                    "max_unused_mut",
+                    "junit",
+                    "rustdoc_json",
+                    "tree",
+                    "public_api",
+                    "semantic",
+                ]),
+        )
+        .arg(
+            Arg::new("jobs")
+                .long("jobs")
+                .short('j')
+                .help("Number of threads analysing files in parallel (defaults to, and 0 means, one per CPU)")
+                .value_name("N")
+                .value_parser(clap::value_parser!(usize)),
+        )
+        .arg(
+            Arg::new("rustdoc_json")
+                .long("rustdoc-json")
+                .help("Merge item IDs, docs and resolved paths from a rustdoc JSON file into the data structures")
+                .value_name("FILE"),
+        )
+        .arg(
+            Arg::new("macro_bodies")
+                .long("macro-bodies")
+                .help("Also analyse the bodies of macro_rules! definitions (best effort, marked as macro body)")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("analyse_doc_tests")
+                .long("analyse-doc-tests")
+                .help("Analyse the code blocks in doc comments and report examples that declare mutable state")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("exclude_tests")
+                .long("exclude-tests")
+                .help("Leave test code out of the analysis: files under tests/, #[cfg(test)] items and #[test] functions")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("semantic")
+                .long("semantic")
+                .help("Resolve the types of let bindings without annotations with rust-analyzer instead of guessing them (needs forest built with the semantic feature; slower)")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("doc_coverage")
+                .long("doc-coverage")
+                .help("Report which public items have doc comments and the documentation coverage of each module")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("public_api")
+                .long("public-api")
+                .help("List the public API of the library, one sorted line per item, for diffing between versions")
                 .action(ArgAction::SetTrue),
         )
         .arg(
@@ -65,16 +669,148 @@ pub fn command() -> Command {
         )
 }
 
-pub fn parse_args() -> Args {
-    let matches = command().get_matches();
+// Function to build the analysis options of `forest serve` from its arguments
+fn serve_options(matches: &ArgMatches) -> AnalysisOptions {
+    let mut options = AnalysisOptions::new()
+        .allow_degraded(*matches.get_one::<bool>("allow_degraded").unwrap())
+        .max_depth(matches.get_one::<usize>("max_depth").copied())
+        .respect_ignore(!matches.get_flag("no_ignore"))
+        .follow_symlinks(matches.get_flag("follow_symlinks"))
+        .excludes(
+            matches
+                .get_many::<String>("exclude")
+                .into_iter()
+                .flatten()
+                .cloned(),
+        )
+        .scope_depth(matches.get_one::<usize>("scope_depth").copied())
+        .macro_bodies(matches.get_flag("macro_bodies"))
+        .doc_tests(matches.get_flag("analyse_doc_tests"))
+        .include_tests(!matches.get_flag("exclude_tests"))
+        .doc_coverage(matches.get_flag("doc_coverage"));
+    if let Some(kinds) = matches.get_many::<String>("targets") {
+        options = options.targets(kinds.cloned());
+    }
+    options
+}
+
+pub fn parse_args(formats: &[&str]) -> Args {
+    let matches = command_with_formats(formats).get_matches();
+
+    let subcommand = match matches.subcommand() {
+        Some(("doctor", _)) => Some(SubCommand::Doctor),
+        Some(("schema", _)) => Some(SubCommand::Schema),
+        Some(("precommit", sub_matches)) => Some(SubCommand::Precommit {
+            project_dir: sub_matches.get_one::<String>("project_dir").cloned(),
+            thresholds: Thresholds {
+                max_mutable_vars: sub_matches.get_one::<usize>("max_mutable_vars").copied(),
+                max_unsafe_blocks: sub_matches.get_one::<usize>("max_unsafe_blocks").copied(),
+                max_unused_mut: sub_matches.get_one::<usize>("max_unused_mut").copied(),
+                fail_on_increase: Vec::new(),
+            },
+        }),
+        Some(("diff", sub_matches)) => Some(SubCommand::Diff {
+            baseline: sub_matches.get_one::<String>("baseline").unwrap().clone(),
+            current: sub_matches.get_one::<String>("current").cloned(),
+            format: sub_matches.get_one::<String>("format").unwrap().clone(),
+        }),
+        Some(("stats", sub_matches)) => Some(SubCommand::Stats {
+            project_dir: sub_matches.get_one::<String>("project_dir").cloned(),
+            top: *sub_matches.get_one::<usize>("top").unwrap(),
+            format: sub_matches.get_one::<String>("format").unwrap().clone(),
+        }),
+        Some(("batch", sub_matches)) => Some(SubCommand::Batch {
+            project_dirs: sub_matches
+                .get_many::<String>("project_dirs")
+                .map(|dirs| dirs.cloned().collect())
+                .unwrap_or_default(),
+            manifest: sub_matches.get_one::<String>("manifest").cloned(),
+            format: sub_matches.get_one::<String>("format").unwrap().clone(),
+        }),
+        Some(("serve", sub_matches)) => Some(SubCommand::Serve {
+            project_dir: sub_matches.get_one::<String>("project_dir").cloned(),
+            options: serve_options(sub_matches),
+        }),
+        _ => None,
+    };
 
     Args {
-        project_dir: matches.get_one::<String>("project_dir").unwrap().clone(),
+        subcommand,
+        project_dir: matches.get_one::<String>("project_dir").cloned(),
         output_file: matches.get_one::<String>("output").cloned(),
         format: matches.get_one::<String>("format").unwrap().clone(),
-        sort: matches.get_flag("sort"),
+        graph: matches.get_one::<String>("graph").unwrap().clone(),
+        delimiter: *matches.get_one::<u8>("delimiter").unwrap(),
+        template: matches.get_one::<String>("template").cloned(),
+        csv_single_table: matches.get_flag("csv_single_table"),
+        columns: matches
+            .get_many::<String>("columns")
+            .map(|columns| columns.cloned().collect()),
+        summary_only: matches.get_flag("summary_only"),
+        sections: matches
+            .get_many::<String>("sections")
+            .map(|sections| sections.cloned().collect()),
+        sort_by: if matches.get_flag("sort") {
+            Some(vec!["name".to_string()])
+        } else {
+            matches
+                .get_many::<String>("sort_by")
+                .map(|keys| keys.cloned().collect())
+        },
+        name_filter: matches.get_one::<String>("name_filter").cloned(),
+        type_filter: matches.get_one::<String>("type_filter").cloned(),
+        scope_filter: matches.get_one::<String>("scope_filter").cloned(),
+        file_filter: matches.get_one::<String>("file_filter").cloned(),
+        mutability: matches.get_one::<String>("mutability").cloned(),
+        group_by: matches.get_one::<String>("group_by").cloned(),
         tree: matches.get_flag("tree"),
         markdown_help: matches.get_flag("markdown_help"),
         link: matches.get_flag("link"), // Parse the new flag
+        link_map: matches
+            .get_many::<(PathBuf, PathBuf)>("link_map")
+            .map(|maps| maps.cloned().collect())
+            .unwrap_or_default(),
+        editor: matches.get_one::<String>("editor").unwrap().clone(),
+        editor_url: matches.get_one::<String>("editor_url").cloned(),
+        context_lines: matches.get_one::<usize>("context_lines").copied(),
+        redact: matches.get_flag("redact"),
+        paths: matches.get_one::<String>("paths").cloned(),
+        fail_fast: matches.get_flag("fail_fast"),
+        allow_degraded: *matches.get_one::<bool>("allow_degraded").unwrap(),
+        strict: matches.get_flag("strict"),
+        status_file: matches.get_one::<String>("status_file").cloned(),
+        baseline: matches.get_one::<String>("baseline").cloned(),
+        max_mutable_vars: matches.get_one::<usize>("max_mutable_vars").copied(),
+        max_unsafe_blocks: matches.get_one::<usize>("max_unsafe_blocks").copied(),
+        max_unused_mut: matches.get_one::<usize>("max_unused_mut").copied(),
+        fail_on_increase: matches
+            .get_many::<String>("fail_on_increase")
+            .map(|metrics| metrics.cloned().collect()),
+        junit: matches.get_one::<String>("junit").cloned(),
+        sample: matches.get_one::<SampleSpec>("sample").copied(),
+        sample_seed: matches.get_one::<u64>("sample_seed").copied(),
+        max_depth: matches.get_one::<usize>("max_depth").copied(),
+        no_ignore: matches.get_flag("no_ignore"),
+        follow_symlinks: matches.get_flag("follow_symlinks"),
+        exclude: matches
+            .get_many::<String>("exclude")
+            .map(|globs| globs.cloned().collect())
+            .unwrap_or_default(),
+        scope_depth: matches.get_one::<usize>("scope_depth").copied(),
+        targets: matches
+            .get_many::<String>("targets")
+            .map(|kinds| kinds.cloned().collect()),
+        changed: matches.get_flag("changed"),
+        changed_since: matches.get_one::<String>("changed_since").cloned(),
+        resume: matches.get_flag("resume"),
+        stream: matches.get_flag("stream"),
+        jobs: matches.get_one::<usize>("jobs").copied(),
+        rustdoc_json: matches.get_one::<String>("rustdoc_json").cloned(),
+        macro_bodies: matches.get_flag("macro_bodies"),
+        analyse_doc_tests: matches.get_flag("analyse_doc_tests"),
+        exclude_tests: matches.get_flag("exclude_tests"),
+        doc_coverage: matches.get_flag("doc_coverage"),
+        public_api: matches.get_flag("public_api"),
+        semantic: matches.get_flag("semantic"),
     }
 }
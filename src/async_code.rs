@@ -0,0 +1,131 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Async functions and await points.
+// Every `async fn` is recorded with the number of `.await` expressions in its body, as is
+// any other function containing awaits (inside async blocks or closures), so it is easy
+// to see where async complexity concentrates.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use syn::visit::{self, Visit};
+
+// A function that is async or awaits
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AsyncFunction {
+    pub function: String, // Path of the function, e.g. crate::net::Client::fetch
+    #[serde(rename = "async")]
+    pub is_async: bool,
+    pub awaits: usize,
+    #[serde(rename = "file")]
+    pub file_path: PathBuf,
+    #[serde(rename = "line")]
+    pub line_number: usize,
+}
+
+// Visitor that counts await points per function
+struct AsyncVisitor<'a> {
+    file_path: &'a Path,
+    scope: String,
+    awaits: usize, // Await points seen in the current function so far
+    functions: &'a mut Vec<AsyncFunction>,
+}
+
+impl AsyncVisitor<'_> {
+    // Visit a function body, then record it if it is async or awaits
+    fn visit_function(&mut self, sig: &syn::Signature, visit: impl FnOnce(&mut Self)) {
+        let inner_scope = format!("{}::{}", self.scope, sig.ident);
+        let outer_scope = std::mem::replace(&mut self.scope, inner_scope);
+        let outer_awaits = std::mem::replace(&mut self.awaits, 0);
+        visit(self);
+
+        let is_async = sig.asyncness.is_some();
+        if is_async || self.awaits > 0 {
+            self.functions.push(AsyncFunction {
+                function: self.scope.clone(),
+                is_async,
+                awaits: self.awaits,
+                file_path: self.file_path.to_path_buf(),
+                line_number: sig.fn_token.span.start().line,
+            });
+        }
+        self.scope = outer_scope;
+        self.awaits = outer_awaits;
+    }
+
+    fn visit_in_scope(&mut self, name: String, visit: impl FnOnce(&mut Self)) {
+        let inner_scope = format!("{}::{}", self.scope, name);
+        let outer_scope = std::mem::replace(&mut self.scope, inner_scope);
+        visit(self);
+        self.scope = outer_scope;
+    }
+}
+
+impl<'ast> Visit<'ast> for AsyncVisitor<'_> {
+    fn visit_expr_await(&mut self, expr_await: &'ast syn::ExprAwait) {
+        self.awaits += 1;
+        visit::visit_expr_await(self, expr_await);
+    }
+
+    fn visit_item_fn(&mut self, item_fn: &'ast syn::ItemFn) {
+        self.visit_function(&item_fn.sig, |visitor| {
+            visit::visit_item_fn(visitor, item_fn)
+        });
+    }
+
+    fn visit_impl_item_fn(&mut self, method: &'ast syn::ImplItemFn) {
+        self.visit_function(&method.sig, |visitor| {
+            visit::visit_impl_item_fn(visitor, method)
+        });
+    }
+
+    fn visit_trait_item_fn(&mut self, method: &'ast syn::TraitItemFn) {
+        self.visit_function(&method.sig, |visitor| {
+            visit::visit_trait_item_fn(visitor, method)
+        });
+    }
+
+    fn visit_item_impl(&mut self, item_impl: &'ast syn::ItemImpl) {
+        self.visit_in_scope(
+            crate::impl_blocks::type_name(&item_impl.self_ty),
+            |visitor| visit::visit_item_impl(visitor, item_impl),
+        );
+    }
+
+    fn visit_item_trait(&mut self, item_trait: &'ast syn::ItemTrait) {
+        self.visit_in_scope(item_trait.ident.to_string(), |visitor| {
+            visit::visit_item_trait(visitor, item_trait)
+        });
+    }
+
+    fn visit_item_mod(&mut self, item_mod: &'ast syn::ItemMod) {
+        self.visit_in_scope(item_mod.ident.to_string(), |visitor| {
+            visit::visit_item_mod(visitor, item_mod)
+        });
+    }
+}
+
+// Function to collect the async functions of a parsed file
+pub fn collect_async(
+    file_ast: &syn::File,
+    file_path: &Path,
+    module_path: &str,
+    functions: &mut Vec<AsyncFunction>,
+) {
+    let mut visitor = AsyncVisitor {
+        file_path,
+        scope: module_path.to_string(),
+        awaits: 0,
+        functions,
+    };
+    visitor.visit_file(file_ast);
+}
+
+// Function to order functions by their number of await points, most first
+pub fn by_awaits(functions: &[AsyncFunction]) -> Vec<&AsyncFunction> {
+    let mut sorted: Vec<&AsyncFunction> = functions.iter().collect();
+    sorted.sort_by(|a, b| {
+        b.awaits
+            .cmp(&a.awaits)
+            .then_with(|| a.function.cmp(&b.function))
+    });
+    sorted
+}
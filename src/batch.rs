@@ -0,0 +1,149 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// `forest batch`: many projects analysed in one invocation.
+// Projects are given as directories, or listed one per line in a manifest file (blank lines
+// and `#` comments are skipped, relative paths are relative to the manifest). Each project
+// is analysed with the default options and tagged with its name and path in the combined
+// report; the text report compares the projects' metrics side by side, and the JSON report
+// also holds each project's full results. A project that cannot be analysed is reported
+// and the remaining projects still run.
+
+use crate::output_model;
+use crate::thresholds::{self, Metrics};
+use crate::ForestError;
+use serde::Serialize;
+use std::path::Path;
+
+// One project of the batch
+#[derive(Serialize)]
+pub struct ProjectReport {
+    pub project: String, // Package or workspace name
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metrics: Option<Metrics>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub report: Option<output_model::Output>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>, // Why the project could not be analysed
+}
+
+// The combined report of the batch
+#[derive(Serialize)]
+pub struct BatchReport {
+    pub projects: Vec<ProjectReport>,
+    pub totals: Metrics, // Sums over the projects that were analysed
+}
+
+impl BatchReport {
+    // Function to count the projects that could not be analysed
+    pub fn failures(&self) -> usize {
+        self.projects
+            .iter()
+            .filter(|project| project.error.is_some())
+            .count()
+    }
+}
+
+// Function to read the project directories listed in a manifest file
+fn read_manifest(manifest: &str) -> Result<Vec<String>, ForestError> {
+    let content = std::fs::read_to_string(manifest).map_err(|e| ForestError::io(manifest, e))?;
+    let base = Path::new(manifest).parent().unwrap_or(Path::new(""));
+    Ok(content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| base.join(line).display().to_string())
+        .collect())
+}
+
+// Function to analyse every project of the batch
+pub fn run_batch(
+    dirs: &[String],
+    manifest: Option<&str>,
+    analyse: impl Fn(&str) -> Result<output_model::Output, ForestError>,
+) -> Result<BatchReport, ForestError> {
+    let mut project_dirs = dirs.to_vec();
+    if let Some(manifest) = manifest {
+        project_dirs.extend(read_manifest(manifest)?);
+    }
+
+    let mut totals: Metrics = thresholds::metric_names()
+        .into_iter()
+        .map(|name| (name, 0))
+        .collect();
+    let mut projects = Vec::new();
+    for dir in project_dirs {
+        eprintln!("Analysing {}", dir);
+        let fallback_name = Path::new(&dir)
+            .file_name()
+            .map_or_else(|| dir.clone(), |name| name.to_string_lossy().to_string());
+        let analysed = analyse(&dir).and_then(|report| {
+            let model =
+                serde_json::to_value(&report).map_err(|e| ForestError::format("json", e))?;
+            let metrics = thresholds::measure(&model);
+            Ok((report, metrics))
+        });
+        projects.push(match analysed {
+            Ok((report, metrics)) => {
+                for (name, value) in &metrics {
+                    *totals.entry(name).or_default() += value;
+                }
+                ProjectReport {
+                    project: report.metadata.project_name.clone(),
+                    path: dir,
+                    metrics: Some(metrics),
+                    report: Some(report),
+                    error: None,
+                }
+            }
+            Err(e) => ProjectReport {
+                project: fallback_name,
+                path: dir,
+                metrics: None,
+                report: None,
+                error: Some(e.to_string()),
+            },
+        });
+    }
+    Ok(BatchReport { projects, totals })
+}
+
+// Function to print the metrics of the projects side by side, with their totals
+pub fn print_batch(report: &BatchReport) {
+    let names = thresholds::metric_names();
+    let width = report
+        .projects
+        .iter()
+        .map(|project| project.project.len())
+        .max()
+        .unwrap_or(0)
+        .max("project".len());
+
+    println!(
+        "\n\x1b[1mBatch Summary ({} projects):\x1b[0m",
+        report.projects.len()
+    );
+    let mut header = format!("  {:<width$}", "project", width = width);
+    for name in &names {
+        header.push_str(&format!(" {:>12}", name));
+    }
+    println!("{}  path", header);
+    for project in &report.projects {
+        let mut row = format!("  {:<width$}", project.project, width = width);
+        match (&project.metrics, &project.error) {
+            (Some(metrics), _) => {
+                for name in &names {
+                    row.push_str(&format!(" {:>12}", metrics[name]));
+                }
+                row.push_str(&format!("  {}", project.path));
+            }
+            (None, Some(error)) => row.push_str(&format!(" error: {} ({})", error, project.path)),
+            (None, None) => {}
+        }
+        println!("{}", row);
+    }
+    let mut total = format!("  {:<width$}", "total", width = width);
+    for name in &names {
+        total.push_str(&format!(" {:>12}", report.totals[name]));
+    }
+    println!("{}", total);
+}
@@ -0,0 +1,30 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Entry point for `cargo forest`.
+// Cargo runs `cargo-forest forest <args>`, so the extra "forest" argument is dropped
+// and the `forest` binary installed next to this one is run with the remaining arguments.
+
+use std::env;
+use std::path::PathBuf;
+use std::process::{exit, Command};
+
+fn main() {
+    let mut args: Vec<_> = env::args_os().skip(1).collect();
+    if args.first().is_some_and(|arg| arg == "forest") {
+        args.remove(0);
+    }
+
+    // Prefer the forest binary from the same installation, then fall back to PATH
+    let forest = env::current_exe()
+        .ok()
+        .map(|exe| exe.with_file_name(format!("forest{}", env::consts::EXE_SUFFIX)))
+        .filter(|path| path.exists())
+        .unwrap_or_else(|| PathBuf::from("forest"));
+
+    match Command::new(&forest).args(&args).status() {
+        Ok(status) => exit(status.code().unwrap_or(1)),
+        Err(e) => {
+            eprintln!("cargo-forest: failed to run {}: {}", forest.display(), e);
+            exit(1);
+        }
+    }
+}
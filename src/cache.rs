@@ -0,0 +1,379 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Per-file result cache used by the changed-files mode.
+// The results of each analysed file are stored under target/forest/cache.json, keyed by the
+// file's path relative to the project directory, and reused while the file is unchanged.
+// Project analyses also append each completed file to target/forest/checkpoint.jsonl, a log
+// whose first line holds the version and settings and every other line one file's entry,
+// which `--resume` restores after an interruption.
+
+use crate::AnalysisResults;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+
+// How often a running analysis flushes its checkpoint log to disk
+pub const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct AnalysisCache {
+    forest_version: String, // Cache entries are discarded when forest itself changes
+    #[serde(default)]
+    settings: String, // ... or when the analysis settings change
+    files: BTreeMap<PathBuf, CachedFile>,
+}
+
+// The checkpoint log borrows the results it writes, the cache owns the results it reads
+#[derive(Serialize, Deserialize)]
+struct CachedFile<R = AnalysisResults> {
+    modified: u64, // Modification time (seconds since the epoch) when the file was analysed
+    len: u64,      // File size when the file was analysed
+    file_path: PathBuf, // Path recorded in the cached results
+    results: R,
+}
+
+// First line of the checkpoint log
+#[derive(Serialize, Deserialize)]
+struct CheckpointHeader {
+    forest_version: String, // Entries are discarded when forest itself changes
+    settings: String,       // ... or when the analysis settings change
+}
+
+// Append-only log of the files completed by a run; entries are buffered and reach the disk
+// when the buffer fills or the log is flushed, and a torn last line is skipped on loading
+pub struct CheckpointLog {
+    out: BufWriter<File>,
+}
+
+// Function to get the location of the cache for a project
+fn cache_path(project_dir: &Path) -> PathBuf {
+    project_dir.join("target").join("forest").join("cache.json")
+}
+
+// Function to get the location of the checkpoint for a project
+fn checkpoint_path(project_dir: &Path) -> PathBuf {
+    project_dir
+        .join("target")
+        .join("forest")
+        .join("checkpoint.jsonl")
+}
+
+// Function to find the relative path and the stamp under which a file's results are stored
+// Files that failed are not stored so they are retried next time
+fn entry_key<'a>(
+    project_dir: &Path,
+    file: &'a Path,
+    file_results: &AnalysisResults,
+) -> Option<(&'a Path, u64, u64)> {
+    if !file_results.errors.is_empty() {
+        return None;
+    }
+    let relative = file.strip_prefix(project_dir).ok()?;
+    let (modified, len) = file_stamp(file)?;
+    Some((relative, modified, len))
+}
+
+// Function to get the modification time and size used to validate cache entries
+pub fn file_stamp(path: &Path) -> Option<(u64, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    let modified = metadata
+        .modified()
+        .ok()?
+        .duration_since(UNIX_EPOCH)
+        .ok()?
+        .as_secs();
+    Some((modified, metadata.len()))
+}
+
+impl AnalysisCache {
+    pub fn new(settings: &str) -> Self {
+        AnalysisCache {
+            forest_version: env!("CARGO_PKG_VERSION").to_string(),
+            settings: settings.to_string(),
+            files: BTreeMap::new(),
+        }
+    }
+
+    // Load the cache, falling back to an empty cache if it is missing, unreadable or stale
+    pub fn load(project_dir: &Path, settings: &str) -> Self {
+        Self::load_from(&cache_path(project_dir), settings)
+    }
+
+    // Load the checkpoint log of an interrupted run, with the same fallbacks as the cache
+    // Later entries for a file replace earlier ones, and unreadable lines are skipped
+    pub fn load_checkpoint(project_dir: &Path, settings: &str) -> Self {
+        let mut checkpoint = Self::new(settings);
+        let Ok(file) = File::open(checkpoint_path(project_dir)) else {
+            return checkpoint;
+        };
+        let mut lines = BufReader::new(file).lines().map_while(Result::ok);
+        let current = lines
+            .next()
+            .and_then(|line| serde_json::from_str::<CheckpointHeader>(&line).ok())
+            .is_some_and(|header| {
+                header.forest_version == checkpoint.forest_version && header.settings == settings
+            });
+        if current {
+            for line in lines {
+                if let Ok((relative, entry)) = serde_json::from_str::<(PathBuf, CachedFile)>(&line)
+                {
+                    checkpoint.files.insert(relative, entry);
+                }
+            }
+        }
+        checkpoint
+    }
+
+    // Function to tell whether there are no entries, e.g. because nothing was loaded
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    fn load_from(path: &Path, settings: &str) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<AnalysisCache>(&content).ok())
+            .filter(|cache| {
+                cache.forest_version == env!("CARGO_PKG_VERSION") && cache.settings == settings
+            })
+            .unwrap_or_else(|| Self::new(settings))
+    }
+
+    pub fn save(&self, project_dir: &Path) -> io::Result<()> {
+        self.save_to(&cache_path(project_dir))
+    }
+
+    fn save_to(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string(self)?)
+    }
+
+    // Add the cached results of an unchanged file to the results
+    // Returns false when there is no up-to-date entry for the file
+    pub fn restore(&self, project_dir: &Path, file: &Path, results: &mut AnalysisResults) -> bool {
+        let Ok(relative) = file.strip_prefix(project_dir) else {
+            return false;
+        };
+        let Some(entry) = self.files.get(relative) else {
+            return false;
+        };
+        if file_stamp(file) != Some((entry.modified, entry.len)) {
+            return false;
+        }
+
+        // Records are moved to the path used for this run
+        let mut cached = entry.results.clone();
+        if entry.file_path != file {
+            cached.relocate(&entry.file_path, file);
+        }
+        results.merge(cached);
+        true
+    }
+
+    // Store the results of a freshly analysed file
+    pub fn update(&mut self, project_dir: &Path, file: &Path, file_results: &AnalysisResults) {
+        let Some((relative, modified, len)) = entry_key(project_dir, file, file_results) else {
+            return;
+        };
+        self.files.insert(
+            relative.to_path_buf(),
+            CachedFile {
+                modified,
+                len,
+                file_path: file.to_path_buf(),
+                results: file_results.clone(),
+            },
+        );
+    }
+}
+
+impl CheckpointLog {
+    // Function to start the checkpoint log of a run
+    // A resumed run appends to the log it resumes, so a second interruption loses nothing
+    pub fn create(project_dir: &Path, settings: &str, append: bool) -> io::Result<Self> {
+        let path = checkpoint_path(project_dir);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        if append {
+            let mut out = BufWriter::new(OpenOptions::new().append(true).open(path)?);
+            // The interrupted run may have left a torn line, which must not swallow the next
+            out.write_all(b"\n")?;
+            return Ok(CheckpointLog { out });
+        }
+        let mut out = BufWriter::new(File::create(path)?);
+        let header = CheckpointHeader {
+            forest_version: env!("CARGO_PKG_VERSION").to_string(),
+            settings: settings.to_string(),
+        };
+        serde_json::to_writer(&mut out, &header)?;
+        out.write_all(b"\n")?;
+        Ok(CheckpointLog { out })
+    }
+
+    // Append the results of a freshly analysed file
+    pub fn update(
+        &mut self,
+        project_dir: &Path,
+        file: &Path,
+        file_results: &AnalysisResults,
+    ) -> io::Result<()> {
+        let Some((relative, modified, len)) = entry_key(project_dir, file, file_results) else {
+            return Ok(());
+        };
+        let entry = CachedFile {
+            modified,
+            len,
+            file_path: file.to_path_buf(),
+            results: file_results,
+        };
+        serde_json::to_writer(&mut self.out, &(relative, entry))?;
+        self.out.write_all(b"\n")
+    }
+
+    // Write the buffered entries to disk
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+
+    // Delete the checkpoint log once a run has completed
+    pub fn remove(project_dir: &Path) {
+        let _ = fs::remove_file(checkpoint_path(project_dir));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AnalysisError, AnalysisOptions};
+
+    // A project directory under the system temporary directory, removed on drop
+    struct Project(PathBuf);
+
+    impl Project {
+        fn new(name: &str) -> Self {
+            let dir =
+                std::env::temp_dir().join(format!("forest-cache-{}-{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(dir.join("src")).unwrap();
+            Project(dir)
+        }
+
+        // Function to write a source file and analyse it
+        fn analyse(&self, name: &str, source: &str) -> (PathBuf, AnalysisResults) {
+            let file = self.0.join("src").join(name);
+            fs::write(&file, source).unwrap();
+            let options = AnalysisOptions::new();
+            let mut results = AnalysisResults::new();
+            crate::analyse_source(
+                &file,
+                source,
+                &mut results,
+                options.policy(),
+                options.source(),
+            )
+            .unwrap();
+            (file, results)
+        }
+    }
+
+    impl Drop for Project {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    const SOURCE: &str = "fn main() {\n    let mut count = 0;\n    count += 1;\n}\n";
+
+    #[test]
+    fn unchanged_files_are_restored() {
+        let project = Project::new("restore");
+        let (file, file_results) = project.analyse("main.rs", SOURCE);
+        let mut cache = AnalysisCache::new("settings");
+        cache.update(&project.0, &file, &file_results);
+
+        let mut results = AnalysisResults::new();
+        assert!(cache.restore(&project.0, &file, &mut results));
+        assert_eq!(results.mutable_variables().len(), 1);
+        assert_eq!(results.mutable_variables()[0].name(), "count");
+    }
+
+    #[test]
+    fn changed_files_are_analysed_again() {
+        let project = Project::new("changed");
+        let (file, file_results) = project.analyse("main.rs", SOURCE);
+        let mut cache = AnalysisCache::new("settings");
+        cache.update(&project.0, &file, &file_results);
+
+        // A different size invalidates the entry even within the same second
+        fs::write(&file, format!("{}\n", SOURCE)).unwrap();
+        assert!(!cache.restore(&project.0, &file, &mut AnalysisResults::new()));
+
+        let outside = std::env::temp_dir().join("main.rs");
+        assert!(!cache.restore(&project.0, &outside, &mut AnalysisResults::new()));
+    }
+
+    #[test]
+    fn failed_files_are_not_cached() {
+        let project = Project::new("failed");
+        let (file, mut file_results) = project.analyse("main.rs", SOURCE);
+        file_results.errors.push(AnalysisError {
+            file_path: file.clone(),
+            message: "parse error".to_string(),
+        });
+        let mut cache = AnalysisCache::new("settings");
+        cache.update(&project.0, &file, &file_results);
+
+        assert!(!cache.restore(&project.0, &file, &mut AnalysisResults::new()));
+    }
+
+    #[test]
+    fn saved_cache_is_discarded_when_settings_or_version_change() {
+        let project = Project::new("stale");
+        let (file, file_results) = project.analyse("main.rs", SOURCE);
+        let mut cache = AnalysisCache::new("settings");
+        cache.update(&project.0, &file, &file_results);
+        cache.save(&project.0).unwrap();
+
+        let restored =
+            |cache: AnalysisCache| cache.restore(&project.0, &file, &mut AnalysisResults::new());
+        assert!(restored(AnalysisCache::load(&project.0, "settings")));
+        assert!(!restored(AnalysisCache::load(&project.0, "other settings")));
+
+        cache.forest_version = "0.0.0".to_string();
+        cache.save(&project.0).unwrap();
+        assert!(!restored(AnalysisCache::load(&project.0, "settings")));
+    }
+
+    #[test]
+    fn checkpoint_is_appended_loaded_and_removed() {
+        let project = Project::new("checkpoint");
+        let (file, file_results) = project.analyse("main.rs", SOURCE);
+        let mut log = CheckpointLog::create(&project.0, "settings", false).unwrap();
+        log.update(&project.0, &file, &file_results).unwrap();
+        log.flush().unwrap();
+        drop(log);
+
+        // An interrupted write leaves a torn line, which a resumed run appends past
+        let path = checkpoint_path(&project.0);
+        let mut torn = OpenOptions::new().append(true).open(&path).unwrap();
+        torn.write_all(b"[\"src/lib.rs\", {\"modif").unwrap();
+        let (other, other_results) = project.analyse("other.rs", SOURCE);
+        let mut log = CheckpointLog::create(&project.0, "settings", true).unwrap();
+        log.update(&project.0, &other, &other_results).unwrap();
+        log.flush().unwrap();
+
+        let loaded = AnalysisCache::load_checkpoint(&project.0, "settings");
+        assert_eq!(loaded.files.len(), 2);
+        assert!(loaded.restore(&project.0, &file, &mut AnalysisResults::new()));
+        assert!(loaded.restore(&project.0, &other, &mut AnalysisResults::new()));
+        assert!(AnalysisCache::load_checkpoint(&project.0, "other settings").is_empty());
+
+        CheckpointLog::remove(&project.0);
+        assert!(AnalysisCache::load_checkpoint(&project.0, "settings").is_empty());
+    }
+}
@@ -0,0 +1,131 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Move vs borrow analysis at call sites.
+// Each argument of a function or method call is classified from the call syntax as
+// moved (passed by value, which includes Copy types), borrowed (`&x`) or mutably
+// borrowed (`&mut x`). Callees receiving the most `&mut` arguments show where
+// aliased mutation concentrates. Method receivers are auto-referenced and not counted.
+
+use quote::ToTokens;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use syn::visit::{self, Visit};
+
+// Number of callees listed in the ranking
+const TOP_CALLEES: usize = 10;
+
+// Argument counts of one callee within one file
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CalleeArgs {
+    pub callee: String, // Called path (e.g. `analyse_file`), or `.method` for method calls
+    pub file_path: PathBuf,
+    pub calls: usize,
+    pub moved: usize,
+    pub borrowed: usize,
+    pub mutably_borrowed: usize,
+}
+
+// Argument counts of the whole project
+pub struct CallArgsSummary {
+    pub moved: usize,
+    pub borrowed: usize,
+    pub mutably_borrowed: usize,
+    pub top_mut_callees: Vec<CalleeArgs>, // Callees with the most `&mut` arguments first
+}
+
+// Visitor that counts call arguments per callee
+struct CallVisitor {
+    counts: HashMap<String, (usize, usize, usize, usize)>, // calls, moved, borrowed, &mut
+}
+
+impl CallVisitor {
+    fn record<'a>(&mut self, callee: String, args: impl Iterator<Item = &'a syn::Expr>) {
+        let entry = self.counts.entry(callee).or_default();
+        entry.0 += 1;
+        for arg in args {
+            match arg {
+                syn::Expr::Reference(reference) if reference.mutability.is_some() => entry.3 += 1,
+                syn::Expr::Reference(_) => entry.2 += 1,
+                _ => entry.1 += 1,
+            }
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for CallVisitor {
+    fn visit_expr_call(&mut self, call: &'ast syn::ExprCall) {
+        // Calls through closures or other expressions have no stable name
+        if let syn::Expr::Path(ref func) = *call.func {
+            let callee = func.path.to_token_stream().to_string().replace(' ', "");
+            self.record(callee, call.args.iter());
+        }
+        visit::visit_expr_call(self, call);
+    }
+
+    fn visit_expr_method_call(&mut self, call: &'ast syn::ExprMethodCall) {
+        self.record(format!(".{}", call.method), call.args.iter());
+        visit::visit_expr_method_call(self, call);
+    }
+}
+
+// Function to count the call arguments of a parsed file
+pub fn collect_call_args(file_ast: &syn::File, file_path: &Path, callees: &mut Vec<CalleeArgs>) {
+    let mut visitor = CallVisitor {
+        counts: HashMap::new(),
+    };
+    visitor.visit_file(file_ast);
+
+    for (callee, (calls, moved, borrowed, mutably_borrowed)) in visitor.counts {
+        callees.push(CalleeArgs {
+            callee,
+            file_path: file_path.to_path_buf(),
+            calls,
+            moved,
+            borrowed,
+            mutably_borrowed,
+        });
+    }
+}
+
+// Function to combine the per-file counts and rank callees by `&mut` arguments
+pub fn summarize(callees: &[CalleeArgs]) -> CallArgsSummary {
+    let mut by_callee: HashMap<&str, CalleeArgs> = HashMap::new();
+    for counts in callees {
+        let total = by_callee
+            .entry(&counts.callee)
+            .or_insert_with(|| CalleeArgs {
+                callee: counts.callee.clone(),
+                file_path: PathBuf::new(), // Callees are combined across files
+                calls: 0,
+                moved: 0,
+                borrowed: 0,
+                mutably_borrowed: 0,
+            });
+        total.calls += counts.calls;
+        total.moved += counts.moved;
+        total.borrowed += counts.borrowed;
+        total.mutably_borrowed += counts.mutably_borrowed;
+    }
+
+    let mut summary = CallArgsSummary {
+        moved: 0,
+        borrowed: 0,
+        mutably_borrowed: 0,
+        top_mut_callees: Vec::new(),
+    };
+    for total in by_callee.into_values() {
+        summary.moved += total.moved;
+        summary.borrowed += total.borrowed;
+        summary.mutably_borrowed += total.mutably_borrowed;
+        if total.mutably_borrowed > 0 {
+            summary.top_mut_callees.push(total);
+        }
+    }
+    summary.top_mut_callees.sort_by(|a, b| {
+        b.mutably_borrowed
+            .cmp(&a.mutably_borrowed)
+            .then_with(|| a.callee.cmp(&b.callee))
+    });
+    summary.top_mut_callees.truncate(TOP_CALLEES);
+    summary
+}
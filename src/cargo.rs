@@ -0,0 +1,283 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Helpers that ask cargo about the project being analysed.
+// Project information comes from `cargo metadata`, which resolves workspace members,
+// inherited editions, custom target directories and renamed dependencies the way cargo does.
+
+use crate::ForestError;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{Mutex, OnceLock};
+
+// Manifests already reported as invalid, so each is reported once per run
+static INVALID_MANIFESTS: OnceLock<Mutex<HashSet<PathBuf>>> = OnceLock::new();
+// Warnings about invalid manifests not yet handed to the results of an analysis
+static MANIFEST_WARNINGS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+// Function to find the root of the cargo workspace containing the current directory
+pub fn workspace_root() -> Result<String, ForestError> {
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let output = Command::new(&cargo)
+        .args(["locate-project", "--workspace", "--message-format", "plain"])
+        .output()
+        .map_err(|e| ForestError::io(&cargo, e))?;
+
+    if !output.status.success() {
+        return Err(ForestError::Config(format!(
+            "no project_dir given and no cargo workspace found: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    // cargo prints the path of the workspace Cargo.toml
+    let manifest = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let root = Path::new(&manifest).parent().ok_or_else(|| {
+        ForestError::Config(format!("unexpected manifest path from cargo: {}", manifest))
+    })?;
+    Ok(root.display().to_string())
+}
+
+// Function to use the given project directory, or the cargo workspace root if none was given
+pub fn resolve_project_dir(project_dir: Option<&str>) -> Result<String, ForestError> {
+    match project_dir {
+        Some(dir) => Ok(dir.to_string()),
+        None => workspace_root(),
+    }
+}
+
+/// The workspace as described by `cargo metadata`
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CargoMetadata {
+    pub workspace_root: PathBuf,
+    /// Where cargo puts build artifacts, e.g. `target` or `$CARGO_TARGET_DIR`
+    pub target_directory: PathBuf,
+    /// The workspace members
+    pub packages: Vec<Package>,
+}
+
+/// A workspace member
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Package {
+    pub name: String,
+    pub version: String,
+    /// Edition after inheritance from `[workspace.package]`
+    pub edition: String,
+    pub manifest_path: PathBuf,
+    pub targets: Vec<Target>,
+    pub dependencies: Vec<Dependency>,
+}
+
+/// A build target of a package
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Target {
+    pub name: String,
+    /// Kinds of the target, e.g. `lib`, `bin`, `test`, `example`, `bench`, `custom-build`
+    pub kind: Vec<String>,
+    pub src_path: PathBuf,
+    pub edition: String,
+}
+
+/// A declared dependency of a package
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Dependency {
+    pub name: String,
+    /// Version requirement, e.g. `^1.0`
+    pub req: String,
+    /// `dev` or `build`; absent for normal dependencies
+    pub kind: Option<String>,
+    /// Name the dependency is renamed to with `package = ...`
+    pub rename: Option<String>,
+    pub optional: bool,
+}
+
+impl CargoMetadata {
+    // Function to find the package whose manifest is at the workspace root, if any
+    // A virtual workspace has none
+    pub fn root_package(&self) -> Option<&Package> {
+        let root_manifest = self.workspace_root.join("Cargo.toml");
+        self.packages
+            .iter()
+            .find(|package| package.manifest_path == root_manifest)
+    }
+
+    // Function to list the names code uses for the dependencies of all members, sorted
+    pub fn dependency_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .packages
+            .iter()
+            .flat_map(|package| &package.dependencies)
+            .map(|dependency| {
+                dependency
+                    .rename
+                    .clone()
+                    .unwrap_or_else(|| dependency.name.clone())
+            })
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    // Function to rewrite the paths of the metadata, e.g. for --paths
+    pub fn rewrite_paths(&mut self, rewrite: impl Fn(&Path) -> Option<PathBuf>) {
+        let apply = |path: &mut PathBuf| {
+            // The workspace root itself becomes `.` rather than an empty relative path
+            if let Some(rewritten) = rewrite(path) {
+                *path = if rewritten.as_os_str().is_empty() {
+                    PathBuf::from(".")
+                } else {
+                    rewritten
+                };
+            }
+        };
+        apply(&mut self.workspace_root);
+        apply(&mut self.target_directory);
+        for package in &mut self.packages {
+            apply(&mut package.manifest_path);
+            for target in &mut package.targets {
+                apply(&mut target.src_path);
+            }
+        }
+    }
+
+    // Function to describe the workspace in a few lines for the console and text output
+    pub fn describe(&self) -> Vec<String> {
+        let mut lines = vec![
+            format!("Workspace Root: {}", self.workspace_root.display()),
+            format!("Target Directory: {}", self.target_directory.display()),
+        ];
+        for package in &self.packages {
+            let targets: Vec<String> = package
+                .targets
+                .iter()
+                .map(|target| format!("{} {}", target.kind.join("/"), target.name))
+                .collect();
+            lines.push(format!(
+                "Package: {} {} (edition {}): {}",
+                package.name,
+                package.version,
+                package.edition,
+                targets.join(", ")
+            ));
+        }
+        lines
+    }
+}
+
+// Function to ask cargo for the metadata of the workspace the project directory belongs to
+// Dependencies are not resolved, so this needs neither the network nor a lock file
+pub fn metadata(project_dir: &Path) -> Result<CargoMetadata, ForestError> {
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let output = Command::new(&cargo)
+        .args([
+            "metadata",
+            "--format-version",
+            "1",
+            "--no-deps",
+            "--manifest-path",
+        ])
+        .arg(project_dir.join("Cargo.toml"))
+        .output()
+        .map_err(|e| ForestError::io(cargo, e))?;
+
+    if !output.status.success() {
+        return Err(ForestError::Config(format!(
+            "cargo metadata failed for {}: {}",
+            project_dir.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    serde_json::from_slice(&output.stdout)
+        .map_err(|e| ForestError::Config(format!("unexpected output of cargo metadata: {}", e)))
+}
+
+// Function to read and parse a Cargo.toml, or None when there is none
+// A manifest that is not valid TOML is reported once and then treated as missing
+pub fn read_manifest(path: &Path) -> Option<toml::Value> {
+    let content = std::fs::read_to_string(path).ok()?;
+    match toml::from_str(&content) {
+        Ok(manifest) => Some(manifest),
+        Err(source) => {
+            let reported = INVALID_MANIFESTS.get_or_init(Mutex::default);
+            if reported.lock().unwrap().insert(path.to_path_buf()) {
+                let error = ForestError::Toml {
+                    path: path.to_path_buf(),
+                    source,
+                };
+                MANIFEST_WARNINGS
+                    .lock()
+                    .unwrap()
+                    .push(format!("ignoring {}", error));
+            }
+            None
+        }
+    }
+}
+
+// Function to take the warnings about invalid manifests met since it was last called
+pub fn take_manifest_warnings() -> Vec<String> {
+    std::mem::take(&mut MANIFEST_WARNINGS.lock().unwrap())
+}
+
+// Function to find the package a source file belongs to
+// The nearest Cargo.toml above the file with a [package] section decides
+// Returns the package directory and the package name
+pub fn package_for(file: &Path) -> Option<(PathBuf, String)> {
+    let absolute = crate::paths::canonicalize(file)?;
+    absolute.ancestors().skip(1).find_map(|dir| {
+        let manifest = read_manifest(&dir.join("Cargo.toml"))?;
+        let name = manifest.get("package")?.get("name")?.as_str()?;
+        Some((dir.to_path_buf(), name.to_string()))
+    })
+}
+
+// Function to list the features a Cargo.toml declares
+// Optional dependencies count too, since they are implicit features
+pub fn declared_features(manifest: &toml::Value) -> Vec<String> {
+    let mut features: Vec<String> = manifest
+        .get("features")
+        .and_then(|features| features.as_table())
+        .map(|table| table.keys().cloned().collect())
+        .unwrap_or_default();
+    if let Some(dependencies) = manifest.get("dependencies").and_then(|d| d.as_table()) {
+        features.extend(
+            dependencies
+                .iter()
+                .filter(|(_, spec)| spec.get("optional").and_then(|o| o.as_bool()) == Some(true))
+                .map(|(name, _)| name.clone()),
+        );
+    }
+    features.sort();
+    features.dedup();
+    features
+}
+
+// Function to find the package of a source file and the features it declares
+pub fn features_for(file: &Path) -> Option<(String, Vec<String>)> {
+    let (package_dir, name) = package_for(file)?;
+    let manifest = read_manifest(&package_dir.join("Cargo.toml"))?;
+    Some((name, declared_features(&manifest)))
+}
+
+// Function to find the name of the package a source file belongs to
+pub fn crate_name_for(file: &Path) -> Option<String> {
+    package_for(file).map(|(_, name)| name)
+}
+
+// Function to find the edition of the package a source file belongs to
+// `edition.workspace = true` is resolved from the nearest [workspace.package] above it
+pub fn edition_for(file: &Path) -> Option<String> {
+    let (package_dir, _) = package_for(file)?;
+    let manifest = read_manifest(&package_dir.join("Cargo.toml"))?;
+    let edition = manifest.get("package")?.get("edition")?;
+    if let Some(edition) = edition.as_str() {
+        return Some(edition.to_string());
+    }
+    package_dir.ancestors().find_map(|dir| {
+        let manifest = read_manifest(&dir.join("Cargo.toml"))?;
+        let edition = manifest.get("workspace")?.get("package")?.get("edition")?;
+        edition.as_str().map(str::to_string)
+    })
+}
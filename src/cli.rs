@@ -0,0 +1,1788 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// The forest command line: `cli_main` parses the arguments, dispatches the subcommands and
+// runs the analysis, then prints the console report and writes the requested outputs.
+// The library itself does not print; the notes and warnings of an analysis are returned with
+// its results and printed here, notes to stdout and warnings to stderr.
+
+use crate::args::{self, command};
+use crate::text_output::{format_structure_with_link, format_var_with_link};
+use crate::{
+    allocations, analyse_project, async_code, batch, call_args, cargo, code_class, columns,
+    complexity, context, csv_output, diff, doc_coverage, doc_tests, doctor, duplicates,
+    error_handling, feature_gates, filters, formatter, generics, grouping, impl_blocks,
+    interior_mutability, junit, line_stats, links, literals, macros, ordering, output_model,
+    ownership, paths, patterns, precommit, project_metadata, public_api, recursion, redact,
+    results_model, rustdoc, sections, serve, single_file, smart_pointers, state_density, stats,
+    status, streaming, struct_layout, targets, templates, thresholds, trait_objects, type_usage,
+    type_xref, unsafe_code, usage, AnalysisMetadata, AnalysisOptions, AnalysisResults, Analyzer,
+    ForestError, OutputOptions,
+};
+use chrono::Local;
+use clap::CommandFactory;
+use serde::Serialize;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+// Function to run the forest command line, as the `forest` binary does
+pub fn cli_main() -> Result<(), ForestError> {
+    cli_main_with(formatter::Formatters::builtin())
+}
+
+// Function to run the forest command line with the given output formats, so a tool built on
+// the library can offer its own formats through --format
+pub fn cli_main_with(formatters: formatter::Formatters) -> Result<(), ForestError> {
+    // Parse command-line arguments using the clap-based module
+    let args = args::parse_args(&formatters.names());
+
+    let started = Instant::now();
+    let mut run_status = status::RunStatus::default();
+    let outcome = run(&args, &formatters, &mut run_status);
+
+    // The status file is written whether or not the run succeeded
+    if let Some(ref file) = args.status_file {
+        status::write_status_file(file, &run_status, &outcome, started.elapsed())
+            .map_err(|e| ForestError::output("status", file, e))?;
+    }
+
+    outcome
+}
+
+// Function to run the command selected on the command line
+fn run(
+    args: &args::Args,
+    formatters: &formatter::Formatters,
+    run_status: &mut status::RunStatus,
+) -> Result<(), ForestError> {
+    if args.markdown_help {
+        // Create a Command factory function that satisfies CommandFactory trait
+        struct CmdFactory;
+        impl CommandFactory for CmdFactory {
+            fn command() -> clap::Command {
+                command() // Use our imported command function
+            }
+
+            fn command_for_update() -> clap::Command {
+                command() // Use the same command function or customize as needed
+            }
+        }
+
+        // Generate markdown help using the factory
+        clap_markdown::print_help_markdown::<CmdFactory>();
+        return Ok(());
+    }
+
+    if let Some(jobs) = args.jobs {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(jobs)
+            .build_global()
+            .map_err(|e| ForestError::Config(format!("--jobs: {}", e)))?;
+    }
+
+    match args.subcommand {
+        Some(args::SubCommand::Doctor) => return doctor::run_doctor(),
+        Some(args::SubCommand::Schema) => {
+            return print_json(&output_model::schema());
+        }
+        Some(args::SubCommand::Precommit {
+            ref project_dir,
+            ref thresholds,
+        }) => {
+            let project_dir = cargo::resolve_project_dir(project_dir.as_deref())?;
+            return precommit::run_precommit(&project_dir, thresholds);
+        }
+        Some(args::SubCommand::Diff {
+            ref baseline,
+            ref current,
+            ref format,
+        }) => {
+            let baseline = diff::Snapshot::load(baseline)?;
+            let current = match current {
+                Some(report) if report.ends_with(".json") && Path::new(report).is_file() => {
+                    diff::Snapshot::load(report)?
+                }
+                _ => {
+                    let project_dir = cargo::resolve_project_dir(current.as_deref())?;
+                    let model = analyse_to_model(&project_dir)?;
+                    serde_json::to_value(model)
+                        .and_then(diff::Snapshot::from_value)
+                        .map_err(|e| ForestError::format("json", e))?
+                }
+            };
+            let differences = diff::diff(&baseline, &current);
+            if format == "json" {
+                print_json(&differences)?;
+            } else {
+                diff::print_diff(&differences);
+            }
+            return Ok(());
+        }
+        Some(args::SubCommand::Stats {
+            ref project_dir,
+            top,
+            ref format,
+        }) => {
+            let project_dir = cargo::resolve_project_dir(project_dir.as_deref())?;
+            let (results, _) = analyse_with_defaults(&project_dir)?;
+            let rankings = stats::stats(&results, top);
+            if format == "json" {
+                print_json(&rankings)?;
+            } else {
+                stats::print_stats(&rankings, top);
+            }
+            return Ok(());
+        }
+        Some(args::SubCommand::Batch {
+            ref project_dirs,
+            ref manifest,
+            ref format,
+        }) => {
+            let report = batch::run_batch(project_dirs, manifest.as_deref(), analyse_to_model)?;
+            if format == "json" {
+                print_json(&report)?;
+            } else {
+                batch::print_batch(&report);
+            }
+            let failures = report.failures();
+            if failures > 0 {
+                return Err(ForestError::Check(format!(
+                    "{} of {} projects could not be analysed",
+                    failures,
+                    report.projects.len()
+                )));
+            }
+            return Ok(());
+        }
+        Some(args::SubCommand::Serve {
+            ref project_dir,
+            ref options,
+        }) => {
+            let project_dir = cargo::resolve_project_dir(project_dir.as_deref())?;
+            let metadata = project_metadata(&project_dir, Local::now().to_string())?;
+            return serve::serve(&project_dir, metadata, options);
+        }
+        None => {}
+    }
+
+    links::configure(links::LinkConfig::new(
+        args.link_map.clone(),
+        links::Editor::new(&args.editor, args.editor_url.as_deref())
+            .map_err(ForestError::Config)?,
+        args.redact || args.paths.as_deref() == Some("relative"),
+    ));
+
+    // Default to the current cargo workspace, as `cargo forest` users expect
+    let project_dir = cargo::resolve_project_dir(args.project_dir.as_deref())?;
+
+    if args.tree {
+        generate_tree_representation(&project_dir, args.max_depth)?;
+        return Ok(());
+    }
+
+    if args.public_api {
+        return public_api::write_public_api(Path::new(&project_dir), args.output_file.as_deref());
+    }
+
+    // Records are written as the files are analysed, without the console report
+    if args.stream {
+        if single_file::is_single_source(&project_dir) {
+            return Err(ForestError::Config(
+                "--stream needs a project directory".to_string(),
+            ));
+        }
+        let metadata = project_metadata(&project_dir, Local::now().to_string())?;
+        return streaming::stream(args, &project_dir, &metadata);
+    }
+
+    // Get the current datetime
+    let datetime = Local::now().to_string();
+    println!("Analysis run at: {}", datetime);
+
+    // A single file, or `-` for stdin, is analysed without a Cargo.toml
+    let single_source = single_file::is_single_source(&project_dir);
+    let mut metadata = if single_source {
+        if project_options_requested(args) {
+            return Err(ForestError::Config(
+                "--sample, --changed, --changed-since, --resume, --targets and --semantic need a project directory"
+                    .to_string(),
+            ));
+        }
+        println!("Analyzing Rust source: {}", project_dir);
+        AnalysisMetadata {
+            project_name: single_file::project_name(&project_dir),
+            version: "unknown".to_string(),
+            datetime,
+            dependencies: Vec::new(),
+            cargo: None,
+        }
+    } else {
+        let metadata = project_metadata(&project_dir, datetime)?;
+        println!("Analyzing Rust project at: {}", project_dir);
+        println!("Project version: {}", metadata.version);
+        metadata
+    };
+    // Paths of a single file are relative to its directory
+    let root_dir = if single_source {
+        single_file::root_dir(&project_dir)
+    } else {
+        PathBuf::from(&project_dir)
+    };
+    // Invalid filters are reported before the analysis runs
+    let filters = filters::Filters::new(
+        args.name_filter.as_deref(),
+        args.type_filter.as_deref(),
+        args.scope_filter.as_deref(),
+        args.file_filter.as_deref(),
+        args.mutability.as_deref(),
+        &root_dir,
+    )?;
+
+    let options = AnalysisOptions::from_args(args);
+
+    // analyse the project directory
+    let mut results = if single_source {
+        single_file::analyse(&project_dir, options.policy(), options.source())?
+    } else {
+        analyse_project(&project_dir, &options, metadata.cargo.as_ref())?
+    };
+    for note in results.notes() {
+        println!("{}", note);
+    }
+    print_warnings(&results);
+    if let Some(ref cargo_metadata) = metadata.cargo {
+        results.file_targets = targets::Targets::new(cargo_metadata).classify(
+            results
+                .file_lines
+                .iter()
+                .map(|file_lines| file_lines.file_path.as_path()),
+        );
+    }
+    if !filters.is_empty() {
+        filters.apply(&mut results);
+    }
+    if let Some(lines_around) = args.context_lines {
+        context::add_context_windows(
+            results
+                .mutable_vars
+                .iter_mut()
+                .chain(results.immutable_vars.iter_mut()),
+            lines_around,
+        );
+    }
+    if let Some(ref mode) = args.paths {
+        results.rewrite_paths(|path| paths::rewrite(path, &root_dir, mode));
+        if let Some(ref mut cargo_metadata) = metadata.cargo {
+            cargo_metadata.rewrite_paths(|path| paths::rewrite(path, &root_dir, mode));
+        }
+    }
+    run_status.record_results(&results);
+
+    if let Some(ref json_path) = args.rustdoc_json {
+        let matched =
+            rustdoc::merge_rustdoc_json(Path::new(json_path), &mut results.data_structures)?;
+        println!(
+            "Matched {} of {} data structures with rustdoc items",
+            matched,
+            results.data_structures.len()
+        );
+    }
+
+    patterns::annotate(&mut results.data_structures, &results.impl_blocks);
+
+    if args.redact {
+        redact::redact(&mut results, &mut metadata, &root_dir)?;
+    }
+
+    // Sort results if requested
+    if let Some(ref keys) = args.sort_by {
+        ordering::sort_vars(&mut results.mutable_vars, keys);
+        ordering::sort_vars(&mut results.immutable_vars, keys);
+    }
+
+    println!("\n\x1b[1mSummary:\x1b[0m");
+    println!("Found {} mutable variables", results.mutable_vars.len());
+    println!("Found {} immutable variables", results.immutable_vars.len());
+    println!(
+        "Found {} data structure objects",
+        results.data_structures.len()
+    );
+    if let Some(ref sample) = results.sample {
+        println!(
+            "Estimated for the whole project (sampled {} of {} files, seed {}):",
+            sample.sampled_files, sample.total_files, sample.seed
+        );
+        println!(
+            "  ~{} mutable variables",
+            sample.estimate(results.mutable_vars.len())
+        );
+        println!(
+            "  ~{} immutable variables",
+            sample.estimate(results.immutable_vars.len())
+        );
+        println!(
+            "  ~{} data structure objects",
+            sample.estimate(results.data_structures.len())
+        );
+    }
+    let duplicate_groups = duplicates::find_duplicates(&results.function_fingerprints);
+    if !duplicate_groups.is_empty() {
+        println!(
+            "Found {} groups of duplicate functions",
+            duplicate_groups.len()
+        );
+    }
+    if !results.scope_suggestions.is_empty() {
+        println!(
+            "Found {} variables that could be declared in a narrower scope",
+            results.scope_suggestions.len()
+        );
+    }
+    if !results.copy_refs.is_empty() {
+        println!(
+            "Found {} references to small Copy types that could be passed by value",
+            results.copy_refs.len()
+        );
+    }
+    if !results.unsafe_impls.is_empty() || !results.unsafe_sites.is_empty() {
+        println!(
+            "Found {} unsafe blocks, {} unsafe fns, {} unsafe traits and {} unsafe trait impls ({} manual Send/Sync)",
+            unsafe_code::count_kind(&results.unsafe_sites, "block"),
+            unsafe_code::count_kind(&results.unsafe_sites, "fn"),
+            unsafe_code::count_kind(&results.unsafe_sites, "trait"),
+            results.unsafe_impls.len(),
+            results
+                .unsafe_impls
+                .iter()
+                .filter(|i| i.is_send_or_sync())
+                .count()
+        );
+    }
+    if args.macro_bodies {
+        println!(
+            "Found {} variables and {} data structure objects inside macro_rules! bodies",
+            results
+                .mutable_vars
+                .iter()
+                .chain(&results.immutable_vars)
+                .filter(|var| var.macro_body.is_some())
+                .count(),
+            results
+                .data_structures
+                .iter()
+                .filter(|data_structure| data_structure.macro_body.is_some())
+                .count()
+        );
+    }
+    if !results.doc_tests.is_empty() {
+        println!(
+            "Found {} doc tests in {} items",
+            results
+                .doc_tests
+                .iter()
+                .map(|item| item.doc_tests)
+                .sum::<usize>(),
+            results.doc_tests.len()
+        );
+    }
+    if !results.impl_blocks.is_empty() {
+        println!(
+            "Found {} impl blocks with {} methods",
+            results.impl_blocks.len(),
+            results
+                .impl_blocks
+                .iter()
+                .map(|block| block.methods.len())
+                .sum::<usize>()
+        );
+    }
+    if !results.globals.is_empty() {
+        println!(
+            "Found {} constants and statics ({} static mut)",
+            results.globals.len(),
+            results
+                .globals
+                .iter()
+                .filter(|global| global.mutable)
+                .count()
+        );
+    }
+    if !results.closures.is_empty() {
+        println!(
+            "Found {} closures ({} move, {} with mut parameters)",
+            results.closures.len(),
+            results.closures.iter().filter(|c| c.is_move).count(),
+            results
+                .closures
+                .iter()
+                .filter(|c| c.params.iter().any(|param| param.mutable))
+                .count()
+        );
+    }
+    if !results.macro_definitions.is_empty() || !results.macro_invocations.is_empty() {
+        let top: Vec<String> = macros::top_invoked(&results.macro_invocations)
+            .iter()
+            .map(|(name, count)| format!("{}! ({})", name, count))
+            .collect();
+        println!(
+            "Found {} macro_rules! definitions and {} macro invocations (most invoked: {})",
+            results.macro_definitions.len(),
+            results
+                .macro_invocations
+                .iter()
+                .map(|file| file.total())
+                .sum::<usize>(),
+            if top.is_empty() {
+                "none".to_string()
+            } else {
+                top.join(", ")
+            }
+        );
+    }
+    if !results.async_functions.is_empty() {
+        println!(
+            "Found {} async functions, {} await points",
+            results
+                .async_functions
+                .iter()
+                .filter(|function| function.is_async)
+                .count(),
+            results
+                .async_functions
+                .iter()
+                .map(|function| function.awaits)
+                .sum::<usize>()
+        );
+    }
+    if !results.generic_items.is_empty() {
+        println!(
+            "Found {} generic items ({} with lifetime parameters)",
+            results.generic_items.len(),
+            results
+                .generic_items
+                .iter()
+                .filter(|item| !item.lifetimes.is_empty())
+                .count()
+        );
+    }
+    if !results.feature_gates.is_empty() {
+        let report =
+            feature_gates::feature_report(&results.feature_gates, &results.declared_features);
+        println!(
+            "Found {} feature-gated items and checks across {} features ({} undeclared, {} unused)",
+            results.feature_gates.len(),
+            report.len(),
+            report.iter().filter(|feature| !feature.declared).count(),
+            report
+                .iter()
+                .filter(|feature| feature.gates.is_empty())
+                .count()
+        );
+    }
+    let non_production = |class: &str| !class.is_empty() && class != code_class::PRODUCTION;
+    let test_vars = results
+        .mutable_vars
+        .iter()
+        .chain(&results.immutable_vars)
+        .filter(|var| non_production(&var.code_class))
+        .count();
+    let test_structures = results
+        .data_structures
+        .iter()
+        .filter(|structure| non_production(&structure.code_class))
+        .count();
+    if test_vars + test_structures > 0 {
+        println!(
+            "Found {} variables and {} data structures in test, example or bench code",
+            test_vars, test_structures
+        );
+    }
+    if !results.doc_items.is_empty() {
+        let public_items = doc_coverage::public_items(&results.doc_items);
+        let documented = public_items
+            .iter()
+            .filter(|&&(_, documented)| documented)
+            .count();
+        println!(
+            "Documented {} of {} public items ({:.1}%)",
+            documented,
+            public_items.len(),
+            doc_coverage::percentage(documented, public_items.len())
+        );
+    }
+    let unused_mut = usage::unused_mut(&results.mutable_vars, &results.degraded_files);
+    if !unused_mut.is_empty() {
+        println!(
+            "Found {} mutable variables that are never mutated",
+            unused_mut.len()
+        );
+    }
+    if !results.interior_mutability.is_empty() {
+        let uses = &results.interior_mutability;
+        println!(
+            "Found {} interior mutability usages ({} fields, {} statics, {} locals)",
+            uses.len(),
+            interior_mutability::count_storage(uses, "field"),
+            interior_mutability::count_storage(uses, "static"),
+            interior_mutability::count_storage(uses, "local")
+        );
+    }
+    if !results.smart_pointers.is_empty() {
+        println!(
+            "Found {} smart pointers across {} modules",
+            results.smart_pointers.len(),
+            smart_pointers::module_tally(&results.smart_pointers).len()
+        );
+    }
+    if !results.error_sites.is_empty() {
+        let sites = &results.error_sites;
+        println!(
+            "Found {} error-handling sites ({} unwrap, {} expect, {} ?, {} panicking macros)",
+            sites.len(),
+            error_handling::count_kind(sites, "unwrap"),
+            error_handling::count_kind(sites, "expect"),
+            error_handling::count_kind(sites, "?"),
+            sites.len()
+                - error_handling::count_kind(sites, "unwrap")
+                - error_handling::count_kind(sites, "expect")
+                - error_handling::count_kind(sites, "?")
+        );
+    }
+    if !results.allocations.is_empty() {
+        println!(
+            "Found {} clone and allocation calls",
+            results.allocations.len()
+        );
+    }
+    let xrefs = type_xref::cross_reference(&results.data_structures, &results.type_references);
+    if !xrefs.is_empty() {
+        println!(
+            "Cross-referenced {} types ({} never referenced)",
+            xrefs.len(),
+            xrefs
+                .iter()
+                .filter(|xref| xref.references.is_empty())
+                .count()
+        );
+    }
+    if let Some(most_complex) = complexity::most_complex(&results.function_metrics).first() {
+        println!(
+            "Measured {} functions (highest cyclomatic complexity {} in {})",
+            results.function_metrics.len(),
+            most_complex.cyclomatic,
+            most_complex.function
+        );
+    }
+    if !results.file_lines.is_empty() {
+        let totals = line_stats::project_totals(&results.file_lines);
+        println!(
+            "Counted {} lines in {} files ({} code, {} comment, {} blank)",
+            totals.total,
+            results.file_lines.len(),
+            totals.code,
+            totals.comment,
+            totals.blank
+        );
+    }
+    if !results.file_targets.is_empty() {
+        let counts = targets::counts(&results);
+        let per_target: Vec<String> = counts
+            .iter()
+            .map(|target| format!("{}: {}", target.label(), target.files))
+            .collect();
+        println!(
+            "Classified {} files into {} targets ({})",
+            results.file_targets.len(),
+            counts.len(),
+            per_target.join(", ")
+        );
+    }
+    if !results.trait_uses.is_empty() {
+        let dyn_uses = results
+            .trait_uses
+            .iter()
+            .filter(|trait_use| trait_use.dispatch == "dyn")
+            .count();
+        println!(
+            "Found {} dyn Trait and {} impl Trait uses",
+            dyn_uses,
+            results.trait_uses.len() - dyn_uses
+        );
+    }
+    if !results.foreign_items.is_empty()
+        || !results.exported_symbols.is_empty()
+        || !results.pointer_params.is_empty()
+    {
+        println!(
+            "Found {} extern block items, {} exported symbols and {} raw pointer parameters",
+            results.foreign_items.len(),
+            results.exported_symbols.len(),
+            results.pointer_params.len()
+        );
+    }
+    if !results.global_state.is_empty() {
+        println!(
+            "Found {} items of global mutable state",
+            results.global_state.len()
+        );
+    }
+    if !results.literals.is_empty() {
+        let groups = literals::group_literals(&results.literals);
+        println!(
+            "Found {} literals with {} distinct values ({} repeated)",
+            results.literals.len(),
+            groups.len(),
+            groups.iter().filter(|group| group.uses.len() > 1).count()
+        );
+    }
+    let cycles = recursion::find_cycles(&results.function_calls);
+    if !cycles.is_empty() {
+        println!(
+            "Found {} directly recursive functions and {} mutual recursion cycles",
+            cycles
+                .iter()
+                .filter(|cycle| cycle.members.len() == 1)
+                .count(),
+            cycles
+                .iter()
+                .filter(|cycle| cycle.members.len() > 1)
+                .count()
+        );
+    }
+    let ownership_by_module = ownership::module_ownership(
+        &results.param_ownership,
+        results.mutable_vars.iter().chain(&results.immutable_vars),
+    );
+    if !ownership_by_module.is_empty() {
+        let total = ownership::project_ownership(&ownership_by_module);
+        println!(
+            "Parameters: {}; locals: {}",
+            total.params.ratios_text(),
+            total.locals.ratios_text()
+        );
+    }
+    let layouts = struct_layout::estimate_layouts(&results.data_structures);
+    if !layouts.is_empty() {
+        println!(
+            "Struct sizes estimated: {} ({} heap-heavy)",
+            layouts.len(),
+            layouts.iter().filter(|layout| layout.heap_heavy()).count()
+        );
+    }
+    let newtypes = patterns::count(&results.data_structures, patterns::NEWTYPE);
+    let builders = patterns::count(&results.data_structures, patterns::BUILDER);
+    if newtypes + builders > 0 {
+        println!("Found {} newtypes and {} builders", newtypes, builders);
+    }
+    if !results.degraded_files.is_empty() {
+        println!(
+            "{} files analysed with the fallback parser",
+            results.degraded_files.len()
+        );
+    }
+    if !results.errors.is_empty() {
+        println!("{} files could not be analysed", results.errors.len());
+    }
+
+    // Output results
+    if let Some(ref template) = args.template {
+        let model = results_model(&results, &metadata, args.link);
+        let rendered = templates::render(Path::new(template), &model)?;
+        match args.output_file {
+            Some(ref file) => {
+                fs::write(file, rendered).map_err(|e| ForestError::output("template", file, e))?;
+                println!("Results written to: {}", file);
+            }
+            None => print!("{}", rendered),
+        }
+    } else {
+        match args.output_file {
+            Some(ref file) => {
+                let options = OutputOptions {
+                    graph: &args.graph,
+                    csv: csv_output::CsvOptions {
+                        delimiter: args.delimiter,
+                        single_table: args.csv_single_table,
+                    },
+                    columns: args.columns.as_deref(),
+                    group_by: args.group_by.as_deref(),
+                    link: args.link,
+                };
+                let report = formatter::Report::with_options(&results, &metadata, options);
+                formatters.write(&args.format, &report, file)?;
+                println!("Results written to: {}", file);
+            }
+            None if args.summary_only => {}
+            None => {
+                // Print to console
+                print_results(
+                    &results,
+                    &metadata,
+                    args.link,
+                    &sections::Sections::new(args.sections.clone()),
+                    args.columns.as_deref(),
+                    args.group_by.as_deref(),
+                );
+            }
+        }
+    }
+
+    // Thresholds are checked once the results are written, so a failing CI run keeps them
+    let thresholds = thresholds::Thresholds {
+        max_mutable_vars: args.max_mutable_vars,
+        max_unsafe_blocks: args.max_unsafe_blocks,
+        max_unused_mut: args.max_unused_mut,
+        fail_on_increase: args.fail_on_increase.clone().unwrap_or_default(),
+    };
+    if args.baseline.is_some() || args.junit.is_some() || !thresholds.is_empty() {
+        let baseline = match args.baseline {
+            Some(ref path) => Some(thresholds::load_baseline(path)?),
+            None => None,
+        };
+        let (current, checks) = thresholds.evaluate(&results, &metadata, baseline.as_ref())?;
+        if let Some(ref baseline) = baseline {
+            thresholds::print_comparison(baseline, &current);
+        }
+        if let Some(ref file) = args.junit {
+            junit::write_junit(file, &results, &checks)?;
+            println!("JUnit report written to: {}", file);
+        }
+        let violations: Vec<&thresholds::Check> =
+            checks.iter().filter(|check| !check.passed).collect();
+        for violation in &violations {
+            println!("error: {}", violation.message);
+        }
+        let violations = violations.len();
+        run_status.thresholds = checks;
+        if violations > 0 {
+            return Err(ForestError::Check(format!(
+                "{} thresholds exceeded",
+                violations
+            )));
+        }
+    }
+
+    // Degraded files count as failures too with --strict
+    if args.strict && !results.parse_failures.is_empty() {
+        return Err(ForestError::Check(format!(
+            "strict: {} files could not be parsed by syn",
+            results.parse_failures.len()
+        )));
+    }
+
+    // Results are still written when errors were collected, but the run is reported as failed
+    if !results.errors.is_empty() {
+        return Err(ForestError::Check(format!(
+            "analysis incomplete: {} files could not be analysed",
+            results.errors.len()
+        )));
+    }
+
+    Ok(())
+}
+
+// Function to print the warnings of an analysis to stderr
+fn print_warnings(results: &AnalysisResults) {
+    for warning in results.warnings() {
+        eprintln!("Warning: {}", warning);
+    }
+}
+
+// Function to print the report of a subcommand as JSON
+fn print_json(report: &impl Serialize) -> Result<(), ForestError> {
+    let json = serde_json::to_string_pretty(report).map_err(|e| ForestError::format("json", e))?;
+    println!("{}", json);
+    Ok(())
+}
+
+// Function to analyse a project with the default options, for the subcommands
+// Paths are relative to the project directory
+fn analyse_with_defaults(
+    project_dir: &str,
+) -> Result<(AnalysisResults, AnalysisMetadata), ForestError> {
+    let (mut results, mut metadata) =
+        Analyzer::default().analyze_with_metadata(Path::new(project_dir))?;
+    print_warnings(&results);
+    let root_dir = Path::new(project_dir);
+    results.rewrite_paths(|path| paths::rewrite(path, root_dir, "relative"));
+    if let Some(ref mut cargo_metadata) = metadata.cargo {
+        cargo_metadata.rewrite_paths(|path| paths::rewrite(path, root_dir, "relative"));
+    }
+    Ok((results, metadata))
+}
+
+// Function to analyse a project with the default options into the JSON results model
+fn analyse_to_model(project_dir: &str) -> Result<output_model::Output, ForestError> {
+    let (results, metadata) = analyse_with_defaults(project_dir)?;
+    Ok(results_model(&results, &metadata, false))
+}
+
+// Function to check whether options that only apply to project directories were given
+fn project_options_requested(args: &args::Args) -> bool {
+    args.sample.is_some()
+        || args.changed
+        || args.changed_since.is_some()
+        || args.resume
+        || args.targets.is_some()
+        || args.semantic
+}
+
+// Function to print the directories and Rust files of the project as a tree (--tree)
+fn generate_tree_representation(dir: &str, max_depth: Option<usize>) -> Result<(), ForestError> {
+    println!(
+        "Generating tree-like representation for project at: {}",
+        dir
+    );
+
+    // Recursively visit directories and print the structure
+    fn visit_tree(dir: &Path, indent: usize, max_depth: Option<usize>) -> Result<(), ForestError> {
+        if dir.is_dir() {
+            for entry in fs::read_dir(dir).map_err(|e| ForestError::io(dir, e))? {
+                let entry = entry.map_err(|e| ForestError::io(dir, e))?;
+                let path = entry.path();
+
+                if path.is_dir() {
+                    println!(
+                        "{:indent$}📂 {}",
+                        "",
+                        path.file_name().unwrap().to_string_lossy(),
+                        indent = indent
+                    );
+                    // Each level of nesting adds two spaces of indentation
+                    let within_depth = max_depth.is_none_or(|max| indent / 2 < max);
+                    if path.file_name().unwrap_or_default() != "target" && within_depth {
+                        visit_tree(&path, indent + 2, max_depth)?;
+                    }
+                } else if let Some(extension) = path.extension() {
+                    if extension == "rs" {
+                        println!(
+                            "{:indent$}📄 {}",
+                            "",
+                            path.file_name().unwrap().to_string_lossy(),
+                            indent = indent
+                        );
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    visit_tree(Path::new(dir), 0, max_depth)?;
+    Ok(())
+}
+
+// Function to print analysis results to the console
+fn print_results(
+    results: &AnalysisResults,
+    metadata: &AnalysisMetadata,
+    link: bool,
+    sections: &sections::Sections,
+    columns: Option<&[String]>,
+    group_by: Option<&str>,
+) {
+    if sections.shows("project") {
+        println!("\n\x1b[1mProject Information:\x1b[0m");
+        println!("Project Name: {}", metadata.project_name);
+        println!("Version: {}", metadata.version);
+        println!("Analysis Run At: {}", metadata.datetime);
+        if let Some(ref cargo_metadata) = metadata.cargo {
+            for line in cargo_metadata.describe() {
+                println!("{}", line);
+            }
+        }
+    }
+
+    if let Some(by) = group_by {
+        let groups = grouping::group(results, by);
+        println!(
+            "\n\x1b[1mGrouped by {} ({} groups):\x1b[0m",
+            by,
+            groups.len()
+        );
+        grouping::write_table(&mut io::stdout(), "  ", by, &groups)
+            .expect("failed to write to stdout");
+    }
+
+    if group_by.is_none() && sections.shows("mutable") {
+        println!(
+            "\n\x1b[1mMutable Variables ({}):\x1b[0m",
+            results.mutable_vars.len()
+        );
+        if let Some(columns) = columns {
+            columns::write_table(
+                &mut io::stdout(),
+                "  ",
+                &results.mutable_vars,
+                columns,
+                link,
+            )
+            .expect("failed to write to stdout");
+        } else {
+            for var in &results.mutable_vars {
+                if link {
+                    println!("  {}", format_var_with_link(var));
+                } else {
+                    println!("  {}", var);
+                }
+                if let Some(ref window) = var.context_window {
+                    for line in window.numbered(var.line_number) {
+                        println!("    {}", line);
+                    }
+                }
+            }
+        }
+    }
+
+    if group_by.is_none() && sections.shows("immutable") {
+        println!(
+            "\n\x1b[1mImmutable Variables ({}):\x1b[0m",
+            results.immutable_vars.len()
+        );
+        if let Some(columns) = columns {
+            columns::write_table(
+                &mut io::stdout(),
+                "  ",
+                &results.immutable_vars,
+                columns,
+                link,
+            )
+            .expect("failed to write to stdout");
+        } else {
+            for var in &results.immutable_vars {
+                if link {
+                    println!("  {}", format_var_with_link(var));
+                } else {
+                    println!("  {}", var);
+                }
+                if let Some(ref window) = var.context_window {
+                    for line in window.numbered(var.line_number) {
+                        println!("    {}", line);
+                    }
+                }
+            }
+        }
+    }
+
+    if group_by.is_none() && sections.shows("structures") {
+        println!(
+            "\n\x1b[1mdata_structures ({}):\x1b[0m",
+            results.data_structures.len()
+        );
+        for data_structure in &results.data_structures {
+            if link {
+                println!("  {}", format_structure_with_link(data_structure));
+            } else {
+                println!("  {}", data_structure);
+            }
+            if let Some(ref item) = data_structure.rustdoc {
+                println!("    {}", item.summary());
+            }
+            for field in &data_structure.fields {
+                println!("    {}", field.describe());
+            }
+            for variant in &data_structure.variants {
+                println!("    {}", variant.describe());
+            }
+        }
+    }
+
+    let duplicate_groups = duplicates::find_duplicates(&results.function_fingerprints);
+    if sections.shows("duplicates") && !duplicate_groups.is_empty() {
+        println!(
+            "\n\x1b[1mDuplicate Functions ({} groups):\x1b[0m",
+            duplicate_groups.len()
+        );
+        for group in &duplicate_groups {
+            println!("  {} duplicate ({} tokens):", group.kind, group.token_count);
+            for function in &group.functions {
+                println!(
+                    "    {} at {}:{}",
+                    function.name,
+                    function.file_path.display(),
+                    function.line_number
+                );
+            }
+        }
+    }
+
+    if sections.shows("type-usage") {
+        let type_summary = type_usage::summarize(
+            &results.type_uses,
+            &metadata.dependencies,
+            &results.data_structures,
+        );
+        println!(
+            "\n\x1b[1mType Usage ({} std, {} external, {} project-local):\x1b[0m",
+            type_summary.std_uses, type_summary.external_uses, type_summary.local_uses
+        );
+        for usage in &type_summary.by_crate {
+            println!("  {} ({} uses):", usage.crate_name, usage.uses);
+            for (type_name, count) in &usage.types {
+                println!("    {:>5}  {}", count, type_name);
+            }
+        }
+    }
+
+    if sections.shows("narrower-scope") && !results.scope_suggestions.is_empty() {
+        println!(
+            "\n\x1b[1mNarrower Scope Suggestions ({}):\x1b[0m",
+            results.scope_suggestions.len()
+        );
+        for s in &results.scope_suggestions {
+            println!(
+                "  {} in {} at {}:{} (in scope for lines {}-{}) is only used in one {} (lines {}-{})",
+                s.name,
+                s.function,
+                s.file_path.display(),
+                s.declaration_line,
+                s.declaration_line,
+                s.scope_end_line,
+                s.narrower_scope,
+                s.usage_start_line,
+                s.usage_end_line
+            );
+        }
+    }
+
+    if sections.shows("call-args") {
+        let call_summary = call_args::summarize(&results.call_args);
+        println!(
+            "\n\x1b[1mCall Arguments ({} moved, {} borrowed, {} mutably borrowed):\x1b[0m",
+            call_summary.moved, call_summary.borrowed, call_summary.mutably_borrowed
+        );
+        for callee in &call_summary.top_mut_callees {
+            println!(
+                "  {:>5} &mut  {} ({} calls, {} moved, {} borrowed)",
+                callee.mutably_borrowed, callee.callee, callee.calls, callee.moved, callee.borrowed
+            );
+        }
+    }
+
+    if sections.shows("copy-refs") && !results.copy_refs.is_empty() {
+        println!(
+            "\n\x1b[1mCopy Types Passed by Reference ({}):\x1b[0m",
+            results.copy_refs.len()
+        );
+        for finding in &results.copy_refs {
+            println!(
+                "  {} {}: {} in {} at {}:{} (pass by value)",
+                finding.kind,
+                finding.name,
+                finding.type_name,
+                finding.function,
+                finding.file_path.display(),
+                finding.line_number
+            );
+        }
+    }
+
+    if sections.shows("state-density") {
+        let density =
+            state_density::summarize(&results.function_states, &results.interior_mutable_fields);
+        println!(
+            "\n\x1b[1mMutable State Density (overall {:.3} per line):\x1b[0m",
+            density.overall
+        );
+        for ranked in &density.top_functions {
+            let function = ranked.function;
+            println!(
+            "  {:.3}  {} at {}:{} ({} lines, {} mutable locals, {} &mut params, {} interior-mutability fields)",
+            ranked.density,
+            function.name,
+            function.file_path.display(),
+            function.line_number,
+            function.lines,
+            function.mutable_locals,
+            function.mut_params,
+            ranked.interior_fields
+        );
+        }
+    }
+
+    if sections.shows("unsafe")
+        && (!results.unsafe_impls.is_empty() || !results.unsafe_sites.is_empty())
+    {
+        println!(
+            "\n\x1b[1mUnsafe Code ({} unsafe trait impls, review each; {} other unsafe sites):\x1b[0m",
+            results.unsafe_impls.len(),
+            results.unsafe_sites.len()
+        );
+        for unsafe_impl in unsafe_code::sorted_for_review(&results.unsafe_impls) {
+            println!(
+                "  {}unsafe impl {} for {} at {}:{}",
+                if unsafe_impl.is_send_or_sync() {
+                    "\x1b[31m!\x1b[0m "
+                } else {
+                    "  "
+                },
+                unsafe_impl.trait_name,
+                unsafe_impl.self_type,
+                unsafe_impl.file_path.display(),
+                unsafe_impl.line_number
+            );
+        }
+        for site in &results.unsafe_sites {
+            println!(
+                "    {} in {} at {}:{}",
+                site.label(),
+                site.scope,
+                site.file_path.display(),
+                site.line_number
+            );
+        }
+    }
+
+    if sections.shows("ffi") {
+        if !results.foreign_items.is_empty()
+            || !results.exported_symbols.is_empty()
+            || !results.pointer_params.is_empty()
+        {
+            println!("\n\x1b[1mFFI:\x1b[0m");
+        }
+        if !results.foreign_items.is_empty() {
+            println!("  Extern blocks ({} items):", results.foreign_items.len());
+            for item in &results.foreign_items {
+                println!(
+                    "    extern \"{}\" {}{} at {}:{}",
+                    item.abi,
+                    item.declaration,
+                    item.link
+                        .as_ref()
+                        .map(|link| format!(" [link: {}]", link))
+                        .unwrap_or_default(),
+                    item.file_path.display(),
+                    item.line_number
+                );
+            }
+        }
+        if !results.exported_symbols.is_empty() {
+            println!("  Exported symbols ({}):", results.exported_symbols.len());
+            println!(
+                "    {:<30} {:<6} {:<11} {:<6} {:<24} location",
+                "symbol", "kind", "reason", "abi", "item"
+            );
+            for symbol in &results.exported_symbols {
+                println!(
+                    "    {:<30} {:<6} {:<11} {:<6} {:<24} {}:{}",
+                    symbol.symbol,
+                    symbol.kind,
+                    symbol.reason,
+                    symbol.abi.as_deref().unwrap_or("-"),
+                    symbol.item_name,
+                    symbol.file_path.display(),
+                    symbol.line_number
+                );
+            }
+        }
+
+        if !results.pointer_params.is_empty() {
+            println!(
+                "  Raw pointer parameters ({}):",
+                results.pointer_params.len()
+            );
+            for param in &results.pointer_params {
+                println!(
+                    "    {}: {} in {} at {}:{}",
+                    param.param,
+                    param.type_name,
+                    param.function,
+                    param.file_path.display(),
+                    param.line_number
+                );
+            }
+        }
+    }
+
+    if sections.shows("doc-tests") && !results.doc_tests.is_empty() {
+        let per_crate = doc_tests::tests_per_crate(&results.doc_tests);
+        println!("\n\x1b[1mDoc Tests ({} crates):\x1b[0m", per_crate.len());
+        for (crate_name, count) in &per_crate {
+            println!("  {}: {} doc tests", crate_name, count);
+        }
+        for item in &results.doc_tests {
+            let mutable = match item.mutable_vars {
+                Some(count) if count > 0 => {
+                    format!(", examples declare {} mutable variables", count)
+                }
+                _ => String::new(),
+            };
+            println!(
+                "    {} {} at {}:{} ({} doc tests{})",
+                item.kind,
+                item.item,
+                item.file_path.display(),
+                item.line_number,
+                item.doc_tests,
+                mutable
+            );
+        }
+    }
+
+    if sections.shows("impls") && !results.impl_blocks.is_empty() {
+        println!(
+            "\n\x1b[1mImpl Blocks ({}):\x1b[0m",
+            results.impl_blocks.len()
+        );
+        println!("  Types with the most methods:");
+        for (type_name, methods) in impl_blocks::methods_per_type(&results.impl_blocks) {
+            println!("    {}: {} methods", type_name, methods);
+        }
+        for block in &results.impl_blocks {
+            println!(
+                "  {} at {}:{}: {}",
+                block.header(),
+                block.file_path.display(),
+                block.line_number,
+                block.method_list()
+            );
+        }
+    }
+
+    if sections.shows("globals") && !results.globals.is_empty() {
+        println!("\n\x1b[1mGlobals ({}):\x1b[0m", results.globals.len());
+        for global in &results.globals {
+            let location = if link {
+                format!(
+                    "[{}:{}]({})",
+                    global.file_path.display(),
+                    global.line_number,
+                    links::editor_link(&global.file_path, global.line_number)
+                )
+            } else {
+                format!("{}:{}", global.file_path.display(), global.line_number)
+            };
+            println!("  {} at {}", global.declaration(), location);
+        }
+    }
+
+    if sections.shows("closures") && !results.closures.is_empty() {
+        println!("\n\x1b[1mClosures ({}):\x1b[0m", results.closures.len());
+        for closure in &results.closures {
+            println!(
+                "  {} in {} at {}:{}, captures: {}",
+                closure.signature(),
+                closure.scope,
+                closure.file_path.display(),
+                closure.line_number,
+                if closure.captures.is_empty() {
+                    "none".to_string()
+                } else {
+                    closure.captures.join(", ")
+                }
+            );
+        }
+    }
+
+    if sections.shows("macros")
+        && (!results.macro_definitions.is_empty() || !results.macro_invocations.is_empty())
+    {
+        println!(
+            "\n\x1b[1mMacros ({} definitions):\x1b[0m",
+            results.macro_definitions.len()
+        );
+        for definition in &results.macro_definitions {
+            println!(
+                "  macro_rules! {} ({} rules{}) at {}:{}",
+                definition.name,
+                definition.rules,
+                if definition.exported {
+                    ", exported"
+                } else {
+                    ""
+                },
+                definition.file_path.display(),
+                definition.line_number
+            );
+        }
+        println!("  Invocations per file:");
+        for file in &results.macro_invocations {
+            println!("    {}: {}", file.file_path.display(), file.total());
+        }
+    }
+
+    if sections.shows("async") && !results.async_functions.is_empty() {
+        println!(
+            "\n\x1b[1mAsync Functions ({}):\x1b[0m",
+            results.async_functions.len()
+        );
+        for function in async_code::by_awaits(&results.async_functions) {
+            println!(
+                "  {:>4} awaits  {}{} at {}:{}",
+                function.awaits,
+                if function.is_async { "async " } else { "" },
+                function.function,
+                function.file_path.display(),
+                function.line_number
+            );
+        }
+    }
+
+    if sections.shows("generics") && !results.generic_items.is_empty() {
+        println!(
+            "\n\x1b[1mGenerics ({} items):\x1b[0m",
+            results.generic_items.len()
+        );
+        let heavy = generics::lifetime_heavy_modules(&results.generic_items);
+        if !heavy.is_empty() {
+            println!("  Lifetime-heavy modules:");
+            for (module, lifetimes) in heavy {
+                println!("    {}: {} lifetime parameters", module, lifetimes);
+            }
+        }
+        for item in &results.generic_items {
+            println!(
+                "  {} {}{} at {}:{}",
+                item.kind,
+                item.item,
+                item.signature(),
+                item.file_path.display(),
+                item.line_number
+            );
+        }
+    }
+
+    let feature_report =
+        feature_gates::feature_report(&results.feature_gates, &results.declared_features);
+    if sections.shows("features") && !feature_report.is_empty() {
+        println!(
+            "\n\x1b[1mFeature Gates ({} features):\x1b[0m",
+            feature_report.len()
+        );
+        for feature in &feature_report {
+            let note = match (feature.declared, feature.gates.is_empty()) {
+                (false, _) => " \x1b[31m[not declared in Cargo.toml]\x1b[0m",
+                (true, true) => " [declared but never used]",
+                (true, false) => "",
+            };
+            println!(
+                "  {}/{}: {} gated{}",
+                feature.crate_name,
+                feature.feature,
+                feature.gates.len(),
+                note
+            );
+            for gate in &feature.gates {
+                println!(
+                    "    {} at {}:{}",
+                    gate.gated,
+                    gate.file_path.display(),
+                    gate.line_number
+                );
+            }
+        }
+    }
+
+    if sections.shows("doc-coverage") && !results.doc_items.is_empty() {
+        println!("\n\x1b[1mDocumentation Coverage (by module, least documented first):\x1b[0m");
+        for module in doc_coverage::module_coverage(&results.doc_items) {
+            println!(
+                "  {}: {:.1}% ({} of {} public items)",
+                module.module,
+                module.percentage(),
+                module.documented,
+                module.total
+            );
+            for item in &module.undocumented {
+                if link {
+                    println!(
+                        "    undocumented {} {} at [{}:{}]({})",
+                        item.kind,
+                        item.item,
+                        item.file_path.display(),
+                        item.line_number,
+                        links::editor_link(&item.file_path, item.line_number)
+                    );
+                } else {
+                    println!(
+                        "    undocumented {} {} at {}:{}",
+                        item.kind,
+                        item.item,
+                        item.file_path.display(),
+                        item.line_number
+                    );
+                }
+            }
+        }
+    }
+
+    let unused_mut = usage::unused_mut(&results.mutable_vars, &results.degraded_files);
+    if sections.shows("unused-mut") && !unused_mut.is_empty() {
+        println!(
+            "\n\x1b[1mUnused mut ({} variables never mutated):\x1b[0m",
+            unused_mut.len()
+        );
+        for var in &unused_mut {
+            if link {
+                println!(
+                    "  {} in {} at [{}:{}]({})",
+                    var.name,
+                    var.scope,
+                    var.file_path.display(),
+                    var.line_number,
+                    var.editor_link()
+                );
+            } else {
+                println!(
+                    "  {} in {} at {}:{}",
+                    var.name,
+                    var.scope,
+                    var.file_path.display(),
+                    var.line_number
+                );
+            }
+        }
+    }
+
+    let long_lived = usage::longest_lived(&results.mutable_vars);
+    if sections.shows("long-lived") && !long_lived.is_empty() {
+        println!(
+            "\n\x1b[1mLongest-Lived Mutable Variables (lines from declaration to last use):\x1b[0m"
+        );
+        for var in &long_lived {
+            if link {
+                println!(
+                    "  {} in {}: {} lines, declared at [{}:{}]({})",
+                    var.name,
+                    var.scope,
+                    var.span(),
+                    var.file_path.display(),
+                    var.line_number,
+                    var.editor_link()
+                );
+            } else {
+                println!(
+                    "  {} in {}: {} lines, declared at {}:{}",
+                    var.name,
+                    var.scope,
+                    var.span(),
+                    var.file_path.display(),
+                    var.line_number
+                );
+            }
+        }
+    }
+
+    if sections.shows("interior-mutability") && !results.interior_mutability.is_empty() {
+        println!(
+            "\n\x1b[1mInterior Mutability ({} usages):\x1b[0m",
+            results.interior_mutability.len()
+        );
+        for used in &results.interior_mutability {
+            println!(
+                "  {} {} {}: {} at {}:{}",
+                used.wrapper,
+                used.storage,
+                used.owner,
+                used.type_name,
+                used.file_path.display(),
+                used.line_number
+            );
+        }
+    }
+
+    if sections.shows("smart-pointers") && !results.smart_pointers.is_empty() {
+        println!(
+            "\n\x1b[1mSmart Pointers ({} usages):\x1b[0m",
+            results.smart_pointers.len()
+        );
+        for (module, counts) in smart_pointers::module_tally(&results.smart_pointers) {
+            println!("  {}: {}", module, smart_pointers::tally_text(&counts));
+        }
+        for pointer in &results.smart_pointers {
+            println!(
+                "    {}<{}> in {} at {}:{}",
+                pointer.pointer,
+                pointer.wrapped,
+                pointer.module,
+                pointer.file_path.display(),
+                pointer.line_number
+            );
+        }
+    }
+
+    if sections.shows("error-handling") && !results.error_sites.is_empty() {
+        println!(
+            "\n\x1b[1mError Handling ({} sites):\x1b[0m",
+            results.error_sites.len()
+        );
+        let riskiest = error_handling::riskiest(&results.function_errors);
+        if !riskiest.is_empty() {
+            println!("  Highest unwrap density:");
+            for function in riskiest {
+                println!(
+                    "    {:.3}  {} at {}:{} ({} lines, {} unwrap, {} expect, {} panics, {} ?)",
+                    function.unwrap_density(),
+                    function.function,
+                    function.file_path.display(),
+                    function.line_number,
+                    function.lines,
+                    function.unwraps,
+                    function.expects,
+                    function.panics,
+                    function.tries
+                );
+            }
+        }
+        for site in &results.error_sites {
+            println!(
+                "  {} in {} at {}:{}",
+                site.kind,
+                site.function,
+                site.file_path.display(),
+                site.line_number
+            );
+        }
+    }
+
+    if sections.shows("allocations") && !results.allocations.is_empty() {
+        println!(
+            "\n\x1b[1mClone and Allocation Hotspots ({} calls):\x1b[0m",
+            results.allocations.len()
+        );
+        for hotspot in allocations::hotspots(&results.allocations) {
+            println!(
+                "  {:>4}  {} at {}:{}",
+                hotspot.count,
+                hotspot.function,
+                hotspot.file_path.display(),
+                hotspot.first_line
+            );
+        }
+        for allocation in &results.allocations {
+            println!(
+                "    {} in {} at {}:{}",
+                allocation.kind,
+                allocation.function,
+                allocation.file_path.display(),
+                allocation.line_number
+            );
+        }
+    }
+
+    let xrefs = type_xref::cross_reference(&results.data_structures, &results.type_references);
+    if sections.shows("type-xref") && !xrefs.is_empty() {
+        println!(
+            "\n\x1b[1mType Cross-Reference ({} types, least referenced first):\x1b[0m",
+            xrefs.len()
+        );
+        for xref in &xrefs {
+            if xref.references.is_empty() {
+                println!(
+                    "  {} ({}): \x1b[33mnever referenced\x1b[0m",
+                    xref.name(),
+                    xref.kind()
+                );
+                continue;
+            }
+            println!(
+                "  {} ({}): {} references ({})",
+                xref.name(),
+                xref.kind(),
+                xref.references.len(),
+                xref.contexts_text()
+            );
+            for reference in &xref.references {
+                println!(
+                    "    {} in {} at {}:{}",
+                    reference.context,
+                    reference.owner,
+                    reference.file_path.display(),
+                    reference.line_number
+                );
+            }
+        }
+    }
+
+    if sections.shows("complexity") && !results.function_metrics.is_empty() {
+        println!("\n\x1b[1mMost Complex Functions:\x1b[0m");
+        for function in complexity::most_complex(&results.function_metrics) {
+            println!(
+                "  {:>3}  {} at {}:{} ({} lines, {} params, nesting {}, {} branches)",
+                function.cyclomatic,
+                function.function,
+                function.file_path.display(),
+                function.line_number,
+                function.lines,
+                function.params,
+                function.nesting_depth,
+                function.branches
+            );
+        }
+    }
+
+    if sections.shows("lines") && !results.file_lines.is_empty() {
+        println!("\n\x1b[1mLines of Code by Module:\x1b[0m");
+        println!(
+            "  {:>8} {:>8} {:>8} {:>8}  module",
+            "total", "code", "comment", "blank"
+        );
+        for (module, lines) in line_stats::module_totals(&results.file_lines) {
+            println!(
+                "  {:>8} {:>8} {:>8} {:>8}  {}",
+                lines.total, lines.code, lines.comment, lines.blank, module
+            );
+        }
+    }
+
+    if sections.shows("targets") && !results.file_targets.is_empty() {
+        println!("\n\x1b[1mCargo Targets:\x1b[0m");
+        for target in targets::counts(results) {
+            println!("  {}", target.describe());
+        }
+    }
+
+    if sections.shows("trait-objects") && !results.trait_uses.is_empty() {
+        println!(
+            "\n\x1b[1mTrait Objects and impl Trait ({} uses):\x1b[0m",
+            results.trait_uses.len()
+        );
+        for (trait_name, counts) in trait_objects::dispatch_by_trait(&results.trait_uses) {
+            println!(
+                "  {}: {} ({} dyn, {} impl)",
+                trait_name,
+                counts.label(),
+                counts.dyn_uses,
+                counts.impl_uses
+            );
+        }
+        for trait_use in &results.trait_uses {
+            println!(
+                "    {} {} in {} of {} at {}:{}",
+                trait_use.dispatch,
+                trait_use.trait_name,
+                trait_use.context,
+                trait_use.owner,
+                trait_use.file_path.display(),
+                trait_use.line_number
+            );
+        }
+    }
+
+    if sections.shows("global-state") && !results.global_state.is_empty() {
+        println!(
+            "\n\x1b[1mGlobal Mutable State ({}):\x1b[0m",
+            results.global_state.len()
+        );
+        for state in &results.global_state {
+            println!(
+                "  {} {}: {} at {}:{}",
+                state.kind,
+                state.name,
+                state.type_name,
+                state.file_path.display(),
+                state.line_number
+            );
+        }
+    }
+
+    if sections.shows("literals") && !results.literals.is_empty() {
+        let groups = literals::group_literals(&results.literals);
+        println!(
+            "\n\x1b[1mMagic Numbers and String Literals ({} values):\x1b[0m",
+            groups.len()
+        );
+        for group in &groups {
+            let value = match group.kind {
+                "string" => format!("{:?}", group.value),
+                _ => group.value.to_string(),
+            };
+            println!("  {} ({}, {} uses):", value, group.kind, group.uses.len());
+            for literal in &group.uses {
+                println!(
+                    "    in {} at {}:{}",
+                    literal.scope,
+                    literal.file_path.display(),
+                    literal.line_number
+                );
+            }
+        }
+    }
+
+    let cycles = recursion::find_cycles(&results.function_calls);
+    if sections.shows("recursion") && !cycles.is_empty() {
+        println!("\n\x1b[1mRecursion ({} cycles):\x1b[0m", cycles.len());
+        for cycle in &cycles {
+            let kind = if cycle.members.len() == 1 {
+                "direct"
+            } else {
+                "mutual"
+            };
+            println!("  {}: {}", kind, recursion::cycle_text(cycle));
+            for member in &cycle.members {
+                println!(
+                    "    {} at {}:{}",
+                    member.function,
+                    member.file_path.display(),
+                    member.line_number
+                );
+            }
+        }
+    }
+
+    let ownership_by_module = ownership::module_ownership(
+        &results.param_ownership,
+        results.mutable_vars.iter().chain(&results.immutable_vars),
+    );
+    if sections.shows("ownership") && !ownership_by_module.is_empty() {
+        println!("\n\x1b[1mOwnership by Module:\x1b[0m");
+        for (module, counts) in &ownership_by_module {
+            println!("  {}:", module);
+            println!(
+                "    {} parameters: {}",
+                counts.params.total(),
+                counts.params.ratios_text()
+            );
+            println!(
+                "    {} locals: {}",
+                counts.locals.total(),
+                counts.locals.ratios_text()
+            );
+        }
+    }
+
+    let layouts = struct_layout::estimate_layouts(&results.data_structures);
+    if sections.shows("struct-layout") && !layouts.is_empty() {
+        println!("\n\x1b[1mStruct Layout (estimate):\x1b[0m");
+        println!("  Sizes assume a 64-bit target and rustc field reordering");
+        println!("  {:>6} {:>5} {:>4}  struct", "bytes", "align", "heap");
+        for layout in &layouts {
+            println!(
+                "  {:>6} {:>5} {:>4}  {}{} ({}:{})",
+                layout.size,
+                layout.align,
+                layout.heap_fields,
+                layout.structure.display_name(),
+                if layout.heap_heavy() {
+                    " [heap-heavy]"
+                } else {
+                    ""
+                },
+                layout.structure.file_path.display(),
+                layout.structure.line_number
+            );
+        }
+    }
+
+    if sections.shows("parse-failures") && !results.parse_failures.is_empty() {
+        println!(
+            "\n\x1b[1mParse Failures ({}):\x1b[0m",
+            results.parse_failures.len()
+        );
+        for failure in &results.parse_failures {
+            println!(
+                "  {}: {} ({})",
+                failure.file_path.display(),
+                failure.describe(),
+                failure.outcome()
+            );
+        }
+    }
+
+    if sections.shows("errors") && !results.errors.is_empty() {
+        println!("\n\x1b[1mErrors ({}):\x1b[0m", results.errors.len());
+        for error in &results.errors {
+            println!("  {}: {}", error.file_path.display(), error.message);
+        }
+    }
+}
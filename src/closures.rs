@@ -0,0 +1,231 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Closure analysis.
+// Every closure is recorded with its parameters, whether it is `move` and, best effort,
+// the outer variables it captures: names used in its body that are bound in the
+// enclosing function and not rebound inside the closure itself.
+
+use crate::collector::macro_args;
+use quote::ToTokens;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use syn::visit::{self, Visit};
+
+// A closure parameter
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ClosureParam {
+    pub name: String,
+    pub mutable: bool,
+    #[serde(rename = "type")]
+    pub type_name: Option<String>, // Only when annotated, e.g. |x: u32|
+}
+
+// A closure expression
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ClosureInfo {
+    pub scope: String, // Enclosing function, e.g. crate::parser::parse
+    #[serde(rename = "move")]
+    pub is_move: bool,
+    pub params: Vec<ClosureParam>,
+    pub captures: Vec<String>, // Outer variables used in the body
+    #[serde(rename = "file")]
+    pub file_path: PathBuf,
+    #[serde(rename = "line")]
+    pub line_number: usize,
+}
+
+impl ClosureInfo {
+    // The parameter list as written, e.g. `move |a, mut b: u32|`
+    pub fn signature(&self) -> String {
+        let params: Vec<String> = self
+            .params
+            .iter()
+            .map(|param| {
+                let mut text = if param.mutable {
+                    format!("mut {}", param.name)
+                } else {
+                    param.name.clone()
+                };
+                if let Some(ref type_name) = param.type_name {
+                    text.push_str(": ");
+                    text.push_str(type_name);
+                }
+                text
+            })
+            .collect();
+        format!(
+            "{}|{}|",
+            if self.is_move { "move " } else { "" },
+            params.join(", ")
+        )
+    }
+}
+
+// Visitor that collects the names bound by patterns
+#[derive(Default)]
+struct BindingCollector {
+    names: BTreeSet<String>,
+}
+
+impl<'ast> Visit<'ast> for BindingCollector {
+    fn visit_pat_ident(&mut self, pat_ident: &'ast syn::PatIdent) {
+        self.names.insert(pat_ident.ident.to_string());
+        visit::visit_pat_ident(self, pat_ident);
+    }
+}
+
+// Visitor that collects single-segment paths used as expressions, e.g. `count` in `count + 1`
+#[derive(Default)]
+struct UseCollector {
+    names: BTreeSet<String>,
+}
+
+impl<'ast> Visit<'ast> for UseCollector {
+    fn visit_expr_path(&mut self, expr_path: &'ast syn::ExprPath) {
+        if let Some(ident) = expr_path.path.get_ident() {
+            self.names.insert(ident.to_string());
+        }
+        visit::visit_expr_path(self, expr_path);
+    }
+
+    // Macro arguments such as `println!("{}", count)` are only tokens to syn
+    fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+        if let Some(args) = macro_args(mac) {
+            for arg in &args {
+                self.visit_expr(arg);
+            }
+        }
+    }
+}
+
+// Visitor that finds closures, tracking the enclosing function and its bindings
+struct ClosureVisitor<'a> {
+    file_path: &'a Path,
+    scope: String,
+    outer_bindings: BTreeSet<String>, // Names bound in the enclosing function
+    closures: &'a mut Vec<ClosureInfo>,
+}
+
+impl ClosureVisitor<'_> {
+    // Visit a function body with its own scope and bindings
+    fn visit_function(
+        &mut self,
+        name: &syn::Ident,
+        sig: &syn::Signature,
+        block: &syn::Block,
+        visit: impl FnOnce(&mut Self),
+    ) {
+        let mut bindings = BindingCollector::default();
+        bindings.visit_signature(sig);
+        bindings.visit_block(block);
+
+        let inner_scope = format!("{}::{}", self.scope, name);
+        let outer_scope = std::mem::replace(&mut self.scope, inner_scope);
+        let outer_bindings = std::mem::replace(&mut self.outer_bindings, bindings.names);
+        visit(self);
+        self.scope = outer_scope;
+        self.outer_bindings = outer_bindings;
+    }
+}
+
+impl<'ast> Visit<'ast> for ClosureVisitor<'_> {
+    fn visit_item_fn(&mut self, item_fn: &'ast syn::ItemFn) {
+        self.visit_function(
+            &item_fn.sig.ident,
+            &item_fn.sig,
+            &item_fn.block,
+            |visitor| visit::visit_item_fn(visitor, item_fn),
+        );
+    }
+
+    fn visit_impl_item_fn(&mut self, method: &'ast syn::ImplItemFn) {
+        self.visit_function(&method.sig.ident, &method.sig, &method.block, |visitor| {
+            visit::visit_impl_item_fn(visitor, method)
+        });
+    }
+
+    fn visit_item_impl(&mut self, item_impl: &'ast syn::ItemImpl) {
+        let inner_scope = format!(
+            "{}::{}",
+            self.scope,
+            crate::impl_blocks::type_name(&item_impl.self_ty)
+        );
+        let outer_scope = std::mem::replace(&mut self.scope, inner_scope);
+        visit::visit_item_impl(self, item_impl);
+        self.scope = outer_scope;
+    }
+
+    fn visit_item_mod(&mut self, item_mod: &'ast syn::ItemMod) {
+        let inner_scope = format!("{}::{}", self.scope, item_mod.ident);
+        let outer_scope = std::mem::replace(&mut self.scope, inner_scope);
+        visit::visit_item_mod(self, item_mod);
+        self.scope = outer_scope;
+    }
+
+    fn visit_expr_closure(&mut self, closure: &'ast syn::ExprClosure) {
+        let params = closure
+            .inputs
+            .iter()
+            .map(|input| {
+                let (pat, type_name) = match input {
+                    syn::Pat::Type(pat_type) => (
+                        &*pat_type.pat,
+                        Some(crate::format_type(&pat_type.ty).replace(' ', "")),
+                    ),
+                    other => (other, None),
+                };
+                let (name, mutable) = match pat {
+                    syn::Pat::Ident(pat_ident) => {
+                        (pat_ident.ident.to_string(), pat_ident.mutability.is_some())
+                    }
+                    other => (other.to_token_stream().to_string(), false),
+                };
+                ClosureParam {
+                    name,
+                    mutable,
+                    type_name,
+                }
+            })
+            .collect();
+
+        // Names bound inside the closure shadow the outer ones
+        let mut inner = BindingCollector::default();
+        for input in &closure.inputs {
+            inner.visit_pat(input);
+        }
+        inner.visit_expr(&closure.body);
+        let mut uses = UseCollector::default();
+        uses.visit_expr(&closure.body);
+        let captures = uses
+            .names
+            .into_iter()
+            .filter(|name| self.outer_bindings.contains(name) && !inner.names.contains(name))
+            .collect();
+
+        self.closures.push(ClosureInfo {
+            scope: self.scope.clone(),
+            is_move: closure.capture.is_some(),
+            params,
+            captures,
+            file_path: self.file_path.to_path_buf(),
+            line_number: closure.or1_token.span.start().line,
+        });
+        visit::visit_expr_closure(self, closure);
+    }
+}
+
+// Function to collect the closures of a parsed file
+pub fn collect_closures(
+    file_ast: &syn::File,
+    file_path: &Path,
+    module_path: &str,
+    closures: &mut Vec<ClosureInfo>,
+) {
+    let mut visitor = ClosureVisitor {
+        file_path,
+        scope: module_path.to_string(),
+        outer_bindings: BTreeSet::new(),
+        closures,
+    };
+    visitor.visit_file(file_ast);
+}
@@ -0,0 +1,147 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Test code detection.
+// Files under tests/, examples/ and benches/ are test, example and bench code. Inside other
+// files, `#[cfg(test)]` items and `#[test]` / `#[bench]` functions are too, so every
+// variable and data structure can be told apart from production code, or left out of the
+// analysis altogether.
+
+use std::path::{Component, Path};
+use syn::spanned::Spanned;
+
+pub const PRODUCTION: &str = "production";
+pub const TEST: &str = "test";
+const EXAMPLE: &str = "example";
+const BENCH: &str = "bench";
+
+// The code classes of a file: its own class and the line ranges of test code inside it
+pub struct CodeClasses {
+    pub file_class: &'static str,
+    regions: Vec<(usize, usize, &'static str)>, // First line, last line, class
+}
+
+impl CodeClasses {
+    // The class of the code at a line
+    pub fn class_at(&self, line_number: usize) -> &'static str {
+        self.regions
+            .iter()
+            .find(|&&(first, last, _)| (first..=last).contains(&line_number))
+            .map(|&(_, _, class)| class)
+            .unwrap_or(self.file_class)
+    }
+}
+
+// Function to classify a file by the package directory it sits in
+pub fn file_class(file_path: &Path) -> &'static str {
+    let relative = crate::cargo::package_for(file_path).and_then(|(package_dir, _)| {
+        let absolute = crate::paths::canonicalize(file_path)?;
+        Some(absolute.strip_prefix(&package_dir).ok()?.to_path_buf())
+    });
+    let first_dir = relative
+        .as_deref()
+        .and_then(|relative| match relative.components().next() {
+            Some(Component::Normal(name)) => name.to_str().map(str::to_string),
+            _ => None,
+        });
+    match first_dir.as_deref() {
+        Some("tests") => TEST,
+        Some("examples") => EXAMPLE,
+        Some("benches") => BENCH,
+        _ => PRODUCTION,
+    }
+}
+
+// Function to find the class an item's attributes give it, if any
+// `#[tokio::test]` and similar runtime attributes count as tests too
+fn attribute_class(attrs: &[syn::Attribute]) -> Option<&'static str> {
+    for attr in attrs {
+        let path = attr.path();
+        let last = path
+            .segments
+            .last()
+            .map(|segment| segment.ident.to_string());
+        match last.as_deref() {
+            Some("test") => return Some(TEST),
+            Some("bench") => return Some(BENCH),
+            Some("cfg") => {
+                if let syn::Meta::List(ref list) = attr.meta {
+                    let is_cfg_test = list.tokens.clone().into_iter().any(|token| {
+                        matches!(token, proc_macro2::TokenTree::Ident(ref ident) if ident == "test")
+                    });
+                    // `#[cfg(not(test))]` marks production code, so only a bare `test` counts
+                    if is_cfg_test && !list.tokens.to_string().contains("not") {
+                        return Some(TEST);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+// Function to find the attributes of the items that can be test code
+fn item_attrs(item: &syn::Item) -> Option<&[syn::Attribute]> {
+    match item {
+        syn::Item::Fn(item) => Some(&item.attrs),
+        syn::Item::Mod(item) => Some(&item.attrs),
+        syn::Item::Impl(item) => Some(&item.attrs),
+        syn::Item::Struct(item) => Some(&item.attrs),
+        syn::Item::Enum(item) => Some(&item.attrs),
+        syn::Item::Use(item) => Some(&item.attrs),
+        syn::Item::Const(item) => Some(&item.attrs),
+        syn::Item::Static(item) => Some(&item.attrs),
+        _ => None,
+    }
+}
+
+// Function to find the class an item's attributes give it, e.g. test for `#[cfg(test)] mod tests`
+pub fn item_class(item: &syn::Item) -> Option<&'static str> {
+    item_attrs(item).and_then(attribute_class)
+}
+
+// Function to collect the test regions of a list of items, recursing into inline modules
+fn collect_regions(items: &[syn::Item], regions: &mut Vec<(usize, usize, &'static str)>) {
+    for item in items {
+        if let Some(class) = item_class(item) {
+            let span = item.span();
+            regions.push((span.start().line, span.end().line, class));
+        } else if let syn::Item::Mod(syn::ItemMod {
+            content: Some((_, ref inner)),
+            ..
+        }) = item
+        {
+            collect_regions(inner, regions);
+        }
+    }
+}
+
+// Function to classify the code of a file; without an AST only the file itself is classified
+pub fn classify(file_path: &Path, file_ast: Option<&syn::File>) -> CodeClasses {
+    let mut regions = Vec::new();
+    if let Some(file_ast) = file_ast {
+        collect_regions(&file_ast.items, &mut regions);
+    }
+    CodeClasses {
+        file_class: file_class(file_path),
+        regions,
+    }
+}
+
+// Function to remove test items from a list of items, recursing into inline modules
+fn strip_items(items: &mut Vec<syn::Item>) {
+    items.retain(|item| item_class(item) != Some(TEST));
+    for item in items {
+        if let syn::Item::Mod(syn::ItemMod {
+            content: Some((_, ref mut inner)),
+            ..
+        }) = item
+        {
+            strip_items(inner);
+        }
+    }
+}
+
+// Function to remove `#[cfg(test)]` items and `#[test]` functions from a parsed file
+pub fn strip_test_code(file_ast: &mut syn::File) {
+    strip_items(&mut file_ast.items);
+}
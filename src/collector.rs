@@ -0,0 +1,254 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Analyses plugged into the walk of each file (the `Collector` trait).
+// A collector is called for the items, impl and trait items, function parameters, `let`
+// statements, patterns, match arms, closures and expressions met while forest walks a parsed
+// file, with the file, module, scope and line table of where the walk is, and records what it
+// finds. forest's own variable and data structure listings are built by two such collectors
+// (see variables.rs and data_structures.rs), which run before the ones registered with
+// `AnalysisOptions::collector`. The records of a registered collector are kept with the other
+// results (and cached with them); once the whole project is analysed, the collector can
+// summarise them. Both appear in the JSON report under `collectors`, keyed by the collector's
+// name. Collectors run on several files at once, so they only read `&self`.
+
+use crate::line_index::LineIndex;
+use crate::AnalysisResults;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use syn::punctuated::Punctuated;
+use syn::token::Comma;
+
+// A custom analysis; every event does nothing unless the collector overrides it
+pub trait Collector: Send + Sync {
+    // Name of the collector, which keys its records and summary in the report
+    fn name(&self) -> &str;
+
+    // Called for each item: fn, struct, enum, impl, mod, use, ...
+    fn item(&self, _item: &syn::Item, _context: &mut Context) {}
+
+    // Called for each item of an impl block: method, associated const or type, ...
+    fn impl_item(&self, _item: &syn::ImplItem, _context: &mut Context) {}
+
+    // Called for each item of a trait definition
+    fn trait_item(&self, _item: &syn::TraitItem, _context: &mut Context) {}
+
+    // Called for each parameter of a function or method, in the function's scope
+    fn fn_param(&self, _param: &syn::FnArg, _context: &mut Context) {}
+
+    // Called for each `let` statement
+    fn local(&self, _local: &syn::Local, _context: &mut Context) {}
+
+    // Called for the pattern of each `for` loop, `if let`, `while let` and closure parameter
+    fn pattern(&self, _pattern: Pattern, _context: &mut Context) {}
+
+    // Called for each arm of a `match`, with the expression it matches
+    fn arm(&self, _arm: &syn::Arm, _scrutinee: &syn::Expr, _context: &mut Context) {}
+
+    // Called for each closure, in the scope that encloses it
+    fn closure(&self, _closure: &syn::ExprClosure, _context: &mut Context) {}
+
+    // Called for each expression, outermost first
+    fn expr(&self, _expr: &syn::Expr, _context: &mut Context) {}
+
+    // Function to summarise the records of the whole analysis; nothing by default
+    fn summary(&self, _records: &[Record]) -> Option<serde_json::Value> {
+        None
+    }
+}
+
+// A pattern that binds variables, other than the pattern of a `let` statement
+#[derive(Clone, Copy)]
+pub enum Pattern<'a> {
+    ForLoop(&'a syn::ExprForLoop), // `for pat in expr`
+    IfLet(&'a syn::ExprLet),       // `if let pat = expr`
+    WhileLet(&'a syn::ExprLet),    // `while let pat = expr`
+    ClosureParam(&'a syn::Pat),    // `|pat| ...`, called in the closure's scope
+}
+
+impl<'a> Pattern<'a> {
+    pub fn pat(&self) -> &'a syn::Pat {
+        match self {
+            Pattern::ForLoop(for_loop) => &for_loop.pat,
+            Pattern::IfLet(expr_let) | Pattern::WhileLet(expr_let) => &expr_let.pat,
+            Pattern::ClosureParam(pat) => pat,
+        }
+    }
+
+    // The expression whose value the pattern matches (the iterator of a `for` loop)
+    pub fn matched(&self) -> Option<&'a syn::Expr> {
+        match self {
+            Pattern::ForLoop(for_loop) => Some(&for_loop.expr),
+            Pattern::IfLet(expr_let) | Pattern::WhileLet(expr_let) => Some(&expr_let.expr),
+            Pattern::ClosureParam(_) => None,
+        }
+    }
+}
+
+// Something a collector found
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Record {
+    pub collector: String,
+    pub name: String,
+    #[serde(rename = "file")]
+    pub file_path: PathBuf,
+    #[serde(rename = "line")]
+    pub line_number: usize,
+    pub data: serde_json::Value, // Whatever else the collector wants to report
+}
+
+// Where the walk is
+#[derive(Clone, Copy)]
+pub(crate) struct Position<'a> {
+    pub file_path: &'a Path,
+    pub lines: &'a LineIndex<'a>, // Line table of the source the file was parsed from
+    pub module_path: &'a str,
+    pub scope: &'a str,
+    pub code_class: &'static str,
+    pub block_depth: usize,
+}
+
+// Where the walk is when a collector is called, and where its records go
+pub struct Context<'a> {
+    collector: &'a str,
+    position: Position<'a>,
+    results: &'a mut AnalysisResults,
+}
+
+impl<'a> Context<'a> {
+    pub(crate) fn new(
+        collector: &'a str,
+        position: Position<'a>,
+        results: &'a mut AnalysisResults,
+    ) -> Self {
+        Context {
+            collector,
+            position,
+            results,
+        }
+    }
+
+    pub fn file_path(&self) -> &Path {
+        self.position.file_path
+    }
+
+    // Logical path of the module, e.g. `crate::parser`
+    pub fn module_path(&self) -> &str {
+        self.position.module_path
+    }
+
+    // Logical path of the innermost function, closure or module, e.g. `crate::parser::parse`
+    pub fn scope(&self) -> &str {
+        self.position.scope
+    }
+
+    // Logical path of an item declared in the module, e.g. `crate::parser::Lexer`
+    pub fn item_path(&self, name: &impl std::fmt::Display) -> String {
+        if self.position.module_path.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}::{}", self.position.module_path, name)
+        }
+    }
+
+    // "test", "example", "bench" or "production"; empty when the code is classified by line
+    // once the file is analysed
+    pub fn code_class(&self) -> &str {
+        self.position.code_class
+    }
+
+    // Blocks around the code; function bodies are 1
+    pub fn block_depth(&self) -> usize {
+        self.position.block_depth
+    }
+
+    // Function to find the line of the start of a span, e.g. `context.line_of(item.span())`
+    pub fn line_of(&self, span: proc_macro2::Span) -> usize {
+        self.position.lines.line_of(span.byte_range().start)
+    }
+
+    // Function to find the column of the start of a span, counting from 1 as editors do
+    pub fn column_of(&self, span: proc_macro2::Span) -> usize {
+        self.position.lines.column_of(span.byte_range().start)
+    }
+
+    // Function to get the text of a source line, without its line ending
+    pub fn source_line(&self, line_number: usize) -> Option<&str> {
+        self.position.lines.line(line_number)
+    }
+
+    // The results of the file, where the built-in collectors add variables and data structures
+    pub(crate) fn results(&mut self) -> &mut AnalysisResults {
+        self.results
+    }
+
+    // Function to keep a record, e.g. `context.record("parse", context.line_of(span), json!({}))`
+    pub fn record(&mut self, name: impl Into<String>, line_number: usize, data: serde_json::Value) {
+        self.results.collected.push(Record {
+            collector: self.collector.to_string(),
+            name: name.into(),
+            file_path: self.position.file_path.to_path_buf(),
+            line_number,
+            data,
+        });
+    }
+}
+
+// Function to parse the arguments of a macro invocation such as `println!("{}", count)` as
+// comma-separated expressions; syn leaves them as tokens, so visitors that look inside
+// macro calls parse them here. Macros whose arguments are not expressions give None.
+pub fn macro_args(mac: &syn::Macro) -> Option<Punctuated<syn::Expr, Comma>> {
+    mac.parse_body_with(Punctuated::<syn::Expr, Comma>::parse_terminated)
+        .ok()
+}
+
+// Function to summarise the records of each collector once the analysis is complete
+pub fn summarize(
+    collectors: &[Arc<dyn Collector>],
+    records: &[Record],
+) -> BTreeMap<String, serde_json::Value> {
+    collectors
+        .iter()
+        .filter_map(|collector| {
+            let own: Vec<Record> = records
+                .iter()
+                .filter(|record| record.collector == collector.name())
+                .cloned()
+                .collect();
+            collector
+                .summary(&own)
+                .map(|summary| (collector.name().to_string(), summary))
+        })
+        .collect()
+}
+
+// Function to build the `collectors` section of the report: the summary and the records
+// of each collector
+pub fn section(
+    records: &[Record],
+    summaries: &BTreeMap<String, serde_json::Value>,
+) -> serde_json::Value {
+    let mut by_collector: BTreeMap<&str, Vec<&Record>> = BTreeMap::new();
+    for record in records {
+        by_collector
+            .entry(&record.collector)
+            .or_default()
+            .push(record);
+    }
+    for name in summaries.keys() {
+        by_collector.entry(name).or_default();
+    }
+    by_collector
+        .into_iter()
+        .map(|(name, records)| {
+            (
+                name.to_string(),
+                serde_json::json!({
+                    "summary": summaries.get(name),
+                    "records": records
+                }),
+            )
+        })
+        .collect::<serde_json::Map<String, serde_json::Value>>()
+        .into()
+}
@@ -0,0 +1,104 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Column selection for the variable listings (`--columns`).
+// The CSV variables table always uses these columns, all of them by default. The console
+// and text listings print their usual one-line descriptions unless `--columns` is given,
+// in which case they print an aligned table of the chosen columns in the chosen order.
+
+use crate::{DataStructureInfo, VarInfo};
+use std::io::{self, Write};
+
+// Names of the variable columns, in their default order
+pub const COLUMNS: &[&str] = &[
+    "mutability",
+    "name",
+    "file",
+    "line",
+    "column",
+    "context",
+    "kind",
+    "type",
+    "basic_type",
+    "scope",
+    "reads",
+    "writes",
+];
+
+// Function to give the default columns, used when --columns is not given
+pub fn default_columns() -> Vec<String> {
+    COLUMNS.iter().map(|column| column.to_string()).collect()
+}
+
+// Function to give the value of a variable in the named column
+pub fn value(var: &VarInfo, column: &str) -> String {
+    match column {
+        "mutability" => if var.mutable { "mutable" } else { "immutable" }.to_string(),
+        "name" => var.name.clone(),
+        "file" => var.file_path.display().to_string(),
+        "line" => var.line_number.to_string(),
+        "column" => var.column.to_string(),
+        "context" => var.context.trim().to_string(),
+        "kind" => var.var_kind.to_string(),
+        "type" => var.var_type.to_string(),
+        "basic_type" => var.basic_type.to_string(),
+        "scope" => var.scope.to_string(),
+        "reads" => var.read_count.to_string(),
+        "writes" => var.write_count.to_string(),
+        _ => String::new(),
+    }
+}
+
+// Function to give the value of a data structure in the named column, for tables that mix
+// variables and data structures; columns that only apply to variables are empty
+pub fn structure_value(data_structure: &DataStructureInfo, column: &str) -> String {
+    match column {
+        "name" => data_structure.display_name().to_string(),
+        "file" => data_structure.file_path.display().to_string(),
+        "line" => data_structure.line_number.to_string(),
+        "column" => data_structure.column.to_string(),
+        "type" => data_structure.aliased_type.clone().unwrap_or_default(),
+        _ => String::new(),
+    }
+}
+
+// Function to write variables as an aligned table of the chosen columns
+// With `link`, a column with the vscode link is added at the end
+pub fn write_table<'a>(
+    out: &mut impl Write,
+    indent: &str,
+    vars: impl IntoIterator<Item = &'a VarInfo>,
+    columns: &[String],
+    link: bool,
+) -> io::Result<()> {
+    let mut header: Vec<String> = columns.to_vec();
+    if link {
+        header.push("vscode_link".to_string());
+    }
+    let rows: Vec<Vec<String>> = vars
+        .into_iter()
+        .map(|var| {
+            let mut row: Vec<String> = columns.iter().map(|column| value(var, column)).collect();
+            if link {
+                row.push(var.editor_link());
+            }
+            row
+        })
+        .collect();
+    let widths: Vec<usize> = (0..header.len())
+        .map(|index| {
+            rows.iter()
+                .map(|row| row[index].chars().count())
+                .chain([header[index].len()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    for row in std::iter::once(&header).chain(&rows) {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, &width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        writeln!(out, "{}{}", indent, cells.join("  ").trim_end())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,206 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Function complexity metrics.
+// Each function and method is measured by its length in lines, its parameter count, the
+// deepest nesting of control flow in its body and the number of branches (`if`, `match`,
+// `loop`, `while` and `for`). The cyclomatic complexity is estimated as one plus the
+// decision points: each `if`, loop, extra match arm, `&&`, `||` and `?`. Closures count
+// towards the function that contains them; nested functions are measured on their own.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+
+// Number of functions listed as most complex
+const TOP_FUNCTIONS: usize = 10;
+
+// Complexity metrics of a single function
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct FunctionMetrics {
+    pub function: String, // Path of the function, e.g. crate::parser::Parser::parse
+    #[serde(rename = "file")]
+    pub file_path: PathBuf,
+    #[serde(rename = "line")]
+    pub line_number: usize,
+    pub lines: usize,
+    pub params: usize, // Including self
+    pub nesting_depth: usize,
+    pub branches: usize,
+    pub cyclomatic: usize,
+}
+
+// Visitor that measures the functions of a file
+struct ComplexityVisitor<'a> {
+    file_path: &'a Path,
+    scope: String,
+    current: Option<usize>, // Index of the function being measured
+    depth: usize,           // Control-flow nesting at the current point of its body
+    functions: &'a mut Vec<FunctionMetrics>,
+}
+
+impl ComplexityVisitor<'_> {
+    fn count(&mut self, branches: usize, decisions: usize) {
+        if let Some(function) = self.current.map(|index| &mut self.functions[index]) {
+            function.branches += branches;
+            function.cyclomatic += decisions;
+        }
+    }
+
+    // Visit a body one level of control flow deeper
+    fn nested(&mut self, visit: impl FnOnce(&mut Self)) {
+        self.depth += 1;
+        if let Some(function) = self.current.map(|index| &mut self.functions[index]) {
+            function.nesting_depth = function.nesting_depth.max(self.depth);
+        }
+        visit(self);
+        self.depth -= 1;
+    }
+
+    fn visit_in_scope(&mut self, name: &impl std::fmt::Display, visit: impl FnOnce(&mut Self)) {
+        let inner_scope = format!("{}::{}", self.scope, name);
+        let outer_scope = std::mem::replace(&mut self.scope, inner_scope);
+        visit(self);
+        self.scope = outer_scope;
+    }
+
+    fn measure(&mut self, sig: &syn::Signature, body: &syn::Block, visit: impl FnOnce(&mut Self)) {
+        let start = sig.span().start().line;
+        let end = body.brace_token.span.close().end().line;
+        self.visit_in_scope(&sig.ident, |visitor| {
+            visitor.functions.push(FunctionMetrics {
+                function: visitor.scope.clone(),
+                file_path: visitor.file_path.to_path_buf(),
+                line_number: start,
+                lines: end.saturating_sub(start) + 1,
+                params: sig.inputs.len(),
+                cyclomatic: 1,
+                ..FunctionMetrics::default()
+            });
+            let outer = visitor.current.replace(visitor.functions.len() - 1);
+            let outer_depth = std::mem::replace(&mut visitor.depth, 0);
+            visit(visitor);
+            visitor.current = outer;
+            visitor.depth = outer_depth;
+        });
+    }
+}
+
+impl<'ast> Visit<'ast> for ComplexityVisitor<'_> {
+    fn visit_item_fn(&mut self, item_fn: &'ast syn::ItemFn) {
+        self.measure(&item_fn.sig, &item_fn.block, |visitor| {
+            visit::visit_item_fn(visitor, item_fn)
+        });
+    }
+
+    fn visit_impl_item_fn(&mut self, method: &'ast syn::ImplItemFn) {
+        self.measure(&method.sig, &method.block, |visitor| {
+            visit::visit_impl_item_fn(visitor, method)
+        });
+    }
+
+    fn visit_trait_item_fn(&mut self, method: &'ast syn::TraitItemFn) {
+        match method.default {
+            Some(ref body) => self.measure(&method.sig, body, |visitor| {
+                visit::visit_trait_item_fn(visitor, method)
+            }),
+            None => visit::visit_trait_item_fn(self, method),
+        }
+    }
+
+    fn visit_item_impl(&mut self, item_impl: &'ast syn::ItemImpl) {
+        let name = crate::impl_blocks::type_name(&item_impl.self_ty);
+        self.visit_in_scope(&name, |visitor| visit::visit_item_impl(visitor, item_impl));
+    }
+
+    fn visit_item_trait(&mut self, item_trait: &'ast syn::ItemTrait) {
+        self.visit_in_scope(&item_trait.ident, |visitor| {
+            visit::visit_item_trait(visitor, item_trait)
+        });
+    }
+
+    fn visit_item_mod(&mut self, item_mod: &'ast syn::ItemMod) {
+        self.visit_in_scope(&item_mod.ident, |visitor| {
+            visit::visit_item_mod(visitor, item_mod)
+        });
+    }
+
+    // `else if` continues the chain at the same depth rather than nesting
+    fn visit_expr_if(&mut self, expr_if: &'ast syn::ExprIf) {
+        self.count(1, 1);
+        self.visit_expr(&expr_if.cond);
+        self.nested(|visitor| visitor.visit_block(&expr_if.then_branch));
+        if let Some((_, ref else_branch)) = expr_if.else_branch {
+            match **else_branch {
+                syn::Expr::If(ref else_if) => self.visit_expr_if(else_if),
+                ref other => self.nested(|visitor| visitor.visit_expr(other)),
+            }
+        }
+    }
+
+    fn visit_expr_match(&mut self, expr_match: &'ast syn::ExprMatch) {
+        self.count(1, expr_match.arms.len().saturating_sub(1));
+        self.visit_expr(&expr_match.expr);
+        self.nested(|visitor| {
+            for arm in &expr_match.arms {
+                visitor.visit_arm(arm);
+            }
+        });
+    }
+
+    fn visit_expr_loop(&mut self, expr_loop: &'ast syn::ExprLoop) {
+        self.count(1, 1);
+        self.nested(|visitor| visit::visit_expr_loop(visitor, expr_loop));
+    }
+
+    fn visit_expr_while(&mut self, expr_while: &'ast syn::ExprWhile) {
+        self.count(1, 1);
+        self.nested(|visitor| visit::visit_expr_while(visitor, expr_while));
+    }
+
+    fn visit_expr_for_loop(&mut self, expr_for: &'ast syn::ExprForLoop) {
+        self.count(1, 1);
+        self.nested(|visitor| visit::visit_expr_for_loop(visitor, expr_for));
+    }
+
+    fn visit_expr_binary(&mut self, binary: &'ast syn::ExprBinary) {
+        if matches!(binary.op, syn::BinOp::And(_) | syn::BinOp::Or(_)) {
+            self.count(0, 1);
+        }
+        visit::visit_expr_binary(self, binary);
+    }
+
+    fn visit_expr_try(&mut self, expr_try: &'ast syn::ExprTry) {
+        self.count(0, 1);
+        visit::visit_expr_try(self, expr_try);
+    }
+}
+
+// Function to measure the functions of a parsed file
+pub fn collect_function_metrics(
+    file_ast: &syn::File,
+    file_path: &Path,
+    module_path: &str,
+    functions: &mut Vec<FunctionMetrics>,
+) {
+    let mut visitor = ComplexityVisitor {
+        file_path,
+        scope: module_path.to_string(),
+        current: None,
+        depth: 0,
+        functions,
+    };
+    visitor.visit_file(file_ast);
+}
+
+// Function to rank functions by cyclomatic complexity, most complex first
+pub fn most_complex(functions: &[FunctionMetrics]) -> Vec<&FunctionMetrics> {
+    let mut ranked: Vec<&FunctionMetrics> = functions.iter().collect();
+    ranked.sort_by(|a, b| {
+        b.cyclomatic
+            .cmp(&a.cyclomatic)
+            .then_with(|| b.nesting_depth.cmp(&a.nesting_depth))
+            .then_with(|| a.function.cmp(&b.function))
+    });
+    ranked.truncate(TOP_FUNCTIONS);
+    ranked
+}
@@ -0,0 +1,75 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Source lines around variable declarations (`--context-lines N`).
+// The window is read from the file after the analysis, so cached results get it too. It
+// holds up to N lines before and after the declaration, fewer at the start and end of a
+// file; variables whose line is not in the file, e.g. in doc tests, get no window.
+
+use crate::VarInfo;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+// Lines around a declaration
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ContextWindow {
+    /// Line number of the first line of the window
+    pub first_line: usize,
+    /// The lines of the window, including the declaration line
+    pub lines: Vec<String>,
+}
+
+impl ContextWindow {
+    // The window as one block of source text
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    // The lines prefixed with their line numbers, the declaration line marked with `>`
+    pub fn numbered(&self, declaration_line: usize) -> Vec<String> {
+        let width = (self.first_line + self.lines.len()).to_string().len();
+        self.lines
+            .iter()
+            .enumerate()
+            .map(|(offset, line)| {
+                let line_number = self.first_line + offset;
+                format!(
+                    "{} {:>width$} | {}",
+                    if line_number == declaration_line {
+                        ">"
+                    } else {
+                        " "
+                    },
+                    line_number,
+                    line,
+                    width = width
+                )
+            })
+            .collect()
+    }
+}
+
+// Function to attach the lines around the declaration to each variable
+// Each file is read once; files that cannot be read leave their variables without a window
+pub fn add_context_windows<'a>(vars: impl Iterator<Item = &'a mut VarInfo>, lines_around: usize) {
+    let mut files: HashMap<PathBuf, Option<Vec<String>>> = HashMap::new();
+    for var in vars {
+        let lines = files.entry(var.file_path.to_path_buf()).or_insert_with(|| {
+            std::fs::read_to_string(&var.file_path)
+                .ok()
+                .map(|content| content.lines().map(str::to_string).collect())
+        });
+        let Some(lines) = lines else {
+            continue;
+        };
+        if var.line_number == 0 || var.line_number > lines.len() {
+            continue;
+        }
+        let first = var.line_number.saturating_sub(lines_around).max(1);
+        let last = (var.line_number + lines_around).min(lines.len());
+        var.context_window = Some(ContextWindow {
+            first_line: first,
+            lines: lines[first - 1..last].to_vec(),
+        });
+    }
+}
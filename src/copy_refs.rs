@@ -0,0 +1,149 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Copy-type reference heuristic.
+// Parameters and bindings of small Copy types (integers, floats, bool, char and small
+// tuples of those) that are held as shared references are flagged, as passing them by
+// value is as cheap as passing the reference and avoids the indirection.
+// Methods of trait impls are skipped, since their signatures are dictated by the trait.
+
+use quote::ToTokens;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+
+// Tuples larger than this (in bytes) are no longer considered small
+const MAX_TUPLE_SIZE: usize = 16;
+
+// A parameter or binding that could be passed by value
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CopyRefFinding {
+    pub name: String,
+    pub kind: String, // "parameter" or "let binding"
+    #[serde(rename = "type")]
+    pub type_name: String, // The referenced type, e.g. &u32
+    pub function: String,
+    #[serde(rename = "file")]
+    pub file_path: PathBuf,
+    #[serde(rename = "line")]
+    pub line_number: usize,
+}
+
+// Function to get the size of a small Copy type, or None for any other type
+fn copy_type_size(ty: &syn::Type) -> Option<usize> {
+    match ty {
+        syn::Type::Path(type_path) if type_path.qself.is_none() => {
+            let ident = type_path.path.get_ident()?.to_string();
+            match ident.as_str() {
+                "u8" | "i8" | "bool" => Some(1),
+                "u16" | "i16" => Some(2),
+                "u32" | "i32" | "f32" | "char" => Some(4),
+                "u64" | "i64" | "f64" => Some(8),
+                "usize" | "isize" => Some(std::mem::size_of::<usize>()),
+                "u128" | "i128" => Some(16),
+                _ => None,
+            }
+        }
+        syn::Type::Tuple(tuple) if !tuple.elems.is_empty() => {
+            let size = tuple
+                .elems
+                .iter()
+                .map(copy_type_size)
+                .sum::<Option<usize>>()?;
+            (size <= MAX_TUPLE_SIZE).then_some(size)
+        }
+        syn::Type::Paren(paren) => copy_type_size(&paren.elem),
+        _ => None,
+    }
+}
+
+// Function to check for a shared reference to a small Copy type
+fn is_copy_reference(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Reference(reference) => {
+            reference.mutability.is_none() && copy_type_size(&reference.elem).is_some()
+        }
+        _ => false,
+    }
+}
+
+// Visitor that checks function signatures and typed let bindings
+struct CopyRefVisitor<'a> {
+    file_path: &'a Path,
+    function: String,
+    in_trait_impl: bool,
+    findings: &'a mut Vec<CopyRefFinding>,
+}
+
+impl CopyRefVisitor<'_> {
+    fn push(&mut self, name: String, kind: &str, ty: &syn::Type) {
+        self.findings.push(CopyRefFinding {
+            name,
+            kind: kind.to_string(),
+            type_name: ty.to_token_stream().to_string().replace(' ', ""),
+            function: self.function.clone(),
+            file_path: self.file_path.to_path_buf(),
+            line_number: ty.span().start().line,
+        });
+    }
+
+    fn check_signature(&mut self, sig: &syn::Signature) {
+        for input in &sig.inputs {
+            if let syn::FnArg::Typed(pat_type) = input {
+                if is_copy_reference(&pat_type.ty) {
+                    let name = pat_type.pat.to_token_stream().to_string();
+                    self.push(name, "parameter", &pat_type.ty);
+                }
+            }
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for CopyRefVisitor<'_> {
+    fn visit_item_fn(&mut self, item_fn: &'ast syn::ItemFn) {
+        let previous = std::mem::replace(&mut self.function, item_fn.sig.ident.to_string());
+        self.check_signature(&item_fn.sig);
+        visit::visit_item_fn(self, item_fn);
+        self.function = previous;
+    }
+
+    fn visit_item_impl(&mut self, item_impl: &'ast syn::ItemImpl) {
+        let previous = self.in_trait_impl;
+        self.in_trait_impl = item_impl.trait_.is_some();
+        visit::visit_item_impl(self, item_impl);
+        self.in_trait_impl = previous;
+    }
+
+    fn visit_impl_item_fn(&mut self, method: &'ast syn::ImplItemFn) {
+        let previous = std::mem::replace(&mut self.function, method.sig.ident.to_string());
+        if !self.in_trait_impl {
+            self.check_signature(&method.sig);
+        }
+        visit::visit_impl_item_fn(self, method);
+        self.function = previous;
+    }
+
+    fn visit_local(&mut self, local: &'ast syn::Local) {
+        if let syn::Pat::Type(ref pat_type) = local.pat {
+            if is_copy_reference(&pat_type.ty) {
+                let name = pat_type.pat.to_token_stream().to_string();
+                self.push(name, "let binding", &pat_type.ty);
+            }
+        }
+        visit::visit_local(self, local);
+    }
+}
+
+// Function to collect the Copy-type references of a parsed file
+pub fn collect_copy_refs(
+    file_ast: &syn::File,
+    file_path: &Path,
+    findings: &mut Vec<CopyRefFinding>,
+) {
+    let mut visitor = CopyRefVisitor {
+        file_path,
+        function: String::new(),
+        in_trait_impl: false,
+        findings,
+    };
+    visitor.visit_file(file_ast);
+}
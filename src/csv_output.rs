@@ -0,0 +1,375 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// CSV output (`--format csv`).
+// Each table goes to its own file so that every file is a single well-formed table: the
+// variables go to the output file itself and the other tables to siblings named after it,
+// e.g. `out.data_structures.csv`. `--csv-single-table` instead writes variables and data
+// structures as one table with a `record` column. Quoting follows RFC 4180, so commas,
+// quotes and newlines in code snippets survive, and `--delimiter` selects e.g. TSV.
+// `--columns` chooses and orders the columns of the variables. With `--group-by`, the
+// output file holds the aggregated counts instead of the variables.
+
+use crate::{
+    columns, grouping, links, struct_layout, AnalysisMetadata, AnalysisResults, ForestError,
+    VarInfo,
+};
+use std::path::{Path, PathBuf};
+
+// Settings of the CSV writer
+pub struct CsvOptions {
+    pub delimiter: u8,
+    pub single_table: bool, // Variables and data structures in one table, in one file
+}
+
+// Function to parse a --delimiter value: a single ASCII character, or `tab` / `\t`
+pub fn parse_delimiter(value: &str) -> Result<u8, String> {
+    match value {
+        "tab" | "\\t" | "\t" => Ok(b'\t'),
+        _ if value.len() == 1 && value.is_ascii() => Ok(value.as_bytes()[0]),
+        _ => Err(format!(
+            "expected a single ASCII character or `tab`, got `{}`",
+            value
+        )),
+    }
+}
+
+// Function to name the file of a table next to the output file
+// `out.csv` and "fields" give `out.fields.csv`
+pub fn table_path(output: &Path, table: &str) -> PathBuf {
+    let stem = output
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let file_name = match output.extension() {
+        Some(extension) => format!("{}.{}.{}", stem, table, extension.to_string_lossy()),
+        None => format!("{}.{}", stem, table),
+    };
+    output.with_file_name(file_name)
+}
+
+// A CSV file being written, named in the errors of its writes
+struct Table {
+    writer: csv::Writer<std::fs::File>,
+    path: PathBuf,
+}
+
+impl Table {
+    fn error(&self, error: impl std::fmt::Display) -> ForestError {
+        ForestError::output("csv", &self.path, error)
+    }
+
+    fn write_record<I, T>(&mut self, record: I) -> Result<(), ForestError>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<[u8]>,
+    {
+        self.writer.write_record(record).map_err(|e| self.error(e))
+    }
+
+    fn flush(&mut self) -> Result<(), ForestError> {
+        self.writer.flush().map_err(|e| self.error(e))
+    }
+}
+
+// Function to open a CSV table with the chosen delimiter
+fn writer(path: &Path, options: &CsvOptions) -> Result<Table, ForestError> {
+    let writer = csv::WriterBuilder::new()
+        .delimiter(options.delimiter)
+        .from_path(path)
+        .map_err(|e| ForestError::output("csv", path, e))?;
+    Ok(Table {
+        writer,
+        path: path.to_path_buf(),
+    })
+}
+
+// Function to describe a variable as a row of the chosen columns
+// With --context-lines, the context column holds the lines around the declaration
+fn var_row(var: &VarInfo, columns: &[&str]) -> Vec<String> {
+    columns
+        .iter()
+        .map(|&column| match (column, &var.context_window) {
+            ("context", Some(window)) => window.text(),
+            _ => columns::value(var, column),
+        })
+        .collect()
+}
+
+// Function to write variables and data structures as one table with a `record` column
+// The record column holds the mutability of variables and the kind of data structures
+fn write_single_table(
+    results: &AnalysisResults,
+    path: &Path,
+    options: &CsvOptions,
+    columns: &[&str],
+    link: bool,
+) -> Result<(), ForestError> {
+    let columns: Vec<&str> = columns
+        .iter()
+        .copied()
+        .filter(|&column| column != "mutability")
+        .collect();
+    let mut table = writer(path, options)?;
+    let mut header = vec!["record"];
+    header.extend(&columns);
+    header.push("visibility");
+    if link {
+        header.push("vscode_link");
+    }
+    table.write_record(&header)?;
+    for var in results.mutable_vars.iter().chain(&results.immutable_vars) {
+        let mut row = vec![columns::value(var, "mutability")];
+        row.extend(var_row(var, &columns));
+        row.push(String::new());
+        if link {
+            row.push(var.editor_link());
+        }
+        table.write_record(&row)?;
+    }
+    for data_structure in &results.data_structures {
+        let mut row = vec![data_structure.data_structure_type.clone()];
+        row.extend(
+            columns
+                .iter()
+                .map(|column| columns::structure_value(data_structure, column)),
+        );
+        row.push(data_structure.visibility.clone());
+        if link {
+            row.push(data_structure.editor_link());
+        }
+        table.write_record(&row)?;
+    }
+    table.flush()?;
+    Ok(())
+}
+
+// Function to write the counts of --group-by as a table
+fn write_groups(
+    results: &AnalysisResults,
+    path: &Path,
+    options: &CsvOptions,
+    by: &str,
+) -> Result<(), ForestError> {
+    let groups = grouping::group(results, by);
+    let with_structures = groups.iter().any(|group| group.data_structures.is_some());
+    let mut table = writer(path, options)?;
+    let mut header = vec![by, "mutable_variables", "immutable_variables"];
+    if with_structures {
+        header.push("data_structures");
+    }
+    table.write_record(&header)?;
+    for group in &groups {
+        let mut row = vec![
+            group.key.clone(),
+            group.mutable_variables.to_string(),
+            group.immutable_variables.to_string(),
+        ];
+        if let Some(data_structures) = group.data_structures {
+            row.push(data_structures.to_string());
+        }
+        table.write_record(&row)?;
+    }
+    table.flush()?;
+    Ok(())
+}
+
+// Function to output results in CSV format
+pub fn output_csv(
+    results: &AnalysisResults,
+    metadata: &AnalysisMetadata,
+    file: &str,
+    options: &CsvOptions,
+    columns: &[String],
+    group_by: Option<&str>,
+    link: bool,
+) -> Result<(), ForestError> {
+    let columns: Vec<&str> = columns.iter().map(String::as_str).collect();
+    let output = Path::new(file);
+    match group_by {
+        Some(by) if options.single_table => return write_groups(results, output, options, by),
+        Some(by) => write_groups(results, output, options, by)?,
+        None if options.single_table => {
+            return write_single_table(results, output, options, &columns, link)
+        }
+        None => {
+            // Variables
+            let mut table = writer(output, options)?;
+            let mut header = columns.clone();
+            if link {
+                header.push("vscode_link");
+            }
+            table.write_record(&header)?;
+            for var in results.mutable_vars.iter().chain(&results.immutable_vars) {
+                let mut row = var_row(var, &columns);
+                if link {
+                    row.push(var.editor_link());
+                }
+                table.write_record(&row)?;
+            }
+            table.flush()?;
+        }
+    }
+
+    // Analysis metadata
+    let mut table = writer(&table_path(output, "metadata"), options)?;
+    table.write_record(["key", "value"])?;
+    table.write_record(["project_name", &metadata.project_name])?;
+    table.write_record(["version", &metadata.version])?;
+    table.write_record(["analysis_run_at", &metadata.datetime])?;
+    table.flush()?;
+
+    // Data structures
+    let mut table = writer(&table_path(output, "data_structures"), options)?;
+    let mut header = vec!["type", "name", "file", "line", "column", "visibility"];
+    if link {
+        header.push("vscode_link");
+    }
+    table.write_record(&header)?;
+    for data_structure in &results.data_structures {
+        let mut row = vec![
+            data_structure.data_structure_type.clone(),
+            data_structure.name.clone(),
+            data_structure.file_path.display().to_string(),
+            data_structure.line_number.to_string(),
+            data_structure.column.to_string(),
+            data_structure.visibility.clone(),
+        ];
+        if link {
+            row.push(data_structure.editor_link());
+        }
+        table.write_record(&row)?;
+    }
+    table.flush()?;
+
+    // Struct fields, one row per field
+    let mut table = writer(&table_path(output, "fields"), options)?;
+    table.write_record([
+        "struct",
+        "field",
+        "type",
+        "visibility",
+        "shape",
+        "file",
+        "line",
+    ])?;
+    for data_structure in &results.data_structures {
+        for field in &data_structure.fields {
+            table.write_record([
+                data_structure.display_name(),
+                &field.name,
+                &field.type_name,
+                &field.visibility,
+                &field.shape,
+                &data_structure.file_path.display().to_string(),
+                &data_structure.line_number.to_string(),
+            ])?;
+        }
+    }
+    table.flush()?;
+
+    // Enum variants, one row per variant
+    let mut table = writer(&table_path(output, "variants"), options)?;
+    table.write_record([
+        "enum",
+        "variant",
+        "kind",
+        "payload",
+        "discriminant",
+        "file",
+        "line",
+    ])?;
+    for data_structure in &results.data_structures {
+        for variant in &data_structure.variants {
+            let payload: Vec<&str> = variant
+                .payload
+                .iter()
+                .map(|field| field.type_name.as_str())
+                .collect();
+            table.write_record([
+                data_structure.display_name(),
+                &variant.name,
+                &variant.kind,
+                &payload.join("; "),
+                variant.discriminant.as_deref().unwrap_or_default(),
+                &data_structure.file_path.display().to_string(),
+                &data_structure.line_number.to_string(),
+            ])?;
+        }
+    }
+    table.flush()?;
+
+    // Line counts, one row per file
+    let mut table = writer(&table_path(output, "lines"), options)?;
+    table.write_record(["file", "module", "total", "code", "comment", "blank"])?;
+    for file_lines in &results.file_lines {
+        let lines = file_lines.lines;
+        table.write_record([
+            file_lines.file_path.display().to_string(),
+            file_lines.module.clone(),
+            lines.total.to_string(),
+            lines.code.to_string(),
+            lines.comment.to_string(),
+            lines.blank.to_string(),
+        ])?;
+    }
+    table.flush()?;
+
+    // Estimated struct layouts, largest first
+    let mut table = writer(&table_path(output, "struct_layout"), options)?;
+    table.write_record([
+        "struct",
+        "estimated_size",
+        "estimated_align",
+        "heap_fields",
+        "heap_heavy",
+        "file",
+        "line",
+    ])?;
+    for layout in struct_layout::estimate_layouts(&results.data_structures) {
+        table.write_record([
+            layout.structure.display_name().to_string(),
+            layout.size.to_string(),
+            layout.align.to_string(),
+            layout.heap_fields.to_string(),
+            layout.heap_heavy().to_string(),
+            layout.structure.file_path.display().to_string(),
+            layout.structure.line_number.to_string(),
+        ])?;
+    }
+    table.flush()?;
+
+    // Constants and statics
+    let mut table = writer(&table_path(output, "globals"), options)?;
+    let mut header = vec![
+        "kind",
+        "name",
+        "type",
+        "value",
+        "visibility",
+        "mutable",
+        "file",
+        "line",
+    ];
+    if link {
+        header.push("vscode_link");
+    }
+    table.write_record(&header)?;
+    for global in &results.globals {
+        let mut row = vec![
+            global.kind.clone(),
+            global.name.clone(),
+            global.type_name.clone(),
+            global.value.clone(),
+            global.visibility.clone(),
+            global.mutable.to_string(),
+            global.file_path.display().to_string(),
+            global.line_number.to_string(),
+        ];
+        if link {
+            row.push(links::editor_link(&global.file_path, global.line_number));
+        }
+        table.write_record(&row)?;
+    }
+    table.flush()?;
+
+    Ok(())
+}
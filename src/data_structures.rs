@@ -0,0 +1,69 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// The built-in collector of data structures.
+// Functions, structs, enums, modules, type aliases and traits are listed with their logical
+// path, visibility and position, and with what is specific to their kind: the fields of a
+// struct, the variants of an enum, the signature of a function, the aliased type and whether
+// a trait has default methods. Methods are listed by impl_blocks.rs instead.
+
+use crate::collector::{Collector, Context};
+use crate::{fields, globals, signatures, DataStructureInfo};
+
+pub struct DataStructures;
+
+impl Collector for DataStructures {
+    fn name(&self) -> &str {
+        "data_structures"
+    }
+
+    fn item(&self, item: &syn::Item, context: &mut Context) {
+        let (ident, vis, kind) = match item {
+            syn::Item::Fn(item_fn) => (&item_fn.sig.ident, &item_fn.vis, "function"),
+            syn::Item::Struct(item_struct) => (&item_struct.ident, &item_struct.vis, "struct"),
+            syn::Item::Enum(item_enum) => (&item_enum.ident, &item_enum.vis, "enum"),
+            syn::Item::Mod(item_mod) => (&item_mod.ident, &item_mod.vis, "module"),
+            syn::Item::Type(item_type) => (&item_type.ident, &item_type.vis, "type alias"),
+            syn::Item::Trait(item_trait) => (&item_trait.ident, &item_trait.vis, "trait"),
+            _ => return,
+        };
+
+        let mut data_structure = DataStructureInfo {
+            name: ident.to_string(),
+            data_structure_type: kind.to_string(),
+            file_path: context.file_path().to_path_buf(),
+            line_number: context.line_of(ident.span()),
+            column: context.column_of(ident.span()),
+            rustdoc: None,
+            macro_body: None,
+            has_default_methods: None,
+            item_path: context.item_path(ident),
+            fields: Vec::new(),
+            variants: Vec::new(),
+            visibility: globals::visibility_name(vis),
+            aliased_type: None,
+            code_class: context.code_class().to_string(),
+            patterns: Vec::new(),
+            signature: None,
+        };
+        match item {
+            syn::Item::Fn(item_fn) => {
+                data_structure.signature = Some(signatures::signature_of(&item_fn.sig));
+            }
+            syn::Item::Struct(item_struct) => {
+                data_structure.fields = fields::collect_fields(&item_struct.fields);
+            }
+            syn::Item::Enum(item_enum) => {
+                data_structure.variants = fields::collect_variants(item_enum);
+            }
+            syn::Item::Type(item_type) => {
+                data_structure.aliased_type = Some(globals::type_text(&item_type.ty));
+            }
+            syn::Item::Trait(item_trait) => {
+                data_structure.has_default_methods = Some(item_trait.items.iter().any(
+                    |item| matches!(item, syn::TraitItem::Fn(method) if method.default.is_some()),
+                ));
+            }
+            _ => {}
+        }
+        context.results().data_structures.push(data_structure);
+    }
+}
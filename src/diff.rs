@@ -0,0 +1,361 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// `forest diff`: compare a JSON report with another report or with a fresh analysis.
+// Variables are matched by file, scope and name, and data structures by file, kind and
+// path, so records that only moved to another line are unchanged. Shadowed variables with
+// the same name are matched in order. A matched variable has changed when its mutability
+// or type changed, and a matched data structure when its visibility, fields, variants,
+// signature or aliased type changed. Paths under the workspace root are compared relative
+// to it; reports written with `--paths relative` compare best.
+
+use crate::ForestError;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+
+// A variable of a report
+#[derive(Deserialize)]
+struct Variable {
+    name: String,
+    file: String,
+    line: usize,
+    #[serde(default)]
+    scope: String,
+    #[serde(default, rename = "type")]
+    var_type: String,
+}
+
+// A data structure of a report, with the parts that make up its shape
+#[derive(Deserialize)]
+struct DataStructure {
+    name: String,
+    #[serde(rename = "type")]
+    item_type: String,
+    path: Option<String>,
+    file: String,
+    line: usize,
+    visibility: Option<String>,
+    aliased_type: Option<String>,
+    parameters: Option<serde_json::Value>,
+    return_type: Option<String>,
+    fields: Option<serde_json::Value>,
+    variants: Option<serde_json::Value>,
+}
+
+#[derive(Default, Deserialize)]
+struct CargoMetadata {
+    workspace_root: Option<PathBuf>,
+}
+
+#[derive(Default, Deserialize)]
+struct Metadata {
+    #[serde(default)]
+    cargo: CargoMetadata,
+}
+
+// The records of a report that are compared
+#[derive(Deserialize)]
+pub struct Snapshot {
+    #[serde(default)]
+    metadata: Metadata,
+    #[serde(default)]
+    mutable_variables: Vec<Variable>,
+    #[serde(default)]
+    immutable_variables: Vec<Variable>,
+    #[serde(default)]
+    data_structures: Vec<DataStructure>,
+}
+
+// Function to make a report path comparable: relative to the workspace root, without `./`
+fn normalize(file: &str, workspace_root: Option<&Path>) -> String {
+    let path = Path::new(file);
+    let path = workspace_root
+        .and_then(|root| path.strip_prefix(root).ok())
+        .unwrap_or(path);
+    let parts: Vec<String> = path
+        .components()
+        .filter(|component| !matches!(component, Component::CurDir))
+        .map(|component| component.as_os_str().to_string_lossy().to_string())
+        .collect();
+    parts.join("/")
+}
+
+impl Snapshot {
+    // Function to read the records of a JSON report
+    pub fn from_value(value: serde_json::Value) -> Result<Self, serde_json::Error> {
+        let mut snapshot: Snapshot = serde_json::from_value(value)?;
+        let root = snapshot.metadata.cargo.workspace_root.clone();
+        let root = root.as_deref();
+        for var in snapshot
+            .mutable_variables
+            .iter_mut()
+            .chain(snapshot.immutable_variables.iter_mut())
+        {
+            var.file = normalize(&var.file, root);
+        }
+        for data_structure in &mut snapshot.data_structures {
+            data_structure.file = normalize(&data_structure.file, root);
+        }
+        Ok(snapshot)
+    }
+
+    // Function to load a JSON report written by --format json
+    pub fn load(path: &str) -> Result<Self, ForestError> {
+        let content = std::fs::read_to_string(path).map_err(|e| ForestError::io(path, e))?;
+        let value = serde_json::from_str(&content).map_err(|e| ForestError::json(path, &e))?;
+        Snapshot::from_value(value).map_err(|e| {
+            ForestError::Config(format!("{} is not a forest JSON report: {}", path, e))
+        })
+    }
+}
+
+// An added, removed or changed record
+#[derive(Serialize)]
+pub struct Change {
+    pub change: &'static str, // added, removed or changed
+    pub record: String,       // `mutable variable`, `immutable variable` or the item kind
+    pub name: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub scope: String, // Enclosing scope of variables
+    pub file: String,
+    pub line: usize, // In the current report, or the baseline for removed records
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+// Net changes in one file
+#[derive(Default, Serialize)]
+pub struct FileChanges {
+    pub file: String,
+    pub mutable_variables: isize,
+    pub immutable_variables: isize,
+    pub data_structures: isize,
+    pub changed: usize,
+}
+
+impl FileChanges {
+    // Function to describe the changes in one line, e.g. `+12 mutable variables`
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        for (count, what) in [
+            (self.mutable_variables, "mutable variables"),
+            (self.immutable_variables, "immutable variables"),
+            (self.data_structures, "data structures"),
+        ] {
+            if count != 0 {
+                parts.push(format!("{:+} {}", count, what));
+            }
+        }
+        if self.changed > 0 {
+            parts.push(format!("{} changed", self.changed));
+        }
+        parts.join(", ")
+    }
+}
+
+// Differences between two reports
+#[derive(Serialize)]
+pub struct Diff {
+    pub files: Vec<FileChanges>,
+    pub changes: Vec<Change>,
+}
+
+// Function to pair records with equal keys, in order, as (baseline, current)
+fn pair<'a, T, K: Ord>(
+    baseline: impl IntoIterator<Item = &'a T>,
+    current: impl IntoIterator<Item = &'a T>,
+    key: impl Fn(&T) -> K,
+) -> Vec<(Option<&'a T>, Option<&'a T>)>
+where
+    T: 'a,
+{
+    let mut by_key: BTreeMap<K, (Vec<&T>, Vec<&T>)> = BTreeMap::new();
+    for record in baseline {
+        by_key.entry(key(record)).or_default().0.push(record);
+    }
+    for record in current {
+        by_key.entry(key(record)).or_default().1.push(record);
+    }
+    let mut pairs = Vec::new();
+    for (old, new) in by_key.into_values() {
+        for index in 0..old.len().max(new.len()) {
+            pairs.push((old.get(index).copied(), new.get(index).copied()));
+        }
+    }
+    pairs
+}
+
+// Function to describe how the shape of a data structure changed, if it did
+fn structure_changes(old: &DataStructure, new: &DataStructure) -> Option<String> {
+    let mut changed = Vec::new();
+    if old.visibility != new.visibility {
+        changed.push("visibility");
+    }
+    if old.fields != new.fields {
+        changed.push("fields");
+    }
+    if old.variants != new.variants {
+        changed.push("variants");
+    }
+    if old.parameters != new.parameters || old.return_type != new.return_type {
+        changed.push("signature");
+    }
+    if old.aliased_type != new.aliased_type {
+        changed.push("aliased type");
+    }
+    (!changed.is_empty()).then(|| format!("{} changed", changed.join(", ")))
+}
+
+// Function to find the net changes of a file, adding them when first needed
+fn file_changes<'a>(
+    files: &'a mut BTreeMap<String, FileChanges>,
+    file: &str,
+) -> &'a mut FileChanges {
+    files
+        .entry(file.to_string())
+        .or_insert_with(|| FileChanges {
+            file: file.to_string(),
+            ..FileChanges::default()
+        })
+}
+
+// Function to list the variables of a report with their mutability
+fn tagged(snapshot: &Snapshot) -> Vec<(bool, &Variable)> {
+    let mutable = snapshot.mutable_variables.iter().map(|var| (true, var));
+    let immutable = snapshot.immutable_variables.iter().map(|var| (false, var));
+    mutable.chain(immutable).collect()
+}
+
+// Function to compare two reports
+pub fn diff(baseline: &Snapshot, current: &Snapshot) -> Diff {
+    let mut changes = Vec::new();
+    let mut files: BTreeMap<String, FileChanges> = BTreeMap::new();
+
+    // Variables, with their mutability, so a `let` that became `let mut` is a change
+    let (old_vars, new_vars) = (tagged(baseline), tagged(current));
+    let mutability = |mutable: bool| if mutable { "mutable" } else { "immutable" };
+    for (old, new) in pair(&old_vars, &new_vars, |(_, var)| {
+        (var.file.clone(), var.scope.clone(), var.name.clone())
+    }) {
+        let (change, (mutable, var), detail) = match (old, new) {
+            (Some(&old), None) => ("removed", old, None),
+            (None, Some(&new)) => ("added", new, None),
+            (Some(&(old_mutable, old_var)), Some(&(new_mutable, new_var))) => {
+                let mut details = Vec::new();
+                if old_mutable != new_mutable {
+                    details.push(format!("now {}", mutability(new_mutable)));
+                }
+                if old_var.var_type != new_var.var_type {
+                    details.push(format!("type {} -> {}", old_var.var_type, new_var.var_type));
+                }
+                if details.is_empty() {
+                    continue;
+                }
+                ("changed", (new_mutable, new_var), Some(details.join(", ")))
+            }
+            (None, None) => continue,
+        };
+        let counts = file_changes(&mut files, &var.file);
+        let step = if mutable { 1 } else { -1 };
+        match change {
+            "added" if mutable => counts.mutable_variables += 1,
+            "added" => counts.immutable_variables += 1,
+            "removed" if mutable => counts.mutable_variables -= 1,
+            "removed" => counts.immutable_variables -= 1,
+            _ => {
+                counts.changed += 1;
+                // A variable that changed mutability moves between the two counts
+                if old.is_some_and(|&(old_mutable, _)| old_mutable != mutable) {
+                    counts.mutable_variables += step;
+                    counts.immutable_variables -= step;
+                }
+            }
+        }
+        changes.push(Change {
+            change,
+            record: format!("{} variable", mutability(mutable)),
+            name: var.name.clone(),
+            scope: var.scope.clone(),
+            file: var.file.clone(),
+            line: var.line,
+            detail,
+        });
+    }
+
+    // Data structures
+    for (old, new) in pair(
+        &baseline.data_structures,
+        &current.data_structures,
+        |item| {
+            (
+                item.file.clone(),
+                item.item_type.clone(),
+                item.path.clone().unwrap_or_else(|| item.name.clone()),
+            )
+        },
+    ) {
+        let (change, item, detail) = match (old, new) {
+            (Some(old), None) => ("removed", old, None),
+            (None, Some(new)) => ("added", new, None),
+            (Some(old), Some(new)) => match structure_changes(old, new) {
+                Some(detail) => ("changed", new, Some(detail)),
+                None => continue,
+            },
+            (None, None) => continue,
+        };
+        let counts = file_changes(&mut files, &item.file);
+        match change {
+            "added" => counts.data_structures += 1,
+            "removed" => counts.data_structures -= 1,
+            _ => counts.changed += 1,
+        }
+        changes.push(Change {
+            change,
+            record: item.item_type.clone(),
+            name: item.path.clone().unwrap_or_else(|| item.name.clone()),
+            scope: String::new(),
+            file: item.file.clone(),
+            line: item.line,
+            detail,
+        });
+    }
+
+    changes.sort_by(|a, b| (&a.file, a.line, &a.name).cmp(&(&b.file, b.line, &b.name)));
+    let files = files
+        .into_values()
+        .filter(|file| !file.describe().is_empty())
+        .collect();
+    Diff { files, changes }
+}
+
+// Function to print a diff for people, per file and then record by record
+pub fn print_diff(diff: &Diff) {
+    if diff.changes.is_empty() {
+        println!("No differences");
+        return;
+    }
+    for file in &diff.files {
+        println!("{}: {}", file.file, file.describe());
+    }
+    println!();
+    for change in &diff.changes {
+        let sign = match change.change {
+            "added" => '+',
+            "removed" => '-',
+            _ => '~',
+        };
+        let scope = if change.scope.is_empty() {
+            String::new()
+        } else {
+            format!(" in {}", change.scope)
+        };
+        let detail = change
+            .detail
+            .as_ref()
+            .map(|detail| format!(": {}", detail))
+            .unwrap_or_default();
+        println!(
+            "{} {} `{}`{} ({}:{}){}",
+            sign, change.record, change.name, scope, change.file, change.line, detail
+        );
+    }
+}
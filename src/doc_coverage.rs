@@ -0,0 +1,241 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Documentation coverage of public items.
+// Every `pub` item is recorded with whether it carries a doc comment, and coverage is
+// totalled per module, like a lightweight `cargo doc` audit. A `pub mod x;` declaration is
+// documented by outer docs or by the `//!` docs at the top of the module's own file.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use syn::visit::{self, Visit};
+
+// A public item and whether it is documented
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DocItem {
+    pub item: String, // Path of the item, e.g. crate::parser::Parser
+    pub kind: String, // "fn", "struct", "enum", "trait", "mod", ...
+    pub module: String,
+    pub documented: bool,
+    pub module_docs_only: bool, // The `//!` docs of a module file, not an item declaration
+    pub file_path: PathBuf,
+    pub line_number: usize,
+}
+
+// Documentation coverage of one module
+pub struct ModuleCoverage<'a> {
+    pub module: &'a str,
+    pub documented: usize,
+    pub total: usize,
+    pub undocumented: Vec<&'a DocItem>,
+}
+
+impl ModuleCoverage<'_> {
+    pub fn percentage(&self) -> f64 {
+        percentage(self.documented, self.total)
+    }
+}
+
+// Function to compute a coverage percentage; nothing to document counts as fully documented
+pub fn percentage(documented: usize, total: usize) -> f64 {
+    if total == 0 {
+        100.0
+    } else {
+        documented as f64 * 100.0 / total as f64
+    }
+}
+
+// Function to check whether attributes include a doc comment
+fn has_docs(attrs: &[syn::Attribute]) -> bool {
+    attrs.iter().any(|attr| attr.path().is_ident("doc"))
+}
+
+// Visitor that collects the public items of a file
+struct DocVisitor<'a> {
+    file_path: &'a Path,
+    module: String,
+    scope: String, // Path of the enclosing trait or impl, for methods
+    items: &'a mut Vec<DocItem>,
+}
+
+impl DocVisitor<'_> {
+    fn record(&mut self, name: &syn::Ident, kind: &str, attrs: &[syn::Attribute]) {
+        self.items.push(DocItem {
+            item: format!("{}::{}", self.scope, name),
+            kind: kind.to_string(),
+            module: self.module.clone(),
+            documented: has_docs(attrs),
+            module_docs_only: false,
+            file_path: self.file_path.to_path_buf(),
+            line_number: name.span().start().line,
+        });
+    }
+
+    fn visit_in_scope(&mut self, name: &str, visit: impl FnOnce(&mut Self)) {
+        let inner_scope = format!("{}::{}", self.scope, name);
+        let outer_scope = std::mem::replace(&mut self.scope, inner_scope);
+        visit(self);
+        self.scope = outer_scope;
+    }
+}
+
+impl<'ast> Visit<'ast> for DocVisitor<'_> {
+    fn visit_item(&mut self, item: &'ast syn::Item) {
+        let public = |vis: &syn::Visibility| matches!(vis, syn::Visibility::Public(_));
+        match item {
+            syn::Item::Fn(item) if public(&item.vis) => {
+                self.record(&item.sig.ident, "fn", &item.attrs)
+            }
+            syn::Item::Struct(item) if public(&item.vis) => {
+                self.record(&item.ident, "struct", &item.attrs)
+            }
+            syn::Item::Enum(item) if public(&item.vis) => {
+                self.record(&item.ident, "enum", &item.attrs)
+            }
+            syn::Item::Union(item) if public(&item.vis) => {
+                self.record(&item.ident, "union", &item.attrs)
+            }
+            syn::Item::Trait(item) if public(&item.vis) => {
+                self.record(&item.ident, "trait", &item.attrs)
+            }
+            syn::Item::Type(item) if public(&item.vis) => {
+                self.record(&item.ident, "type", &item.attrs)
+            }
+            syn::Item::Const(item) if public(&item.vis) => {
+                self.record(&item.ident, "const", &item.attrs)
+            }
+            syn::Item::Static(item) if public(&item.vis) => {
+                self.record(&item.ident, "static", &item.attrs)
+            }
+            syn::Item::Mod(item) if public(&item.vis) => {
+                self.record(&item.ident, "mod", &item.attrs)
+            }
+            // Exported macros are public whatever the module they are defined in
+            syn::Item::Macro(item)
+                if item
+                    .attrs
+                    .iter()
+                    .any(|attr| attr.path().is_ident("macro_export")) =>
+            {
+                if let Some(ref ident) = item.ident {
+                    self.record(ident, "macro", &item.attrs);
+                }
+            }
+            _ => {}
+        }
+        visit::visit_item(self, item);
+    }
+
+    // Methods of inherent impls; trait impls take their docs from the trait
+    fn visit_item_impl(&mut self, item_impl: &'ast syn::ItemImpl) {
+        if item_impl.trait_.is_some() {
+            return;
+        }
+        let name = crate::impl_blocks::type_name(&item_impl.self_ty);
+        self.visit_in_scope(&name, |visitor| {
+            for item in &item_impl.items {
+                if let syn::ImplItem::Fn(method) = item {
+                    if matches!(method.vis, syn::Visibility::Public(_)) {
+                        visitor.record(&method.sig.ident, "method", &method.attrs);
+                    }
+                }
+            }
+        });
+    }
+
+    // Every item of a public trait is public
+    fn visit_item_trait(&mut self, item_trait: &'ast syn::ItemTrait) {
+        if !matches!(item_trait.vis, syn::Visibility::Public(_)) {
+            return;
+        }
+        self.visit_in_scope(&item_trait.ident.to_string(), |visitor| {
+            for item in &item_trait.items {
+                if let syn::TraitItem::Fn(method) = item {
+                    visitor.record(&method.sig.ident, "method", &method.attrs);
+                }
+            }
+        });
+    }
+
+    // Items inside functions are not reachable from outside
+    fn visit_item_fn(&mut self, _item_fn: &'ast syn::ItemFn) {}
+
+    fn visit_item_mod(&mut self, item_mod: &'ast syn::ItemMod) {
+        let inner_module = format!("{}::{}", self.module, item_mod.ident);
+        let outer_module = std::mem::replace(&mut self.module, inner_module.clone());
+        let outer_scope = std::mem::replace(&mut self.scope, inner_module);
+        visit::visit_item_mod(self, item_mod);
+        self.module = outer_module;
+        self.scope = outer_scope;
+    }
+}
+
+// Function to collect the public items of a parsed file and whether they are documented
+pub fn collect_doc_items(
+    file_ast: &syn::File,
+    file_path: &Path,
+    module_path: &str,
+    items: &mut Vec<DocItem>,
+) {
+    // `//!` docs document the module of the file, which is declared elsewhere
+    if has_docs(&file_ast.attrs) {
+        let (module, _) = module_path.rsplit_once("::").unwrap_or(("crate", ""));
+        items.push(DocItem {
+            item: module_path.to_string(),
+            kind: "mod".to_string(),
+            module: module.to_string(),
+            documented: true,
+            module_docs_only: true,
+            file_path: file_path.to_path_buf(),
+            line_number: 1,
+        });
+    }
+    let mut visitor = DocVisitor {
+        file_path,
+        module: module_path.to_string(),
+        scope: module_path.to_string(),
+        items,
+    };
+    visitor.visit_file(file_ast);
+}
+
+// Function to resolve the public items, counting module docs towards their declarations
+pub fn public_items(items: &[DocItem]) -> Vec<(&DocItem, bool)> {
+    let module_docs: Vec<&str> = items
+        .iter()
+        .filter(|item| item.module_docs_only)
+        .map(|item| item.item.as_str())
+        .collect();
+    items
+        .iter()
+        .filter(|item| !item.module_docs_only)
+        .map(|item| {
+            let documented = item.documented
+                || (item.kind == "mod" && module_docs.contains(&item.item.as_str()));
+            (item, documented)
+        })
+        .collect()
+}
+
+// Function to total the documentation coverage per module, least documented first
+pub fn module_coverage(items: &[DocItem]) -> Vec<ModuleCoverage<'_>> {
+    let mut modules: BTreeMap<&str, ModuleCoverage> = BTreeMap::new();
+    for (item, documented) in public_items(items) {
+        let coverage = modules
+            .entry(item.module.as_str())
+            .or_insert_with(|| ModuleCoverage {
+                module: &item.module,
+                documented: 0,
+                total: 0,
+                undocumented: Vec::new(),
+            });
+        coverage.total += 1;
+        if documented {
+            coverage.documented += 1;
+        } else {
+            coverage.undocumented.push(item);
+        }
+    }
+    let mut coverage: Vec<ModuleCoverage> = modules.into_values().collect();
+    coverage.sort_by(|a, b| a.percentage().total_cmp(&b.percentage()));
+    coverage
+}
@@ -0,0 +1,260 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Doc-test extraction and counting.
+// Fenced code blocks in doc comments are Rust doc tests unless their info string names
+// another language. They are counted per item and per crate and, with
+// `--analyse-doc-tests`, the snippets themselves are analysed (hidden `# ` lines
+// included) to report items whose examples declare mutable state.
+
+use crate::line_index::LineIndex;
+use crate::walker::Walker;
+use crate::{code_class, AnalysisResults};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+
+// Info string words that still mean a Rust code block
+const RUST_BLOCK_ATTRIBUTES: &[&str] = &[
+    "rust",
+    "ignore",
+    "no_run",
+    "should_panic",
+    "compile_fail",
+    "test_harness",
+    "edition2015",
+    "edition2018",
+    "edition2021",
+    "edition2024",
+];
+
+// The doc tests of a single item
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DocTestItem {
+    pub item: String, // Item name, or the file stem for the `//!` docs of a file
+    pub kind: String, // Kind of item (fn, struct, mod, ...)
+    #[serde(rename = "crate")]
+    pub crate_name: String,
+    #[serde(rename = "file")]
+    pub file_path: PathBuf,
+    #[serde(rename = "line")]
+    pub line_number: usize,
+    pub doc_tests: usize,
+    #[serde(rename = "example_mutable_variables")]
+    pub mutable_vars: Option<usize>, // Mutable variables declared by the examples, when analysed
+}
+
+// Function to extract the Rust code blocks from a doc comment
+fn rust_code_blocks(docs: &str) -> Vec<String> {
+    let mut blocks = Vec::new();
+    // Some(Some(code)) inside a Rust block, Some(None) inside a block of another language
+    let mut open: Option<Option<String>> = None;
+    for line in docs.lines() {
+        let trimmed = line.trim_start();
+        if let Some(info) = trimmed
+            .strip_prefix("```")
+            .or_else(|| trimmed.strip_prefix("~~~"))
+        {
+            match open.take() {
+                Some(Some(code)) => blocks.push(code),
+                Some(None) => {}
+                None => open = Some(is_rust_info(info).then(String::new)),
+            }
+            continue;
+        }
+        if let Some(Some(ref mut code)) = open {
+            code.push_str(line);
+            code.push('\n');
+        }
+    }
+    blocks
+}
+
+// Function to check whether a fence info string describes Rust code
+fn is_rust_info(info: &str) -> bool {
+    info.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|word| !word.is_empty())
+        .all(|word| RUST_BLOCK_ATTRIBUTES.contains(&word))
+}
+
+// Function to get the documentation text of a list of attributes
+fn doc_text(attrs: &[syn::Attribute], inner: bool) -> Option<(String, usize)> {
+    let mut text = String::new();
+    let mut line = None;
+    for attr in attrs {
+        let is_inner = matches!(attr.style, syn::AttrStyle::Inner(_));
+        if is_inner != inner || !attr.path().is_ident("doc") {
+            continue;
+        }
+        if let syn::Meta::NameValue(ref name_value) = attr.meta {
+            if let syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(ref doc),
+                ..
+            }) = name_value.value
+            {
+                line.get_or_insert(attr.span().start().line);
+                text.push_str(&doc.value());
+                text.push('\n');
+            }
+        }
+    }
+    line.map(|line| (text, line))
+}
+
+// Function to count the mutable variables declared by a doc-test snippet
+fn mutable_vars_in_snippet(snippet: &str, file_path: &Path) -> usize {
+    // Hidden lines (`# `) are part of the compiled test
+    let code: String = snippet
+        .lines()
+        .map(|line| match line.trim_start().strip_prefix('#') {
+            Some(rest) if rest.is_empty() || rest.starts_with(' ') => rest.trim_start(),
+            _ => line,
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    // Like rustdoc, snippets without their own `fn main` are wrapped in one
+    let wrapped = format!("fn main() {{\n{}\n}}", code);
+    let parsed = syn::parse_file(&code)
+        .ok()
+        .filter(|file| !file.items.is_empty())
+        .map(|file| (file, code.as_str()))
+        .or_else(|| {
+            syn::parse_file(&wrapped)
+                .ok()
+                .map(|file| (file, wrapped.as_str()))
+        });
+    let Some((file, source)) = parsed else {
+        return 0;
+    };
+    let lines = LineIndex::new(source);
+
+    let mut results = AnalysisResults::new();
+    // Each doc test is compiled as its own crate
+    Walker::new(file_path, &lines, "crate", &mut results)
+        .code_class(code_class::TEST)
+        .visit_file(&file);
+    results.mutable_vars.len()
+}
+
+// Visitor that counts the doc tests of every documented item
+struct DocTestVisitor<'a> {
+    file_path: &'a Path,
+    crate_name: &'a str,
+    analyse: bool,
+    items: &'a mut Vec<DocTestItem>,
+}
+
+impl DocTestVisitor<'_> {
+    fn record(&mut self, item: String, kind: &str, attrs: &[syn::Attribute], inner: bool) {
+        let Some((docs, line_number)) = doc_text(attrs, inner) else {
+            return;
+        };
+        let blocks = rust_code_blocks(&docs);
+        if blocks.is_empty() {
+            return;
+        }
+        let mutable_vars = self.analyse.then(|| {
+            blocks
+                .iter()
+                .map(|block| mutable_vars_in_snippet(block, self.file_path))
+                .sum()
+        });
+        self.items.push(DocTestItem {
+            item,
+            kind: kind.to_string(),
+            crate_name: self.crate_name.to_string(),
+            file_path: self.file_path.to_path_buf(),
+            line_number,
+            doc_tests: blocks.len(),
+            mutable_vars,
+        });
+    }
+}
+
+impl<'ast> Visit<'ast> for DocTestVisitor<'_> {
+    fn visit_item_fn(&mut self, node: &'ast syn::ItemFn) {
+        self.record(node.sig.ident.to_string(), "fn", &node.attrs, false);
+        visit::visit_item_fn(self, node);
+    }
+
+    fn visit_item_struct(&mut self, node: &'ast syn::ItemStruct) {
+        self.record(node.ident.to_string(), "struct", &node.attrs, false);
+        visit::visit_item_struct(self, node);
+    }
+
+    fn visit_item_enum(&mut self, node: &'ast syn::ItemEnum) {
+        self.record(node.ident.to_string(), "enum", &node.attrs, false);
+        visit::visit_item_enum(self, node);
+    }
+
+    fn visit_item_trait(&mut self, node: &'ast syn::ItemTrait) {
+        self.record(node.ident.to_string(), "trait", &node.attrs, false);
+        visit::visit_item_trait(self, node);
+    }
+
+    fn visit_item_mod(&mut self, node: &'ast syn::ItemMod) {
+        self.record(node.ident.to_string(), "mod", &node.attrs, false);
+        self.record(node.ident.to_string(), "mod", &node.attrs, true);
+        visit::visit_item_mod(self, node);
+    }
+
+    fn visit_item_macro(&mut self, node: &'ast syn::ItemMacro) {
+        if let Some(ref ident) = node.ident {
+            self.record(ident.to_string(), "macro", &node.attrs, false);
+        }
+        visit::visit_item_macro(self, node);
+    }
+
+    fn visit_item_type(&mut self, node: &'ast syn::ItemType) {
+        self.record(node.ident.to_string(), "type", &node.attrs, false);
+        visit::visit_item_type(self, node);
+    }
+
+    fn visit_item_const(&mut self, node: &'ast syn::ItemConst) {
+        self.record(node.ident.to_string(), "const", &node.attrs, false);
+        visit::visit_item_const(self, node);
+    }
+
+    fn visit_impl_item_fn(&mut self, node: &'ast syn::ImplItemFn) {
+        self.record(node.sig.ident.to_string(), "fn", &node.attrs, false);
+        visit::visit_impl_item_fn(self, node);
+    }
+
+    fn visit_trait_item_fn(&mut self, node: &'ast syn::TraitItemFn) {
+        self.record(node.sig.ident.to_string(), "fn", &node.attrs, false);
+        visit::visit_trait_item_fn(self, node);
+    }
+}
+
+// Function to count the doc tests of a parsed file
+pub fn collect_doc_tests(
+    file_ast: &syn::File,
+    file_path: &Path,
+    analyse: bool,
+    items: &mut Vec<DocTestItem>,
+) {
+    let crate_name = crate::cargo::crate_name_for(file_path).unwrap_or_default();
+    let mut visitor = DocTestVisitor {
+        file_path,
+        crate_name: &crate_name,
+        analyse,
+        items,
+    };
+    // `//!` docs at the top of the file document the module itself
+    let module = file_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    visitor.record(module, "mod", &file_ast.attrs, true);
+    visitor.visit_file(file_ast);
+}
+
+// Function to count doc tests per crate
+pub fn tests_per_crate(items: &[DocTestItem]) -> BTreeMap<&str, usize> {
+    let mut per_crate = BTreeMap::new();
+    for item in items {
+        *per_crate.entry(item.crate_name.as_str()).or_insert(0) += item.doc_tests;
+    }
+    per_crate
+}
@@ -0,0 +1,250 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Self-check used by `forest doctor` to diagnose why an analysis finds nothing.
+// It analyses a small embedded fixture project, verifies the expected counts and
+// checks the environment forest depends on.
+
+use crate::{analyse_project, AnalysisOptions, ForestError};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+// Embedded fixture project written to a temporary directory for each run
+const FIXTURE_CARGO_TOML: &str = r#"[package]
+name = "forest-doctor-fixture"
+version = "0.1.0"
+edition = "2021"
+"#;
+
+const FIXTURE_MAIN_RS: &str = r#"struct Counter {
+    count: u32,
+}
+
+enum Mode {
+    Fast,
+    Slow,
+}
+
+fn main() {
+    let mut counter = Counter { count: 0 };
+    let step: u32 = 2;
+    for mut i in 0..3 {
+        i += step;
+        counter.count += i;
+    }
+    let name = "fixture";
+    println!("{} {}", name, counter.count);
+}
+"#;
+
+// Counts the fixture is expected to produce (mutable, immutable, data structures)
+const EXPECTED_COUNTS: (usize, usize, usize) = (2, 2, 3);
+
+// Outcome of a single check
+enum CheckStatus {
+    Ok,
+    Warn, // Optional component missing; analysis still works
+    Fail,
+}
+
+struct CheckResult {
+    name: &'static str,
+    status: CheckStatus,
+    detail: String,
+}
+
+// Entry point for `forest doctor`
+pub fn run_doctor() -> Result<(), ForestError> {
+    println!("\x1b[1mForest doctor\x1b[0m");
+
+    let fixture_dir = std::env::temp_dir().join(format!("forest-doctor-{}", std::process::id()));
+    let checks = vec![
+        check_fixture_analysis(&fixture_dir),
+        check_link_scheme(&fixture_dir),
+        check_write_permissions(),
+        check_backend("cargo"),
+        check_backend("rust-analyzer"),
+    ];
+    // Best effort cleanup; a leftover fixture in the temp directory is harmless
+    let _ = fs::remove_dir_all(&fixture_dir);
+
+    let mut failures = 0;
+    for check in &checks {
+        let label = match check.status {
+            CheckStatus::Ok => "\x1b[32m[ok]  \x1b[0m",
+            CheckStatus::Warn => "\x1b[33m[warn]\x1b[0m",
+            CheckStatus::Fail => {
+                failures += 1;
+                "\x1b[31m[FAIL]\x1b[0m"
+            }
+        };
+        println!("  {} {}: {}", label, check.name, check.detail);
+    }
+
+    println!("\n\x1b[1mDiagnosis:\x1b[0m");
+    if failures == 0 {
+        println!("forest is working correctly.");
+        println!("If it finds nothing in your project, check that project_dir is the directory");
+        println!(
+            "containing Cargo.toml and that your .rs files are not inside a target/ directory"
+        );
+        println!("or excluded by .gitignore (see --no-ignore).");
+        Ok(())
+    } else {
+        println!(
+            "{} of {} checks failed; see the details above.",
+            failures,
+            checks.len()
+        );
+        Err(ForestError::Check(format!(
+            "forest doctor found {} problems",
+            failures
+        )))
+    }
+}
+
+// Write the fixture project to disk
+fn write_fixture(dir: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(dir.join("src"))?;
+    fs::write(dir.join("Cargo.toml"), FIXTURE_CARGO_TOML)?;
+    fs::write(dir.join("src").join("main.rs"), FIXTURE_MAIN_RS)?;
+    Ok(())
+}
+
+// Analyse the fixture and compare the counts with the expected ones
+fn check_fixture_analysis(dir: &Path) -> CheckResult {
+    let name = "fixture analysis";
+    if let Err(e) = write_fixture(dir) {
+        return CheckResult {
+            name,
+            status: CheckStatus::Fail,
+            detail: format!("could not write fixture to {}: {}", dir.display(), e),
+        };
+    }
+
+    let options = AnalysisOptions::new().allow_degraded(false);
+    let results = match analyse_project(&dir.display().to_string(), &options, None) {
+        Ok(results) => results,
+        Err(e) => {
+            return CheckResult {
+                name,
+                status: CheckStatus::Fail,
+                detail: format!("analysis failed: {}", e),
+            }
+        }
+    };
+
+    let counts = (
+        results.mutable_vars.len(),
+        results.immutable_vars.len(),
+        results.data_structures.len(),
+    );
+    let detail = format!(
+        "{} mutable, {} immutable, {} data structures (expected {}, {}, {})",
+        counts.0, counts.1, counts.2, EXPECTED_COUNTS.0, EXPECTED_COUNTS.1, EXPECTED_COUNTS.2
+    );
+
+    if !results.errors.is_empty() {
+        CheckResult {
+            name,
+            status: CheckStatus::Fail,
+            detail: format!("{} errors while parsing the fixture", results.errors.len()),
+        }
+    } else if counts != EXPECTED_COUNTS {
+        CheckResult {
+            name,
+            status: CheckStatus::Fail,
+            detail,
+        }
+    } else {
+        CheckResult {
+            name,
+            status: CheckStatus::Ok,
+            detail,
+        }
+    }
+}
+
+// Verify that editor links are absolute and carry the line number
+fn check_link_scheme(dir: &Path) -> CheckResult {
+    let name = "link scheme";
+    let options = AnalysisOptions::new();
+    let results = match analyse_project(&dir.display().to_string(), &options, None) {
+        Ok(results) => results,
+        Err(e) => {
+            return CheckResult {
+                name,
+                status: CheckStatus::Fail,
+                detail: format!("analysis failed: {}", e),
+            }
+        }
+    };
+
+    let Some(var) = results.mutable_vars.first() else {
+        return CheckResult {
+            name,
+            status: CheckStatus::Fail,
+            detail: "no variables found to build a link for".to_string(),
+        };
+    };
+
+    let link = var.editor_link();
+    // Remote links carry an authority (e.g. wsl+Ubuntu) before the path
+    let path_part = match link.strip_prefix("vscode://vscode-remote/") {
+        Some(remote) => remote.find('/').map_or("", |start| &remote[start..]),
+        None => link.trim_start_matches("vscode://file/"),
+    };
+    let absolute = path_part.starts_with('/') || path_part.chars().nth(1) == Some(':');
+    if (link.starts_with("vscode://file/") || link.starts_with("vscode://vscode-remote/"))
+        && absolute
+        && link.ends_with(&format!(":{}:{}", var.line_number, var.column))
+    {
+        CheckResult {
+            name,
+            status: CheckStatus::Ok,
+            detail: link,
+        }
+    } else {
+        CheckResult {
+            name,
+            status: CheckStatus::Fail,
+            detail: format!("malformed link: {}", link),
+        }
+    }
+}
+
+// Verify that output files can be created in the current directory
+fn check_write_permissions() -> CheckResult {
+    let name = "write permissions";
+    let probe = Path::new(".forest-doctor-write-check");
+    match fs::write(probe, b"forest") {
+        Ok(()) => {
+            let _ = fs::remove_file(probe);
+            CheckResult {
+                name,
+                status: CheckStatus::Ok,
+                detail: "current directory is writable".to_string(),
+            }
+        }
+        Err(e) => CheckResult {
+            name,
+            status: CheckStatus::Fail,
+            detail: format!("cannot write to the current directory: {}", e),
+        },
+    }
+}
+
+// Check whether an optional external tool is available on PATH
+fn check_backend(tool: &'static str) -> CheckResult {
+    match Command::new(tool).arg("--version").output() {
+        Ok(output) if output.status.success() => CheckResult {
+            name: tool,
+            status: CheckStatus::Ok,
+            detail: String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        },
+        _ => CheckResult {
+            name: tool,
+            status: CheckStatus::Warn,
+            detail: "not found on PATH (optional)".to_string(),
+        },
+    }
+}
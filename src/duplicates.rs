@@ -0,0 +1,256 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Duplicate and near-duplicate function detection.
+// Each function body is reduced to a token sequence. Exact duplicates share the same
+// tokens; near duplicates share the same tokens once identifiers and literals are
+// canonicalized, so copies with renamed variables are still found. Free functions,
+// methods and the default methods of traits are fingerprinted. The hashes use FNV-1a, whose
+// output does not depend on the Rust release, since they are kept in the analysis cache.
+
+use proc_macro2::{Delimiter, TokenStream, TokenTree};
+use quote::ToTokens;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use syn::visit::{self, Visit};
+
+// Functions shorter than this are expected to look alike (getters, constructors, ...)
+const MIN_TOKENS: usize = 30;
+
+// Keywords keep their spelling so that the structure of the code is preserved
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type",
+    "unsafe", "use", "where", "while",
+];
+
+// Fingerprint of a single function body
+#[derive(Clone, Serialize, Deserialize)]
+pub struct FunctionFingerprint {
+    pub name: String, // Function name, qualified with the impl type for methods
+    pub file_path: PathBuf,
+    pub line_number: usize,
+    pub token_count: usize,
+    pub exact_hash: u64,      // Hash of the tokens as written
+    pub normalized_hash: u64, // Hash of the tokens with identifiers and literals canonicalized
+}
+
+// A set of functions that are duplicates of each other
+pub struct DuplicateGroup<'a> {
+    pub kind: &'static str, // "exact" or "near"
+    pub token_count: usize,
+    pub functions: Vec<&'a FunctionFingerprint>,
+}
+
+// Visitor that fingerprints free functions and methods
+struct FingerprintVisitor<'a> {
+    file_path: &'a Path,
+    impl_type: Option<String>, // Self type of the impl block, or the trait, being visited
+    fingerprints: &'a mut Vec<FunctionFingerprint>,
+}
+
+impl FingerprintVisitor<'_> {
+    fn fingerprint(&mut self, name: String, line_number: usize, body: &syn::Block) {
+        let mut exact = Vec::new();
+        let mut normalized = Vec::new();
+        let mut idents = HashMap::new();
+        flatten_tokens(
+            body.to_token_stream(),
+            &mut exact,
+            &mut normalized,
+            &mut idents,
+        );
+
+        if exact.len() < MIN_TOKENS {
+            return;
+        }
+
+        self.fingerprints.push(FunctionFingerprint {
+            name,
+            file_path: self.file_path.to_path_buf(),
+            line_number,
+            token_count: exact.len(),
+            exact_hash: hash_tokens(&exact),
+            normalized_hash: hash_tokens(&normalized),
+        });
+    }
+}
+
+impl<'ast> Visit<'ast> for FingerprintVisitor<'_> {
+    fn visit_item_fn(&mut self, item_fn: &'ast syn::ItemFn) {
+        self.fingerprint(
+            item_fn.sig.ident.to_string(),
+            item_fn.sig.ident.span().start().line,
+            &item_fn.block,
+        );
+        visit::visit_item_fn(self, item_fn);
+    }
+
+    fn visit_item_impl(&mut self, item_impl: &'ast syn::ItemImpl) {
+        let previous = self.impl_type.replace(
+            item_impl
+                .self_ty
+                .to_token_stream()
+                .to_string()
+                .replace(' ', ""),
+        );
+        visit::visit_item_impl(self, item_impl);
+        self.impl_type = previous;
+    }
+
+    fn visit_item_trait(&mut self, item_trait: &'ast syn::ItemTrait) {
+        let previous = self.impl_type.replace(item_trait.ident.to_string());
+        visit::visit_item_trait(self, item_trait);
+        self.impl_type = previous;
+    }
+
+    // Default methods of traits; required methods have no body
+    fn visit_trait_item_fn(&mut self, method: &'ast syn::TraitItemFn) {
+        if let Some(ref body) = method.default {
+            let name = match self.impl_type {
+                Some(ref ty) => format!("{}::{}", ty, method.sig.ident),
+                None => method.sig.ident.to_string(),
+            };
+            self.fingerprint(name, method.sig.ident.span().start().line, body);
+        }
+        visit::visit_trait_item_fn(self, method);
+    }
+
+    fn visit_impl_item_fn(&mut self, method: &'ast syn::ImplItemFn) {
+        let name = match self.impl_type {
+            Some(ref ty) => format!("{}::{}", ty, method.sig.ident),
+            None => method.sig.ident.to_string(),
+        };
+        self.fingerprint(name, method.sig.ident.span().start().line, &method.block);
+        visit::visit_impl_item_fn(self, method);
+    }
+}
+
+// Function to flatten a token stream into exact and normalized token sequences
+fn flatten_tokens(
+    tokens: TokenStream,
+    exact: &mut Vec<String>,
+    normalized: &mut Vec<String>,
+    idents: &mut HashMap<String, usize>,
+) {
+    for token in tokens {
+        match token {
+            TokenTree::Group(group) => {
+                let (open, close) = match group.delimiter() {
+                    Delimiter::Parenthesis => ("(", ")"),
+                    Delimiter::Brace => ("{", "}"),
+                    Delimiter::Bracket => ("[", "]"),
+                    Delimiter::None => ("", ""),
+                };
+                exact.push(open.to_string());
+                normalized.push(open.to_string());
+                flatten_tokens(group.stream(), exact, normalized, idents);
+                exact.push(close.to_string());
+                normalized.push(close.to_string());
+            }
+            TokenTree::Ident(ident) => {
+                let text = ident.to_string();
+                if KEYWORDS.contains(&text.as_str()) {
+                    normalized.push(text.clone());
+                } else {
+                    // The same identifier always maps to the same placeholder
+                    let next = idents.len();
+                    let index = *idents.entry(text.clone()).or_insert(next);
+                    normalized.push(format!("id{}", index));
+                }
+                exact.push(text);
+            }
+            TokenTree::Punct(punct) => {
+                exact.push(punct.as_char().to_string());
+                normalized.push(punct.as_char().to_string());
+            }
+            TokenTree::Literal(literal) => {
+                exact.push(literal.to_string());
+                normalized.push("LIT".to_string());
+            }
+        }
+    }
+}
+
+fn hash_tokens(tokens: &[String]) -> u64 {
+    // FNV-1a, with a 0xff byte after each token so that token boundaries count
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+    let mut hash = OFFSET_BASIS;
+    for token in tokens {
+        for &byte in token.as_bytes().iter().chain(&[0xff]) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(PRIME);
+        }
+    }
+    hash
+}
+
+// Function to fingerprint all functions of a parsed file
+pub fn collect_fingerprints(
+    file_ast: &syn::File,
+    file_path: &Path,
+    fingerprints: &mut Vec<FunctionFingerprint>,
+) {
+    let mut visitor = FingerprintVisitor {
+        file_path,
+        impl_type: None,
+        fingerprints,
+    };
+    visitor.visit_file(file_ast);
+}
+
+// Function to group the fingerprints of all files into duplicate sets
+pub fn find_duplicates(fingerprints: &[FunctionFingerprint]) -> Vec<DuplicateGroup<'_>> {
+    let mut groups = Vec::new();
+
+    let mut by_exact: HashMap<u64, Vec<&FunctionFingerprint>> = HashMap::new();
+    let mut by_normalized: HashMap<u64, Vec<&FunctionFingerprint>> = HashMap::new();
+    for fingerprint in fingerprints {
+        by_exact
+            .entry(fingerprint.exact_hash)
+            .or_default()
+            .push(fingerprint);
+        by_normalized
+            .entry(fingerprint.normalized_hash)
+            .or_default()
+            .push(fingerprint);
+    }
+
+    for functions in by_exact.into_values().filter(|f| f.len() > 1) {
+        groups.push(DuplicateGroup {
+            kind: "exact",
+            token_count: functions[0].token_count,
+            functions,
+        });
+    }
+
+    // A near-duplicate group is only reported if it is not just a single exact group
+    for functions in by_normalized.into_values().filter(|f| f.len() > 1) {
+        let first_hash = functions[0].exact_hash;
+        if functions.iter().all(|f| f.exact_hash == first_hash) {
+            continue;
+        }
+        groups.push(DuplicateGroup {
+            kind: "near",
+            token_count: functions[0].token_count,
+            functions,
+        });
+    }
+
+    // Largest duplicates first, then by location for stable output
+    for group in &mut groups {
+        group
+            .functions
+            .sort_by(|a, b| (&a.file_path, a.line_number).cmp(&(&b.file_path, b.line_number)));
+    }
+    groups.sort_by(|a, b| {
+        b.token_count.cmp(&a.token_count).then_with(|| {
+            let a_first = &a.functions[0];
+            let b_first = &b.functions[0];
+            (&a_first.file_path, a_first.line_number)
+                .cmp(&(&b_first.file_path, b_first.line_number))
+        })
+    });
+    groups
+}
@@ -0,0 +1,181 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Edition-aware parsing.
+// The edition of each file comes from the Cargo.toml of its package. syn already parses
+// let-chains, raw identifiers and C-string literals, but not the `gen` blocks of the 2024
+// edition: in 2024 crates the `gen` keyword of a block is blanked out before parsing, so
+// the block parses as a plain block with `yield` expressions and all spans stay in place.
+// Files that still fail to parse are recorded with the edition, the syn error and whether
+// the fallback parser analysed them instead. The fallback parser keeps the items that parse
+// on their own, skipping the tokens in between, so their spans stay in place.
+
+use proc_macro2::{Delimiter, TokenStream, TokenTree};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use syn::parse::discouraged::Speculative;
+use syn::parse::{ParseStream, Parser};
+
+// Edition of packages whose Cargo.toml does not set one
+pub const DEFAULT_EDITION: &str = "2015";
+
+// A file syn could not parse
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ParseFailure {
+    #[serde(rename = "file")]
+    pub file_path: PathBuf,
+    pub edition: String,
+    #[serde(rename = "line")]
+    pub line_number: usize,
+    pub column: usize,
+    pub message: String, // The syn parse error
+    pub fallback: bool,  // Whether the file was analysed with the fallback parser instead
+}
+
+impl ParseFailure {
+    // One-line description, e.g. `edition 2021, line 3:12: expected one of ...`
+    pub fn describe(&self) -> String {
+        format!(
+            "edition {}, line {}:{}: {}",
+            self.edition, self.line_number, self.column, self.message
+        )
+    }
+
+    // What happened to the file instead
+    pub fn outcome(&self) -> &'static str {
+        if self.fallback {
+            "analysed with the fallback parser"
+        } else {
+            "skipped"
+        }
+    }
+}
+
+// Function to find the edition of the package a source file belongs to
+pub fn edition_for(file_path: &Path) -> String {
+    crate::cargo::edition_for(file_path).unwrap_or_else(|| DEFAULT_EDITION.to_string())
+}
+
+// Function to collect the line, column and length of the keywords to blank out: `gen`
+// before a block, and the `move` of `gen move { .. }`, which is not valid before a plain block
+fn gen_keywords(tokens: TokenStream, positions: &mut HashMap<(usize, usize), usize>) {
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    for (index, token) in tokens.iter().enumerate() {
+        match token {
+            TokenTree::Ident(ident) if ident == "gen" => {
+                let after_async = matches!(
+                    index.checked_sub(1).map(|previous| &tokens[previous]),
+                    Some(TokenTree::Ident(previous)) if previous == "async"
+                );
+                match (tokens.get(index + 1), tokens.get(index + 2)) {
+                    (Some(TokenTree::Group(group)), _) if group.delimiter() == Delimiter::Brace => {
+                        let start = ident.span().start();
+                        positions.insert((start.line, start.column), "gen".len());
+                    }
+                    (Some(TokenTree::Ident(keyword)), Some(TokenTree::Group(group)))
+                        if keyword == "move" && group.delimiter() == Delimiter::Brace =>
+                    {
+                        let start = ident.span().start();
+                        positions.insert((start.line, start.column), "gen".len());
+                        if !after_async {
+                            let start = keyword.span().start();
+                            positions.insert((start.line, start.column), "move".len());
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            TokenTree::Group(group) => gen_keywords(group.stream(), positions),
+            _ => {}
+        }
+    }
+}
+
+// Function to prepare the source of a file for syn according to its edition
+pub fn prepare_source<'a>(content: &'a str, edition: &str) -> Cow<'a, str> {
+    if edition < "2024" || !content.contains("gen") {
+        return Cow::Borrowed(content);
+    }
+    let Ok(tokens) = content.parse::<TokenStream>() else {
+        return Cow::Borrowed(content);
+    };
+    let mut positions = HashMap::new();
+    gen_keywords(tokens, &mut positions);
+    if positions.is_empty() {
+        return Cow::Borrowed(content);
+    }
+
+    // Token columns count characters from 0 on lines counted from 1
+    let mut prepared = String::with_capacity(content.len());
+    for (line_index, line) in content.split_inclusive('\n').enumerate() {
+        let mut blank = 0;
+        for (column, ch) in line.chars().enumerate() {
+            if let Some(&len) = positions.get(&(line_index + 1, column)) {
+                blank = len;
+            }
+            if blank > 0 {
+                prepared.push(' ');
+                blank -= 1;
+            } else {
+                prepared.push(ch);
+            }
+        }
+    }
+    Cow::Owned(prepared)
+}
+
+// Function to parse a source file as the given edition
+pub fn parse_file(content: &str, edition: &str) -> syn::Result<syn::File> {
+    syn::parse_file(&prepare_source(content, edition))
+}
+
+// Function to parse the items of a file syn cannot parse as a whole
+// Tokens that do not start an item are skipped one token tree at a time, so an item with a
+// syntax error is lost with the items nested in it; source that cannot even be split into
+// tokens gives no items
+pub fn recover_items(content: &str, edition: &str) -> syn::File {
+    let items = |input: ParseStream| -> syn::Result<Vec<syn::Item>> {
+        let mut items = Vec::new();
+        while !input.is_empty() {
+            let fork = input.fork();
+            match fork.parse::<syn::Item>() {
+                Ok(item) => {
+                    input.advance_to(&fork);
+                    items.push(item);
+                }
+                Err(_) => {
+                    input.parse::<TokenTree>()?;
+                }
+            }
+        }
+        Ok(items)
+    };
+    let items = prepare_source(content, edition)
+        .parse::<TokenStream>()
+        .ok()
+        .and_then(|tokens| items.parse2(tokens).ok())
+        .unwrap_or_default();
+    syn::File {
+        shebang: None,
+        attrs: Vec::new(),
+        items,
+    }
+}
+
+// Function to record why a file could not be parsed
+pub fn parse_failure(
+    file_path: &Path,
+    edition: &str,
+    error: &syn::Error,
+    fallback: bool,
+) -> ParseFailure {
+    let start = error.span().start();
+    ParseFailure {
+        file_path: file_path.to_path_buf(),
+        edition: edition.to_string(),
+        line_number: start.line,
+        column: start.column + 1,
+        message: error.to_string(),
+        fallback,
+    }
+}
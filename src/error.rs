@@ -0,0 +1,201 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Errors of the analysis and the library API.
+// Every failure names what it failed on: the file that could not be read or parsed, the
+// manifest that is not valid TOML, the output format that could not be written, the
+// option that is wrong or the check the run failed. Library users match on the variants;
+// the command line prints the message and exits with the code of the variant (see
+// `exit_code`), so scripts can tell a bad option from an unreadable project.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+// Exit codes, following the BSD sysexits convention
+pub const EXIT_FAILURE: u8 = 1; // A check failed, e.g. a threshold that was exceeded
+pub const EXIT_DATAERR: u8 = 65; // A source file, manifest or report could not be parsed
+pub const EXIT_CANTCREAT: u8 = 73; // An output could not be written
+pub const EXIT_IOERR: u8 = 74; // A file or directory could not be read
+pub const EXIT_CONFIG: u8 = 78; // An option or the project setup is wrong
+
+// A failure of the analysis
+#[derive(Debug, Error)]
+pub enum ForestError {
+    // A file or directory could not be read
+    #[error("{}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    // syn could not parse a source file, and the fallback parser was not allowed, or a JSON
+    // report (baseline, rustdoc JSON) is not valid JSON
+    #[error("{}:{line}:{column}: parse error: {message}", path.display())]
+    Parse {
+        path: PathBuf,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    // A Cargo.toml is not valid TOML
+    #[error("{}: invalid TOML: {source}", path.display())]
+    Toml {
+        path: PathBuf,
+        #[source]
+        source: toml::de::Error,
+    },
+    // An output format could not be written
+    #[error("cannot write the {format} output: {message}")]
+    Format { format: String, message: String },
+    // An option, or the project it points at, is wrong
+    #[error("{0}")]
+    Config(String),
+    // The analysis ran, but the run failed a check: a threshold, --strict or --fail-on
+    #[error("{0}")]
+    Check(String),
+}
+
+impl ForestError {
+    pub fn io(path: impl Into<PathBuf>, source: io::Error) -> Self {
+        ForestError::Io {
+            path: path.into(),
+            source,
+        }
+    }
+
+    // Function to describe a syn parse error at the location it points at
+    pub fn parse(path: impl Into<PathBuf>, error: &syn::Error) -> Self {
+        let start = error.span().start();
+        ForestError::Parse {
+            path: path.into(),
+            line: start.line,
+            column: start.column + 1,
+            message: error.to_string(),
+        }
+    }
+
+    // Function to describe a serde_json parse error of a report at the location it points at
+    pub fn json(path: impl Into<PathBuf>, error: &serde_json::Error) -> Self {
+        ForestError::Parse {
+            path: path.into(),
+            line: error.line(),
+            column: error.column(),
+            message: error.to_string(),
+        }
+    }
+
+    pub fn format(format: &str, error: impl std::fmt::Display) -> Self {
+        ForestError::Format {
+            format: format.to_string(),
+            message: error.to_string(),
+        }
+    }
+
+    // Function to describe an output file of the given format that could not be written
+    pub fn output(format: &str, path: impl AsRef<Path>, error: impl std::fmt::Display) -> Self {
+        ForestError::format(format, format!("{}: {}", path.as_ref().display(), error))
+    }
+
+    // The file the error is about, if any
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            ForestError::Io { path, .. }
+            | ForestError::Parse { path, .. }
+            | ForestError::Toml { path, .. } => Some(path),
+            ForestError::Format { .. } | ForestError::Config(_) | ForestError::Check(_) => None,
+        }
+    }
+
+    // The message without the path, as recorded for a skipped file
+    pub fn detail(&self) -> String {
+        match self {
+            ForestError::Io { source, .. } => source.to_string(),
+            ForestError::Parse { message, .. } => format!("parse error: {}", message),
+            ForestError::Toml { source, .. } => format!("invalid TOML: {}", source),
+            ForestError::Format { .. } | ForestError::Config(_) | ForestError::Check(_) => {
+                self.to_string()
+            }
+        }
+    }
+
+    // Exit code of the command line for this error
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            ForestError::Io { .. } => EXIT_IOERR,
+            ForestError::Parse { .. } | ForestError::Toml { .. } => EXIT_DATAERR,
+            ForestError::Format { .. } => EXIT_CANTCREAT,
+            ForestError::Config(_) => EXIT_CONFIG,
+            ForestError::Check(_) => EXIT_FAILURE,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_error() -> ForestError {
+        let error = syn::parse_file("fn main() {\n    let = 1;\n}\n").unwrap_err();
+        ForestError::parse("src/main.rs", &error)
+    }
+
+    #[test]
+    fn exit_codes_follow_sysexits() {
+        let io = ForestError::io("src", io::Error::from(io::ErrorKind::NotFound));
+        assert_eq!(io.exit_code(), EXIT_IOERR);
+        assert_eq!(parse_error().exit_code(), EXIT_DATAERR);
+        assert_eq!(
+            ForestError::format("csv", "disk full").exit_code(),
+            EXIT_CANTCREAT
+        );
+        assert_eq!(ForestError::Config("bad".into()).exit_code(), EXIT_CONFIG);
+        assert_eq!(
+            ForestError::Check("failed".into()).exit_code(),
+            EXIT_FAILURE
+        );
+    }
+
+    #[test]
+    fn parse_errors_point_at_the_source() {
+        let error = parse_error();
+        assert!(matches!(
+            error,
+            ForestError::Parse {
+                line: 2,
+                column: 9,
+                ..
+            }
+        ));
+        assert!(error
+            .to_string()
+            .starts_with("src/main.rs:2:9: parse error: "));
+        assert!(error.detail().starts_with("parse error: "));
+        assert_eq!(error.path(), Some(Path::new("src/main.rs")));
+    }
+
+    #[test]
+    fn json_errors_point_at_the_report() {
+        let json_error = serde_json::from_str::<serde_json::Value>("{\n  \"a\": }").unwrap_err();
+        let error = ForestError::json("baseline.json", &json_error);
+        assert!(matches!(error, ForestError::Parse { line: 2, .. }));
+        assert_eq!(error.exit_code(), EXIT_DATAERR);
+    }
+
+    #[test]
+    fn output_errors_name_the_format_and_file() {
+        let error = ForestError::output("html", "out/report.html", "permission denied");
+        assert_eq!(
+            error.to_string(),
+            "cannot write the html output: out/report.html: permission denied"
+        );
+        assert_eq!(error.path(), None);
+        assert_eq!(error.detail(), error.to_string());
+    }
+
+    #[test]
+    fn io_detail_leaves_out_the_path() {
+        let error = ForestError::io("src/lib.rs", io::Error::other("denied"));
+        assert_eq!(error.to_string(), "src/lib.rs: denied");
+        assert_eq!(error.detail(), "denied");
+        assert_eq!(error.path(), Some(Path::new("src/lib.rs")));
+    }
+}
@@ -0,0 +1,212 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Error-handling sites.
+// Calls of `unwrap()` and `expect()`, the `panic!`, `todo!` and `unimplemented!` macros and
+// the `?` operator are listed with their location and counted per function. Functions are
+// then ranked by unwrap density, the number of `unwrap`/`expect` calls per line, as the
+// places most likely to panic on bad input.
+
+use crate::collector::macro_args;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+
+// Number of functions listed in the risk ranking
+const TOP_RISKY: usize = 10;
+
+// Macros that abort the program
+const PANIC_MACROS: &[&str] = &["panic", "todo", "unimplemented"];
+
+// A single error-handling site
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ErrorSite {
+    pub kind: String, // "unwrap", "expect", "panic!", "todo!", "unimplemented!" or "?"
+    pub function: String, // Path of the enclosing function, or module for code outside one
+    #[serde(rename = "file")]
+    pub file_path: PathBuf,
+    #[serde(rename = "line")]
+    pub line_number: usize,
+}
+
+// Error-handling counts of a single function
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct FunctionErrors {
+    pub function: String,
+    pub file_path: PathBuf,
+    pub line_number: usize,
+    pub lines: usize,
+    pub unwraps: usize,
+    pub expects: usize,
+    pub panics: usize, // panic!, todo! and unimplemented!
+    pub tries: usize,  // ? operators
+}
+
+impl FunctionErrors {
+    // unwrap and expect calls per line
+    pub fn unwrap_density(&self) -> f64 {
+        (self.unwraps + self.expects) as f64 / self.lines.max(1) as f64
+    }
+}
+
+// Visitor that finds the error-handling sites of a file
+struct ErrorVisitor<'a> {
+    file_path: &'a Path,
+    scope: String,
+    current: Option<usize>, // Index of the function being visited
+    sites: &'a mut Vec<ErrorSite>,
+    functions: &'a mut Vec<FunctionErrors>,
+}
+
+impl ErrorVisitor<'_> {
+    fn record(&mut self, kind: &str, line_number: usize) {
+        if let Some(function) = self.current.map(|index| &mut self.functions[index]) {
+            match kind {
+                "unwrap" => function.unwraps += 1,
+                "expect" => function.expects += 1,
+                "?" => function.tries += 1,
+                _ => function.panics += 1,
+            }
+        }
+        self.sites.push(ErrorSite {
+            kind: kind.to_string(),
+            function: self.scope.clone(),
+            file_path: self.file_path.to_path_buf(),
+            line_number,
+        });
+    }
+
+    fn visit_in_scope(&mut self, name: &impl std::fmt::Display, visit: impl FnOnce(&mut Self)) {
+        let inner_scope = format!("{}::{}", self.scope, name);
+        let outer_scope = std::mem::replace(&mut self.scope, inner_scope);
+        visit(self);
+        self.scope = outer_scope;
+    }
+
+    fn visit_function(
+        &mut self,
+        sig: &syn::Signature,
+        body: &syn::Block,
+        visit: impl FnOnce(&mut Self),
+    ) {
+        let start = sig.span().start().line;
+        let end = body.brace_token.span.close().end().line;
+        self.visit_in_scope(&sig.ident, |visitor| {
+            visitor.functions.push(FunctionErrors {
+                function: visitor.scope.clone(),
+                file_path: visitor.file_path.to_path_buf(),
+                line_number: start,
+                lines: end.saturating_sub(start) + 1,
+                ..FunctionErrors::default()
+            });
+            let outer = visitor.current.replace(visitor.functions.len() - 1);
+            visit(visitor);
+            visitor.current = outer;
+        });
+    }
+}
+
+impl<'ast> Visit<'ast> for ErrorVisitor<'_> {
+    fn visit_item_fn(&mut self, item_fn: &'ast syn::ItemFn) {
+        self.visit_function(&item_fn.sig, &item_fn.block, |visitor| {
+            visit::visit_item_fn(visitor, item_fn)
+        });
+    }
+
+    fn visit_impl_item_fn(&mut self, method: &'ast syn::ImplItemFn) {
+        self.visit_function(&method.sig, &method.block, |visitor| {
+            visit::visit_impl_item_fn(visitor, method)
+        });
+    }
+
+    fn visit_trait_item_fn(&mut self, method: &'ast syn::TraitItemFn) {
+        match method.default {
+            Some(ref body) => self.visit_function(&method.sig, body, |visitor| {
+                visit::visit_trait_item_fn(visitor, method)
+            }),
+            None => visit::visit_trait_item_fn(self, method),
+        }
+    }
+
+    fn visit_item_impl(&mut self, item_impl: &'ast syn::ItemImpl) {
+        let name = crate::impl_blocks::type_name(&item_impl.self_ty);
+        self.visit_in_scope(&name, |visitor| visit::visit_item_impl(visitor, item_impl));
+    }
+
+    fn visit_item_trait(&mut self, item_trait: &'ast syn::ItemTrait) {
+        self.visit_in_scope(&item_trait.ident, |visitor| {
+            visit::visit_item_trait(visitor, item_trait)
+        });
+    }
+
+    fn visit_item_mod(&mut self, item_mod: &'ast syn::ItemMod) {
+        self.visit_in_scope(&item_mod.ident, |visitor| {
+            visit::visit_item_mod(visitor, item_mod)
+        });
+    }
+
+    fn visit_expr_method_call(&mut self, call: &'ast syn::ExprMethodCall) {
+        let method = call.method.to_string();
+        if method == "unwrap" || method == "expect" {
+            self.record(&method, call.method.span().start().line);
+        }
+        visit::visit_expr_method_call(self, call);
+    }
+
+    fn visit_expr_try(&mut self, expr_try: &'ast syn::ExprTry) {
+        self.record("?", expr_try.question_token.span.start().line);
+        visit::visit_expr_try(self, expr_try);
+    }
+
+    // Macro arguments such as `println!("{}", x.unwrap())` are only tokens to syn
+    fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+        if let Some(segment) = mac.path.segments.last() {
+            let name = segment.ident.to_string();
+            if PANIC_MACROS.contains(&name.as_str()) {
+                self.record(&format!("{}!", name), segment.ident.span().start().line);
+            }
+        }
+        if let Some(args) = macro_args(mac) {
+            for arg in &args {
+                self.visit_expr(arg);
+            }
+        }
+    }
+}
+
+// Function to collect the error-handling sites and per-function counts of a parsed file
+pub fn collect_error_handling(
+    file_ast: &syn::File,
+    file_path: &Path,
+    module_path: &str,
+    sites: &mut Vec<ErrorSite>,
+    functions: &mut Vec<FunctionErrors>,
+) {
+    let mut visitor = ErrorVisitor {
+        file_path,
+        scope: module_path.to_string(),
+        current: None,
+        sites,
+        functions,
+    };
+    visitor.visit_file(file_ast);
+}
+
+// Function to count the sites of one kind, e.g. "unwrap"
+pub fn count_kind(sites: &[ErrorSite], kind: &str) -> usize {
+    sites.iter().filter(|site| site.kind == kind).count()
+}
+
+// Function to rank functions by unwrap density, riskiest first
+pub fn riskiest(functions: &[FunctionErrors]) -> Vec<&FunctionErrors> {
+    let mut ranked: Vec<&FunctionErrors> = functions
+        .iter()
+        .filter(|function| function.unwraps + function.expects > 0)
+        .collect();
+    ranked.sort_by(|a, b| {
+        b.unwrap_density()
+            .total_cmp(&a.unwrap_density())
+            .then_with(|| a.function.cmp(&b.function))
+    });
+    ranked.truncate(TOP_RISKY);
+    ranked
+}
@@ -0,0 +1,143 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Exported symbol inventory.
+// Items marked `#[no_mangle]` or `#[export_name = "..."]` (also in their edition 2024
+// `#[unsafe(...)]` form) and `pub extern "C"` functions make up the binary interface
+// of a cdylib or staticlib, so they are collected into one table for auditing.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use syn::visit::{self, Visit};
+
+// A symbol exported from the compiled crate
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ExportedSymbol {
+    pub symbol: String, // Name in the binary
+    #[serde(rename = "item")]
+    pub item_name: String, // Name in the Rust source
+    pub kind: String,   // "fn" or "static"
+    pub reason: String, // "no_mangle", "export_name" or "extern"
+    pub abi: Option<String>,
+    #[serde(rename = "file")]
+    pub file_path: PathBuf,
+    #[serde(rename = "line")]
+    pub line_number: usize,
+}
+
+// How the attributes of an item export it
+enum Export {
+    NoMangle,
+    ExportName(String),
+}
+
+// Function to find the export attribute of an item
+fn export_attribute(attrs: &[syn::Attribute]) -> Option<Export> {
+    for attr in attrs {
+        if attr.path().is_ident("no_mangle") {
+            return Some(Export::NoMangle);
+        }
+        if let syn::Meta::NameValue(ref name_value) = attr.meta {
+            if name_value.path.is_ident("export_name") {
+                return export_name_value(&name_value.value).map(Export::ExportName);
+            }
+        }
+        // Edition 2024 spells these `#[unsafe(no_mangle)]` and `#[unsafe(export_name = "...")]`
+        if attr.path().is_ident("unsafe") {
+            if let Ok(inner) = attr.parse_args::<syn::Meta>() {
+                if inner.path().is_ident("no_mangle") {
+                    return Some(Export::NoMangle);
+                }
+                if let syn::Meta::NameValue(ref name_value) = inner {
+                    if name_value.path.is_ident("export_name") {
+                        return export_name_value(&name_value.value).map(Export::ExportName);
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+fn export_name_value(value: &syn::Expr) -> Option<String> {
+    match value {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Str(name),
+            ..
+        }) => Some(name.value()),
+        _ => None,
+    }
+}
+
+// Function to get the ABI name of an `extern` qualifier (`extern` alone means "C")
+pub fn abi_name(abi: &syn::Abi) -> String {
+    abi.name
+        .as_ref()
+        .map(|name| name.value())
+        .unwrap_or_else(|| "C".to_string())
+}
+
+// Visitor that collects exported functions and statics
+struct ExportVisitor<'a> {
+    file_path: &'a Path,
+    symbols: &'a mut Vec<ExportedSymbol>,
+}
+
+impl ExportVisitor<'_> {
+    fn push(
+        &mut self,
+        ident: &syn::Ident,
+        kind: &str,
+        export: Option<Export>,
+        abi: Option<String>,
+    ) {
+        let item_name = ident.to_string();
+        let (symbol, reason) = match export {
+            Some(Export::NoMangle) => (item_name.clone(), "no_mangle"),
+            Some(Export::ExportName(name)) => (name, "export_name"),
+            // Without either attribute the symbol name is mangled
+            None => (format!("{} (mangled)", item_name), "extern"),
+        };
+        self.symbols.push(ExportedSymbol {
+            symbol,
+            item_name,
+            kind: kind.to_string(),
+            reason: reason.to_string(),
+            abi,
+            file_path: self.file_path.to_path_buf(),
+            line_number: ident.span().start().line,
+        });
+    }
+}
+
+impl<'ast> Visit<'ast> for ExportVisitor<'_> {
+    fn visit_item_fn(&mut self, item_fn: &'ast syn::ItemFn) {
+        let export = export_attribute(&item_fn.attrs);
+        let abi = item_fn.sig.abi.as_ref().map(abi_name);
+        let public_extern = matches!(item_fn.vis, syn::Visibility::Public(_))
+            && abi.as_deref().is_some_and(|abi| abi != "Rust");
+        if export.is_some() || public_extern {
+            self.push(&item_fn.sig.ident, "fn", export, abi);
+        }
+        visit::visit_item_fn(self, item_fn);
+    }
+
+    fn visit_item_static(&mut self, item_static: &'ast syn::ItemStatic) {
+        if let Some(export) = export_attribute(&item_static.attrs) {
+            self.push(&item_static.ident, "static", Some(export), None);
+        }
+        visit::visit_item_static(self, item_static);
+    }
+
+    fn visit_impl_item_fn(&mut self, method: &'ast syn::ImplItemFn) {
+        if let Some(export) = export_attribute(&method.attrs) {
+            let abi = method.sig.abi.as_ref().map(abi_name);
+            self.push(&method.sig.ident, "fn", Some(export), abi);
+        }
+        visit::visit_impl_item_fn(self, method);
+    }
+}
+
+// Function to collect the exported symbols of a parsed file
+pub fn collect_exports(file_ast: &syn::File, file_path: &Path, symbols: &mut Vec<ExportedSymbol>) {
+    let mut visitor = ExportVisitor { file_path, symbols };
+    visitor.visit_file(file_ast);
+}
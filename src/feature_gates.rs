@@ -0,0 +1,249 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Feature-gate map.
+// Items behind `#[cfg(feature = "...")]` or `#[cfg_attr(feature = "...", ...)]` and
+// `cfg!(feature = "...")` checks are grouped by feature and cross-checked against the
+// features the package declares in Cargo.toml, so code that only exists behind a flag,
+// gates on undeclared features and declared features nothing uses all stand out.
+
+use proc_macro2::{TokenStream, TokenTree};
+use quote::ToTokens;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+
+// Something gated on a feature
+#[derive(Clone, Serialize, Deserialize)]
+pub struct FeatureGate {
+    pub feature: String,
+    pub crate_name: String,
+    pub gated: String, // What is gated, e.g. "fn parse" or "cfg!"
+    pub file_path: PathBuf,
+    pub line_number: usize,
+}
+
+// The features a package declares
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DeclaredFeatures {
+    pub crate_name: String,
+    pub features: Vec<String>,
+}
+
+// All the gates of one feature of one crate
+pub struct FeatureReport<'a> {
+    pub crate_name: &'a str,
+    pub feature: &'a str,
+    pub declared: bool,
+    pub gates: Vec<&'a FeatureGate>,
+}
+
+// Function to find the feature names in a cfg predicate, e.g. all(feature = "a", unix)
+fn feature_names(tokens: TokenStream, names: &mut Vec<String>) {
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    for (index, token) in tokens.iter().enumerate() {
+        match token {
+            TokenTree::Ident(ident) if ident == "feature" => {
+                if let (Some(TokenTree::Punct(eq)), Some(TokenTree::Literal(literal))) =
+                    (tokens.get(index + 1), tokens.get(index + 2))
+                {
+                    if eq.as_char() == '=' {
+                        names.push(literal.to_string().trim_matches('"').to_string());
+                    }
+                }
+            }
+            TokenTree::Group(group) => feature_names(group.stream(), names),
+            _ => {}
+        }
+    }
+}
+
+// Function to find the features an item's attributes gate it on
+fn gating_features(attrs: &[syn::Attribute]) -> Vec<String> {
+    let mut names = Vec::new();
+    for attr in attrs {
+        if attr.path().is_ident("cfg") || attr.path().is_ident("cfg_attr") {
+            if let syn::Meta::List(ref list) = attr.meta {
+                let predicate = if attr.path().is_ident("cfg_attr") {
+                    // Only the predicate before the first comma gates the attributes
+                    list.tokens
+                        .clone()
+                        .into_iter()
+                        .take_while(
+                            |token| !matches!(token, TokenTree::Punct(p) if p.as_char() == ','),
+                        )
+                        .collect()
+                } else {
+                    list.tokens.clone()
+                };
+                feature_names(predicate, &mut names);
+            }
+        }
+    }
+    names
+}
+
+// Function to describe an item, e.g. "fn parse"; None for items without attributes to check
+fn describe_item(item: &syn::Item) -> Option<(&[syn::Attribute], String)> {
+    let (attrs, description) = match item {
+        syn::Item::Fn(item) => (&item.attrs, format!("fn {}", item.sig.ident)),
+        syn::Item::Struct(item) => (&item.attrs, format!("struct {}", item.ident)),
+        syn::Item::Enum(item) => (&item.attrs, format!("enum {}", item.ident)),
+        syn::Item::Trait(item) => (&item.attrs, format!("trait {}", item.ident)),
+        syn::Item::Mod(item) => (&item.attrs, format!("mod {}", item.ident)),
+        syn::Item::Const(item) => (&item.attrs, format!("const {}", item.ident)),
+        syn::Item::Static(item) => (&item.attrs, format!("static {}", item.ident)),
+        syn::Item::Type(item) => (&item.attrs, format!("type {}", item.ident)),
+        syn::Item::Union(item) => (&item.attrs, format!("union {}", item.ident)),
+        syn::Item::Use(item) => (
+            &item.attrs,
+            format!("use {}", item.tree.to_token_stream()).replace(' ', ""),
+        ),
+        syn::Item::Impl(item) => (
+            &item.attrs,
+            format!("impl {}", crate::impl_blocks::type_name(&item.self_ty)),
+        ),
+        syn::Item::Macro(item) => (
+            &item.attrs,
+            format!("{}!", item.mac.path.to_token_stream()).replace(' ', ""),
+        ),
+        _ => return None,
+    };
+    Some((attrs, description))
+}
+
+// Visitor that collects feature gates
+struct FeatureVisitor<'a> {
+    file_path: &'a Path,
+    crate_name: &'a str,
+    gates: &'a mut Vec<FeatureGate>,
+}
+
+impl FeatureVisitor<'_> {
+    fn record(&mut self, attrs: &[syn::Attribute], gated: String, line_number: usize) {
+        for feature in gating_features(attrs) {
+            self.gates.push(FeatureGate {
+                feature,
+                crate_name: self.crate_name.to_string(),
+                gated: gated.clone(),
+                file_path: self.file_path.to_path_buf(),
+                line_number,
+            });
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for FeatureVisitor<'_> {
+    fn visit_item(&mut self, item: &'ast syn::Item) {
+        if let Some((attrs, description)) = describe_item(item) {
+            self.record(attrs, description, item.span().start().line);
+        }
+        visit::visit_item(self, item);
+    }
+
+    fn visit_impl_item_fn(&mut self, method: &'ast syn::ImplItemFn) {
+        self.record(
+            &method.attrs,
+            format!("fn {}", method.sig.ident),
+            method.sig.ident.span().start().line,
+        );
+        visit::visit_impl_item_fn(self, method);
+    }
+
+    fn visit_field(&mut self, field: &'ast syn::Field) {
+        if let Some(ref ident) = field.ident {
+            self.record(
+                &field.attrs,
+                format!("field {}", ident),
+                ident.span().start().line,
+            );
+        }
+        visit::visit_field(self, field);
+    }
+
+    fn visit_variant(&mut self, variant: &'ast syn::Variant) {
+        self.record(
+            &variant.attrs,
+            format!("variant {}", variant.ident),
+            variant.ident.span().start().line,
+        );
+        visit::visit_variant(self, variant);
+    }
+
+    fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+        if mac.path.is_ident("cfg") {
+            let mut names = Vec::new();
+            feature_names(mac.tokens.clone(), &mut names);
+            for feature in names {
+                self.gates.push(FeatureGate {
+                    feature,
+                    crate_name: self.crate_name.to_string(),
+                    gated: "cfg!".to_string(),
+                    file_path: self.file_path.to_path_buf(),
+                    line_number: mac.path.span().start().line,
+                });
+            }
+        }
+        visit::visit_macro(self, mac);
+    }
+}
+
+// Function to collect the feature gates of a parsed file
+pub fn collect_feature_gates(
+    file_ast: &syn::File,
+    file_path: &Path,
+    gates: &mut Vec<FeatureGate>,
+    declared: &mut Vec<DeclaredFeatures>,
+) {
+    let (crate_name, features) = crate::cargo::features_for(file_path).unwrap_or_default();
+    let mut visitor = FeatureVisitor {
+        file_path,
+        crate_name: &crate_name,
+        gates,
+    };
+    visitor.visit_file(file_ast);
+    declared.push(DeclaredFeatures {
+        crate_name,
+        features,
+    });
+}
+
+// Function to group the gates by crate and feature, including declared but unused features
+pub fn feature_report<'a>(
+    gates: &'a [FeatureGate],
+    declared: &'a [DeclaredFeatures],
+) -> Vec<FeatureReport<'a>> {
+    let declared_set: BTreeSet<(&str, &str)> = declared
+        .iter()
+        .flat_map(|package| {
+            package
+                .features
+                .iter()
+                .map(move |feature| (package.crate_name.as_str(), feature.as_str()))
+        })
+        .collect();
+
+    let mut by_feature: BTreeMap<(&str, &str), Vec<&FeatureGate>> = BTreeMap::new();
+    for gate in gates {
+        by_feature
+            .entry((gate.crate_name.as_str(), gate.feature.as_str()))
+            .or_default()
+            .push(gate);
+    }
+    // "default" only lists other features, so it is never gated on directly
+    for &key in &declared_set {
+        if key.1 != "default" {
+            by_feature.entry(key).or_default();
+        }
+    }
+
+    by_feature
+        .into_iter()
+        .map(|((crate_name, feature), gates)| FeatureReport {
+            crate_name,
+            feature,
+            declared: declared_set.contains(&(crate_name, feature)),
+            gates,
+        })
+        .collect()
+}
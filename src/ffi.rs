@@ -0,0 +1,222 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Foreign function interface inventory.
+// The items declared in `extern` blocks are collected with their ABI and the library
+// named by `#[link(name = "...")]`, and every function parameter whose type holds a raw
+// pointer is listed. Together with the exported symbols they make up the FFI surface a
+// security review has to cover.
+
+use quote::ToTokens;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use syn::visit::{self, Visit};
+
+use crate::exports::abi_name;
+use crate::globals::type_text;
+
+// A function, static or type declared in an `extern` block
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ForeignItem {
+    pub name: String,
+    pub kind: String, // "fn", "static" or "type"
+    pub abi: String,
+    pub link: Option<String>, // Library from #[link(name = "...")]
+    pub declaration: String,  // e.g. fn strlen(s: *const c_char) -> usize
+    #[serde(rename = "file")]
+    pub file_path: PathBuf,
+    #[serde(rename = "line")]
+    pub line_number: usize,
+}
+
+// A function parameter that takes a raw pointer
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RawPointerParam {
+    pub function: String, // Path of the function
+    pub param: String,
+    #[serde(rename = "type")]
+    pub type_name: String,
+    #[serde(rename = "file")]
+    pub file_path: PathBuf,
+    #[serde(rename = "line")]
+    pub line_number: usize,
+}
+
+// Visitor that checks whether a type holds a raw pointer anywhere, e.g. Option<*mut T>
+#[derive(Default)]
+struct PointerFinder {
+    found: bool,
+}
+
+impl<'ast> Visit<'ast> for PointerFinder {
+    fn visit_type_ptr(&mut self, _ptr: &'ast syn::TypePtr) {
+        self.found = true;
+    }
+}
+
+// Function to find the library named by a `#[link(name = "...")]` attribute
+fn link_name(attrs: &[syn::Attribute]) -> Option<String> {
+    let mut name = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("link")) {
+        let _ = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                name = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+            } else if meta.input.peek(syn::Token![=]) {
+                // Skip the value of kind = "...", modifiers = "..." and the like
+                meta.value()?.parse::<syn::Expr>()?;
+            }
+            Ok(())
+        });
+    }
+    name
+}
+
+// Function to format a declaration without the spacing added by the token printer
+fn declaration_text(tokens: &impl ToTokens) -> String {
+    tokens
+        .to_token_stream()
+        .to_string()
+        .replace(" (", "(")
+        .replace("( ", "(")
+        .replace(" )", ")")
+        .replace(" :", ":")
+        .replace(" ,", ",")
+        .replace(" ;", "")
+        .replace("* ", "*")
+        .replace(" :: ", "::")
+}
+
+// Visitor that collects foreign items and raw pointer parameters
+struct FfiVisitor<'a> {
+    file_path: &'a Path,
+    scope: String,
+    foreign_items: &'a mut Vec<ForeignItem>,
+    pointer_params: &'a mut Vec<RawPointerParam>,
+}
+
+impl FfiVisitor<'_> {
+    fn check_params(&mut self, sig: &syn::Signature) {
+        for input in &sig.inputs {
+            if let syn::FnArg::Typed(pat_type) = input {
+                let mut finder = PointerFinder::default();
+                finder.visit_type(&pat_type.ty);
+                if finder.found {
+                    self.pointer_params.push(RawPointerParam {
+                        function: format!("{}::{}", self.scope, sig.ident),
+                        param: pat_type.pat.to_token_stream().to_string(),
+                        type_name: type_text(&pat_type.ty),
+                        file_path: self.file_path.to_path_buf(),
+                        line_number: sig.ident.span().start().line,
+                    });
+                }
+            }
+        }
+    }
+
+    fn visit_in_scope(&mut self, name: &impl std::fmt::Display, visit: impl FnOnce(&mut Self)) {
+        let inner_scope = format!("{}::{}", self.scope, name);
+        let outer_scope = std::mem::replace(&mut self.scope, inner_scope);
+        visit(self);
+        self.scope = outer_scope;
+    }
+}
+
+impl<'ast> Visit<'ast> for FfiVisitor<'_> {
+    fn visit_item_foreign_mod(&mut self, foreign_mod: &'ast syn::ItemForeignMod) {
+        let abi = abi_name(&foreign_mod.abi);
+        let link = link_name(&foreign_mod.attrs);
+        for item in &foreign_mod.items {
+            let (ident, kind, declaration) = match item {
+                syn::ForeignItem::Fn(foreign_fn) => {
+                    self.check_params(&foreign_fn.sig);
+                    (
+                        &foreign_fn.sig.ident,
+                        "fn",
+                        declaration_text(&foreign_fn.sig),
+                    )
+                }
+                syn::ForeignItem::Static(foreign_static) => (
+                    &foreign_static.ident,
+                    "static",
+                    format!(
+                        "static {}{}: {}",
+                        match foreign_static.mutability {
+                            syn::StaticMutability::Mut(_) => "mut ",
+                            _ => "",
+                        },
+                        foreign_static.ident,
+                        type_text(&foreign_static.ty)
+                    ),
+                ),
+                syn::ForeignItem::Type(foreign_type) => (
+                    &foreign_type.ident,
+                    "type",
+                    format!("type {}", foreign_type.ident),
+                ),
+                _ => continue,
+            };
+            self.foreign_items.push(ForeignItem {
+                name: ident.to_string(),
+                kind: kind.to_string(),
+                abi: abi.clone(),
+                link: link.clone(),
+                declaration,
+                file_path: self.file_path.to_path_buf(),
+                line_number: ident.span().start().line,
+            });
+        }
+    }
+
+    fn visit_item_fn(&mut self, item_fn: &'ast syn::ItemFn) {
+        self.check_params(&item_fn.sig);
+        self.visit_in_scope(&item_fn.sig.ident, |visitor| {
+            visit::visit_item_fn(visitor, item_fn)
+        });
+    }
+
+    fn visit_impl_item_fn(&mut self, method: &'ast syn::ImplItemFn) {
+        self.check_params(&method.sig);
+        self.visit_in_scope(&method.sig.ident, |visitor| {
+            visit::visit_impl_item_fn(visitor, method)
+        });
+    }
+
+    fn visit_trait_item_fn(&mut self, method: &'ast syn::TraitItemFn) {
+        self.check_params(&method.sig);
+        self.visit_in_scope(&method.sig.ident, |visitor| {
+            visit::visit_trait_item_fn(visitor, method)
+        });
+    }
+
+    fn visit_item_impl(&mut self, item_impl: &'ast syn::ItemImpl) {
+        let name = crate::impl_blocks::type_name(&item_impl.self_ty);
+        self.visit_in_scope(&name, |visitor| visit::visit_item_impl(visitor, item_impl));
+    }
+
+    fn visit_item_trait(&mut self, item_trait: &'ast syn::ItemTrait) {
+        self.visit_in_scope(&item_trait.ident, |visitor| {
+            visit::visit_item_trait(visitor, item_trait)
+        });
+    }
+
+    fn visit_item_mod(&mut self, item_mod: &'ast syn::ItemMod) {
+        self.visit_in_scope(&item_mod.ident, |visitor| {
+            visit::visit_item_mod(visitor, item_mod)
+        });
+    }
+}
+
+// Function to collect the foreign items and raw pointer parameters of a parsed file
+pub fn collect_ffi(
+    file_ast: &syn::File,
+    file_path: &Path,
+    module_path: &str,
+    foreign_items: &mut Vec<ForeignItem>,
+    pointer_params: &mut Vec<RawPointerParam>,
+) {
+    let mut visitor = FfiVisitor {
+        file_path,
+        scope: module_path.to_string(),
+        foreign_items,
+        pointer_params,
+    };
+    visitor.visit_file(file_ast);
+}
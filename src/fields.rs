@@ -0,0 +1,136 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Struct field and enum variant details.
+// The fields of each struct are recorded with their declared type and visibility, and the
+// type is classified as a reference, an Option, a collection or a plain value, which is
+// what matters most when auditing a data layout. Enum variants are recorded with their
+// kind, payload fields and explicit discriminant.
+
+use serde::{Deserialize, Serialize};
+
+// Standard collection types, matched on the last path segment
+const COLLECTIONS: &[&str] = &[
+    "Vec",
+    "VecDeque",
+    "LinkedList",
+    "HashMap",
+    "BTreeMap",
+    "HashSet",
+    "BTreeSet",
+    "BinaryHeap",
+];
+
+// A field of a struct or enum variant
+#[derive(Clone, Serialize, Deserialize)]
+pub struct FieldInfo {
+    pub name: String, // Field name, or its index for tuple fields
+    pub type_name: String,
+    pub visibility: String, // "pub", "pub(crate)", ... or "private"
+    pub shape: String,      // "reference", "option", "collection" or "value"
+}
+
+impl FieldInfo {
+    // One-line description, e.g. `pub items: Vec<u32> (collection)`
+    pub fn describe(&self) -> String {
+        let visibility = if self.visibility == "private" {
+            String::new()
+        } else {
+            format!("{} ", self.visibility)
+        };
+        format!(
+            "{}{}: {} ({})",
+            visibility, self.name, self.type_name, self.shape
+        )
+    }
+}
+
+// A variant of an enum
+#[derive(Clone, Serialize, Deserialize)]
+pub struct VariantInfo {
+    pub name: String,
+    pub kind: String, // "unit", "tuple" or "struct"
+    pub payload: Vec<FieldInfo>,
+    pub discriminant: Option<String>, // Explicit `= value`, if any
+}
+
+impl VariantInfo {
+    // One-line description, e.g. `Move { x: i32, y: i32 }` or `Quit = 3`
+    pub fn describe(&self) -> String {
+        let payload: Vec<String> = self
+            .payload
+            .iter()
+            .map(|field| match self.kind.as_str() {
+                "struct" => format!("{}: {}", field.name, field.type_name),
+                _ => field.type_name.clone(),
+            })
+            .collect();
+        let mut text = match self.kind.as_str() {
+            "struct" => format!("{} {{ {} }}", self.name, payload.join(", ")),
+            "tuple" => format!("{}({})", self.name, payload.join(", ")),
+            _ => self.name.clone(),
+        };
+        if let Some(ref discriminant) = self.discriminant {
+            text.push_str(" = ");
+            text.push_str(discriminant);
+        }
+        text
+    }
+}
+
+// Function to check whether a type name is a standard collection
+pub fn is_collection(name: &str) -> bool {
+    COLLECTIONS.contains(&name)
+}
+
+// Function to classify a field type
+fn shape(ty: &syn::Type) -> &'static str {
+    match ty {
+        syn::Type::Reference(_) | syn::Type::Ptr(_) => "reference",
+        syn::Type::Array(_) | syn::Type::Slice(_) => "collection",
+        syn::Type::Path(type_path) => match type_path.path.segments.last() {
+            Some(segment) if segment.ident == "Option" => "option",
+            Some(segment) if is_collection(&segment.ident.to_string()) => "collection",
+            _ => "value",
+        },
+        _ => "value",
+    }
+}
+
+// Function to describe the fields of a struct or enum variant
+pub fn collect_fields(fields: &syn::Fields) -> Vec<FieldInfo> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| FieldInfo {
+            name: field
+                .ident
+                .as_ref()
+                .map(|ident| ident.to_string())
+                .unwrap_or_else(|| index.to_string()),
+            type_name: crate::globals::type_text(&field.ty),
+            visibility: crate::globals::visibility_name(&field.vis),
+            shape: shape(&field.ty).to_string(),
+        })
+        .collect()
+}
+
+// Function to describe the variants of an enum
+pub fn collect_variants(item_enum: &syn::ItemEnum) -> Vec<VariantInfo> {
+    item_enum
+        .variants
+        .iter()
+        .map(|variant| VariantInfo {
+            name: variant.ident.to_string(),
+            kind: match variant.fields {
+                syn::Fields::Named(_) => "struct",
+                syn::Fields::Unnamed(_) => "tuple",
+                syn::Fields::Unit => "unit",
+            }
+            .to_string(),
+            payload: collect_fields(&variant.fields),
+            discriminant: variant
+                .discriminant
+                .as_ref()
+                .map(|(_, expr)| quote::quote!(#expr).to_string()),
+        })
+        .collect()
+}
@@ -0,0 +1,133 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Record filters (`--name`, `--type`, `--scope`, `--file`, `--mutability`).
+// The filters select the variables and data structures that are reported, in every output
+// format; the other sections still describe the whole project. A record is kept when it
+// matches every filter given. Regexes match anywhere in the text unless anchored: `--name`
+// matches names, `--type` the type of variables and the kind of data structures (`struct`,
+// `enum`, ...), and `--scope` the enclosing scope of variables and the path of data
+// structures. `--file` globs match paths relative to the project directory, e.g.
+// `src/parser/**`. `--mutability` only selects variables; data structures are kept.
+
+use crate::aggregate::Selection;
+use crate::{AnalysisResults, DataStructureInfo, ForestError, VarInfo};
+use globset::{GlobBuilder, GlobMatcher};
+use regex::Regex;
+use std::path::{Path, PathBuf};
+
+// Mutability values accepted by --mutability
+pub const MUTABILITIES: &[&str] = &["mutable", "immutable"];
+
+// The filters given on the command line
+pub struct Filters {
+    name: Option<Regex>,
+    type_name: Option<Regex>,
+    scope: Option<Regex>,
+    file: Option<GlobMatcher>,
+    mutability: Option<String>,
+    project_dir: PathBuf, // --file globs are relative to it
+}
+
+// Function to compile an optional regex, naming the flag it came from in errors
+fn compile(pattern: Option<&str>, flag: &str) -> Result<Option<Regex>, ForestError> {
+    pattern
+        .map(|pattern| {
+            Regex::new(pattern)
+                .map_err(|e| ForestError::Config(format!("invalid {} regex: {}", flag, e)))
+        })
+        .transpose()
+}
+
+impl Filters {
+    pub fn new(
+        name: Option<&str>,
+        type_name: Option<&str>,
+        scope: Option<&str>,
+        file: Option<&str>,
+        mutability: Option<&str>,
+        project_dir: &Path,
+    ) -> Result<Self, ForestError> {
+        // `*` stays within a directory, `**` crosses directories
+        let file = file
+            .map(|pattern| {
+                GlobBuilder::new(pattern)
+                    .literal_separator(true)
+                    .build()
+                    .map(|glob| glob.compile_matcher())
+                    .map_err(|e| ForestError::Config(format!("invalid --file glob: {}", e)))
+            })
+            .transpose()?;
+        Ok(Filters {
+            name: compile(name, "--name")?,
+            type_name: compile(type_name, "--type")?,
+            scope: compile(scope, "--scope")?,
+            file,
+            mutability: mutability.map(str::to_string),
+            project_dir: project_dir.to_path_buf(),
+        })
+    }
+
+    // Function to check whether any filter was given
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none()
+            && self.type_name.is_none()
+            && self.scope.is_none()
+            && self.file.is_none()
+            && self.mutability.is_none()
+    }
+
+    // Function to check a path against the --file glob, as given or relative to the project
+    fn keeps_file(&self, path: &Path) -> bool {
+        self.file.as_ref().is_none_or(|glob| {
+            let relative = crate::paths::rewrite(path, &self.project_dir, "relative");
+            glob.is_match(path) || relative.is_some_and(|relative| glob.is_match(relative))
+        })
+    }
+
+    fn keeps_var(&self, var: &VarInfo) -> bool {
+        let matches = |regex: &Option<Regex>, text: &str| {
+            regex.as_ref().is_none_or(|regex| regex.is_match(text))
+        };
+        matches(&self.name, &var.name)
+            && (matches(&self.type_name, &var.var_type)
+                || matches(&self.type_name, &var.basic_type))
+            && matches(&self.scope, &var.scope)
+            && self.keeps_file(&var.file_path)
+    }
+
+    fn keeps_structure(&self, data_structure: &DataStructureInfo) -> bool {
+        let matches = |regex: &Option<Regex>, text: &str| {
+            regex.as_ref().is_none_or(|regex| regex.is_match(text))
+        };
+        matches(&self.name, &data_structure.name)
+            && matches(&self.type_name, &data_structure.data_structure_type)
+            && matches(&self.scope, &data_structure.item_path)
+            && self.keeps_file(&data_structure.file_path)
+    }
+
+    // Function to select the matching variables and data structures without copying them
+    pub fn select<'a>(&self, results: &'a AnalysisResults) -> Selection<'a> {
+        let mutability = self.mutability.as_deref();
+        results
+            .filter(|var| {
+                mutability.is_none_or(|mutability| (mutability == "mutable") == var.mutable)
+                    && self.keeps_var(var)
+            })
+            .filter_data_structures(|data_structure| self.keeps_structure(data_structure))
+    }
+
+    // Function to drop the variables and data structures that do not match
+    pub fn apply(&self, results: &mut AnalysisResults) {
+        let mutability = self.mutability.as_deref();
+        if mutability == Some("immutable") {
+            results.mutable_vars.clear();
+        }
+        if mutability == Some("mutable") {
+            results.immutable_vars.clear();
+        }
+        results.mutable_vars.retain(|var| self.keeps_var(var));
+        results.immutable_vars.retain(|var| self.keeps_var(var));
+        results
+            .data_structures
+            .retain(|data_structure| self.keeps_structure(data_structure));
+    }
+}
@@ -0,0 +1,223 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Output formats written with --output, looked up by name in a registry.
+// Each format implements `OutputFormatter` and is registered under the name `--format`
+// selects. The built-in formats (text, json, csv, html and dot) are registered by
+// `Formatters::builtin()`; library users can register their own, or replace a built-in
+// one, and write a `Report` with it. A format that cannot be written fails with
+// `ForestError::Format`, naming the format.
+
+use crate::{
+    columns, csv_output, graph, html, output_json, results_model, text_output::output_text,
+    AnalysisMetadata, AnalysisResults, ForestError, OutputOptions,
+};
+
+// The results of an analysis as handed to a formatter, with the output settings
+pub struct Report<'a> {
+    results: &'a AnalysisResults,
+    metadata: &'a AnalysisMetadata,
+    options: OutputOptions<'a>,
+}
+
+impl<'a> Report<'a> {
+    // A report with the default output settings of the command line
+    pub fn new(results: &'a AnalysisResults, metadata: &'a AnalysisMetadata) -> Self {
+        Report::with_options(
+            results,
+            metadata,
+            OutputOptions {
+                graph: "modules",
+                csv: csv_output::CsvOptions {
+                    delimiter: b',',
+                    single_table: false,
+                },
+                columns: None,
+                group_by: None,
+                link: false,
+            },
+        )
+    }
+
+    pub(crate) fn with_options(
+        results: &'a AnalysisResults,
+        metadata: &'a AnalysisMetadata,
+        options: OutputOptions<'a>,
+    ) -> Self {
+        Report {
+            results,
+            metadata,
+            options,
+        }
+    }
+
+    pub fn results(&self) -> &AnalysisResults {
+        self.results
+    }
+
+    pub fn metadata(&self) -> &AnalysisMetadata {
+        self.metadata
+    }
+
+    // Whether editor links were asked for (--link)
+    pub fn link(&self) -> bool {
+        self.options.link
+    }
+
+    // The report as written by --format json, for formatters that convert it
+    pub fn json(&self) -> Result<serde_json::Value, serde_json::Error> {
+        serde_json::to_value(results_model(
+            self.results,
+            self.metadata,
+            self.options.link,
+        ))
+    }
+}
+
+// An output format
+pub trait OutputFormatter: Send + Sync {
+    // Name selected with --format, e.g. `json`
+    fn name(&self) -> &str;
+
+    // Function to write the report to the output file; a format may derive other file
+    // names from it, as csv does for its tables
+    fn write(&self, report: &Report, file: &str) -> Result<(), ForestError>;
+}
+
+// The formats that can be written, by name
+pub struct Formatters {
+    formatters: Vec<Box<dyn OutputFormatter>>,
+}
+
+impl Formatters {
+    // The formats built into forest
+    pub fn builtin() -> Self {
+        let mut formatters = Formatters {
+            formatters: Vec::new(),
+        };
+        formatters.register(TextFormatter);
+        formatters.register(JsonFormatter);
+        formatters.register(CsvFormatter);
+        formatters.register(HtmlFormatter);
+        formatters.register(DotFormatter);
+        formatters
+    }
+
+    // Function to add a format, replacing any format registered under the same name
+    pub fn register(&mut self, formatter: impl OutputFormatter + 'static) {
+        self.formatters
+            .retain(|registered| registered.name() != formatter.name());
+        self.formatters.push(Box::new(formatter));
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn OutputFormatter> {
+        self.formatters
+            .iter()
+            .find(|formatter| formatter.name() == name)
+            .map(|formatter| formatter.as_ref())
+    }
+
+    // Names of the registered formats, in the order they were registered
+    pub fn names(&self) -> Vec<&str> {
+        self.formatters
+            .iter()
+            .map(|formatter| formatter.name())
+            .collect()
+    }
+
+    // Function to write a report in the named format
+    pub fn write(&self, format: &str, report: &Report, file: &str) -> Result<(), ForestError> {
+        match self.get(format) {
+            Some(formatter) => formatter.write(report, file),
+            None => Err(ForestError::Config(format!(
+                "unknown format {} (expected one of: {})",
+                format,
+                self.names().join(", ")
+            ))),
+        }
+    }
+}
+
+struct TextFormatter;
+
+impl OutputFormatter for TextFormatter {
+    fn name(&self) -> &str {
+        "text"
+    }
+
+    fn write(&self, report: &Report, file: &str) -> Result<(), ForestError> {
+        let options = &report.options;
+        output_text(
+            report.results,
+            report.metadata,
+            file,
+            options.columns,
+            options.group_by,
+            options.link,
+        )
+    }
+}
+
+struct JsonFormatter;
+
+impl OutputFormatter for JsonFormatter {
+    fn name(&self) -> &str {
+        "json"
+    }
+
+    fn write(&self, report: &Report, file: &str) -> Result<(), ForestError> {
+        let options = &report.options;
+        output_json(
+            report.results,
+            report.metadata,
+            file,
+            options.group_by,
+            options.link,
+        )
+    }
+}
+
+struct CsvFormatter;
+
+impl OutputFormatter for CsvFormatter {
+    fn name(&self) -> &str {
+        "csv"
+    }
+
+    fn write(&self, report: &Report, file: &str) -> Result<(), ForestError> {
+        let options = &report.options;
+        csv_output::output_csv(
+            report.results,
+            report.metadata,
+            file,
+            &options.csv,
+            &options
+                .columns
+                .map_or_else(columns::default_columns, <[String]>::to_vec),
+            options.group_by,
+            options.link,
+        )
+    }
+}
+
+struct HtmlFormatter;
+
+impl OutputFormatter for HtmlFormatter {
+    fn name(&self) -> &str {
+        "html"
+    }
+
+    fn write(&self, report: &Report, file: &str) -> Result<(), ForestError> {
+        html::output_html(report.results, report.metadata, file)
+    }
+}
+
+struct DotFormatter;
+
+impl OutputFormatter for DotFormatter {
+    fn name(&self) -> &str {
+        "dot"
+    }
+
+    fn write(&self, report: &Report, file: &str) -> Result<(), ForestError> {
+        graph::output_dot(report.results, file, report.options.graph)
+    }
+}
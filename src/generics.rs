@@ -0,0 +1,227 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Generic and lifetime parameters.
+// Functions, structs, enums, traits and impls are recorded with their type, lifetime and
+// const parameters and their where-clause predicates, to help spot over-generic APIs.
+// Lifetime parameters are also totalled per module to show lifetime-heavy modules.
+
+use quote::ToTokens;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use syn::visit::{self, Visit};
+
+// Maximum number of modules listed as lifetime-heavy
+const TOP_MODULES: usize = 5;
+
+// The generic parameters of one item
+#[derive(Clone, Serialize, Deserialize)]
+pub struct GenericItem {
+    pub item: String, // Path of the item, e.g. crate::parser::Parser
+    pub kind: String, // "fn", "struct", "enum", "trait" or "impl"
+    pub module: String,
+    pub type_params: Vec<String>, // With their bounds, e.g. `T: Clone`
+    pub lifetimes: Vec<String>,
+    pub const_params: Vec<String>,
+    pub where_clauses: Vec<String>,
+    #[serde(rename = "file")]
+    pub file_path: PathBuf,
+    #[serde(rename = "line")]
+    pub line_number: usize,
+}
+
+impl GenericItem {
+    // The parameter list as written, e.g. `<'a, T: Clone> where T: Debug`
+    pub fn signature(&self) -> String {
+        let params: Vec<&str> = self
+            .lifetimes
+            .iter()
+            .chain(&self.type_params)
+            .chain(&self.const_params)
+            .map(String::as_str)
+            .collect();
+        let mut text = format!("<{}>", params.join(", "));
+        if !self.where_clauses.is_empty() {
+            text.push_str(" where ");
+            text.push_str(&self.where_clauses.join(", "));
+        }
+        text
+    }
+}
+
+// Function to format a generic parameter or predicate without the token printer's spacing
+fn tidy(tokens: &impl ToTokens) -> String {
+    tokens
+        .to_token_stream()
+        .to_string()
+        .replace(" :", ":")
+        .replace(":: ", "::")
+        .replace("' ", "'")
+        .replace(" <", "<")
+        .replace("< ", "<")
+        .replace(" >", ">")
+        .replace(" ,", ",")
+        .replace("& ", "&")
+}
+
+// Visitor that collects the generics of items
+struct GenericsVisitor<'a> {
+    file_path: &'a Path,
+    module: String,
+    scope: String, // Path of the enclosing impl or trait, for methods
+    items: &'a mut Vec<GenericItem>,
+}
+
+impl GenericsVisitor<'_> {
+    fn record(&mut self, name: &str, kind: &str, generics: &syn::Generics, line_number: usize) {
+        if generics.params.is_empty() && generics.where_clause.is_none() {
+            return;
+        }
+        let mut item = GenericItem {
+            item: format!("{}::{}", self.scope, name),
+            kind: kind.to_string(),
+            module: self.module.clone(),
+            type_params: Vec::new(),
+            lifetimes: Vec::new(),
+            const_params: Vec::new(),
+            where_clauses: Vec::new(),
+            file_path: self.file_path.to_path_buf(),
+            line_number,
+        };
+        for param in &generics.params {
+            match param {
+                syn::GenericParam::Type(_) => item.type_params.push(tidy(param)),
+                syn::GenericParam::Lifetime(_) => item.lifetimes.push(tidy(param)),
+                syn::GenericParam::Const(_) => item.const_params.push(tidy(param)),
+            }
+        }
+        if let Some(ref where_clause) = generics.where_clause {
+            item.where_clauses = where_clause.predicates.iter().map(tidy).collect();
+        }
+        self.items.push(item);
+    }
+
+    fn visit_in_scope(&mut self, name: &str, visit: impl FnOnce(&mut Self)) {
+        let inner_scope = format!("{}::{}", self.scope, name);
+        let outer_scope = std::mem::replace(&mut self.scope, inner_scope);
+        visit(self);
+        self.scope = outer_scope;
+    }
+}
+
+impl<'ast> Visit<'ast> for GenericsVisitor<'_> {
+    fn visit_item_fn(&mut self, item_fn: &'ast syn::ItemFn) {
+        let sig = &item_fn.sig;
+        self.record(
+            &sig.ident.to_string(),
+            "fn",
+            &sig.generics,
+            sig.fn_token.span.start().line,
+        );
+        visit::visit_item_fn(self, item_fn);
+    }
+
+    fn visit_impl_item_fn(&mut self, method: &'ast syn::ImplItemFn) {
+        let sig = &method.sig;
+        self.record(
+            &sig.ident.to_string(),
+            "fn",
+            &sig.generics,
+            sig.fn_token.span.start().line,
+        );
+        visit::visit_impl_item_fn(self, method);
+    }
+
+    fn visit_trait_item_fn(&mut self, method: &'ast syn::TraitItemFn) {
+        let sig = &method.sig;
+        self.record(
+            &sig.ident.to_string(),
+            "fn",
+            &sig.generics,
+            sig.fn_token.span.start().line,
+        );
+        visit::visit_trait_item_fn(self, method);
+    }
+
+    fn visit_item_struct(&mut self, item_struct: &'ast syn::ItemStruct) {
+        self.record(
+            &item_struct.ident.to_string(),
+            "struct",
+            &item_struct.generics,
+            item_struct.struct_token.span.start().line,
+        );
+        visit::visit_item_struct(self, item_struct);
+    }
+
+    fn visit_item_enum(&mut self, item_enum: &'ast syn::ItemEnum) {
+        self.record(
+            &item_enum.ident.to_string(),
+            "enum",
+            &item_enum.generics,
+            item_enum.enum_token.span.start().line,
+        );
+        visit::visit_item_enum(self, item_enum);
+    }
+
+    fn visit_item_trait(&mut self, item_trait: &'ast syn::ItemTrait) {
+        let name = item_trait.ident.to_string();
+        self.record(
+            &name,
+            "trait",
+            &item_trait.generics,
+            item_trait.trait_token.span.start().line,
+        );
+        self.visit_in_scope(&name, |visitor| {
+            visit::visit_item_trait(visitor, item_trait)
+        });
+    }
+
+    fn visit_item_impl(&mut self, item_impl: &'ast syn::ItemImpl) {
+        let name = crate::impl_blocks::type_name(&item_impl.self_ty);
+        self.record(
+            &name,
+            "impl",
+            &item_impl.generics,
+            item_impl.impl_token.span.start().line,
+        );
+        self.visit_in_scope(&name, |visitor| visit::visit_item_impl(visitor, item_impl));
+    }
+
+    fn visit_item_mod(&mut self, item_mod: &'ast syn::ItemMod) {
+        let inner_module = format!("{}::{}", self.module, item_mod.ident);
+        let outer_module = std::mem::replace(&mut self.module, inner_module.clone());
+        let outer_scope = std::mem::replace(&mut self.scope, inner_module);
+        visit::visit_item_mod(self, item_mod);
+        self.module = outer_module;
+        self.scope = outer_scope;
+    }
+}
+
+// Function to collect the generic items of a parsed file
+pub fn collect_generics(
+    file_ast: &syn::File,
+    file_path: &Path,
+    module_path: &str,
+    items: &mut Vec<GenericItem>,
+) {
+    let mut visitor = GenericsVisitor {
+        file_path,
+        module: module_path.to_string(),
+        scope: module_path.to_string(),
+        items,
+    };
+    visitor.visit_file(file_ast);
+}
+
+// Function to rank modules by the number of lifetime parameters they declare
+pub fn lifetime_heavy_modules(items: &[GenericItem]) -> Vec<(&str, usize)> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for item in items {
+        if !item.lifetimes.is_empty() {
+            *counts.entry(item.module.as_str()).or_insert(0) += item.lifetimes.len();
+        }
+    }
+    let mut ranked: Vec<(&str, usize)> = counts.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    ranked.truncate(TOP_MODULES);
+    ranked
+}
@@ -0,0 +1,100 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Helpers that ask git which Rust files have changed.
+
+use crate::ForestError;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+// Function to run git in `dir` and return the non-empty lines of its output
+fn git_lines(dir: &Path, args: &[&str]) -> Result<Vec<String>, ForestError> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .map_err(|e| ForestError::io("git", e))?;
+
+    if !output.status.success() {
+        return Err(ForestError::Config(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect())
+}
+
+// Function to keep existing .rs files, as paths joined onto the project directory
+fn rust_files_in(dir: &Path, names: Vec<String>) -> HashSet<PathBuf> {
+    names
+        .into_iter()
+        .filter(|name| name.ends_with(".rs"))
+        .map(|name| dir.join(name))
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+// Function to list .rs files modified relative to `since` (HEAD by default),
+// including untracked files
+pub fn changed_rust_files(
+    dir: &Path,
+    since: Option<&str>,
+) -> Result<HashSet<PathBuf>, ForestError> {
+    let rev = since.unwrap_or("HEAD");
+    let mut names = git_lines(dir, &["diff", "--name-only", "--relative", rev])?;
+    names.extend(git_lines(
+        dir,
+        &["ls-files", "--others", "--exclude-standard"],
+    )?);
+    Ok(rust_files_in(dir, names))
+}
+
+// Function to list the staged (added, copied, modified or renamed) .rs files
+pub fn staged_rust_files(dir: &Path) -> Result<Vec<PathBuf>, ForestError> {
+    let names = git_lines(
+        dir,
+        &[
+            "diff",
+            "--cached",
+            "--name-only",
+            "--relative",
+            "--diff-filter=ACMR",
+        ],
+    )?;
+    Ok(names
+        .into_iter()
+        .filter(|name| name.ends_with(".rs"))
+        .map(PathBuf::from)
+        .collect())
+}
+
+// Function to read the staged content of a file (which may differ from the working tree)
+pub fn staged_content(dir: &Path, relative: &Path) -> Result<String, ForestError> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .arg("show")
+        .arg(format!(":./{}", relative.display()))
+        .output()
+        .map_err(|e| ForestError::io("git", e))?;
+
+    if !output.status.success() {
+        return Err(ForestError::Config(format!(
+            "git show failed for {}: {}",
+            relative.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    String::from_utf8(output.stdout).map_err(|e| {
+        ForestError::io(
+            relative,
+            std::io::Error::new(std::io::ErrorKind::InvalidData, e),
+        )
+    })
+}
@@ -0,0 +1,118 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Global mutable state.
+// Besides `static mut`, state shared by the whole program hides behind lazily initialised
+// statics: `lazy_static!`, once_cell's `Lazy` and `OnceCell`, std's `LazyLock` and
+// `OnceLock`, and per-thread globals declared with `thread_local!`. Each one is reported
+// with the type it holds. The macros are parsed from their tokens, as syn does not expand them.
+
+use quote::ToTokens;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use syn::parse::{ParseStream, Parser};
+use syn::visit::{self, Visit};
+
+use crate::globals::type_text;
+
+// Wrapper types that initialise a static on first use
+const LAZY_TYPES: &[&str] = &["Lazy", "LazyLock", "OnceCell", "OnceLock"];
+
+// A piece of global mutable state
+#[derive(Clone, Serialize, Deserialize)]
+pub struct GlobalState {
+    pub name: String,
+    pub kind: String, // "static mut", "lazy_static!", "thread_local!" or the lazy type, e.g. "OnceLock"
+    #[serde(rename = "type")]
+    pub type_name: String,
+    #[serde(rename = "file")]
+    pub file_path: PathBuf,
+    #[serde(rename = "line")]
+    pub line_number: usize,
+}
+
+// Function to find the lazy wrapper of a static's type, e.g. OnceLock for OnceLock<Config>
+fn lazy_wrapper(ty: &syn::Type) -> Option<String> {
+    match ty {
+        syn::Type::Path(type_path) => type_path
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.ident.to_string())
+            .filter(|name| LAZY_TYPES.contains(&name.as_str())),
+        _ => None,
+    }
+}
+
+// Function to parse the statics declared in a `lazy_static!` or `thread_local!` body:
+// `[attrs] [vis] static [ref] NAME: Type = init;` repeated, the last `;` optional
+fn parse_macro_statics(input: ParseStream) -> syn::Result<Vec<(syn::Ident, syn::Type)>> {
+    let mut statics = Vec::new();
+    while !input.is_empty() {
+        input.call(syn::Attribute::parse_outer)?;
+        input.parse::<syn::Visibility>()?;
+        input.parse::<syn::Token![static]>()?;
+        input.parse::<Option<syn::Token![ref]>>()?;
+        let ident: syn::Ident = input.parse()?;
+        input.parse::<syn::Token![:]>()?;
+        let ty: syn::Type = input.parse()?;
+        input.parse::<syn::Token![=]>()?;
+        input.parse::<syn::Expr>()?;
+        input.parse::<Option<syn::Token![;]>>()?;
+        statics.push((ident, ty));
+    }
+    Ok(statics)
+}
+
+// Visitor that collects the global mutable state of a file
+struct GlobalStateVisitor<'a> {
+    file_path: &'a Path,
+    states: &'a mut Vec<GlobalState>,
+}
+
+impl GlobalStateVisitor<'_> {
+    fn push(&mut self, ident: &syn::Ident, kind: String, ty: &syn::Type) {
+        self.states.push(GlobalState {
+            name: ident.to_string(),
+            kind,
+            type_name: type_text(ty),
+            file_path: self.file_path.to_path_buf(),
+            line_number: ident.span().start().line,
+        });
+    }
+}
+
+impl<'ast> Visit<'ast> for GlobalStateVisitor<'_> {
+    fn visit_item_static(&mut self, item_static: &'ast syn::ItemStatic) {
+        if matches!(item_static.mutability, syn::StaticMutability::Mut(_)) {
+            self.push(
+                &item_static.ident,
+                "static mut".to_string(),
+                &item_static.ty,
+            );
+        } else if let Some(wrapper) = lazy_wrapper(&item_static.ty) {
+            self.push(&item_static.ident, wrapper, &item_static.ty);
+        }
+        visit::visit_item_static(self, item_static);
+    }
+
+    fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+        let name = mac
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.ident.to_string());
+        if let Some(name @ ("lazy_static" | "thread_local")) = name.as_deref() {
+            if let Ok(statics) = parse_macro_statics.parse2(mac.tokens.to_token_stream()) {
+                for (ident, ty) in statics {
+                    self.push(&ident, format!("{}!", name), &ty);
+                }
+            }
+        }
+        visit::visit_macro(self, mac);
+    }
+}
+
+// Function to collect the global mutable state of a parsed file
+pub fn collect_global_state(file_ast: &syn::File, file_path: &Path, states: &mut Vec<GlobalState>) {
+    let mut visitor = GlobalStateVisitor { file_path, states };
+    visitor.visit_file(file_ast);
+}
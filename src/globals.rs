@@ -0,0 +1,123 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Constants and statics.
+// `const` and `static` items live outside any function, so the variable visitor never
+// sees them. They are collected here with their type, value and visibility; `static mut`
+// items are flagged since every access to them is unsafe.
+
+use quote::ToTokens;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use syn::visit::{self, Visit};
+
+// Longest value expression kept before it is shortened
+const MAX_VALUE_LEN: usize = 60;
+
+// A `const` or `static` item
+#[derive(Clone, Serialize, Deserialize)]
+pub struct GlobalItem {
+    pub name: String,
+    pub kind: String, // "const" or "static"
+    #[serde(rename = "type")]
+    pub type_name: String,
+    pub value: String,      // Value expression, shortened when long
+    pub visibility: String, // "pub", "pub(crate)", ... or "private"
+    pub mutable: bool,      // static mut
+    #[serde(rename = "file")]
+    pub file_path: PathBuf,
+    #[serde(rename = "line")]
+    pub line_number: usize,
+}
+
+impl GlobalItem {
+    // The declaration as written in the source, e.g. `pub static mut COUNT: u32 = 0`
+    pub fn declaration(&self) -> String {
+        let visibility = if self.visibility == "private" {
+            String::new()
+        } else {
+            format!("{} ", self.visibility)
+        };
+        format!(
+            "{}{}{} {}: {} = {}",
+            visibility,
+            self.kind,
+            if self.mutable { " mut" } else { "" },
+            self.name,
+            self.type_name,
+            self.value
+        )
+    }
+}
+
+// Function to format a visibility qualifier
+pub fn visibility_name(visibility: &syn::Visibility) -> String {
+    match visibility {
+        syn::Visibility::Inherited => "private".to_string(),
+        other => other.to_token_stream().to_string().replace(' ', ""),
+    }
+}
+
+// Function to format a type without the spacing added by the token printer
+pub fn type_text(ty: &syn::Type) -> String {
+    compact(ty)
+        .replace(" :: ", "::")
+        .replace("& ", "&")
+        .replace("* ", "*")
+        .replace(" ;", ";")
+        .replace(" ,", ",")
+        .replace(" <", "<")
+        .replace("< ", "<")
+        .replace(" >", ">")
+}
+
+// Function to format a token tree compactly, shortening long values
+fn compact(tokens: &impl ToTokens) -> String {
+    let text = tokens.to_token_stream().to_string();
+    if text.chars().count() > MAX_VALUE_LEN {
+        let short: String = text.chars().take(MAX_VALUE_LEN).collect();
+        format!("{}...", short.trim_end())
+    } else {
+        text
+    }
+}
+
+// Visitor that collects const and static items
+struct GlobalsVisitor<'a> {
+    file_path: &'a Path,
+    globals: &'a mut Vec<GlobalItem>,
+}
+
+impl<'ast> Visit<'ast> for GlobalsVisitor<'_> {
+    fn visit_item_const(&mut self, item_const: &'ast syn::ItemConst) {
+        self.globals.push(GlobalItem {
+            name: item_const.ident.to_string(),
+            kind: "const".to_string(),
+            type_name: type_text(&item_const.ty),
+            value: compact(&item_const.expr),
+            visibility: visibility_name(&item_const.vis),
+            mutable: false,
+            file_path: self.file_path.to_path_buf(),
+            line_number: item_const.const_token.span.start().line,
+        });
+        visit::visit_item_const(self, item_const);
+    }
+
+    fn visit_item_static(&mut self, item_static: &'ast syn::ItemStatic) {
+        self.globals.push(GlobalItem {
+            name: item_static.ident.to_string(),
+            kind: "static".to_string(),
+            type_name: type_text(&item_static.ty),
+            value: compact(&item_static.expr),
+            visibility: visibility_name(&item_static.vis),
+            mutable: matches!(item_static.mutability, syn::StaticMutability::Mut(_)),
+            file_path: self.file_path.to_path_buf(),
+            line_number: item_static.static_token.span.start().line,
+        });
+        visit::visit_item_static(self, item_static);
+    }
+}
+
+// Function to collect the constants and statics of a parsed file
+pub fn collect_globals(file_ast: &syn::File, file_path: &Path, globals: &mut Vec<GlobalItem>) {
+    let mut visitor = GlobalsVisitor { file_path, globals };
+    visitor.visit_file(file_ast);
+}
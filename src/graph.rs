@@ -0,0 +1,118 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// GraphViz DOT output (`--format dot`).
+// `--graph` selects the graph: the module tree, the call graph resolved for the recursion
+// check, or the type-usage graph linking each item to the project types it mentions.
+// Render the output with standard tooling, e.g. `dot -Tsvg forest.dot -o forest.svg`.
+
+use crate::{AnalysisResults, ForestError};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::{self, Write};
+
+// Graphs that can be written
+pub const GRAPHS: &[&str] = &["modules", "calls", "types"];
+
+// Function to quote a node name or label for DOT
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+// Function to build the module tree from the modules of all files and inline modules
+// Returns parent-child edges between module paths
+fn module_edges(results: &AnalysisResults) -> BTreeSet<(String, String)> {
+    let modules = results
+        .file_lines
+        .iter()
+        .map(|file_lines| file_lines.module.as_str())
+        .chain(
+            results
+                .data_structures
+                .iter()
+                .filter(|item| item.data_structure_type == "module" && !item.item_path.is_empty())
+                .map(|item| item.item_path.as_str()),
+        );
+    let mut edges = BTreeSet::new();
+    for module in modules {
+        let mut path = module;
+        while let Some((parent, _)) = path.rsplit_once("::") {
+            edges.insert((parent.to_string(), path.to_string()));
+            path = parent;
+        }
+    }
+    edges
+}
+
+// Function to resolve the call graph into caller-callee edges
+fn call_edges(results: &AnalysisResults) -> BTreeSet<(String, String)> {
+    let functions = &results.function_calls;
+    crate::recursion::call_edges(functions)
+        .iter()
+        .enumerate()
+        .flat_map(|(caller, callees)| {
+            callees.iter().map(move |&callee| {
+                (
+                    functions[caller].function.clone(),
+                    functions[callee].function.clone(),
+                )
+            })
+        })
+        .collect()
+}
+
+// Function to count the references from each item to each project type
+fn type_edges(results: &AnalysisResults) -> BTreeMap<(String, String), usize> {
+    let mut edges = BTreeMap::new();
+    for xref in
+        crate::type_xref::cross_reference(&results.data_structures, &results.type_references)
+    {
+        for reference in &xref.references {
+            *edges
+                .entry((reference.owner.clone(), xref.name().to_string()))
+                .or_insert(0) += 1;
+        }
+    }
+    edges
+}
+
+// Function to output a structural graph in GraphViz DOT format
+pub fn output_dot(results: &AnalysisResults, file: &str, graph: &str) -> Result<(), ForestError> {
+    if !matches!(graph, "modules" | "calls" | "types") {
+        return Err(ForestError::Config(format!("unknown graph: {}", graph)));
+    }
+    File::create(file)
+        .and_then(|mut out| write_dot(&mut out, results, graph))
+        .map_err(|e| ForestError::output("dot", file, e))
+}
+
+// Function to write the edges of one graph
+fn write_dot(file: &mut impl Write, results: &AnalysisResults, graph: &str) -> io::Result<()> {
+    writeln!(file, "digraph {} {{", graph)?;
+    writeln!(file, "    rankdir=LR;")?;
+    writeln!(file, "    node [shape=box, fontname=\"monospace\"];")?;
+    match graph {
+        "modules" => {
+            for (parent, child) in module_edges(results) {
+                writeln!(file, "    {} -> {};", quote(&parent), quote(&child))?;
+            }
+        }
+        "calls" => {
+            for (caller, callee) in call_edges(results) {
+                writeln!(file, "    {} -> {};", quote(&caller), quote(&callee))?;
+            }
+        }
+        "types" => {
+            for ((owner, type_name), count) in type_edges(results) {
+                writeln!(
+                    file,
+                    "    {} -> {} [label={}];",
+                    quote(&owner),
+                    quote(&type_name),
+                    count
+                )?;
+            }
+        }
+        _ => {}
+    }
+    writeln!(file, "}}")?;
+    Ok(())
+}
@@ -0,0 +1,50 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Aggregated counts (`--group-by`).
+// Instead of listing every variable and data structure, the listings are replaced by one
+// row per file, module, scope, basic type or mutability with the number of mutable and
+// immutable variables in it. Data structures are counted too when grouping by file or
+// module. The module of a record is the module of the file it is declared in. Groups are
+// listed largest first. The counting itself is `AnalysisResults::group_by`.
+
+use crate::aggregate::{Group, GroupKey};
+use crate::AnalysisResults;
+use std::io::{self, Write};
+
+// Keys accepted by --group-by
+pub const GROUP_KEYS: &[&str] = &["file", "module", "scope", "basic_type", "mutability"];
+
+// Function to aggregate the variables and data structures by the named --group-by key
+pub fn group(results: &AnalysisResults, by: &str) -> Vec<Group> {
+    results.group_by(GroupKey::from_name(by).unwrap_or(GroupKey::File))
+}
+
+// Function to write the groups as an aligned table
+pub fn write_table(
+    out: &mut impl Write,
+    indent: &str,
+    by: &str,
+    groups: &[Group],
+) -> io::Result<()> {
+    let with_structures = groups.iter().any(|group| group.data_structures.is_some());
+    let mut header = format!("{}{:>8} {:>9}", indent, "mutable", "immutable");
+    if with_structures {
+        header.push_str(&format!(" {:>10}", "structures"));
+    }
+    writeln!(out, "{}  {}", header, by)?;
+    for group in groups {
+        let mut row = format!(
+            "{}{:>8} {:>9}",
+            indent, group.mutable_variables, group.immutable_variables
+        );
+        if let Some(data_structures) = group.data_structures {
+            row.push_str(&format!(" {:>10}", data_structures));
+        }
+        let key = if group.key.is_empty() {
+            "<none>"
+        } else {
+            &group.key
+        };
+        writeln!(out, "{}  {}", row, key)?;
+    }
+    Ok(())
+}
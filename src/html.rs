@@ -0,0 +1,272 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Self-contained HTML report (`--format html`).
+// A single file with embedded CSS and JavaScript: the summary comes first, followed by the
+// variables and data structures of each file in collapsible groups. Every table can be
+// sorted by clicking a column header, and one filter box narrows the rows of all tables.
+// Line numbers link to the source through vscode:// links.
+
+use crate::{AnalysisMetadata, AnalysisResults, DataStructureInfo, ForestError, VarInfo};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+
+const STYLE: &str = r#"
+body { font-family: system-ui, sans-serif; margin: 2em; color: #222; }
+h1 { margin-bottom: 0.2em; }
+.meta { color: #666; margin-top: 0; }
+table { border-collapse: collapse; margin: 0.5em 0 1.5em; width: 100%; }
+th, td { border: 1px solid #ddd; padding: 0.3em 0.6em; text-align: left; vertical-align: top; }
+th { background: #f3f3f3; cursor: pointer; user-select: none; white-space: nowrap; }
+th[data-order="asc"]::after { content: " \25B2"; }
+th[data-order="desc"]::after { content: " \25BC"; }
+td.number { text-align: right; }
+table.summary { width: auto; }
+table.summary th { cursor: default; }
+details { margin-bottom: 0.8em; }
+summary { cursor: pointer; font-family: monospace; font-size: 1.05em; }
+summary .counts { font-family: system-ui, sans-serif; color: #666; }
+#filter { width: 30em; padding: 0.4em; margin: 1em 0; }
+.mutable { color: #b34700; }
+code { font-size: 0.95em; }
+"#;
+
+const SCRIPT: &str = r#"
+document.querySelectorAll('table.sortable th').forEach(function (th) {
+  th.addEventListener('click', function () {
+    var body = th.closest('table').tBodies[0];
+    var index = Array.prototype.indexOf.call(th.parentNode.children, th);
+    var ascending = th.dataset.order !== 'asc';
+    th.parentNode.querySelectorAll('th').forEach(function (other) { delete other.dataset.order; });
+    th.dataset.order = ascending ? 'asc' : 'desc';
+    var rows = Array.prototype.slice.call(body.rows);
+    rows.sort(function (a, b) {
+      var x = a.cells[index].innerText, y = b.cells[index].innerText;
+      var difference = parseFloat(x) - parseFloat(y);
+      var order = isNaN(difference) ? x.localeCompare(y) : difference;
+      return ascending ? order : -order;
+    });
+    rows.forEach(function (row) { body.appendChild(row); });
+  });
+});
+document.getElementById('filter').addEventListener('input', function (event) {
+  var query = event.target.value.toLowerCase();
+  document.querySelectorAll('table.sortable tbody tr').forEach(function (row) {
+    row.style.display = row.innerText.toLowerCase().includes(query) ? '' : 'none';
+  });
+  document.querySelectorAll('details.file').forEach(function (group) {
+    if (query) { group.open = true; }
+  });
+});
+"#;
+
+// Function to escape text for HTML content and attribute values
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// Function to format a line number as a link to the source
+fn line_link(href: &str, line_number: usize) -> String {
+    format!("<a href=\"{}\">{}</a>", escape(href), line_number)
+}
+
+// Records of one source file
+#[derive(Default)]
+struct FileGroup<'a> {
+    vars: Vec<&'a VarInfo>,
+    structures: Vec<&'a DataStructureInfo>,
+    lines: Option<crate::line_stats::LineCounts>,
+}
+
+// Function to describe a data structure beyond its name and kind
+fn structure_details(structure: &DataStructureInfo) -> String {
+    let mut details = match (&structure.signature, &structure.aliased_type) {
+        (Some(signature), _) => format!("fn{}", signature.text()),
+        (None, Some(aliased_type)) => format!("= {}", aliased_type),
+        _ if !structure.fields.is_empty() => format!("{} fields", structure.fields.len()),
+        _ if !structure.variants.is_empty() => format!("{} variants", structure.variants.len()),
+        _ => String::new(),
+    };
+    details.push_str(&crate::patterns::marker(&structure.patterns));
+    details
+}
+
+// Function to write the per-file group of variables and data structures
+fn write_file_group(file: &mut File, path: &Path, group: &FileGroup) -> io::Result<()> {
+    let mutable = group.vars.iter().filter(|var| var.mutable).count();
+    write!(
+        file,
+        "<details class=\"file\"><summary>{} <span class=\"counts\">{} mutable, {} immutable, {} data structures",
+        escape(&path.display().to_string()),
+        mutable,
+        group.vars.len() - mutable,
+        group.structures.len()
+    )?;
+    if let Some(lines) = group.lines {
+        write!(file, ", {} lines of code", lines.code)?;
+    }
+    writeln!(file, "</span></summary>")?;
+
+    if !group.vars.is_empty() {
+        writeln!(file, "<table class=\"sortable\"><thead><tr><th>Name</th><th>Mutability</th><th>Line</th><th>Kind</th><th>Type</th><th>Scope</th><th>Reads</th><th>Writes</th></tr></thead><tbody>")?;
+        for var in &group.vars {
+            writeln!(
+                file,
+                "<tr><td><code>{}</code></td><td{}>{}</td><td class=\"number\">{}</td><td>{}</td><td><code>{}</code></td><td><code>{}</code></td><td class=\"number\">{}</td><td class=\"number\">{}</td></tr>",
+                escape(&var.name),
+                if var.mutable { " class=\"mutable\"" } else { "" },
+                if var.mutable { "mutable" } else { "immutable" },
+                line_link(&var.editor_link(), var.line_number),
+                escape(&var.var_kind),
+                escape(&var.basic_type),
+                escape(&var.scope),
+                var.read_count,
+                var.write_count
+            )?;
+        }
+        writeln!(file, "</tbody></table>")?;
+    }
+
+    if !group.structures.is_empty() {
+        writeln!(file, "<table class=\"sortable\"><thead><tr><th>Name</th><th>Kind</th><th>Visibility</th><th>Line</th><th>Details</th></tr></thead><tbody>")?;
+        for structure in &group.structures {
+            writeln!(
+                file,
+                "<tr><td><code>{}</code></td><td>{}</td><td>{}</td><td class=\"number\">{}</td><td><code>{}</code></td></tr>",
+                escape(structure.display_name()),
+                escape(&structure.data_structure_type),
+                escape(&structure.visibility),
+                line_link(&structure.editor_link(), structure.line_number),
+                escape(&structure_details(structure))
+            )?;
+        }
+        writeln!(file, "</tbody></table>")?;
+    }
+    writeln!(file, "</details>")?;
+    Ok(())
+}
+
+// Function to output results as a self-contained HTML report
+pub fn output_html(
+    results: &AnalysisResults,
+    metadata: &AnalysisMetadata,
+    file: &str,
+) -> Result<(), ForestError> {
+    File::create(file)
+        .and_then(|mut out| write_html(&mut out, results, metadata))
+        .map_err(|e| ForestError::output("html", file, e))
+}
+
+// Function to write the report
+fn write_html(
+    file: &mut File,
+    results: &AnalysisResults,
+    metadata: &AnalysisMetadata,
+) -> io::Result<()> {
+    let mut groups: BTreeMap<&Path, FileGroup> = BTreeMap::new();
+    for var in results.mutable_vars.iter().chain(&results.immutable_vars) {
+        groups.entry(&var.file_path).or_default().vars.push(var);
+    }
+    for structure in &results.data_structures {
+        groups
+            .entry(&structure.file_path)
+            .or_default()
+            .structures
+            .push(structure);
+    }
+    for file_lines in &results.file_lines {
+        groups.entry(&file_lines.file_path).or_default().lines = Some(file_lines.lines);
+    }
+    let totals = crate::line_stats::project_totals(&results.file_lines);
+
+    writeln!(file, "<!DOCTYPE html>")?;
+    writeln!(file, "<html lang=\"en\"><head><meta charset=\"utf-8\">")?;
+    writeln!(
+        file,
+        "<title>forest report: {}</title>",
+        escape(&metadata.project_name)
+    )?;
+    writeln!(file, "<style>{}</style></head><body>", STYLE)?;
+    writeln!(
+        file,
+        "<h1>{} {}</h1>",
+        escape(&metadata.project_name),
+        escape(&metadata.version)
+    )?;
+    writeln!(
+        file,
+        "<p class=\"meta\">Analysed by forest at {}</p>",
+        escape(&metadata.datetime)
+    )?;
+
+    writeln!(file, "<h2>Summary</h2>")?;
+    writeln!(file, "<table class=\"summary\"><tbody>")?;
+    let summary = [
+        ("Files", groups.len()),
+        ("Mutable variables", results.mutable_vars.len()),
+        ("Immutable variables", results.immutable_vars.len()),
+        ("Data structures", results.data_structures.len()),
+        ("Lines of code", totals.code),
+        ("Comment lines", totals.comment),
+        ("Blank lines", totals.blank),
+        ("Degraded files", results.degraded_files.len()),
+        ("Errors", results.errors.len()),
+    ];
+    for (label, count) in summary {
+        writeln!(
+            file,
+            "<tr><th>{}</th><td class=\"number\">{}</td></tr>",
+            label, count
+        )?;
+    }
+    writeln!(file, "</tbody></table>")?;
+
+    writeln!(
+        file,
+        "<input id=\"filter\" type=\"search\" placeholder=\"Filter rows of all tables\">"
+    )?;
+    writeln!(file, "<h2>Files</h2>")?;
+    for (path, group) in &groups {
+        write_file_group(file, path, group)?;
+    }
+
+    if !results.parse_failures.is_empty() {
+        writeln!(file, "<h2>Parse Failures</h2>")?;
+        writeln!(file, "<table class=\"sortable\"><thead><tr><th>File</th><th>Edition</th><th>Line</th><th>Parse error</th><th>Fallback parser</th></tr></thead><tbody>")?;
+        for failure in &results.parse_failures {
+            writeln!(
+                file,
+                "<tr><td><code>{}</code></td><td>{}</td><td class=\"number\">{}</td><td>{}</td><td>{}</td></tr>",
+                escape(&failure.file_path.display().to_string()),
+                escape(&failure.edition),
+                line_link(
+                    &crate::links::editor_link(&failure.file_path, failure.line_number),
+                    failure.line_number
+                ),
+                escape(&failure.message),
+                if failure.fallback { "used" } else { "not used" }
+            )?;
+        }
+        writeln!(file, "</tbody></table>")?;
+    }
+
+    if !results.errors.is_empty() {
+        writeln!(file, "<h2>Errors</h2>")?;
+        writeln!(file, "<table class=\"sortable\"><thead><tr><th>File</th><th>Message</th></tr></thead><tbody>")?;
+        for error in &results.errors {
+            writeln!(
+                file,
+                "<tr><td><code>{}</code></td><td>{}</td></tr>",
+                escape(&error.file_path.display().to_string()),
+                escape(&error.message)
+            )?;
+        }
+        writeln!(file, "</tbody></table>")?;
+    }
+
+    writeln!(file, "<script>{}</script></body></html>", SCRIPT)?;
+    Ok(())
+}
@@ -0,0 +1,139 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Inventory of impl blocks.
+// Every inherent and trait impl is recorded with its self type, the trait it implements
+// and its methods, so it is easy to see where the methods of a type live and which types
+// carry the most behaviour.
+
+use quote::ToTokens;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use syn::visit::{self, Visit};
+
+// Maximum number of types listed in the methods-per-type ranking
+const TOP_TYPES: usize = 10;
+
+// An `impl Type` or `impl Trait for Type` block
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ImplBlock {
+    pub self_type: String,
+    #[serde(rename = "trait")]
+    pub trait_name: Option<String>, // None for inherent impls
+    pub methods: Vec<String>,
+    #[serde(default)]
+    pub builder_methods: Vec<String>, // Chainable methods, e.g. `fn name(mut self, ..) -> Self`
+    #[serde(rename = "file")]
+    pub file_path: PathBuf,
+    #[serde(rename = "line")]
+    pub line_number: usize,
+}
+
+impl ImplBlock {
+    // The block header as written in the source, e.g. `impl Display for Point`
+    pub fn header(&self) -> String {
+        match self.trait_name {
+            Some(ref trait_name) => format!("impl {} for {}", trait_name, self.self_type),
+            None => format!("impl {}", self.self_type),
+        }
+    }
+
+    // The method names of the block, for the text outputs
+    pub fn method_list(&self) -> String {
+        if self.methods.is_empty() {
+            "(no methods)".to_string()
+        } else {
+            self.methods.join(", ")
+        }
+    }
+}
+
+// Function to format the self type of an impl without generic arguments
+// `Wrapper<T>` and `Wrapper<u8>` both carry behaviour for `Wrapper`
+pub fn type_name(ty: &syn::Type) -> String {
+    match ty {
+        syn::Type::Path(type_path) => type_path
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.ident.to_string())
+            .unwrap_or_default(),
+        syn::Type::Reference(reference) => type_name(&reference.elem),
+        other => other.to_token_stream().to_string().replace(' ', ""),
+    }
+}
+
+// Function to check whether a method can be chained like a builder setter
+// It takes `self` by value and returns `Self`, or takes `&mut self` and returns `&mut Self`
+fn is_chainable(sig: &syn::Signature, self_type: &str) -> bool {
+    let Some(syn::FnArg::Receiver(receiver)) = sig.inputs.first() else {
+        return false;
+    };
+    let syn::ReturnType::Type(_, ref output) = sig.output else {
+        return false;
+    };
+    let returns_self = |ty: &syn::Type| match ty {
+        syn::Type::Path(type_path) => type_path.path.is_ident("Self") || type_name(ty) == self_type,
+        _ => false,
+    };
+    match (&receiver.reference, output.as_ref()) {
+        (None, output) => returns_self(output),
+        (Some(_), syn::Type::Reference(reference)) => {
+            receiver.mutability.is_some()
+                && reference.mutability.is_some()
+                && returns_self(&reference.elem)
+        }
+        _ => false,
+    }
+}
+
+// Visitor that collects the impl blocks of a file
+struct ImplVisitor<'a> {
+    file_path: &'a Path,
+    blocks: &'a mut Vec<ImplBlock>,
+}
+
+impl<'ast> Visit<'ast> for ImplVisitor<'_> {
+    fn visit_item_impl(&mut self, item_impl: &'ast syn::ItemImpl) {
+        let self_type = type_name(&item_impl.self_ty);
+        let mut methods = Vec::new();
+        let mut builder_methods = Vec::new();
+        for item in &item_impl.items {
+            if let syn::ImplItem::Fn(method) = item {
+                methods.push(method.sig.ident.to_string());
+                if is_chainable(&method.sig, &self_type) {
+                    builder_methods.push(method.sig.ident.to_string());
+                }
+            }
+        }
+        self.blocks.push(ImplBlock {
+            self_type,
+            trait_name: item_impl
+                .trait_
+                .as_ref()
+                .map(|(_, path, _)| path.to_token_stream().to_string().replace(' ', "")),
+            methods,
+            builder_methods,
+            file_path: self.file_path.to_path_buf(),
+            line_number: item_impl.impl_token.span.start().line,
+        });
+        visit::visit_item_impl(self, item_impl);
+    }
+}
+
+// Function to collect the impl blocks of a parsed file
+pub fn collect_impl_blocks(file_ast: &syn::File, file_path: &Path, blocks: &mut Vec<ImplBlock>) {
+    let mut visitor = ImplVisitor { file_path, blocks };
+    visitor.visit_file(file_ast);
+}
+
+// Function to rank the types with the most methods across all their impl blocks
+pub fn methods_per_type(blocks: &[ImplBlock]) -> Vec<(&str, usize)> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for block in blocks {
+        *counts.entry(block.self_type.as_str()).or_insert(0) += block.methods.len();
+    }
+    let mut ranked: Vec<(&str, usize)> = counts.into_iter().filter(|&(_, n)| n > 0).collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    ranked.truncate(TOP_TYPES);
+    ranked
+}
@@ -0,0 +1,145 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Interior mutability inventory.
+// `Cell`, `RefCell`, `UnsafeCell`, `Mutex`, `RwLock` and the atomics allow mutation through
+// a shared reference, so they hide state that `let mut` never shows. Every use is recorded
+// with where the value is stored: a struct field, a static or a local variable.
+
+use quote::ToTokens;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use syn::visit::{self, Visit};
+
+use crate::globals::type_text;
+use crate::state_density::interior_wrapper;
+
+// A value with interior mutability
+#[derive(Clone, Serialize, Deserialize)]
+pub struct InteriorMutability {
+    pub wrapper: String, // Interior-mutability type, e.g. RefCell or AtomicUsize
+    #[serde(rename = "type")]
+    pub type_name: String, // Declared type, or the initializer of an unannotated local
+    pub storage: String, // "field", "static" or "local"
+    pub owner: String,   // Path of the field, static or local, e.g. crate::cache::Cache::hits
+    #[serde(rename = "file")]
+    pub file_path: PathBuf,
+    #[serde(rename = "line")]
+    pub line_number: usize,
+}
+
+// Visitor that finds interior-mutability types in fields, statics and locals
+struct InteriorVisitor<'a> {
+    file_path: &'a Path,
+    scope: String, // Path of the enclosing item, e.g. crate::cache::Cache
+    uses: &'a mut Vec<InteriorMutability>,
+}
+
+impl InteriorVisitor<'_> {
+    fn record(
+        &mut self,
+        tokens: &impl ToTokens,
+        type_name: String,
+        storage: &str,
+        name: &syn::Ident,
+    ) {
+        if let Some(wrapper) = interior_wrapper(tokens.to_token_stream()) {
+            self.uses.push(InteriorMutability {
+                wrapper,
+                type_name,
+                storage: storage.to_string(),
+                owner: format!("{}::{}", self.scope, name),
+                file_path: self.file_path.to_path_buf(),
+                line_number: name.span().start().line,
+            });
+        }
+    }
+
+    fn visit_in_scope(&mut self, name: &impl std::fmt::Display, visit: impl FnOnce(&mut Self)) {
+        let inner_scope = format!("{}::{}", self.scope, name);
+        let outer_scope = std::mem::replace(&mut self.scope, inner_scope);
+        visit(self);
+        self.scope = outer_scope;
+    }
+}
+
+impl<'ast> Visit<'ast> for InteriorVisitor<'_> {
+    fn visit_item_struct(&mut self, item_struct: &'ast syn::ItemStruct) {
+        self.visit_in_scope(&item_struct.ident, |visitor| {
+            visit::visit_item_struct(visitor, item_struct)
+        });
+    }
+
+    fn visit_field(&mut self, field: &'ast syn::Field) {
+        if let Some(ref ident) = field.ident {
+            self.record(&field.ty, type_text(&field.ty), "field", ident);
+        }
+        visit::visit_field(self, field);
+    }
+
+    fn visit_item_static(&mut self, item_static: &'ast syn::ItemStatic) {
+        let type_name = type_text(&item_static.ty);
+        self.record(&item_static.ty, type_name, "static", &item_static.ident);
+        visit::visit_item_static(self, item_static);
+    }
+
+    fn visit_item_fn(&mut self, item_fn: &'ast syn::ItemFn) {
+        self.visit_in_scope(&item_fn.sig.ident, |visitor| {
+            visit::visit_item_fn(visitor, item_fn)
+        });
+    }
+
+    fn visit_item_impl(&mut self, item_impl: &'ast syn::ItemImpl) {
+        let name = crate::impl_blocks::type_name(&item_impl.self_ty);
+        self.visit_in_scope(&name, |visitor| visit::visit_item_impl(visitor, item_impl));
+    }
+
+    fn visit_impl_item_fn(&mut self, method: &'ast syn::ImplItemFn) {
+        self.visit_in_scope(&method.sig.ident, |visitor| {
+            visit::visit_impl_item_fn(visitor, method)
+        });
+    }
+
+    fn visit_item_mod(&mut self, item_mod: &'ast syn::ItemMod) {
+        self.visit_in_scope(&item_mod.ident, |visitor| {
+            visit::visit_item_mod(visitor, item_mod)
+        });
+    }
+
+    // A local is recorded from its type annotation, or else from its initializer
+    fn visit_local(&mut self, local: &'ast syn::Local) {
+        let (pat, ty) = match local.pat {
+            syn::Pat::Type(ref pat_type) => (&*pat_type.pat, Some(&*pat_type.ty)),
+            ref pat => (pat, None),
+        };
+        if let syn::Pat::Ident(ref pat_ident) = pat {
+            match (ty, &local.init) {
+                (Some(ty), _) => self.record(ty, type_text(ty), "local", &pat_ident.ident),
+                (None, Some(init)) => {
+                    let text = init.expr.to_token_stream().to_string().replace(' ', "");
+                    self.record(&init.expr, text, "local", &pat_ident.ident)
+                }
+                (None, None) => {}
+            }
+        }
+        visit::visit_local(self, local);
+    }
+}
+
+// Function to collect the interior-mutability uses of a parsed file
+pub fn collect_interior_mutability(
+    file_ast: &syn::File,
+    file_path: &Path,
+    module_path: &str,
+    uses: &mut Vec<InteriorMutability>,
+) {
+    let mut visitor = InteriorVisitor {
+        file_path,
+        scope: module_path.to_string(),
+        uses,
+    };
+    visitor.visit_file(file_ast);
+}
+
+// Function to count the uses stored in one way, e.g. "field"
+pub fn count_storage(uses: &[InteriorMutability], storage: &str) -> usize {
+    uses.iter().filter(|used| used.storage == storage).count()
+}
@@ -0,0 +1,59 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Interned strings and paths for the text repeated across variable records.
+// Kinds, types, scopes and file paths repeat across thousands of variables, so each distinct
+// value is stored once and records share it through an `Arc<str>` or `Arc<Path>`. Each worker
+// thread keeps its own pools, so interning never waits on a lock; a string seen on two
+// threads is stored twice. The pools are emptied once an analysis is complete, so a
+// long-running process such as `forest serve` does not keep the strings of earlier analyses;
+// the records keep their own copies alive.
+
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::Arc;
+
+thread_local! {
+    static POOL: RefCell<HashSet<Arc<str>>> = RefCell::new(HashSet::new());
+}
+
+// Function to get the shared copy of a string, adding it to the pool when it is new
+pub fn intern(text: &str) -> Arc<str> {
+    POOL.with(|pool| {
+        let mut pool = pool.borrow_mut();
+        if let Some(interned) = pool.get(text) {
+            return Arc::clone(interned);
+        }
+        let interned: Arc<str> = Arc::from(text);
+        pool.insert(Arc::clone(&interned));
+        interned
+    })
+}
+
+thread_local! {
+    static PATHS: RefCell<HashSet<Arc<Path>>> = RefCell::new(HashSet::new());
+}
+
+// Function to get the shared copy of a file path, so the records of a file share one path
+pub fn intern_path(path: &Path) -> Arc<Path> {
+    PATHS.with(|paths| {
+        let mut paths = paths.borrow_mut();
+        if let Some(interned) = paths.get(path) {
+            return Arc::clone(interned);
+        }
+        let interned: Arc<Path> = Arc::from(path);
+        paths.insert(Arc::clone(&interned));
+        interned
+    })
+}
+
+// Function to empty the pools of the current thread
+fn clear() {
+    POOL.with(|pool| pool.borrow_mut().clear());
+    PATHS.with(|paths| paths.borrow_mut().clear());
+}
+
+// Function to empty the pools of every analysis thread and of the current thread
+pub fn clear_all() {
+    rayon::broadcast(|_| clear());
+    clear();
+}
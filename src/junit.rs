@@ -0,0 +1,150 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// JUnit XML report (`--junit`), so CI systems show forest results in their test-report UIs.
+// The `forest.thresholds` suite has one test case per threshold given on the command line,
+// failing when it is exceeded. The `forest.files` suite has one test case per analysed
+// file: it errors when the file could not be analysed, and fails when only the items that
+// parse could be analysed or the file declares `mut` variables that are never mutated.
+
+use crate::html::escape;
+use crate::thresholds::Check;
+use crate::{usage, AnalysisResults, ForestError};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::PathBuf;
+
+// Outcome of a test case
+enum Outcome {
+    Passed,
+    Failure(String, String), // Message, details
+    Error(String),
+}
+
+struct TestCase {
+    name: String,
+    outcome: Outcome,
+}
+
+// Function to write a test suite
+fn write_suite(xml: &mut String, name: &str, cases: &[TestCase]) {
+    let failures = cases
+        .iter()
+        .filter(|case| matches!(case.outcome, Outcome::Failure(..)))
+        .count();
+    let errors = cases
+        .iter()
+        .filter(|case| matches!(case.outcome, Outcome::Error(_)))
+        .count();
+    let _ = writeln!(
+        xml,
+        "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\">",
+        name,
+        cases.len(),
+        failures,
+        errors
+    );
+    for case in cases {
+        let open = format!(
+            "    <testcase classname=\"{}\" name=\"{}\"",
+            name,
+            escape(&case.name)
+        );
+        let _ = match case.outcome {
+            Outcome::Passed => writeln!(xml, "{}/>", open),
+            Outcome::Failure(ref message, ref details) => writeln!(
+                xml,
+                "{}>\n      <failure message=\"{}\">{}</failure>\n    </testcase>",
+                open,
+                escape(message),
+                escape(details)
+            ),
+            Outcome::Error(ref message) => writeln!(
+                xml,
+                "{}>\n      <error message=\"{}\"/>\n    </testcase>",
+                open,
+                escape(message)
+            ),
+        };
+    }
+    xml.push_str("  </testsuite>\n");
+}
+
+// Function to describe each analysed file as a test case
+fn file_cases(results: &AnalysisResults) -> Vec<TestCase> {
+    let mut outcomes: BTreeMap<PathBuf, Outcome> = results
+        .file_lines
+        .iter()
+        .map(|file| (file.file_path.clone(), Outcome::Passed))
+        .collect();
+
+    let mut unused: BTreeMap<PathBuf, Vec<String>> = BTreeMap::new();
+    for var in usage::unused_mut(&results.mutable_vars, &results.degraded_files) {
+        unused
+            .entry(var.file_path.to_path_buf())
+            .or_default()
+            .push(format!(
+                "{}:{}: `{}` is declared mut but never mutated",
+                var.file_path.display(),
+                var.line_number,
+                var.name
+            ));
+    }
+    for (path, lines) in unused {
+        outcomes.insert(
+            path,
+            Outcome::Failure(
+                format!("{} unused mut variables", lines.len()),
+                lines.join("\n"),
+            ),
+        );
+    }
+    for path in &results.degraded_files {
+        outcomes.insert(
+            path.clone(),
+            Outcome::Failure(
+                "analysed with the fallback parser, skipping the items syn could not parse"
+                    .to_string(),
+                String::new(),
+            ),
+        );
+    }
+    for error in &results.errors {
+        outcomes.insert(
+            error.file_path.clone(),
+            Outcome::Error(error.message.clone()),
+        );
+    }
+
+    outcomes
+        .into_iter()
+        .map(|(path, outcome)| TestCase {
+            name: path.display().to_string(),
+            outcome,
+        })
+        .collect()
+}
+
+// Function to write the JUnit XML report
+pub fn write_junit(
+    file: &str,
+    results: &AnalysisResults,
+    checks: &[Check],
+) -> Result<(), ForestError> {
+    let threshold_cases: Vec<TestCase> = checks
+        .iter()
+        .map(|check| TestCase {
+            name: check.rule.clone(),
+            outcome: if check.passed {
+                Outcome::Passed
+            } else {
+                Outcome::Failure(check.message.clone(), check.message.clone())
+            },
+        })
+        .collect();
+
+    let mut xml =
+        String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites name=\"forest\">\n");
+    write_suite(&mut xml, "forest.thresholds", &threshold_cases);
+    write_suite(&mut xml, "forest.files", &file_cases(results));
+    xml.push_str("</testsuites>\n");
+    std::fs::write(file, xml).map_err(|e| ForestError::output("junit", file, e))
+}
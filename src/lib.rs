@@ -0,0 +1,2181 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Forest - Explore a Rust Project
+// This tool analyses Rust projects to summarise variable mutability and data structure usage.
+// It provides insights about where variables and data structures are declared, used, and what their types are.
+//
+// The analysis works by parsing Rust source files using the syn crate, traversing the AST,
+// and extracting information about variables and their properties.
+//
+// The library embeds the analysis in other tools: `forest::Analyzer::new(options).analyze(path)`
+// returns the AnalysisResults of a project directory or a single file. The `forest` binary
+// is a thin wrapper around `cli_main`, which lives in cli.rs with the rest of the command line;
+// the library itself does not print.
+
+// External crates
+use interning::intern_path;
+use rayon::prelude::*; // For analysing files in parallel
+use serde::{Deserialize, Serialize}; // For caching records between runs
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
+use syn::visit::Visit; // For AST traversal
+use syn::Type; // For working with Rust syntax elements
+
+// Internal modules
+pub mod aggregate; // Filters, groups and counts over the results
+mod allocations; // Clone and allocation hotspots
+mod analyzer; // The library API (Analyzer)
+mod args; // Command-line argument parsing
+mod async_code; // Async functions and await points
+mod batch; // Several projects analysed in one run (`forest batch`)
+mod cache; // Per-file result cache for changed-files mode
+mod call_args; // Move vs borrow classification of call arguments
+mod cargo; // Asking cargo about the project
+mod cli; // The command line: subcommands, the console report and the output files
+mod closures; // Closures, their parameters and captured variables
+mod code_class; // Test, example and bench code told apart from production code
+pub mod collector; // Custom analyses plugged into the walk of each file
+mod columns; // Column selection for the variable listings (--columns)
+mod complexity; // Per-function length, nesting, branches and cyclomatic complexity
+mod context; // Source lines around variable declarations (--context-lines)
+mod copy_refs; // Small Copy types held by reference
+mod csv_output; // CSV tables written with the csv crate
+mod data_structures; // The built-in collector of functions, structs, enums, ...
+mod diff; // Differences between two analyses (`forest diff`)
+mod doc_coverage; // Documentation coverage of public items per module
+mod doc_tests; // Doc-test extraction and counting
+mod doctor; // Self-check for `forest doctor`
+mod duplicates; // Duplicate and near-duplicate function detection
+mod editions; // Edition-aware parsing and the reasons files fell back
+pub mod error; // ForestError, the failures of the analysis, and exit codes
+mod error_handling; // unwrap, expect, panic! and ? sites per function
+mod exports; // Exported symbol inventory
+mod feature_gates; // Items and cfg! checks grouped by the feature that gates them
+mod ffi; // extern blocks and raw pointer parameters
+mod fields; // Struct field details
+mod filters; // Filters on the listed variables and data structures (--name, --type, ...)
+pub mod formatter; // Output formats written with --output, by name
+mod generics; // Generic parameters, lifetimes and where-clauses
+mod git; // Asking git which files changed
+mod global_state; // static mut, lazy statics and thread_local! globals
+mod globals; // Constants and statics
+mod graph; // GraphViz DOT output of the module tree, call graph and type usage
+mod grouping; // Aggregated counts per file, module, scope, ... (--group-by)
+mod html; // Self-contained HTML report (--format html)
+mod impl_blocks; // Inventory of impl blocks and their methods
+mod interior_mutability; // RefCell, Mutex, atomics and other interior mutability
+mod interning; // Shared strings for the kinds, types and scopes of variables
+mod junit; // JUnit XML report of thresholds and per-file findings (--junit)
+mod line_index; // Byte offset to line table of a source file
+mod line_stats; // Code, comment and blank lines per file and module
+mod links; // Editor links, including path mapping and vscode-remote
+mod literals; // Magic numbers and repeated string literals
+mod macro_bodies; // Best-effort analysis inside macro_rules! bodies
+mod macros; // macro_rules! definitions and macro invocation counts
+mod module_paths; // Logical module paths of files and items
+mod narrow_scope; // Suggestions to declare variables in a narrower scope
+mod options; // AnalysisOptions, shared by the command line and the library
+mod ordering; // Ordering of the variable listings (--sort-by)
+mod output_model; // Versioned JSON output envelope and its schema
+mod ownership; // Owned, borrowed and mutably borrowed parameters and locals
+mod paths; // How file paths appear in the outputs and links (--paths)
+mod patterns; // Newtype and builder pattern annotations on structs
+mod precommit; // `forest precommit` for staged files
+mod public_api; // Stable listing of the public API (--public-api)
+mod recursion; // Call graph cycles: direct and mutual recursion
+mod redact; // Redacted reports for sharing outside the organisation (--redact)
+mod rustdoc; // Merging rustdoc JSON into the data structures
+mod sampling; // Sampling mode for enormous codebases
+mod sections; // Selection of the console report sections (--sections)
+mod semantic; // Types of let bindings resolved by rust-analyzer (--semantic)
+mod serve; // JSON-RPC server over stdio with results kept in memory (`forest serve`)
+mod signatures; // Parameter lists and return types of functions
+mod single_file; // Analysis of a single file or of stdin (`forest file.rs`, `forest -`)
+mod smart_pointers; // Box, Rc, Arc, Cow and Weak usage per module
+mod state_density; // Per-function mutable-state density
+mod stats; // Top-N rankings of files and functions (`forest stats`)
+mod status; // Machine-readable run outcome for `--status-file`
+mod streaming; // JSON Lines records written while the analysis runs (--stream)
+mod struct_layout; // Estimated struct sizes and heap-heavy structs
+mod targets; // Cargo targets of the analysed files (--targets)
+mod templates; // User templates rendered against the results model (--template)
+mod text_output; // Plain-text report (--format text) and one-line listings
+mod thresholds; // CI thresholds against limits and a baseline report
+mod trait_objects; // dyn Trait and impl Trait usage
+mod type_usage; // Ranking of std, external and project-local types
+mod type_xref; // Where the project's structs, enums and traits are referenced
+mod unsafe_code; // Inventory of unsafe code
+mod usage; // Reads and writes of variables within their scope
+mod variables; // The built-in collector of variables
+mod walker; // The walk of each parsed file that calls the collectors
+
+// The library API
+pub use analyzer::Analyzer;
+pub use cli::{cli_main, cli_main_with};
+pub use collector::Collector;
+pub use error::ForestError;
+pub use formatter::OutputFormatter;
+pub use options::AnalysisOptions;
+pub use sampling::SampleSpec;
+
+// Structure to store information about variables
+// This is the core data structure that holds details about each variable found
+#[derive(Clone, Serialize, Deserialize)]
+pub struct VarInfo {
+    name: String,         // Variable name (identifier)
+    mutable: bool,        // Whether the variable is mutable (true) or immutable (false)
+    file_path: Arc<Path>, // Path to the file where the variable is declared
+    line_number: usize,   // Line number of the declaration in the source file
+    #[serde(default)]
+    column: usize, // Column of the variable name on that line, counting from 1
+    context: String,      // Line of code containing the declaration (for reference)
+    var_kind: Arc<str>, // Kind (how declared) of the variable (let binding, function parameter, etc.)
+    var_type: Arc<str>, // The fundamental Rust type of the variable (with descriptive information)
+    basic_type: Arc<str>, // The basic Rust type (i64, String, etc.) without type parameters
+    scope: Arc<str>,    // Scope of the variable (e.g., function name, module name)
+    #[serde(default)]
+    module: Arc<str>, // Logical path of the module declaring the variable, e.g. crate::parser
+    #[serde(default)]
+    macro_body: Option<String>, // Name of the macro_rules! whose body declares the variable
+    #[serde(default)]
+    code_class: String, // "test", "example", "bench" or "production", set once the file is analysed
+    #[serde(default)]
+    read_count: usize, // Times the variable is read in its scope, set once the file is analysed
+    #[serde(default)]
+    write_count: usize, // Times it is assigned or mutably borrowed
+    #[serde(default)]
+    last_use_line: Option<usize>, // Line of the last read or write in its scope
+    #[serde(default)]
+    context_window: Option<context::ContextWindow>, // Lines around the declaration, with --context-lines
+}
+
+// Add method to generate VSCode link for VarInfo with proper absolute path
+impl VarInfo {
+    fn editor_link(&self) -> String {
+        links::editor_link_at(&self.file_path, self.line_number, self.column)
+    }
+
+    // Lines from the declaration to the last use, for how long the binding stays relevant
+    fn span(&self) -> usize {
+        self.last_use_line
+            .map_or(0, |line| line.saturating_sub(self.line_number))
+    }
+}
+
+// Structure to store information about data_structures
+// data_structures are structural elements like functions, structs, and enums
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DataStructureInfo {
+    name: String,                // data_structure name (identifier)
+    data_structure_type: String, // Type of the data_structure (e.g., struct, function, enum)
+    file_path: PathBuf,          // Path to the file where the data_structure is declared
+    line_number: usize,          // Line number of the declaration in the source file
+    #[serde(default)]
+    column: usize, // Column of the name on that line, counting from 1
+    #[serde(default)]
+    rustdoc: Option<rustdoc::RustdocItem>, // Set when --rustdoc-json matched this item
+    #[serde(default)]
+    macro_body: Option<String>, // Name of the macro_rules! whose body declares the item
+    #[serde(default)]
+    has_default_methods: Option<bool>, // Set for traits: whether any method has a default body
+    #[serde(default)]
+    item_path: String, // Logical path, e.g. crate::parser::Lexer
+    #[serde(default)]
+    fields: Vec<fields::FieldInfo>, // Set for structs
+    #[serde(default)]
+    variants: Vec<fields::VariantInfo>, // Set for enums
+    #[serde(default)]
+    visibility: String, // "pub", "pub(crate)", ... or "private" (empty when unknown)
+    #[serde(default)]
+    aliased_type: Option<String>, // Set for type aliases: the type they name
+    #[serde(default)]
+    code_class: String, // "test", "example", "bench" or "production", set once the file is analysed
+    #[serde(default)]
+    patterns: Vec<String>, // "newtype" or "builder", set once all impl blocks are known
+    #[serde(default)]
+    signature: Option<signatures::Signature>, // Set for functions: parameters and return type
+}
+
+impl DataStructureInfo {
+    // The logical path when known, otherwise the bare name
+    fn display_name(&self) -> &str {
+        if self.item_path.is_empty() {
+            &self.name
+        } else {
+            &self.item_path
+        }
+    }
+
+    // The kind of item with its visibility, e.g. `struct, pub(crate)`
+    fn kind_label(&self) -> String {
+        if self.visibility.is_empty() {
+            self.data_structure_type.clone()
+        } else {
+            format!("{}, {}", self.data_structure_type, self.visibility)
+        }
+    }
+}
+
+// Update method to generate VSCode link for DataStructureInfo with proper absolute path
+impl DataStructureInfo {
+    fn editor_link(&self) -> String {
+        links::editor_link_at(&self.file_path, self.line_number, self.column)
+    }
+}
+
+// Function to format the type
+// Converts a syn::Type to a string representation using quote crate
+fn format_type(ty: &Type) -> String {
+    quote::quote!(#ty).to_string()
+}
+
+// Implementing Display trait for VarInfo to format the output
+// This determines how VarInfo objects are printed in text output
+impl fmt::Display for VarInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({}): {} at {}:{}:{} - kind: {}, type: {}, basic type: {}, scope: {}, reads: {}, writes: {}",
+            self.name,
+            if self.mutable { "mutable" } else { "immutable" },
+            self.context.trim(),
+            self.file_path.display(),
+            self.line_number,
+            self.column,
+            self.var_kind,
+            self.var_type,
+            self.basic_type,
+            self.scope,
+            self.read_count,
+            self.write_count
+        )?;
+        write!(f, "{}", span_marker(self))?;
+        if let Some(ref name) = self.macro_body {
+            write!(f, " [macro body: {}]", name)?;
+        }
+        write!(f, "{}", code_class_marker(&self.code_class))
+    }
+}
+
+// Marker appended to variables whose last use is known, e.g. `, span: 12 lines`
+fn span_marker(var: &VarInfo) -> String {
+    match var.last_use_line {
+        Some(_) => format!(", span: {} lines", var.span()),
+        None => String::new(),
+    }
+}
+
+// Marker appended to records found inside macro_rules! bodies
+fn macro_body_marker(macro_body: &Option<String>) -> String {
+    match macro_body {
+        Some(name) => format!(" [macro body: {}]", name),
+        None => String::new(),
+    }
+}
+
+// Marker appended to records outside production code, e.g. ` [test]`
+fn code_class_marker(code_class: &str) -> String {
+    if code_class.is_empty() || code_class == code_class::PRODUCTION {
+        String::new()
+    } else {
+        format!(" [{}]", code_class)
+    }
+}
+
+// Implementing Display trait for DataStructureInfo to format the output
+impl fmt::Display for DataStructureInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({}): at {}:{}:{}",
+            self.display_name(),
+            self.kind_label(),
+            self.file_path.display(),
+            self.line_number,
+            self.column
+        )?;
+        if let Some(ref name) = self.macro_body {
+            write!(f, " [macro body: {}]", name)?;
+        }
+        if self.has_default_methods == Some(true) {
+            write!(f, " [has default methods]")?;
+        }
+        if let Some(ref aliased_type) = self.aliased_type {
+            write!(f, " = {}", aliased_type)?;
+        }
+        if let Some(ref signature) = self.signature {
+            write!(f, " fn{}", signature.text())?;
+        }
+        write!(f, "{}", patterns::marker(&self.patterns))?;
+        write!(f, "{}", code_class_marker(&self.code_class))
+    }
+}
+
+// Function to extract the basic Rust type
+fn extract_basic_type(ty: &Type) -> String {
+    match ty {
+        Type::Path(path) => {
+            // Extract the last segment as the base type
+            if let Some(segment) = path
+                .path
+                .segments
+                .last()
+                .map(|segment| segment.ident.to_string())
+            {
+                // Check for primitive types
+                match segment.as_str() {
+                    "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "u8" | "u16" | "u32"
+                    | "u64" | "u128" | "usize" | "f32" | "f64" | "bool" | "char" => {
+                        segment.to_string()
+                    }
+
+                    "String" => "String".to_string(),
+                    "Option" => match path.path.segments.last().map(|segment| &segment.arguments) {
+                        Some(syn::PathArguments::AngleBracketed(args)) => {
+                            if let Some(syn::GenericArgument::Type(inner_ty)) = args.args.first() {
+                                format!("Option<{}>", extract_basic_type(inner_ty))
+                            } else {
+                                "Option<T>".to_string()
+                            }
+                        }
+                        _ => "Option<T>".to_string(),
+                    },
+                    "Vec" => match &path.path.segments.last().unwrap().arguments {
+                        syn::PathArguments::AngleBracketed(args) => {
+                            if let Some(syn::GenericArgument::Type(inner_ty)) = args.args.first() {
+                                format!("Vec<{}>", extract_basic_type(inner_ty))
+                            } else {
+                                "Vec<T>".to_string()
+                            }
+                        }
+                        _ => "Vec<T>".to_string(),
+                    },
+                    // Default to the type name itself
+                    _ => segment.to_string(),
+                }
+            } else {
+                "unknown".to_string()
+            }
+        }
+        Type::Reference(ref_type) => {
+            let mutability = if ref_type.mutability.is_some() {
+                "mut "
+            } else {
+                ""
+            };
+            format!("&{}{}", mutability, extract_basic_type(&ref_type.elem))
+        }
+        Type::Array(array_type) => {
+            format!("[{}; N]", extract_basic_type(&array_type.elem))
+        }
+        Type::Tuple(tuple_type) => {
+            if tuple_type.elems.is_empty() {
+                "()".to_string()
+            } else {
+                let types: Vec<String> = tuple_type.elems.iter().map(extract_basic_type).collect();
+                format!("({})", types.join(", "))
+            }
+        }
+        Type::Slice(slice_type) => {
+            format!("[{}]", extract_basic_type(&slice_type.elem))
+        }
+        // For other types, just use the stringified version
+        _ => quote::quote!(#ty).to_string(),
+    }
+}
+
+// Structure to store a file that could not be analysed
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AnalysisError {
+    #[serde(rename = "file")]
+    file_path: PathBuf, // Path to the file (or directory) that failed
+    message: String, // Description of the IO or parse error
+}
+
+// Structure to store analysis results
+// Each file is analysed into its own AnalysisResults, which is cached and then merged
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AnalysisResults {
+    mutable_vars: Vec<VarInfo>,              // List of mutable variables
+    immutable_vars: Vec<VarInfo>,            // List of immutable variables
+    data_structures: Vec<DataStructureInfo>, // List of data_structures (functions, structs, etc.)
+    errors: Vec<AnalysisError>,              // Files skipped because of IO or parse errors
+    degraded_files: Vec<PathBuf>,            // Files only analysed for the items syn could parse
+    function_fingerprints: Vec<duplicates::FunctionFingerprint>, // For duplicate detection
+    type_uses: Vec<type_usage::TypeUse>,     // Types used by variables and struct fields
+    scope_suggestions: Vec<narrow_scope::ScopeSuggestion>, // Variables used in one branch only
+    call_args: Vec<call_args::CalleeArgs>,   // Argument counts per callee
+    copy_refs: Vec<copy_refs::CopyRefFinding>, // Small Copy types held by reference
+    function_states: Vec<state_density::FunctionState>, // Mutable state per function
+    interior_mutable_fields: Vec<String>,    // Names of Cell, RefCell, Mutex, ... fields
+    unsafe_impls: Vec<unsafe_code::UnsafeImpl>, // unsafe impl Trait for Type
+    exported_symbols: Vec<exports::ExportedSymbol>, // #[no_mangle], #[export_name] and pub extern fns
+    doc_tests: Vec<doc_tests::DocTestItem>,         // Items with code blocks in their docs
+    impl_blocks: Vec<impl_blocks::ImplBlock>,       // Inherent and trait impls with their methods
+    globals: Vec<globals::GlobalItem>,              // const and static items
+    unsafe_sites: Vec<unsafe_code::UnsafeSite>,     // unsafe blocks, fns and traits
+    closures: Vec<closures::ClosureInfo>,           // Closure expressions
+    macro_definitions: Vec<macros::MacroDefinition>, // macro_rules! definitions
+    macro_invocations: Vec<macros::MacroInvocations>, // Macro invocation counts per file
+    async_functions: Vec<async_code::AsyncFunction>, // async fns and functions that await
+    generic_items: Vec<generics::GenericItem>,      // Items with generic parameters
+    feature_gates: Vec<feature_gates::FeatureGate>, // #[cfg(feature = ...)] items and cfg! checks
+    declared_features: Vec<feature_gates::DeclaredFeatures>, // [features] of the packages analysed
+    doc_items: Vec<doc_coverage::DocItem>, // Public items and whether they are documented (--doc-coverage)
+    interior_mutability: Vec<interior_mutability::InteriorMutability>, // Fields, statics and locals with interior mutability
+    smart_pointers: Vec<smart_pointers::SmartPointer>, // Box, Rc, Arc, Cow and Weak in types
+    error_sites: Vec<error_handling::ErrorSite>, // unwrap, expect, panic!, todo!, unimplemented! and ? sites
+    function_errors: Vec<error_handling::FunctionErrors>, // Error-handling counts per function
+    allocations: Vec<allocations::Allocation>, // clone, to_owned, to_string, String::from, vec! and collect calls
+    type_references: Vec<type_xref::TypeReference>, // Mentions of type and trait names
+    function_metrics: Vec<complexity::FunctionMetrics>, // Complexity metrics per function
+    file_lines: Vec<line_stats::FileLines>,    // Line counts per file
+    file_targets: Vec<targets::FileTarget>,    // Cargo target of each file, from cargo metadata
+    trait_uses: Vec<trait_objects::TraitUse>,  // Traits named in dyn and impl types
+    foreign_items: Vec<ffi::ForeignItem>,      // Items declared in extern blocks
+    pointer_params: Vec<ffi::RawPointerParam>, // Function parameters taking raw pointers
+    global_state: Vec<global_state::GlobalState>, // static mut, lazily initialised and thread-local globals
+    literals: Vec<literals::LiteralUse>,          // Numeric and string literals in expressions
+    function_calls: Vec<recursion::FunctionCalls>, // Calls made by each function, for the call graph
+    param_ownership: Vec<ownership::ParamOwnership>, // How each function parameter is passed
+    parse_failures: Vec<editions::ParseFailure>,   // Files syn could not parse, degraded or skipped
+    collected: Vec<collector::Record>,             // Records of the custom collectors
+    collector_summaries: BTreeMap<String, serde_json::Value>, // Set once the analysis is complete
+    #[serde(skip)]
+    sample: Option<sampling::SampleInfo>, // Set when only a sample of the files was analysed
+    #[serde(skip)]
+    notes: Vec<String>,          // What was sampled, restored from the cache and resumed
+    #[serde(skip)]
+    warnings: Vec<String>,       // Files skipped and caches that could not be written
+}
+
+impl AnalysisResults {
+    fn new() -> Self {
+        AnalysisResults {
+            mutable_vars: Vec::new(),
+            immutable_vars: Vec::new(),
+            data_structures: Vec::new(),
+            errors: Vec::new(),
+            degraded_files: Vec::new(),
+            function_fingerprints: Vec::new(),
+            type_uses: Vec::new(),
+            scope_suggestions: Vec::new(),
+            call_args: Vec::new(),
+            copy_refs: Vec::new(),
+            function_states: Vec::new(),
+            interior_mutable_fields: Vec::new(),
+            unsafe_impls: Vec::new(),
+            exported_symbols: Vec::new(),
+            doc_tests: Vec::new(),
+            impl_blocks: Vec::new(),
+            globals: Vec::new(),
+            unsafe_sites: Vec::new(),
+            closures: Vec::new(),
+            macro_definitions: Vec::new(),
+            macro_invocations: Vec::new(),
+            async_functions: Vec::new(),
+            generic_items: Vec::new(),
+            feature_gates: Vec::new(),
+            declared_features: Vec::new(),
+            doc_items: Vec::new(),
+            interior_mutability: Vec::new(),
+            smart_pointers: Vec::new(),
+            error_sites: Vec::new(),
+            function_errors: Vec::new(),
+            allocations: Vec::new(),
+            type_references: Vec::new(),
+            function_metrics: Vec::new(),
+            file_lines: Vec::new(),
+            file_targets: Vec::new(),
+            trait_uses: Vec::new(),
+            foreign_items: Vec::new(),
+            pointer_params: Vec::new(),
+            global_state: Vec::new(),
+            literals: Vec::new(),
+            function_calls: Vec::new(),
+            param_ownership: Vec::new(),
+            parse_failures: Vec::new(),
+            collected: Vec::new(),
+            collector_summaries: BTreeMap::new(),
+            sample: None,
+            notes: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    // Append the results of another file (or set of files)
+    fn merge(&mut self, other: AnalysisResults) {
+        self.mutable_vars.extend(other.mutable_vars);
+        self.immutable_vars.extend(other.immutable_vars);
+        self.data_structures.extend(other.data_structures);
+        self.errors.extend(other.errors);
+        self.degraded_files.extend(other.degraded_files);
+        self.function_fingerprints
+            .extend(other.function_fingerprints);
+        self.type_uses.extend(other.type_uses);
+        self.scope_suggestions.extend(other.scope_suggestions);
+        self.call_args.extend(other.call_args);
+        self.copy_refs.extend(other.copy_refs);
+        self.function_states.extend(other.function_states);
+        self.interior_mutable_fields
+            .extend(other.interior_mutable_fields);
+        self.unsafe_impls.extend(other.unsafe_impls);
+        self.exported_symbols.extend(other.exported_symbols);
+        self.doc_tests.extend(other.doc_tests);
+        self.impl_blocks.extend(other.impl_blocks);
+        self.globals.extend(other.globals);
+        self.unsafe_sites.extend(other.unsafe_sites);
+        self.closures.extend(other.closures);
+        self.macro_definitions.extend(other.macro_definitions);
+        self.macro_invocations.extend(other.macro_invocations);
+        self.async_functions.extend(other.async_functions);
+        self.generic_items.extend(other.generic_items);
+        self.feature_gates.extend(other.feature_gates);
+        self.declared_features.extend(other.declared_features);
+        self.doc_items.extend(other.doc_items);
+        self.interior_mutability.extend(other.interior_mutability);
+        self.smart_pointers.extend(other.smart_pointers);
+        self.error_sites.extend(other.error_sites);
+        self.function_errors.extend(other.function_errors);
+        self.allocations.extend(other.allocations);
+        self.type_references.extend(other.type_references);
+        self.function_metrics.extend(other.function_metrics);
+        self.file_lines.extend(other.file_lines);
+        self.file_targets.extend(other.file_targets);
+        self.trait_uses.extend(other.trait_uses);
+        self.foreign_items.extend(other.foreign_items);
+        self.pointer_params.extend(other.pointer_params);
+        self.global_state.extend(other.global_state);
+        self.literals.extend(other.literals);
+        self.function_calls.extend(other.function_calls);
+        self.param_ownership.extend(other.param_ownership);
+        self.parse_failures.extend(other.parse_failures);
+        self.collected.extend(other.collected);
+        self.notes.extend(other.notes);
+        self.warnings.extend(other.warnings);
+    }
+
+    // Point every record of one file at a different path
+    fn relocate(&mut self, from: &Path, to: &Path) {
+        self.rewrite_paths(|path| (path == from).then(|| to.to_path_buf()));
+    }
+
+    // Replace the file path of every record for which `rewrite` gives a new path
+    fn rewrite_paths(&mut self, rewrite: impl Fn(&Path) -> Option<PathBuf>) {
+        for var in self
+            .mutable_vars
+            .iter_mut()
+            .chain(self.immutable_vars.iter_mut())
+        {
+            if let Some(path) = rewrite(&var.file_path) {
+                var.file_path = intern_path(&path);
+            }
+        }
+        for data_structure in &mut self.data_structures {
+            if let Some(path) = rewrite(&data_structure.file_path) {
+                data_structure.file_path = path;
+            }
+        }
+        for path in &mut self.degraded_files {
+            if let Some(rewritten) = rewrite(path) {
+                *path = rewritten;
+            }
+        }
+        for fingerprint in &mut self.function_fingerprints {
+            if let Some(path) = rewrite(&fingerprint.file_path) {
+                fingerprint.file_path = path;
+            }
+        }
+        for type_use in &mut self.type_uses {
+            if let Some(path) = rewrite(&type_use.file_path) {
+                type_use.file_path = path;
+            }
+        }
+        for suggestion in &mut self.scope_suggestions {
+            if let Some(path) = rewrite(&suggestion.file_path) {
+                suggestion.file_path = path;
+            }
+        }
+        for counts in &mut self.call_args {
+            if let Some(path) = rewrite(&counts.file_path) {
+                counts.file_path = path;
+            }
+        }
+        for finding in &mut self.copy_refs {
+            if let Some(path) = rewrite(&finding.file_path) {
+                finding.file_path = path;
+            }
+        }
+        for function in &mut self.function_states {
+            if let Some(path) = rewrite(&function.file_path) {
+                function.file_path = path;
+            }
+        }
+        for unsafe_impl in &mut self.unsafe_impls {
+            if let Some(path) = rewrite(&unsafe_impl.file_path) {
+                unsafe_impl.file_path = path;
+            }
+        }
+        for symbol in &mut self.exported_symbols {
+            if let Some(path) = rewrite(&symbol.file_path) {
+                symbol.file_path = path;
+            }
+        }
+        for item in &mut self.doc_tests {
+            if let Some(path) = rewrite(&item.file_path) {
+                item.file_path = path;
+            }
+        }
+        for block in &mut self.impl_blocks {
+            if let Some(path) = rewrite(&block.file_path) {
+                block.file_path = path;
+            }
+        }
+        for global in &mut self.globals {
+            if let Some(path) = rewrite(&global.file_path) {
+                global.file_path = path;
+            }
+        }
+        for site in &mut self.unsafe_sites {
+            if let Some(path) = rewrite(&site.file_path) {
+                site.file_path = path;
+            }
+        }
+        for closure in &mut self.closures {
+            if let Some(path) = rewrite(&closure.file_path) {
+                closure.file_path = path;
+            }
+        }
+        for definition in &mut self.macro_definitions {
+            if let Some(path) = rewrite(&definition.file_path) {
+                definition.file_path = path;
+            }
+        }
+        for file in &mut self.macro_invocations {
+            if let Some(path) = rewrite(&file.file_path) {
+                file.file_path = path;
+            }
+        }
+        for function in &mut self.async_functions {
+            if let Some(path) = rewrite(&function.file_path) {
+                function.file_path = path;
+            }
+        }
+        for item in &mut self.generic_items {
+            if let Some(path) = rewrite(&item.file_path) {
+                item.file_path = path;
+            }
+        }
+        for gate in &mut self.feature_gates {
+            if let Some(path) = rewrite(&gate.file_path) {
+                gate.file_path = path;
+            }
+        }
+        for item in &mut self.doc_items {
+            if let Some(path) = rewrite(&item.file_path) {
+                item.file_path = path;
+            }
+        }
+        for used in &mut self.interior_mutability {
+            if let Some(path) = rewrite(&used.file_path) {
+                used.file_path = path;
+            }
+        }
+        for pointer in &mut self.smart_pointers {
+            if let Some(path) = rewrite(&pointer.file_path) {
+                pointer.file_path = path;
+            }
+        }
+        for site in &mut self.error_sites {
+            if let Some(path) = rewrite(&site.file_path) {
+                site.file_path = path;
+            }
+        }
+        for function in &mut self.function_errors {
+            if let Some(path) = rewrite(&function.file_path) {
+                function.file_path = path;
+            }
+        }
+        for allocation in &mut self.allocations {
+            if let Some(path) = rewrite(&allocation.file_path) {
+                allocation.file_path = path;
+            }
+        }
+        for reference in &mut self.type_references {
+            if let Some(path) = rewrite(&reference.file_path) {
+                reference.file_path = path;
+            }
+        }
+        for function in &mut self.function_metrics {
+            if let Some(path) = rewrite(&function.file_path) {
+                function.file_path = path;
+            }
+        }
+        for file in &mut self.file_lines {
+            if let Some(path) = rewrite(&file.file_path) {
+                file.file_path = path;
+            }
+        }
+        for file in &mut self.file_targets {
+            if let Some(path) = rewrite(&file.file_path) {
+                file.file_path = path;
+            }
+        }
+        for trait_use in &mut self.trait_uses {
+            if let Some(path) = rewrite(&trait_use.file_path) {
+                trait_use.file_path = path;
+            }
+        }
+        for item in &mut self.foreign_items {
+            if let Some(path) = rewrite(&item.file_path) {
+                item.file_path = path;
+            }
+        }
+        for param in &mut self.pointer_params {
+            if let Some(path) = rewrite(&param.file_path) {
+                param.file_path = path;
+            }
+        }
+        for state in &mut self.global_state {
+            if let Some(path) = rewrite(&state.file_path) {
+                state.file_path = path;
+            }
+        }
+        for literal in &mut self.literals {
+            if let Some(path) = rewrite(&literal.file_path) {
+                literal.file_path = path;
+            }
+        }
+        for function in &mut self.function_calls {
+            if let Some(path) = rewrite(&function.file_path) {
+                function.file_path = path;
+            }
+        }
+        for param in &mut self.param_ownership {
+            if let Some(path) = rewrite(&param.file_path) {
+                param.file_path = path;
+            }
+        }
+        for failure in &mut self.parse_failures {
+            if let Some(path) = rewrite(&failure.file_path) {
+                failure.file_path = path;
+            }
+        }
+        for record in &mut self.collected {
+            if let Some(path) = rewrite(&record.file_path) {
+                record.file_path = path;
+            }
+        }
+        for error in &mut self.errors {
+            if let Some(path) = rewrite(&error.file_path) {
+                error.file_path = path;
+            }
+        }
+    }
+
+    // Drop the records of the files for which `removed` is true, e.g. before re-analysing them
+    // The interior mutable field names and declared features are not tied to a file and are
+    // kept
+    fn remove_files(&mut self, removed: impl Fn(&Path) -> bool) {
+        self.mutable_vars
+            .retain(|record| !removed(&record.file_path));
+        self.immutable_vars
+            .retain(|record| !removed(&record.file_path));
+        self.data_structures
+            .retain(|record| !removed(&record.file_path));
+        self.errors.retain(|record| !removed(&record.file_path));
+        self.function_fingerprints
+            .retain(|record| !removed(&record.file_path));
+        self.type_uses.retain(|record| !removed(&record.file_path));
+        self.scope_suggestions
+            .retain(|record| !removed(&record.file_path));
+        self.call_args.retain(|record| !removed(&record.file_path));
+        self.copy_refs.retain(|record| !removed(&record.file_path));
+        self.function_states
+            .retain(|record| !removed(&record.file_path));
+        self.unsafe_impls
+            .retain(|record| !removed(&record.file_path));
+        self.exported_symbols
+            .retain(|record| !removed(&record.file_path));
+        self.doc_tests.retain(|record| !removed(&record.file_path));
+        self.impl_blocks
+            .retain(|record| !removed(&record.file_path));
+        self.globals.retain(|record| !removed(&record.file_path));
+        self.unsafe_sites
+            .retain(|record| !removed(&record.file_path));
+        self.closures.retain(|record| !removed(&record.file_path));
+        self.macro_definitions
+            .retain(|record| !removed(&record.file_path));
+        self.macro_invocations
+            .retain(|record| !removed(&record.file_path));
+        self.async_functions
+            .retain(|record| !removed(&record.file_path));
+        self.generic_items
+            .retain(|record| !removed(&record.file_path));
+        self.feature_gates
+            .retain(|record| !removed(&record.file_path));
+        self.doc_items.retain(|record| !removed(&record.file_path));
+        self.interior_mutability
+            .retain(|record| !removed(&record.file_path));
+        self.smart_pointers
+            .retain(|record| !removed(&record.file_path));
+        self.error_sites
+            .retain(|record| !removed(&record.file_path));
+        self.function_errors
+            .retain(|record| !removed(&record.file_path));
+        self.allocations
+            .retain(|record| !removed(&record.file_path));
+        self.type_references
+            .retain(|record| !removed(&record.file_path));
+        self.function_metrics
+            .retain(|record| !removed(&record.file_path));
+        self.file_lines.retain(|record| !removed(&record.file_path));
+        self.file_targets
+            .retain(|record| !removed(&record.file_path));
+        self.trait_uses.retain(|record| !removed(&record.file_path));
+        self.foreign_items
+            .retain(|record| !removed(&record.file_path));
+        self.pointer_params
+            .retain(|record| !removed(&record.file_path));
+        self.global_state
+            .retain(|record| !removed(&record.file_path));
+        self.literals.retain(|record| !removed(&record.file_path));
+        self.function_calls
+            .retain(|record| !removed(&record.file_path));
+        self.param_ownership
+            .retain(|record| !removed(&record.file_path));
+        self.parse_failures
+            .retain(|record| !removed(&record.file_path));
+        self.collected.retain(|record| !removed(&record.file_path));
+        self.degraded_files.retain(|path| !removed(path));
+    }
+
+    // Order every record by file, so the output does not depend on the order files were
+    // found or restored from a cache; records of one file keep the order they were found in
+    // and variables and data structures are ordered by line, column and name
+    fn sort_by_location(&mut self) {
+        let by_location = |a: &VarInfo, b: &VarInfo| {
+            (&a.file_path, a.line_number, a.column, &a.name).cmp(&(
+                &b.file_path,
+                b.line_number,
+                b.column,
+                &b.name,
+            ))
+        };
+        self.mutable_vars.sort_by(by_location);
+        self.immutable_vars.sort_by(by_location);
+        self.data_structures.sort_by(|a, b| {
+            (&a.file_path, a.line_number, a.column, &a.name).cmp(&(
+                &b.file_path,
+                b.line_number,
+                b.column,
+                &b.name,
+            ))
+        });
+        self.errors.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        self.degraded_files.sort();
+        self.interior_mutable_fields.sort();
+        self.function_fingerprints
+            .sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        self.type_uses.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        self.scope_suggestions
+            .sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        self.call_args.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        self.copy_refs.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        self.function_states
+            .sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        self.unsafe_impls
+            .sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        self.exported_symbols
+            .sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        self.doc_tests.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        self.impl_blocks
+            .sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        self.globals.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        self.unsafe_sites
+            .sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        self.closures.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        self.macro_definitions
+            .sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        self.macro_invocations
+            .sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        self.async_functions
+            .sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        self.generic_items
+            .sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        self.feature_gates
+            .sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        self.doc_items.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        self.interior_mutability
+            .sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        self.smart_pointers
+            .sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        self.error_sites
+            .sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        self.function_errors
+            .sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        self.allocations
+            .sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        self.type_references
+            .sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        self.function_metrics
+            .sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        self.file_lines
+            .sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        self.file_targets
+            .sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        self.trait_uses
+            .sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        self.foreign_items
+            .sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        self.pointer_params
+            .sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        self.global_state
+            .sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        self.literals.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        self.function_calls
+            .sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        self.param_ownership
+            .sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        self.parse_failures
+            .sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        self.collected.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+    }
+}
+
+// How the analysis reacts to IO and parse errors
+struct FailurePolicy {
+    fail_fast: bool,      // Stop on the first error instead of collecting and continuing
+    allow_degraded: bool, // Analyse the items that parse when syn cannot parse a whole file
+}
+
+// Which files of the project directory are analysed
+struct WalkOptions {
+    max_depth: Option<usize>, // Directory levels below the project directory to descend into
+    respect_ignore: bool,     // Honour .gitignore and .ignore files and skip hidden files
+    follow_symlinks: bool,
+    excludes: Vec<String>, // Globs of files and directories to skip, relative to the project
+    target_dir: Option<PathBuf>, // Build directory reported by cargo, skipped like target/
+    targets: Option<targets::TargetSelection>, // Only files of these target kinds (--targets)
+}
+
+// Optional analyses run on every parsed source file
+#[derive(Default)]
+struct SourceOptions {
+    macro_bodies: bool,         // Also analyse inside macro_rules! bodies
+    analyse_doc_tests: bool,    // Also analyse the snippets of doc tests
+    exclude_tests: bool,        // Leave test files, #[cfg(test)] items and #[test] functions out
+    doc_coverage: bool,         // Record which public items are documented
+    scope_depth: Option<usize>, // Only record variables at most this many blocks deep
+    collectors: Vec<Arc<dyn collector::Collector>>, // Custom analyses registered by library users
+}
+
+// How results are written to an output file
+struct OutputOptions<'a> {
+    graph: &'a str, // Graph written by --format dot
+    csv: csv_output::CsvOptions,
+    columns: Option<&'a [String]>, // Variable columns chosen with --columns
+    group_by: Option<&'a str>,     // Aggregate the listings by this key (--group-by)
+    link: bool,
+}
+
+impl SourceOptions {
+    // Results analysed with different options cannot be reused from a cache or checkpoint
+    fn settings_key(&self) -> String {
+        let collectors: Vec<&str> = self
+            .collectors
+            .iter()
+            .map(|collector| collector.name())
+            .collect();
+        format!(
+            "macro_bodies={} analyse_doc_tests={} exclude_tests={} doc_coverage={} scope_depth={:?} collectors={}",
+            self.macro_bodies,
+            self.analyse_doc_tests,
+            self.exclude_tests,
+            self.doc_coverage,
+            self.scope_depth,
+            collectors.join(",")
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AnalysisMetadata {
+    project_name: String,
+    version: String,
+    datetime: String,
+    dependencies: Vec<String>, // Crates declared in Cargo.toml, to tell external types apart
+    cargo: Option<cargo::CargoMetadata>, // The workspace, unless a single file is analysed
+}
+
+// Function to describe a project from its cargo metadata
+fn project_metadata(project_dir: &str, datetime: String) -> Result<AnalysisMetadata, ForestError> {
+    let cargo_metadata = cargo::metadata(Path::new(project_dir))?;
+    // A virtual workspace is named after its directory
+    let (project_name, version) = match cargo_metadata.root_package() {
+        Some(package) => (package.name.clone(), package.version.clone()),
+        None => (
+            cargo_metadata
+                .workspace_root
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| "unknown".to_string()),
+            "unknown".to_string(),
+        ),
+    };
+    Ok(AnalysisMetadata {
+        project_name,
+        version,
+        datetime,
+        dependencies: cargo_metadata.dependency_names(),
+        cargo: Some(cargo_metadata),
+    })
+}
+
+// Function to find the Rust files of the project directory that are analysed, in path order
+fn project_files(
+    dir: &str,
+    walk: &WalkOptions,
+    options: &SourceOptions,
+    results: &mut AnalysisResults,
+    policy: &FailurePolicy,
+) -> Result<Vec<PathBuf>, ForestError> {
+    // Recursively visit directories to find the Rust files
+    let mut files = Vec::new();
+    visit_dirs(Path::new(dir), walk, &mut files, results, policy)?;
+    // Directory iteration order differs between file systems
+    files.sort();
+    if let Some(ref selection) = walk.targets {
+        files.retain(|file| selection.includes(file));
+    }
+    if options.exclude_tests {
+        files.retain(|file| code_class::file_class(file) != code_class::TEST);
+    }
+    Ok(files)
+}
+
+// Files each thread analyses between two merges of the results
+const FILES_PER_THREAD: usize = 16;
+
+// Files of a project left to analyse, after sampling and restoring unchanged or checkpointed
+// files, with what is needed to store the results of the files analysed
+struct ProjectRun {
+    files: Vec<PathBuf>,                      // Files to analyse, in path order
+    results: AnalysisResults,                 // Errors met walking the directories, sample info
+    restored: Vec<AnalysisResults>,           // Results of each restored file, in path order
+    cache: Option<cache::AnalysisCache>,      // Per-file cache in changed-files mode
+    checkpoint: Option<cache::CheckpointLog>, // Log of the completed files, until it fails
+    notes: Vec<String>,                       // What was sampled, restored and resumed
+}
+
+impl ProjectRun {
+    // Function to store the results of a freshly analysed file in the cache and checkpoint
+    // Returns a warning when the checkpoint cannot be written, after which it is abandoned
+    fn update(
+        &mut self,
+        project_dir: &Path,
+        file: &Path,
+        file_results: &AnalysisResults,
+    ) -> Option<String> {
+        if let Some(ref mut cache) = self.cache {
+            cache.update(project_dir, file, file_results);
+        }
+        let written = self
+            .checkpoint
+            .as_mut()?
+            .update(project_dir, file, file_results);
+        self.checkpoint_failed(written)
+    }
+
+    // Function to flush the checkpoint to disk, returning a warning when it cannot be written
+    fn save_checkpoint(&mut self) -> Option<String> {
+        let flushed = self.checkpoint.as_mut()?.flush();
+        self.checkpoint_failed(flushed)
+    }
+
+    // Function to abandon the checkpoint after a failed write, describing the failure
+    fn checkpoint_failed(&mut self, written: io::Result<()>) -> Option<String> {
+        let e = written.err()?;
+        self.checkpoint = None;
+        Some(format!("could not write the checkpoint: {}", e))
+    }
+
+    // Function to finish a completed run: the checkpoint is removed and the cache written
+    fn finish(self, project_dir: &Path) -> Option<String> {
+        // The run completed, so there is nothing left to resume
+        drop(self.checkpoint);
+        cache::CheckpointLog::remove(project_dir);
+        interning::clear_all();
+        self.cache.and_then(|cache| {
+            cache
+                .save(project_dir)
+                .err()
+                .map(|e| format!("could not write the analysis cache: {}", e))
+        })
+    }
+}
+
+// Function to find the files of the project directory to analyse with the given options
+fn plan_project(
+    dir: &str,
+    analysis_options: &AnalysisOptions,
+    cargo_metadata: Option<&cargo::CargoMetadata>,
+) -> Result<ProjectRun, ForestError> {
+    let (policy, options) = (analysis_options.policy(), analysis_options.source());
+    let walk = analysis_options.walk(cargo_metadata);
+    let mut results = AnalysisResults::new();
+    let mut files = project_files(dir, &walk, options, &mut results, policy)?;
+    let mut notes = Vec::new();
+
+    // Only analyse a subset of the files in sampling mode
+    if let Some((spec, seed)) = analysis_options.sampling() {
+        let (sampled, info) = sampling::sample_files(files, spec, seed);
+        notes.push(format!(
+            "Sampling {} of {} files (seed {})",
+            info.sampled_files, info.total_files, info.seed
+        ));
+        files = sampled;
+        results.sample = Some(info);
+    }
+
+    // Function to keep the files without results in `source`, collecting the others
+    let restore = |files: &mut Vec<PathBuf>,
+                   source: &cache::AnalysisCache,
+                   restored: &mut Vec<AnalysisResults>,
+                   keep: &dyn Fn(&Path) -> bool| {
+        files.retain(|file| {
+            if keep(file) {
+                return true;
+            }
+            let mut file_results = AnalysisResults::new();
+            let found = source.restore(Path::new(dir), file, &mut file_results);
+            if found {
+                restored.push(file_results);
+            }
+            !found
+        });
+    };
+
+    // In changed-files mode, unchanged files are restored from the cache when possible
+    let project_dir = Path::new(dir);
+    let settings = options.settings_key();
+    let mut restored = Vec::new();
+    let mut cache = None;
+    if let Some(since) = analysis_options.changed_since() {
+        // Ask git for the modified files in changed-files mode
+        let changed = git::changed_rust_files(project_dir, Some(since))?;
+        let loaded = cache::AnalysisCache::load(project_dir, &settings);
+        let total = files.len();
+        restore(&mut files, &loaded, &mut restored, &|file| {
+            changed.contains(file)
+        });
+        notes.push(format!(
+            "Analysing {} changed or uncached files, {} restored from cache",
+            files.len(),
+            total - files.len()
+        ));
+        cache = Some(loaded);
+    }
+
+    // When resuming, files completed by an interrupted run are restored from its checkpoint
+    let mut resumed = false;
+    if analysis_options.resumes() {
+        let checkpoint = cache::AnalysisCache::load_checkpoint(project_dir, &settings);
+        let total = files.len();
+        restore(&mut files, &checkpoint, &mut restored, &|_| false);
+        notes.push(format!(
+            "Resuming: {} files restored from the checkpoint, {} left to analyse",
+            total - files.len(),
+            files.len()
+        ));
+        resumed = !checkpoint.is_empty();
+    }
+    // Completed files are appended to the checkpoint log rather than kept in memory
+    let checkpoint = match cache::CheckpointLog::create(project_dir, &settings, resumed) {
+        Ok(log) => Some(log),
+        Err(e) => {
+            results
+                .warnings
+                .push(format!("could not write the checkpoint: {}", e));
+            None
+        }
+    };
+
+    Ok(ProjectRun {
+        files,
+        results,
+        restored,
+        cache,
+        checkpoint,
+        notes,
+    })
+}
+
+// Function to analyse the project directory
+fn analyse_project(
+    dir: &str,
+    analysis_options: &AnalysisOptions,
+    cargo_metadata: Option<&cargo::CargoMetadata>,
+) -> Result<AnalysisResults, ForestError> {
+    let (policy, options) = (analysis_options.policy(), analysis_options.source());
+    let project_dir = Path::new(dir);
+    // rust-analyzer loads the project first, so a project it cannot load fails early
+    let semantic = analysis_options
+        .resolves_types()
+        .then(|| semantic::Project::load(project_dir))
+        .transpose()?;
+    let mut run = plan_project(dir, analysis_options, cargo_metadata)?;
+    let mut results = std::mem::replace(&mut run.results, AnalysisResults::new());
+    results.notes = std::mem::take(&mut run.notes);
+    for restored in std::mem::take(&mut run.restored) {
+        results.merge(restored);
+    }
+    let files = std::mem::take(&mut run.files);
+
+    // Files are analysed in parallel, a batch at a time, each into its own results; the
+    // results are merged in file order between batches, where the checkpoint is written
+    let batch_size = rayon::current_num_threads() * FILES_PER_THREAD;
+    let mut last_checkpoint = Instant::now();
+    for batch in files.chunks(batch_size) {
+        let analysed: Vec<Result<AnalysisResults, ForestError>> = batch
+            .par_iter()
+            .map(|file| {
+                let mut file_results = AnalysisResults::new();
+                analyse_file(file, &mut file_results, policy, options).map(|_| file_results)
+            })
+            .collect();
+        for (file, file_results) in batch.iter().zip(analysed) {
+            let file_results = file_results?;
+            results
+                .warnings
+                .extend(run.update(project_dir, file, &file_results));
+            results.merge(file_results);
+        }
+
+        // Persist the completed files now and then so an interrupted run can be resumed
+        if last_checkpoint.elapsed() >= cache::CHECKPOINT_INTERVAL {
+            results.warnings.extend(run.save_checkpoint());
+            last_checkpoint = Instant::now();
+        }
+    }
+    results.warnings.extend(run.finish(project_dir));
+    results.warnings.extend(cargo::take_manifest_warnings());
+    if let Some(project) = semantic {
+        let resolved = project.resolve_let_types(&mut results);
+        results.notes.push(format!(
+            "Resolved the types of {} let bindings with rust-analyzer",
+            resolved
+        ));
+    }
+    results.sort_by_location();
+    results.collector_summaries = collector::summarize(&options.collectors, &results.collected);
+
+    Ok(results)
+}
+
+// Function to record an error and the warning that the path was skipped, or abort the
+// analysis when failing fast
+fn record_failure(
+    results: &mut AnalysisResults,
+    policy: &FailurePolicy,
+    path: &Path,
+    error: ForestError,
+) -> Result<(), ForestError> {
+    if policy.fail_fast {
+        return Err(error);
+    }
+
+    let message = error.detail();
+    results
+        .warnings
+        .push(format!("skipping {}: {}", path.display(), message));
+    results.errors.push(AnalysisError {
+        file_path: path.to_path_buf(),
+        message,
+    });
+    Ok(())
+}
+
+// Function to visit directories and collect the Rust files to analyse
+// Unless --no-ignore is given, .gitignore, .ignore and global git excludes are honoured
+// and hidden files and directories are skipped
+fn visit_dirs(
+    dir: &Path,
+    walk: &WalkOptions,
+    files: &mut Vec<PathBuf>,
+    results: &mut AnalysisResults,
+    policy: &FailurePolicy,
+) -> Result<(), ForestError> {
+    // Compared canonically, since the walk and cargo may spell the directory differently
+    let target_dir = walk
+        .target_dir
+        .as_ref()
+        .and_then(|target_dir| fs::canonicalize(target_dir).ok());
+    let mut walker = ignore::WalkBuilder::new(dir);
+    // Excluded globs are matched like .gitignore lines, relative to the project directory
+    if !walk.excludes.is_empty() {
+        let mut excludes = ignore::overrides::OverrideBuilder::new(dir);
+        for glob in &walk.excludes {
+            excludes.add(&format!("!{}", glob)).map_err(|e| {
+                ForestError::Config(format!("invalid --exclude glob {}: {}", glob, e))
+            })?;
+        }
+        let excludes = excludes
+            .build()
+            .map_err(|e| ForestError::Config(format!("invalid --exclude globs: {}", e)))?;
+        walker.overrides(excludes);
+    }
+    walker
+        .standard_filters(walk.respect_ignore)
+        .follow_links(walk.follow_symlinks)
+        .require_git(false)
+        // Files directly in the project directory are at depth 1
+        .max_depth(walk.max_depth.map(|max| max + 1))
+        // Skip target directory, which contains build artifacts
+        .filter_entry(move |entry| {
+            !(entry.file_type().is_some_and(|kind| kind.is_dir())
+                && (entry.file_name() == "target"
+                    || target_dir.as_ref().is_some_and(|target_dir| {
+                        fs::canonicalize(entry.path()).is_ok_and(|path| path == *target_dir)
+                    })))
+        });
+
+    for entry in walker.build() {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                record_failure(
+                    results,
+                    policy,
+                    dir,
+                    ForestError::io(dir, io::Error::other(e)),
+                )?;
+                continue;
+            }
+        };
+        let path = entry.path();
+        if entry.file_type().is_some_and(|kind| kind.is_file())
+            && path.extension().is_some_and(|extension| extension == "rs")
+        {
+            files.push(path.to_path_buf());
+        }
+    }
+    Ok(())
+}
+
+// Function to analyse a single file with syn parser
+fn analyse_file(
+    file_path: &Path,
+    results: &mut AnalysisResults,
+    policy: &FailurePolicy,
+    options: &SourceOptions,
+) -> Result<(), ForestError> {
+    let content = match fs::read_to_string(file_path) {
+        Ok(content) => content,
+        Err(e) => return record_failure(results, policy, file_path, ForestError::io(file_path, e)),
+    };
+
+    analyse_source(file_path, &content, results, policy, options)
+}
+
+// Function to analyse the source code of a file that has already been read
+fn analyse_source(
+    file_path: &Path,
+    content: &str,
+    results: &mut AnalysisResults,
+    policy: &FailurePolicy,
+    options: &SourceOptions,
+) -> Result<(), ForestError> {
+    let analysed = analyse_parsed(file_path, content, results, policy, options);
+    // Every line and column has been copied out of the spans by now, so the thread's span
+    // map is cleared; otherwise it keeps the source of every file parsed on this thread
+    proc_macro2::extra::invalidate_current_thread_spans();
+    analysed
+}
+
+// Function to parse the source code of a file and record what it declares
+fn analyse_parsed(
+    file_path: &Path,
+    content: &str,
+    results: &mut AnalysisResults,
+    policy: &FailurePolicy,
+    options: &SourceOptions,
+) -> Result<(), ForestError> {
+    // Records from here on belong to this file and are classified once it is analysed
+    let first_records = (
+        results.mutable_vars.len(),
+        results.immutable_vars.len(),
+        results.data_structures.len(),
+    );
+
+    // Lines are counted from the text, so degraded files are counted too
+    results.file_lines.push(line_stats::FileLines {
+        file_path: file_path.to_path_buf(),
+        module: module_paths::module_path_for(file_path),
+        lines: line_stats::count_lines(content),
+    });
+
+    // Parse with syn to get the AST
+    let edition = editions::edition_for(file_path);
+    let mut file_ast = match editions::parse_file(content, &edition) {
+        Ok(file_ast) => file_ast,
+        Err(e) if policy.allow_degraded => {
+            // Fall back to the items that parse on their own
+            results.degraded_files.push(file_path.to_path_buf());
+            results
+                .parse_failures
+                .push(editions::parse_failure(file_path, &edition, &e, true));
+            editions::recover_items(content, &edition)
+        }
+        Err(e) => {
+            results
+                .parse_failures
+                .push(editions::parse_failure(file_path, &edition, &e, false));
+            return record_failure(
+                results,
+                policy,
+                file_path,
+                ForestError::parse(file_path, &e),
+            );
+        }
+    };
+    if options.exclude_tests {
+        code_class::strip_test_code(&mut file_ast);
+    }
+    let module_path = module_paths::module_path_for(file_path);
+    let classes = code_class::classify(file_path, Some(&file_ast));
+    let lines = line_index::LineIndex::new(content);
+
+    // Walk the AST with the variable and data structure collectors, then the registered ones
+    walker::Walker::new(file_path, &lines, &module_path, results)
+        .code_class(classes.file_class)
+        .scope_depth(options.scope_depth)
+        .collectors(&options.collectors)
+        .visit_file(&file_ast);
+
+    if options.macro_bodies {
+        macro_bodies::analyse_macro_bodies(&file_ast, file_path, &module_path, &lines, results);
+    }
+
+    duplicates::collect_fingerprints(&file_ast, file_path, &mut results.function_fingerprints);
+    type_usage::collect_type_uses(
+        &file_ast,
+        file_path,
+        results.mutable_vars.iter().chain(&results.immutable_vars),
+        &mut results.type_uses,
+    );
+    narrow_scope::collect_scope_suggestions(&file_ast, file_path, &mut results.scope_suggestions);
+    call_args::collect_call_args(&file_ast, file_path, &mut results.call_args);
+    copy_refs::collect_copy_refs(&file_ast, file_path, &mut results.copy_refs);
+    state_density::collect_function_states(
+        &file_ast,
+        file_path,
+        &mut results.function_states,
+        &mut results.interior_mutable_fields,
+    );
+    unsafe_code::collect_unsafe(
+        &file_ast,
+        file_path,
+        &module_path,
+        &mut results.unsafe_impls,
+        &mut results.unsafe_sites,
+    );
+    exports::collect_exports(&file_ast, file_path, &mut results.exported_symbols);
+    doc_tests::collect_doc_tests(
+        &file_ast,
+        file_path,
+        options.analyse_doc_tests,
+        &mut results.doc_tests,
+    );
+    impl_blocks::collect_impl_blocks(&file_ast, file_path, &mut results.impl_blocks);
+    globals::collect_globals(&file_ast, file_path, &mut results.globals);
+    closures::collect_closures(&file_ast, file_path, &module_path, &mut results.closures);
+    macros::collect_macros(
+        &file_ast,
+        file_path,
+        &mut results.macro_definitions,
+        &mut results.macro_invocations,
+    );
+    async_code::collect_async(
+        &file_ast,
+        file_path,
+        &module_path,
+        &mut results.async_functions,
+    );
+    generics::collect_generics(
+        &file_ast,
+        file_path,
+        &module_path,
+        &mut results.generic_items,
+    );
+    feature_gates::collect_feature_gates(
+        &file_ast,
+        file_path,
+        &mut results.feature_gates,
+        &mut results.declared_features,
+    );
+    let usage = usage::collect_usage(&file_ast, &lines);
+    record_usage(results, first_records, &usage);
+    tag_code_classes(results, first_records, &classes);
+    if options.doc_coverage {
+        doc_coverage::collect_doc_items(&file_ast, file_path, &module_path, &mut results.doc_items);
+    }
+    interior_mutability::collect_interior_mutability(
+        &file_ast,
+        file_path,
+        &module_path,
+        &mut results.interior_mutability,
+    );
+    smart_pointers::collect_smart_pointers(
+        &file_ast,
+        file_path,
+        &module_path,
+        &mut results.smart_pointers,
+    );
+    error_handling::collect_error_handling(
+        &file_ast,
+        file_path,
+        &module_path,
+        &mut results.error_sites,
+        &mut results.function_errors,
+    );
+    allocations::collect_allocations(&file_ast, file_path, &module_path, &mut results.allocations);
+    type_xref::collect_type_references(
+        &file_ast,
+        file_path,
+        &module_path,
+        &mut results.type_references,
+    );
+    complexity::collect_function_metrics(
+        &file_ast,
+        file_path,
+        &module_path,
+        &mut results.function_metrics,
+    );
+    trait_objects::collect_trait_objects(
+        &file_ast,
+        file_path,
+        &module_path,
+        &mut results.trait_uses,
+    );
+    ffi::collect_ffi(
+        &file_ast,
+        file_path,
+        &module_path,
+        &mut results.foreign_items,
+        &mut results.pointer_params,
+    );
+    global_state::collect_global_state(&file_ast, file_path, &mut results.global_state);
+    literals::collect_literals(&file_ast, file_path, &module_path, &mut results.literals);
+    recursion::collect_calls(
+        &file_ast,
+        file_path,
+        &module_path,
+        &mut results.function_calls,
+    );
+    ownership::collect_params(
+        &file_ast,
+        file_path,
+        &module_path,
+        &mut results.param_ownership,
+    );
+    Ok(())
+}
+
+// Function to record how often the variables of a file are read and written
+fn record_usage(
+    results: &mut AnalysisResults,
+    (first_mutable, first_immutable, _): (usize, usize, usize),
+    usage: &usage::UsageMap,
+) {
+    for var in results.mutable_vars[first_mutable..]
+        .iter_mut()
+        .chain(&mut results.immutable_vars[first_immutable..])
+    {
+        let counts = usage.usage_of(&var.name, var.line_number, var.column);
+        var.read_count = counts.reads;
+        var.write_count = counts.writes;
+        var.last_use_line = counts.last_line;
+    }
+}
+
+// Function to classify the variables and data structures recorded for a file
+// Records the variable visitor could not classify go by line
+fn tag_code_classes(
+    results: &mut AnalysisResults,
+    (first_mutable, first_immutable, first_structure): (usize, usize, usize),
+    classes: &code_class::CodeClasses,
+) {
+    for var in results.mutable_vars[first_mutable..]
+        .iter_mut()
+        .chain(&mut results.immutable_vars[first_immutable..])
+    {
+        if var.code_class.is_empty() {
+            var.code_class = classes.class_at(var.line_number).to_string();
+        }
+    }
+    for structure in &mut results.data_structures[first_structure..] {
+        if structure.code_class.is_empty() {
+            structure.code_class = classes.class_at(structure.line_number).to_string();
+        }
+    }
+}
+
+// Function to output results in JSON format
+fn output_json(
+    results: &AnalysisResults,
+    metadata: &AnalysisMetadata,
+    file: &str,
+    group_by: Option<&str>,
+    link: bool,
+) -> Result<(), ForestError> {
+    let mut model = results_model(results, metadata, link);
+    if let Some(by) = group_by {
+        model.sections.insert(
+            "groups",
+            output_model::section(output_model::Groups {
+                by,
+                groups: grouping::group(results, by),
+            }),
+        );
+    }
+    let json = serde_json::to_string_pretty(&model).map_err(|e| ForestError::format("json", e))?;
+    fs::write(file, json).map_err(|e| ForestError::output("json", file, e))
+}
+
+// Function to build the results model written by --format json and rendered by --template
+fn results_model(
+    results: &AnalysisResults,
+    metadata: &AnalysisMetadata,
+    link: bool,
+) -> output_model::Output {
+    use output_model::*;
+
+    // Analysis sections, next to the modelled metadata, variables and data structures
+    let mut output = BTreeMap::new();
+
+    if let Some(ref sample) = results.sample {
+        output.insert(
+            "sample",
+            section(Sample {
+                estimated: true,
+                total_files: sample.total_files,
+                sampled_files: sample.sampled_files,
+                seed: sample.seed,
+                estimated_mutable_variable_count: sample.estimate(results.mutable_vars.len()),
+                estimated_immutable_variable_count: sample.estimate(results.immutable_vars.len()),
+                estimated_data_structure_count: sample.estimate(results.data_structures.len()),
+            }),
+        );
+    }
+
+    output.insert("errors", section(&results.errors));
+    if !results.collected.is_empty() || !results.collector_summaries.is_empty() {
+        output.insert(
+            "collectors",
+            collector::section(&results.collected, &results.collector_summaries),
+        );
+    }
+    output.insert("degraded_files", section(&results.degraded_files));
+    let fallback_reasons: Vec<&editions::ParseFailure> = results
+        .parse_failures
+        .iter()
+        .filter(|failure| failure.fallback)
+        .collect();
+    output.insert("fallback_reasons", section(fallback_reasons));
+    // Always present, so consumers can tell a clean parse from a missing section
+    output.insert("parse_failures", section(&results.parse_failures));
+
+    let duplicate_groups: Vec<DuplicateGroup> =
+        duplicates::find_duplicates(&results.function_fingerprints)
+            .iter()
+            .map(|group| DuplicateGroup {
+                kind: group.kind,
+                token_count: group.token_count,
+                functions: group
+                    .functions
+                    .iter()
+                    .map(|f| FunctionLocation {
+                        name: &f.name,
+                        file: f.file_path.display().to_string(),
+                        line: f.line_number,
+                    })
+                    .collect(),
+            })
+            .collect();
+    output.insert("duplicate_functions", section(duplicate_groups));
+
+    let type_summary = type_usage::summarize(
+        &results.type_uses,
+        &metadata.dependencies,
+        &results.data_structures,
+    );
+    let external_types: Vec<CrateTypes> = type_summary
+        .by_crate
+        .iter()
+        .map(|usage| CrateTypes {
+            crate_name: &usage.crate_name,
+            uses: usage.uses,
+            types: usage
+                .types
+                .iter()
+                .map(|(type_name, count)| TypeCount {
+                    type_name,
+                    count: *count,
+                })
+                .collect(),
+        })
+        .collect();
+    let scope_suggestions: Vec<ScopeSuggestion> = results
+        .scope_suggestions
+        .iter()
+        .map(|s| ScopeSuggestion {
+            name: &s.name,
+            function: &s.function,
+            file: s.file_path.display().to_string(),
+            declaration_range: [s.declaration_line, s.scope_end_line],
+            usage_range: [s.usage_start_line, s.usage_end_line],
+            narrower_scope: &s.narrower_scope,
+        })
+        .collect();
+    output.insert("scope_suggestions", section(scope_suggestions));
+
+    let call_summary = call_args::summarize(&results.call_args);
+    output.insert(
+        "call_arguments",
+        section(CallArguments {
+            moved: call_summary.moved,
+            borrowed: call_summary.borrowed,
+            mutably_borrowed: call_summary.mutably_borrowed,
+            top_mut_callees: call_summary
+                .top_mut_callees
+                .iter()
+                .map(|callee| Callee {
+                    callee: &callee.callee,
+                    calls: callee.calls,
+                    moved: callee.moved,
+                    borrowed: callee.borrowed,
+                    mutably_borrowed: callee.mutably_borrowed,
+                })
+                .collect(),
+        }),
+    );
+
+    output.insert("copy_type_references", section(&results.copy_refs));
+
+    let density =
+        state_density::summarize(&results.function_states, &results.interior_mutable_fields);
+    output.insert(
+        "mutable_state_density",
+        section(StateDensity {
+            overall: density.overall,
+            top_functions: density
+                .top_functions
+                .iter()
+                .map(|ranked| {
+                    let function = ranked.function;
+                    StatefulFunction {
+                        name: &function.name,
+                        file: function.file_path.display().to_string(),
+                        line: function.line_number,
+                        lines: function.lines,
+                        mutable_locals: function.mutable_locals,
+                        mut_params: function.mut_params,
+                        interior_mutability_fields: ranked.interior_fields,
+                        density: ranked.density,
+                    }
+                })
+                .collect(),
+        }),
+    );
+
+    output.insert(
+        "unsafe_code",
+        section(UnsafeCode {
+            unsafe_blocks: unsafe_code::count_kind(&results.unsafe_sites, "block"),
+            unsafe_fns: unsafe_code::count_kind(&results.unsafe_sites, "fn"),
+            unsafe_traits: unsafe_code::count_kind(&results.unsafe_sites, "trait"),
+            unsafe_impls: unsafe_code::sorted_for_review(&results.unsafe_impls)
+                .iter()
+                .map(|unsafe_impl| UnsafeImpl {
+                    trait_name: &unsafe_impl.trait_name,
+                    self_type: &unsafe_impl.self_type,
+                    send_or_sync: unsafe_impl.is_send_or_sync(),
+                    file: unsafe_impl.file_path.display().to_string(),
+                    line: unsafe_impl.line_number,
+                })
+                .collect(),
+            unsafe_sites: &results.unsafe_sites,
+        }),
+    );
+
+    output.insert("exported_symbols", section(&results.exported_symbols));
+
+    output.insert(
+        "doc_tests",
+        section(DocTests {
+            per_crate: doc_tests::tests_per_crate(&results.doc_tests),
+            items: &results.doc_tests,
+        }),
+    );
+
+    output.insert(
+        "impl_blocks",
+        section(ImplBlocks {
+            blocks: &results.impl_blocks,
+            methods_per_type: impl_blocks::methods_per_type(&results.impl_blocks)
+                .into_iter()
+                .collect(),
+        }),
+    );
+
+    let globals: Vec<Global> = results
+        .globals
+        .iter()
+        .map(|global| Global {
+            global,
+            vscode_link: link.then(|| links::editor_link(&global.file_path, global.line_number)),
+        })
+        .collect();
+    output.insert("globals", section(globals));
+
+    output.insert("closures", section(&results.closures));
+
+    output.insert(
+        "macros",
+        section(Macros {
+            definitions: &results.macro_definitions,
+            invocations_per_file: results
+                .macro_invocations
+                .iter()
+                .map(|file| MacroInvocations {
+                    file: file.file_path.display().to_string(),
+                    total: file.total(),
+                    counts: &file.counts,
+                })
+                .collect(),
+            top_invoked: macros::top_invoked(&results.macro_invocations)
+                .into_iter()
+                .map(|(name, count)| NameCount { name, count })
+                .collect(),
+        }),
+    );
+
+    output.insert(
+        "async_functions",
+        section(async_code::by_awaits(&results.async_functions)),
+    );
+
+    output.insert(
+        "generics",
+        section(Generics {
+            items: &results.generic_items,
+            lifetime_heavy_modules: generics::lifetime_heavy_modules(&results.generic_items)
+                .into_iter()
+                .collect(),
+        }),
+    );
+
+    let features: Vec<Feature> =
+        feature_gates::feature_report(&results.feature_gates, &results.declared_features)
+            .iter()
+            .map(|feature| Feature {
+                crate_name: feature.crate_name,
+                feature: feature.feature,
+                declared: feature.declared,
+                gates: feature
+                    .gates
+                    .iter()
+                    .map(|gate| FeatureGate {
+                        gated: &gate.gated,
+                        file: gate.file_path.display().to_string(),
+                        line: gate.line_number,
+                    })
+                    .collect(),
+            })
+            .collect();
+    output.insert("feature_gates", section(features));
+
+    if !results.doc_items.is_empty() {
+        let public_items = doc_coverage::public_items(&results.doc_items);
+        let documented = public_items
+            .iter()
+            .filter(|&&(_, documented)| documented)
+            .count();
+        let modules: Vec<ModuleDocCoverage> = doc_coverage::module_coverage(&results.doc_items)
+            .iter()
+            .map(|module| ModuleDocCoverage {
+                module: module.module,
+                documented: module.documented,
+                total: module.total,
+                coverage: module.percentage(),
+                undocumented: module
+                    .undocumented
+                    .iter()
+                    .map(|item| UndocumentedItem {
+                        item: &item.item,
+                        kind: &item.kind,
+                        file: item.file_path.display().to_string(),
+                        line: item.line_number,
+                        vscode_link: link
+                            .then(|| links::editor_link(&item.file_path, item.line_number)),
+                    })
+                    .collect(),
+            })
+            .collect();
+        output.insert(
+            "doc_coverage",
+            section(DocCoverage {
+                documented,
+                total: public_items.len(),
+                coverage: doc_coverage::percentage(documented, public_items.len()),
+                modules,
+            }),
+        );
+    }
+
+    let unused_mut: Vec<UnusedMut> =
+        usage::unused_mut(&results.mutable_vars, &results.degraded_files)
+            .iter()
+            .map(|var| UnusedMut {
+                name: &var.name,
+                scope: &var.scope,
+                file: var.file_path.display().to_string(),
+                line: var.line_number,
+                read_count: var.read_count,
+                vscode_link: link.then(|| var.editor_link()),
+            })
+            .collect();
+    output.insert("unused_mut", section(unused_mut));
+
+    output.insert("interior_mutability", section(&results.interior_mutability));
+
+    output.insert(
+        "smart_pointers",
+        section(SmartPointers {
+            usages: &results.smart_pointers,
+            per_module: smart_pointers::module_tally(&results.smart_pointers),
+        }),
+    );
+
+    let function_errors: Vec<FunctionErrors> = results
+        .function_errors
+        .iter()
+        .filter(|function| {
+            function.unwraps + function.expects + function.panics + function.tries > 0
+        })
+        .map(|function| FunctionErrors {
+            function: &function.function,
+            file: function.file_path.display().to_string(),
+            line: function.line_number,
+            lines: function.lines,
+            unwrap: function.unwraps,
+            expect: function.expects,
+            panics: function.panics,
+            tries: function.tries,
+            unwrap_density: function.unwrap_density(),
+        })
+        .collect();
+    output.insert(
+        "error_handling",
+        section(ErrorHandling {
+            sites: &results.error_sites,
+            functions: function_errors,
+            riskiest: error_handling::riskiest(&results.function_errors)
+                .into_iter()
+                .map(|function| function.function.as_str())
+                .collect(),
+        }),
+    );
+
+    output.insert(
+        "allocations",
+        section(Allocations {
+            calls: &results.allocations,
+            hotspots: allocations::hotspots(&results.allocations)
+                .iter()
+                .map(|hotspot| AllocationHotspot {
+                    function: hotspot.function,
+                    file: hotspot.file_path.display().to_string(),
+                    line: hotspot.first_line,
+                    calls: hotspot.count,
+                })
+                .collect(),
+        }),
+    );
+
+    let xrefs = type_xref::cross_reference(&results.data_structures, &results.type_references);
+    let xrefs: Vec<TypeXref> = xrefs
+        .iter()
+        .map(|xref| TypeXref {
+            name: xref.name(),
+            kind: xref.kind(),
+            file: xref.declaration.file_path.display().to_string(),
+            line: xref.declaration.line_number,
+            count: xref.references.len(),
+            contexts: xref.contexts(),
+            references: xref
+                .references
+                .iter()
+                .map(|reference| TypeReference {
+                    context: &reference.context,
+                    owner: &reference.owner,
+                    file: reference.file_path.display().to_string(),
+                    line: reference.line_number,
+                })
+                .collect(),
+        })
+        .collect();
+    output.insert("type_xref", section(xrefs));
+
+    output.insert(
+        "functions",
+        section(Functions {
+            metrics: &results.function_metrics,
+            most_complex: complexity::most_complex(&results.function_metrics)
+                .into_iter()
+                .map(|function| function.function.as_str())
+                .collect(),
+        }),
+    );
+
+    output.insert(
+        "lines",
+        section(Lines {
+            totals: line_stats::project_totals(&results.file_lines),
+            modules: line_stats::module_totals(&results.file_lines),
+            files: &results.file_lines,
+        }),
+    );
+
+    output.insert(
+        "targets",
+        section(Targets {
+            counts: targets::counts(results),
+            files: &results.file_targets,
+        }),
+    );
+
+    output.insert(
+        "trait_objects",
+        section(TraitObjects {
+            uses: &results.trait_uses,
+            by_trait: trait_objects::dispatch_by_trait(&results.trait_uses)
+                .into_iter()
+                .map(|(trait_name, counts)| {
+                    let dispatch = Dispatch {
+                        dispatch: counts.label(),
+                        dyn_uses: counts.dyn_uses,
+                        impl_uses: counts.impl_uses,
+                    };
+                    (trait_name, dispatch)
+                })
+                .collect(),
+        }),
+    );
+
+    output.insert(
+        "ffi",
+        section(Ffi {
+            extern_items: &results.foreign_items,
+            raw_pointer_params: &results.pointer_params,
+            exported_symbols: results.exported_symbols.len(),
+        }),
+    );
+
+    output.insert("global_mutable_state", section(&results.global_state));
+
+    let literal_groups: Vec<LiteralGroup> = literals::group_literals(&results.literals)
+        .iter()
+        .map(|group| LiteralGroup {
+            kind: group.kind,
+            value: group.value,
+            count: group.uses.len(),
+            locations: group
+                .uses
+                .iter()
+                .map(|literal| LiteralLocation {
+                    scope: &literal.scope,
+                    file: literal.file_path.display().to_string(),
+                    line: literal.line_number,
+                })
+                .collect(),
+        })
+        .collect();
+    output.insert("literals", section(literal_groups));
+
+    let cycles: Vec<Cycle> = recursion::find_cycles(&results.function_calls)
+        .iter()
+        .map(|cycle| Cycle {
+            kind: if cycle.members.len() == 1 {
+                "direct"
+            } else {
+                "mutual"
+            },
+            members: cycle
+                .members
+                .iter()
+                .map(|member| CycleMember {
+                    function: &member.function,
+                    file: member.file_path.display().to_string(),
+                    line: member.line_number,
+                })
+                .collect(),
+        })
+        .collect();
+    output.insert("recursion", section(cycles));
+
+    let ownership_by_module = ownership::module_ownership(
+        &results.param_ownership,
+        results.mutable_vars.iter().chain(&results.immutable_vars),
+    );
+    output.insert(
+        "ownership",
+        section(Ownership {
+            total: ownership::project_ownership(&ownership_by_module),
+            modules: &ownership_by_module,
+            params: results
+                .param_ownership
+                .iter()
+                .map(|param| ParamOwnership {
+                    function: &param.function,
+                    param: &param.param,
+                    basic_type: &param.basic_type,
+                    ownership: param.ownership.label(),
+                    file: param.file_path.display().to_string(),
+                    line: param.line_number,
+                })
+                .collect(),
+        }),
+    );
+
+    let layouts: Vec<StructLayout> = struct_layout::estimate_layouts(&results.data_structures)
+        .iter()
+        .map(|layout| StructLayout {
+            name: layout.structure.display_name(),
+            estimated_size: layout.size,
+            estimated_align: layout.align,
+            heap_fields: layout.heap_fields,
+            heap_heavy: layout.heap_heavy(),
+            file: layout.structure.file_path.display().to_string(),
+            line: layout.structure.line_number,
+        })
+        .collect();
+    output.insert("struct_layout", section(layouts));
+
+    output.insert(
+        "type_usage",
+        section(TypeUsage {
+            std_uses: type_summary.std_uses,
+            external_uses: type_summary.external_uses,
+            local_uses: type_summary.local_uses,
+            external_types,
+        }),
+    );
+
+    output_model::Output::new(results, metadata, link, output)
+}
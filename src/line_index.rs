@@ -0,0 +1,64 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Line table of a source file.
+// The byte offset where each line starts is computed once per file, so the line, column and
+// source text of a declaration are found from the byte offset of its span by a binary
+// search instead of searching the text. Lines and columns count from 1, as editors do.
+
+pub struct LineIndex<'a> {
+    source: &'a str,
+    starts: Vec<usize>, // Byte offset of the first character of each line
+}
+
+impl<'a> LineIndex<'a> {
+    pub fn new(source: &'a str) -> Self {
+        let starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+        LineIndex { source, starts }
+    }
+
+    // Function to find the line a byte offset is on
+    pub fn line_of(&self, offset: usize) -> usize {
+        self.starts.partition_point(|&start| start <= offset)
+    }
+
+    // Function to find the column of a byte offset, counting characters from the line start
+    pub fn column_of(&self, offset: usize) -> usize {
+        let start = self.starts[self.line_of(offset) - 1];
+        let offset = offset.min(self.source.len());
+        self.source
+            .get(start..offset)
+            .map_or(1, |text| text.chars().count() + 1)
+    }
+
+    // Function to get the text of a line, without its line ending
+    pub fn line(&self, line_number: usize) -> Option<&'a str> {
+        let start = *self.starts.get(line_number.checked_sub(1)?)?;
+        let end = self
+            .starts
+            .get(line_number)
+            .map_or(self.source.len(), |next| next - 1);
+        let line = &self.source[start..end];
+        Some(line.strip_suffix('\r').unwrap_or(line))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_lines_and_columns() {
+        let source = "fn main() {\r\n    let é = 1;\n}";
+        let lines = LineIndex::new(source);
+        let offset = source.find("= 1").unwrap();
+
+        assert_eq!(lines.line_of(0), 1);
+        assert_eq!(lines.line_of(offset), 2);
+        assert_eq!(lines.column_of(offset), 11); // é is one character of two bytes
+        assert_eq!(lines.line(1), Some("fn main() {"));
+        assert_eq!(lines.line(3), Some("}"));
+        assert_eq!(lines.line(0), None);
+        assert_eq!(lines.line(4), None);
+    }
+}
@@ -0,0 +1,119 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Line counts per file and module.
+// Each line of a source file is blank, comment or code. A line with any code on it counts
+// as code, even with a trailing comment; lines inside block comments (which may nest)
+// count as comments. The scan is textual, so it works for files syn cannot parse, but
+// comment markers inside string literals are taken at face value.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::ops::AddAssign;
+use std::path::PathBuf;
+
+// Line counts of a file, module or project
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+pub struct LineCounts {
+    pub total: usize,
+    pub code: usize,
+    pub comment: usize,
+    pub blank: usize,
+}
+
+impl AddAssign for LineCounts {
+    fn add_assign(&mut self, other: Self) {
+        self.total += other.total;
+        self.code += other.code;
+        self.comment += other.comment;
+        self.blank += other.blank;
+    }
+}
+
+// Line counts of one source file
+#[derive(Clone, Serialize, Deserialize)]
+pub struct FileLines {
+    #[serde(rename = "file")]
+    pub file_path: PathBuf,
+    pub module: String,
+    pub lines: LineCounts,
+}
+
+// Function to classify the lines of a source file
+pub fn count_lines(content: &str) -> LineCounts {
+    let mut counts = LineCounts::default();
+    let mut comment_depth = 0; // Nesting of the block comments open at the start of a line
+    for line in content.lines() {
+        counts.total += 1;
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            if comment_depth > 0 {
+                counts.comment += 1;
+            } else {
+                counts.blank += 1;
+            }
+            continue;
+        }
+        if has_code(trimmed, &mut comment_depth) {
+            counts.code += 1;
+        } else {
+            counts.comment += 1;
+        }
+    }
+    counts
+}
+
+// Function to check whether a line holds code outside comments, tracking block comments
+fn has_code(line: &str, comment_depth: &mut usize) -> bool {
+    let mut code = false;
+    let mut rest = line;
+    while !rest.is_empty() {
+        if *comment_depth > 0 {
+            let open = rest.find("/*");
+            match rest.find("*/") {
+                Some(close) if open.is_none_or(|open| close < open) => {
+                    *comment_depth -= 1;
+                    rest = &rest[close + 2..];
+                }
+                _ => match open {
+                    Some(open) => {
+                        *comment_depth += 1;
+                        rest = &rest[open + 2..];
+                    }
+                    None => break,
+                },
+            }
+            continue;
+        }
+        let line_comment = rest.find("//");
+        match rest.find("/*") {
+            Some(open) if line_comment.is_none_or(|start| open < start) => {
+                code |= !rest[..open].trim().is_empty();
+                *comment_depth += 1;
+                rest = &rest[open + 2..];
+            }
+            _ => {
+                let end = line_comment.unwrap_or(rest.len());
+                code |= !rest[..end].trim().is_empty();
+                break;
+            }
+        }
+    }
+    code
+}
+
+// Function to total the line counts of the files in each module
+pub fn module_totals(files: &[FileLines]) -> BTreeMap<&str, LineCounts> {
+    let mut totals: BTreeMap<&str, LineCounts> = BTreeMap::new();
+    for file in files {
+        *totals.entry(file.module.as_str()).or_default() += file.lines;
+    }
+    totals
+}
+
+// Function to total the line counts of all files
+pub fn project_totals(files: &[FileLines]) -> LineCounts {
+    let mut totals = LineCounts::default();
+    for file in files {
+        totals += file.lines;
+    }
+    totals
+}
@@ -0,0 +1,187 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Editor links for paths seen by forest, which may not be the paths the editor sees.
+// `--link-map FROM=TO` rewrites path prefixes, e.g. when forest runs in a Docker
+// container over a bind-mounted project. Otherwise, when forest runs in WSL, over SSH
+// or in a devcontainer, links use the vscode-remote scheme so the host editor opens
+// the file inside that environment.
+// `--editor` picks the URL scheme of the links: the VS Code family keeps the remote
+// support, the other editors and `--editor-url` fill a template with the path and position.
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+static LINK_CONFIG: OnceLock<LinkConfig> = OnceLock::new();
+
+// Editors accepted by --editor; `custom` takes its URL template from --editor-url
+pub const EDITORS: &[&str] = &[
+    "vscode", "vscodium", "idea", "sublime", "emacs", "vim", "custom",
+];
+
+// The editor links open in
+pub enum Editor {
+    VsCode(&'static str), // URL scheme of VS Code or a fork, which understand vscode-remote
+    Template(String),     // URL with {path}, {line} and {column} placeholders
+}
+
+impl Editor {
+    // Function to select the editor by its --editor name, with the --editor-url template
+    pub fn new(name: &str, url_template: Option<&str>) -> Result<Self, String> {
+        let template = |url: &str| Ok(Editor::Template(url.to_string()));
+        match (name, url_template) {
+            (_, Some(url)) => template(url),
+            ("vscode", None) => Ok(Editor::VsCode("vscode")),
+            ("vscodium", None) => Ok(Editor::VsCode("vscodium")),
+            ("idea", None) => template("idea://open?file={path}&line={line}&column={column}"),
+            ("sublime", None) => {
+                template("subl://open?url=file://{path}&line={line}&column={column}")
+            }
+            ("emacs", None) => template("emacs://open?url=file://{path}&line={line}&column={column}"),
+            ("vim", None) => template("mvim://open?url=file://{path}&line={line}&column={column}"),
+            _ => Err(format!(
+                "--editor {} needs a URL template, e.g. --editor-url 'idea://open?file={{path}}&line={{line}}'",
+                name
+            )),
+        }
+    }
+}
+
+// How file paths are turned into editor links
+pub struct LinkConfig {
+    mappings: Vec<(PathBuf, PathBuf)>, // Path prefixes seen by forest -> seen by the editor
+    remote: Option<String>,            // vscode-remote authority, e.g. wsl+Ubuntu
+    editor: Editor,
+    relative_paths: bool, // --paths relative: links keep the paths relative to the project
+}
+
+impl LinkConfig {
+    // Build the configuration from the --link-map, --editor and --paths options and the environment
+    pub fn new(mappings: Vec<(PathBuf, PathBuf)>, editor: Editor, relative_paths: bool) -> Self {
+        LinkConfig {
+            mappings,
+            remote: detect_remote(),
+            editor,
+            relative_paths,
+        }
+    }
+}
+
+// Function to parse a --link-map value of the form FROM=TO
+pub fn parse_link_map(value: &str) -> Result<(PathBuf, PathBuf), String> {
+    match value.split_once('=') {
+        Some((from, to)) if !from.is_empty() && !to.is_empty() => {
+            Ok((PathBuf::from(from), PathBuf::from(to)))
+        }
+        _ => Err(format!(
+            "invalid link map '{}': expected FROM=TO, e.g. /container/src=/home/me/project",
+            value
+        )),
+    }
+}
+
+// Function to detect the vscode-remote authority of the environment forest runs in
+fn detect_remote() -> Option<String> {
+    if let Ok(distro) = std::env::var("WSL_DISTRO_NAME") {
+        return Some(format!("wsl+{}", distro));
+    }
+    // Set through `remoteEnv` in devcontainer.json: "${localWorkspaceFolder}"
+    if let Ok(host_folder) = std::env::var("LOCAL_WORKSPACE_FOLDER") {
+        let hex: String = host_folder.bytes().map(|b| format!("{:02x}", b)).collect();
+        return Some(format!("dev-container+{}", hex));
+    }
+    if std::env::var("SSH_CONNECTION").is_ok() {
+        let host = std::env::var("HOSTNAME").ok().or_else(|| {
+            std::fs::read_to_string("/etc/hostname")
+                .ok()
+                .map(|name| name.trim().to_string())
+        })?;
+        return Some(format!("ssh-remote+{}", host));
+    }
+    None
+}
+
+// Function to install the link configuration for this run
+pub fn configure(config: LinkConfig) {
+    // Only the first configuration counts; later calls keep the existing one
+    let _ = LINK_CONFIG.set(config);
+}
+
+// Function to build the editor link for a line of a file
+pub fn editor_link(file_path: &Path, line_number: usize) -> String {
+    link_to(file_path, line_number, None)
+}
+
+// Function to build the editor link for a line and column of a file
+pub fn editor_link_at(file_path: &Path, line_number: usize, column: usize) -> String {
+    link_to(file_path, line_number, Some(column))
+}
+
+// Function to build the editor link for a line, and optionally a column, of a file
+fn link_to(file_path: &Path, line_number: usize, column: Option<usize>) -> String {
+    let config =
+        LINK_CONFIG.get_or_init(|| LinkConfig::new(Vec::new(), Editor::VsCode("vscode"), false));
+
+    // Convert to absolute path if it's not already, unless relative paths were asked for
+    let absolute_path = if file_path.is_absolute() || config.relative_paths {
+        file_path.to_path_buf()
+    } else {
+        // Try to get the absolute path by using canonical path
+        match crate::paths::canonicalize(file_path) {
+            Some(path) => path,
+            None => {
+                // Fallback: try joining with the current directory
+                if let Ok(current_dir) = std::env::current_dir() {
+                    current_dir.join(file_path)
+                } else {
+                    file_path.to_path_buf() // Last resort: use as-is
+                }
+            }
+        }
+    };
+
+    // A mapped path is opened by the editor directly on the host
+    let mapped = config.mappings.iter().find_map(|(from, to)| {
+        absolute_path
+            .strip_prefix(from)
+            .ok()
+            .map(|rest| to.join(rest))
+    });
+
+    let scheme = match config.editor {
+        Editor::VsCode(scheme) => scheme,
+        Editor::Template(ref template) => {
+            return template
+                .replace(
+                    "{path}",
+                    &uri_path(mapped.as_ref().unwrap_or(&absolute_path)),
+                )
+                .replace("{line}", &line_number.to_string())
+                .replace("{column}", &column.unwrap_or(1).to_string())
+        }
+    };
+    let position = match column {
+        Some(column) => format!("{}:{}", line_number, column),
+        None => line_number.to_string(),
+    };
+    match (mapped, &config.remote) {
+        (Some(path), _) => format!("{}://file/{}:{}", scheme, uri_path(&path), position),
+        (None, Some(authority)) => format!(
+            "{}://vscode-remote/{}{}:{}",
+            scheme,
+            authority,
+            uri_path(&absolute_path),
+            position
+        ),
+        // vscode://file/<absolute_path>:<line_number>[:<column>]
+        (None, None) => format!(
+            "{}://file/{}:{}",
+            scheme,
+            uri_path(&absolute_path),
+            position
+        ),
+    }
+}
+
+// Function to format a path for a URI with forward slashes
+fn uri_path(path: &Path) -> String {
+    path.display().to_string().replace('\\', "/")
+}
@@ -0,0 +1,194 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Magic numbers and string literals.
+// Numeric and string literals in expressions are collected and grouped by value, so
+// values repeated across the code stand out as candidates for named constants. 0, 1 and
+// the empty string are left out, as are the initializers of `const` and `static` items
+// (which already name their value), attributes and the format strings of formatting
+// macros. Numbers are grouped by value regardless of suffix, so `42u8` and `42` match.
+
+use crate::collector::macro_args;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use syn::visit::{self, Visit};
+
+// Macros whose first argument is a format string
+const FORMAT_MACROS: &[&str] = &[
+    "format",
+    "print",
+    "println",
+    "eprint",
+    "eprintln",
+    "write",
+    "writeln",
+    "panic",
+    "format_args",
+];
+
+// A numeric or string literal in an expression
+#[derive(Clone, Serialize, Deserialize)]
+pub struct LiteralUse {
+    pub kind: String,  // "number" or "string"
+    pub value: String, // Numbers in base 10 without suffix, strings unquoted
+    pub scope: String, // Path of the enclosing function or item
+    pub file_path: PathBuf,
+    pub line_number: usize,
+}
+
+// All uses of one literal value
+pub struct LiteralGroup<'a> {
+    pub kind: &'a str,
+    pub value: &'a str,
+    pub uses: Vec<&'a LiteralUse>,
+}
+
+// Visitor that collects the literals of a file
+struct LiteralVisitor<'a> {
+    file_path: &'a Path,
+    scope: String,
+    literals: &'a mut Vec<LiteralUse>,
+}
+
+impl LiteralVisitor<'_> {
+    fn record(&mut self, lit: &syn::Lit) {
+        let (kind, value) = match lit {
+            syn::Lit::Int(int) => ("number", int.base10_digits().to_string()),
+            syn::Lit::Float(float) => ("number", float.base10_digits().to_string()),
+            syn::Lit::Str(string) => ("string", string.value()),
+            _ => return,
+        };
+        let trivial = match kind {
+            "number" => matches!(
+                value.parse::<f64>(),
+                Ok(number) if number == 0.0 || number == 1.0
+            ),
+            _ => value.is_empty(),
+        };
+        if trivial {
+            return;
+        }
+        self.literals.push(LiteralUse {
+            kind: kind.to_string(),
+            value,
+            scope: self.scope.clone(),
+            file_path: self.file_path.to_path_buf(),
+            line_number: lit.span().start().line,
+        });
+    }
+
+    fn visit_in_scope(&mut self, name: &impl std::fmt::Display, visit: impl FnOnce(&mut Self)) {
+        let inner_scope = format!("{}::{}", self.scope, name);
+        let outer_scope = std::mem::replace(&mut self.scope, inner_scope);
+        visit(self);
+        self.scope = outer_scope;
+    }
+}
+
+impl<'ast> Visit<'ast> for LiteralVisitor<'_> {
+    fn visit_expr_lit(&mut self, expr_lit: &'ast syn::ExprLit) {
+        self.record(&expr_lit.lit);
+    }
+
+    // Doc comments and other attributes are not code
+    fn visit_attribute(&mut self, _attr: &'ast syn::Attribute) {}
+
+    // Constants and statics already give their values a name
+    fn visit_item_const(&mut self, _item_const: &'ast syn::ItemConst) {}
+
+    fn visit_item_static(&mut self, _item_static: &'ast syn::ItemStatic) {}
+
+    fn visit_impl_item_const(&mut self, _item_const: &'ast syn::ImplItemConst) {}
+
+    // Macro arguments such as `vec![0; 512]` are only tokens to syn
+    fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+        let name = mac
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.ident.to_string())
+            .unwrap_or_default();
+        let format_index = match name.as_str() {
+            "write" | "writeln" => Some(1),
+            name if FORMAT_MACROS.contains(&name) => Some(0),
+            _ => None,
+        };
+        if let Some(args) = macro_args(mac) {
+            for (index, arg) in args.iter().enumerate() {
+                if Some(index) != format_index {
+                    self.visit_expr(arg);
+                }
+            }
+        } else if let Ok(repeat) = mac.parse_body::<syn::ExprRepeat>() {
+            // vec![value; count] parses like an array repeat expression
+            self.visit_expr_repeat(&repeat);
+        }
+    }
+
+    fn visit_item_fn(&mut self, item_fn: &'ast syn::ItemFn) {
+        self.visit_in_scope(&item_fn.sig.ident, |visitor| {
+            visit::visit_item_fn(visitor, item_fn)
+        });
+    }
+
+    fn visit_impl_item_fn(&mut self, method: &'ast syn::ImplItemFn) {
+        self.visit_in_scope(&method.sig.ident, |visitor| {
+            visit::visit_impl_item_fn(visitor, method)
+        });
+    }
+
+    fn visit_trait_item_fn(&mut self, method: &'ast syn::TraitItemFn) {
+        self.visit_in_scope(&method.sig.ident, |visitor| {
+            visit::visit_trait_item_fn(visitor, method)
+        });
+    }
+
+    fn visit_item_impl(&mut self, item_impl: &'ast syn::ItemImpl) {
+        let name = crate::impl_blocks::type_name(&item_impl.self_ty);
+        self.visit_in_scope(&name, |visitor| visit::visit_item_impl(visitor, item_impl));
+    }
+
+    fn visit_item_trait(&mut self, item_trait: &'ast syn::ItemTrait) {
+        self.visit_in_scope(&item_trait.ident, |visitor| {
+            visit::visit_item_trait(visitor, item_trait)
+        });
+    }
+
+    fn visit_item_mod(&mut self, item_mod: &'ast syn::ItemMod) {
+        self.visit_in_scope(&item_mod.ident, |visitor| {
+            visit::visit_item_mod(visitor, item_mod)
+        });
+    }
+}
+
+// Function to collect the literals of a parsed file
+pub fn collect_literals(
+    file_ast: &syn::File,
+    file_path: &Path,
+    module_path: &str,
+    literals: &mut Vec<LiteralUse>,
+) {
+    let mut visitor = LiteralVisitor {
+        file_path,
+        scope: module_path.to_string(),
+        literals,
+    };
+    visitor.visit_file(file_ast);
+}
+
+// Function to group the literals by value, most frequent first
+pub fn group_literals(literals: &[LiteralUse]) -> Vec<LiteralGroup<'_>> {
+    let mut groups: BTreeMap<(&str, &str), Vec<&LiteralUse>> = BTreeMap::new();
+    for literal in literals {
+        groups
+            .entry((literal.kind.as_str(), literal.value.as_str()))
+            .or_default()
+            .push(literal);
+    }
+    let mut groups: Vec<LiteralGroup> = groups
+        .into_iter()
+        .map(|((kind, value), uses)| LiteralGroup { kind, value, uses })
+        .collect();
+    // The sort is stable, so equal counts keep the order of the value
+    groups.sort_by_key(|group| std::cmp::Reverse(group.uses.len()));
+    groups
+}
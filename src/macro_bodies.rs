@@ -0,0 +1,121 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Best-effort analysis inside macro_rules! bodies (`--macro-bodies`).
+// syn keeps macro_rules! definitions as opaque token trees. Each rule's transcriber is
+// turned back into ordinary Rust by dropping the `$` of metavariables and expanding
+// every repetition once, then parsed as items or as a block and analysed like any
+// other code. Records found this way carry the name of the macro as their provenance.
+
+use crate::line_index::LineIndex;
+use crate::walker::Walker;
+use crate::AnalysisResults;
+use proc_macro2::{Delimiter, Group, TokenStream, TokenTree};
+use std::path::Path;
+use syn::visit::{self, Visit};
+
+// Visitor that collects the macro_rules! definitions of a file
+struct MacroRulesVisitor<'a> {
+    macros: Vec<(String, &'a syn::Macro)>,
+}
+
+impl<'ast> Visit<'ast> for MacroRulesVisitor<'ast> {
+    fn visit_item_macro(&mut self, item_macro: &'ast syn::ItemMacro) {
+        if let (true, Some(ref ident)) = (
+            item_macro.mac.path.is_ident("macro_rules"),
+            &item_macro.ident,
+        ) {
+            self.macros.push((ident.to_string(), &item_macro.mac));
+        }
+        visit::visit_item_macro(self, item_macro);
+    }
+}
+
+// Function to split a macro_rules! definition into the transcribers of its rules
+// Rules have the form `(matcher) => {transcriber}` and are separated by `;`
+pub fn transcribers(tokens: TokenStream) -> Vec<TokenStream> {
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    let mut bodies = Vec::new();
+    for window in tokens.windows(4) {
+        if let [TokenTree::Group(_), TokenTree::Punct(eq), TokenTree::Punct(gt), TokenTree::Group(body)] =
+            window
+        {
+            if eq.as_char() == '=' && gt.as_char() == '>' {
+                bodies.push(body.stream());
+            }
+        }
+    }
+    bodies
+}
+
+// Function to turn a transcriber back into plain Rust tokens
+// `$name` becomes `name`, `$crate` becomes `crate` and `$( ... ) sep* ` is expanded once
+fn desugar(tokens: TokenStream) -> TokenStream {
+    let mut output = Vec::new();
+    let mut iter = tokens.into_iter().peekable();
+    while let Some(token) = iter.next() {
+        match token {
+            TokenTree::Punct(ref punct) if punct.as_char() == '$' => match iter.next() {
+                Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+                    output.extend(desugar(group.stream()));
+                    // Skip the optional separator and the repetition operator
+                    while let Some(TokenTree::Punct(next)) = iter.peek() {
+                        let done = matches!(next.as_char(), '*' | '+' | '?');
+                        iter.next();
+                        if done {
+                            break;
+                        }
+                    }
+                }
+                Some(other) => output.push(other),
+                None => {}
+            },
+            TokenTree::Group(group) => {
+                let mut desugared = Group::new(group.delimiter(), desugar(group.stream()));
+                desugared.set_span(group.span());
+                output.push(TokenTree::Group(desugared));
+            }
+            other => output.push(other),
+        }
+    }
+    output.into_iter().collect()
+}
+
+// Function to analyse the bodies of all macro_rules! definitions of a parsed file
+pub fn analyse_macro_bodies(
+    file_ast: &syn::File,
+    file_path: &Path,
+    module_path: &str,
+    lines: &LineIndex,
+    results: &mut AnalysisResults,
+) {
+    let mut macro_visitor = MacroRulesVisitor { macros: Vec::new() };
+    macro_visitor.visit_file(file_ast);
+
+    for (name, mac) in macro_visitor.macros {
+        let first_var = (results.mutable_vars.len(), results.immutable_vars.len());
+        let first_structure = results.data_structures.len();
+
+        for body in transcribers(mac.tokens.clone()) {
+            let body = desugar(body);
+            let mut walker = Walker::new(file_path, lines, module_path, results)
+                .in_scope(format!("macro_rules! {}", name));
+            // Macros expand to either items or statements; anything else is skipped
+            if let Ok(items) = syn::parse2::<syn::File>(body.clone()) {
+                walker.visit_file(&items);
+            } else if let Ok(block) = syn::parse2::<syn::Block>(
+                TokenTree::Group(Group::new(Delimiter::Brace, body)).into(),
+            ) {
+                walker.visit_block(&block);
+            }
+        }
+
+        for var in results.mutable_vars[first_var.0..]
+            .iter_mut()
+            .chain(results.immutable_vars[first_var.1..].iter_mut())
+        {
+            var.macro_body = Some(name.clone());
+        }
+        for data_structure in &mut results.data_structures[first_structure..] {
+            data_structure.macro_body = Some(name.clone());
+        }
+    }
+}
@@ -0,0 +1,116 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Macro definitions and invocations.
+// macro_rules! definitions are listed with their number of rules, and macro invocations
+// are counted per file so the most used macros stand out. Arguments of invocations that
+// parse as expressions are searched too, e.g. the `format!` inside `println!`.
+
+use crate::collector::macro_args;
+use quote::ToTokens;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use syn::visit::{self, Visit};
+
+// Maximum number of macros listed as the most invoked
+const TOP_MACROS: usize = 5;
+
+// A macro_rules! definition
+#[derive(Clone, Serialize, Deserialize)]
+pub struct MacroDefinition {
+    pub name: String,
+    pub rules: usize,
+    pub exported: bool, // #[macro_export]
+    #[serde(rename = "file")]
+    pub file_path: PathBuf,
+    #[serde(rename = "line")]
+    pub line_number: usize,
+}
+
+// The macro invocations of one file
+#[derive(Clone, Serialize, Deserialize)]
+pub struct MacroInvocations {
+    pub file_path: PathBuf,
+    pub counts: BTreeMap<String, usize>, // Invocations per macro name
+}
+
+impl MacroInvocations {
+    pub fn total(&self) -> usize {
+        self.counts.values().sum()
+    }
+}
+
+// Visitor that collects macro definitions and counts invocations
+struct MacroVisitor<'a> {
+    file_path: &'a Path,
+    definitions: &'a mut Vec<MacroDefinition>,
+    counts: BTreeMap<String, usize>,
+}
+
+impl<'ast> Visit<'ast> for MacroVisitor<'_> {
+    fn visit_item_macro(&mut self, item_macro: &'ast syn::ItemMacro) {
+        if let (true, Some(ref ident)) = (
+            item_macro.mac.path.is_ident("macro_rules"),
+            &item_macro.ident,
+        ) {
+            self.definitions.push(MacroDefinition {
+                name: ident.to_string(),
+                rules: crate::macro_bodies::transcribers(item_macro.mac.tokens.clone()).len(),
+                exported: item_macro
+                    .attrs
+                    .iter()
+                    .any(|attr| attr.path().is_ident("macro_export")),
+                file_path: self.file_path.to_path_buf(),
+                line_number: ident.span().start().line,
+            });
+            return;
+        }
+        visit::visit_item_macro(self, item_macro);
+    }
+
+    fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+        let name = mac.path.to_token_stream().to_string().replace(' ', "");
+        *self.counts.entry(name).or_insert(0) += 1;
+
+        // Invocations nested in the arguments are only tokens to syn
+        if let Some(args) = macro_args(mac) {
+            for arg in &args {
+                self.visit_expr(arg);
+            }
+        }
+    }
+}
+
+// Function to collect the macro definitions and invocations of a parsed file
+pub fn collect_macros(
+    file_ast: &syn::File,
+    file_path: &Path,
+    definitions: &mut Vec<MacroDefinition>,
+    invocations: &mut Vec<MacroInvocations>,
+) {
+    let mut visitor = MacroVisitor {
+        file_path,
+        definitions,
+        counts: BTreeMap::new(),
+    };
+    visitor.visit_file(file_ast);
+    if !visitor.counts.is_empty() {
+        invocations.push(MacroInvocations {
+            file_path: file_path.to_path_buf(),
+            counts: visitor.counts,
+        });
+    }
+}
+
+// Function to find the most invoked macros across all files
+pub fn top_invoked(invocations: &[MacroInvocations]) -> Vec<(&str, usize)> {
+    let mut totals: HashMap<&str, usize> = HashMap::new();
+    for file in invocations {
+        for (name, count) in &file.counts {
+            *totals.entry(name.as_str()).or_insert(0) += count;
+        }
+    }
+    let mut ranked: Vec<(&str, usize)> = totals.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    ranked.truncate(TOP_MACROS);
+    ranked
+}
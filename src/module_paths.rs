@@ -0,0 +1,69 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Logical module paths.
+// The module of a file follows from where it sits in its package, the way rustc resolves
+// `mod` declarations: src/parser/lexer.rs and src/parser/lexer/mod.rs are both
+// `crate::parser::lexer`. Crate roots (src/main.rs, src/lib.rs, src/bin/*, tests/*,
+// examples/*, benches/*, build.rs) are `crate`. Inline `mod` items extend the path while
+// the file is visited.
+
+use std::path::{Component, Path};
+
+// Directories whose files are each the root of their own crate
+const CRATE_ROOT_DIRS: &[&str] = &["bin", "tests", "examples", "benches"];
+
+// Function to find the logical module path of a source file, e.g. `crate::parser::lexer`
+pub fn module_path_for(file_path: &Path) -> String {
+    let located = crate::cargo::package_for(file_path).and_then(|(package_dir, _)| {
+        let absolute = crate::paths::canonicalize(file_path)?;
+        let relative = absolute.strip_prefix(&package_dir).ok()?.to_path_buf();
+        Some((package_dir, relative))
+    });
+    let Some((package_dir, relative)) = located else {
+        return "crate".to_string();
+    };
+
+    let mut components: Vec<String> = relative
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => Some(name.to_string_lossy().to_string()),
+            _ => None,
+        })
+        .collect();
+
+    // The file name itself: `mod.rs` names its directory, other files name a module
+    let file_name = components.pop().unwrap_or_default();
+    let stem = file_name.trim_end_matches(".rs").to_string();
+
+    // Strip the directories that do not name modules, remembering where the crate root is
+    let (base, mut modules): (&[String], &[String]) = match components.first().map(String::as_str) {
+        Some("src") => (&components[..1], &components[1..]),
+        Some(_) => (&components[..0], &components[..]),
+        // build.rs and other files next to Cargo.toml
+        None => return "crate".to_string(),
+    };
+    let mut is_crate_root = modules.is_empty() && (stem == "main" || stem == "lib");
+    if modules
+        .first()
+        .is_some_and(|dir| CRATE_ROOT_DIRS.contains(&dir.as_str()))
+    {
+        let target_dir = package_dir.join(base.join("/")).join(&modules[0]);
+        if modules.len() >= 2 && target_dir.join(&modules[1]).join("main.rs").exists() {
+            // src/bin/tool/main.rs is the root of the `tool` crate, src/bin/tool/cli.rs its module
+            is_crate_root = modules.len() == 2 && stem == "main";
+            modules = &modules[2..];
+        } else {
+            // tests/api.rs is a crate root, tests/common/mod.rs a module shared by tests
+            is_crate_root = modules.len() == 1;
+            modules = &modules[1..];
+        }
+    }
+
+    let mut path = vec!["crate".to_string()];
+    if !is_crate_root {
+        path.extend(modules.iter().cloned());
+        if stem != "mod" {
+            path.push(stem);
+        }
+    }
+    path.join("::")
+}
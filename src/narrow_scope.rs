@@ -0,0 +1,210 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Narrower-scope suggestions for variables.
+// A variable declared in the top-level block of a function but only used inside a
+// single branch of one later `if`, `match` or plain block could be declared there.
+// Loop bodies are never suggested, as moving a binding into a loop changes when
+// (and how often) it is initialised.
+
+use proc_macro2::TokenTree;
+use quote::ToTokens;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+
+// A variable that could be declared in a narrower scope
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ScopeSuggestion {
+    pub name: String,
+    pub function: String, // Function whose top-level block declares the variable
+    pub file_path: PathBuf,
+    pub declaration_line: usize,
+    pub scope_end_line: usize, // Last line of the block the variable is declared in
+    pub usage_start_line: usize,
+    pub usage_end_line: usize,
+    pub narrower_scope: String, // e.g. "if branch", "match arm", "block"
+}
+
+// Function to find the lines on which a name is used within a syntax node
+// Tokens are searched rather than the AST, so uses inside macro arguments count too
+fn uses_of<T: ToTokens>(name: &str, node: &T) -> Vec<usize> {
+    let mut lines = Vec::new();
+    find_in_tokens(name, node.to_token_stream(), &mut lines);
+    lines
+}
+
+fn find_in_tokens(name: &str, tokens: proc_macro2::TokenStream, lines: &mut Vec<usize>) {
+    for token in tokens {
+        match token {
+            TokenTree::Ident(ident) if ident == name => lines.push(ident.span().start().line),
+            TokenTree::Group(group) => find_in_tokens(name, group.stream(), lines),
+            _ => {}
+        }
+    }
+}
+
+// Function to find the single branch of a statement that uses a name
+// Returns the kind of branch and the lines of the uses
+fn single_branch_use(name: &str, expr: &syn::Expr) -> Option<(&'static str, Vec<usize>)> {
+    let (header_uses, branch_uses): (Vec<usize>, Vec<(&'static str, Vec<usize>)>) = match expr {
+        syn::Expr::If(expr_if) => {
+            let mut branch_uses = vec![("if branch", uses_of(name, &expr_if.then_branch))];
+            if let Some((_, ref else_branch)) = expr_if.else_branch {
+                branch_uses.push(("else branch", uses_of(name, else_branch)));
+            }
+            (uses_of(name, &expr_if.cond), branch_uses)
+        }
+        syn::Expr::Match(expr_match) => (
+            uses_of(name, &expr_match.expr),
+            expr_match
+                .arms
+                .iter()
+                .map(|arm| ("match arm", uses_of(name, arm)))
+                .collect(),
+        ),
+        syn::Expr::Block(expr_block) => (Vec::new(), vec![("block", uses_of(name, expr_block))]),
+        syn::Expr::Unsafe(expr_unsafe) => (
+            Vec::new(),
+            vec![("unsafe block", uses_of(name, expr_unsafe))],
+        ),
+        _ => return None,
+    };
+
+    if !header_uses.is_empty() {
+        return None;
+    }
+    let mut used = branch_uses
+        .into_iter()
+        .filter(|(_, lines)| !lines.is_empty());
+    match (used.next(), used.next()) {
+        (Some(branch), None) => Some(branch),
+        _ => None,
+    }
+}
+
+// Function to check that an initializer can be evaluated later without changing behaviour
+// Calls and macros may have side effects or depend on timing (e.g. `Instant::now()`)
+fn is_side_effect_free(expr: &syn::Expr) -> bool {
+    match expr {
+        syn::Expr::Lit(_) | syn::Expr::Path(_) => true,
+        syn::Expr::Reference(reference) => is_side_effect_free(&reference.expr),
+        syn::Expr::Field(field) => is_side_effect_free(&field.base),
+        syn::Expr::Paren(paren) => is_side_effect_free(&paren.expr),
+        syn::Expr::Cast(cast) => is_side_effect_free(&cast.expr),
+        syn::Expr::Unary(unary) => is_side_effect_free(&unary.expr),
+        syn::Expr::Binary(binary) => {
+            is_side_effect_free(&binary.left) && is_side_effect_free(&binary.right)
+        }
+        syn::Expr::Tuple(tuple) => tuple.elems.iter().all(is_side_effect_free),
+        syn::Expr::Array(array) => array.elems.iter().all(is_side_effect_free),
+        syn::Expr::Struct(expr_struct) => {
+            expr_struct.rest.is_none()
+                && expr_struct
+                    .fields
+                    .iter()
+                    .all(|field| is_side_effect_free(&field.expr))
+        }
+        _ => false,
+    }
+}
+
+// Function to suggest narrower scopes for the top-level variables of a function body
+fn check_function(
+    function: &str,
+    body: &syn::Block,
+    file_path: &Path,
+    suggestions: &mut Vec<ScopeSuggestion>,
+) {
+    let scope_end_line = body.brace_token.span.close().end().line;
+
+    for (index, stmt) in body.stmts.iter().enumerate() {
+        let syn::Stmt::Local(local) = stmt else {
+            continue;
+        };
+        let ident = match local.pat {
+            syn::Pat::Ident(ref pat_ident) => &pat_ident.ident,
+            syn::Pat::Type(ref pat_type) => match *pat_type.pat {
+                syn::Pat::Ident(ref pat_ident) => &pat_ident.ident,
+                _ => continue,
+            },
+            _ => continue,
+        };
+        let name = ident.to_string();
+        if local
+            .init
+            .as_ref()
+            .is_some_and(|init| init.diverge.is_some() || !is_side_effect_free(&init.expr))
+        {
+            continue;
+        }
+
+        // The variable must be used by exactly one later statement
+        let mut using = body.stmts[index + 1..]
+            .iter()
+            .filter(|later| !uses_of(&name, *later).is_empty());
+        let (Some(user), None) = (using.next(), using.next()) else {
+            continue;
+        };
+        // A later `let` of the same name shadows the variable; leave those alone
+        if matches!(user, syn::Stmt::Local(_)) {
+            continue;
+        }
+        let syn::Stmt::Expr(expr, _) = user else {
+            continue;
+        };
+
+        if let Some((narrower_scope, lines)) = single_branch_use(&name, expr) {
+            suggestions.push(ScopeSuggestion {
+                name,
+                function: function.to_string(),
+                file_path: file_path.to_path_buf(),
+                declaration_line: local.span().start().line,
+                scope_end_line,
+                usage_start_line: lines.iter().copied().min().unwrap_or(0),
+                usage_end_line: lines.iter().copied().max().unwrap_or(0),
+                narrower_scope: narrower_scope.to_string(),
+            });
+        }
+    }
+}
+
+// Visitor that checks free functions and methods
+struct ScopeVisitor<'a> {
+    file_path: &'a Path,
+    suggestions: &'a mut Vec<ScopeSuggestion>,
+}
+
+impl<'ast> Visit<'ast> for ScopeVisitor<'_> {
+    fn visit_item_fn(&mut self, item_fn: &'ast syn::ItemFn) {
+        check_function(
+            &item_fn.sig.ident.to_string(),
+            &item_fn.block,
+            self.file_path,
+            self.suggestions,
+        );
+        visit::visit_item_fn(self, item_fn);
+    }
+
+    fn visit_impl_item_fn(&mut self, method: &'ast syn::ImplItemFn) {
+        check_function(
+            &method.sig.ident.to_string(),
+            &method.block,
+            self.file_path,
+            self.suggestions,
+        );
+        visit::visit_impl_item_fn(self, method);
+    }
+}
+
+// Function to collect the narrower-scope suggestions of a parsed file
+pub fn collect_scope_suggestions(
+    file_ast: &syn::File,
+    file_path: &Path,
+    suggestions: &mut Vec<ScopeSuggestion>,
+) {
+    let mut visitor = ScopeVisitor {
+        file_path,
+        suggestions,
+    };
+    visitor.visit_file(file_ast);
+}
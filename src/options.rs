@@ -0,0 +1,240 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// The options of an analysis, shared by the command line and the library API.
+// `AnalysisOptions` is built with chained setters starting from the defaults of the command
+// line, e.g. `AnalysisOptions::new().include_tests(false).max_depth(Some(3))`, and hands the
+// analysis its failure policy, the optional analyses of each source file, which files of
+// the project are walked and which of them are analysed: a sample, the files changed
+// according to git, or the files an interrupted run left.
+
+use crate::args::Args;
+use crate::cargo::CargoMetadata;
+use crate::collector::Collector;
+use crate::sampling::{self, SampleSpec};
+use crate::{targets, FailurePolicy, SourceOptions, WalkOptions};
+use std::sync::Arc;
+
+// Everything that changes what an analysis looks at and how it reacts to errors
+pub struct AnalysisOptions {
+    source: SourceOptions,
+    policy: FailurePolicy,
+    max_depth: Option<usize>,
+    respect_ignore: bool,
+    follow_symlinks: bool,
+    excludes: Vec<String>,        // Globs relative to the project directory
+    targets: Option<Vec<String>>, // Cargo target kinds, resolved against the project's metadata
+    sample: Option<SampleSpec>,
+    sample_seed: Option<u64>,      // Random unless given
+    changed_since: Option<String>, // Git revision the changed files are compared with
+    resume: bool,
+    semantic: bool, // Resolve the types of let bindings with rust-analyzer
+}
+
+impl Default for AnalysisOptions {
+    fn default() -> Self {
+        AnalysisOptions {
+            source: SourceOptions::default(),
+            policy: FailurePolicy {
+                fail_fast: false,
+                allow_degraded: true,
+            },
+            max_depth: None,
+            respect_ignore: true,
+            follow_symlinks: false,
+            excludes: Vec::new(),
+            targets: None,
+            sample: None,
+            sample_seed: None,
+            changed_since: None,
+            resume: false,
+            semantic: false,
+        }
+    }
+}
+
+impl AnalysisOptions {
+    pub fn new() -> Self {
+        AnalysisOptions::default()
+    }
+
+    // Function to build the options from the command line arguments
+    pub(crate) fn from_args(args: &Args) -> Self {
+        let mut options = AnalysisOptions::new()
+            .fail_fast(args.fail_fast)
+            .allow_degraded(args.allow_degraded)
+            .macro_bodies(args.macro_bodies)
+            .doc_tests(args.analyse_doc_tests)
+            .include_tests(!args.exclude_tests)
+            .doc_coverage(args.doc_coverage)
+            .scope_depth(args.scope_depth)
+            .max_depth(args.max_depth)
+            .respect_ignore(!args.no_ignore)
+            .follow_symlinks(args.follow_symlinks)
+            .excludes(args.exclude.iter().cloned())
+            .resume(args.resume)
+            .semantic(args.semantic);
+        options.targets = args.targets.clone();
+        options.sample = args.sample;
+        options.sample_seed = args.sample_seed;
+        if args.changed || args.changed_since.is_some() {
+            options = options.changed(args.changed_since.as_deref());
+        }
+        options
+    }
+
+    // Stop on the first IO or parse error instead of recording it and continuing
+    pub fn fail_fast(mut self, fail_fast: bool) -> Self {
+        self.policy.fail_fast = fail_fast;
+        self
+    }
+
+    // Analyse the items that parse in files syn cannot parse, instead of skipping the files
+    pub fn allow_degraded(mut self, allow_degraded: bool) -> Self {
+        self.policy.allow_degraded = allow_degraded;
+        self
+    }
+
+    // Also analyse inside macro_rules! bodies
+    pub fn macro_bodies(mut self, macro_bodies: bool) -> Self {
+        self.source.macro_bodies = macro_bodies;
+        self
+    }
+
+    // Also analyse the snippets of doc tests
+    pub fn doc_tests(mut self, doc_tests: bool) -> Self {
+        self.source.analyse_doc_tests = doc_tests;
+        self
+    }
+
+    // Include test files, #[cfg(test)] items and #[test] functions
+    pub fn include_tests(mut self, include_tests: bool) -> Self {
+        self.source.exclude_tests = !include_tests;
+        self
+    }
+
+    // Record which public items are documented
+    pub fn doc_coverage(mut self, doc_coverage: bool) -> Self {
+        self.source.doc_coverage = doc_coverage;
+        self
+    }
+
+    // Only record variables declared at most this many blocks deep, 1 being function bodies
+    pub fn scope_depth(mut self, scope_depth: Option<usize>) -> Self {
+        self.source.scope_depth = scope_depth;
+        self
+    }
+
+    // Directory levels below the project directory to descend into
+    pub fn max_depth(mut self, max_depth: Option<usize>) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    // Honour .gitignore and .ignore files and skip hidden files
+    pub fn respect_ignore(mut self, respect_ignore: bool) -> Self {
+        self.respect_ignore = respect_ignore;
+        self
+    }
+
+    // Follow symbolic links to files and directories
+    pub fn follow_symlinks(mut self, follow_symlinks: bool) -> Self {
+        self.follow_symlinks = follow_symlinks;
+        self
+    }
+
+    // Skip the files and directories matching these globs, e.g. `benches/**`
+    pub fn excludes<S: Into<String>>(mut self, globs: impl IntoIterator<Item = S>) -> Self {
+        self.excludes.extend(globs.into_iter().map(Into::into));
+        self
+    }
+
+    // Only analyse the files of these cargo target kinds, e.g. `lib` or `bin`
+    pub fn targets<S: Into<String>>(mut self, kinds: impl IntoIterator<Item = S>) -> Self {
+        self.targets = Some(kinds.into_iter().map(Into::into).collect());
+        self
+    }
+
+    // Analyse a random sample of the files and extrapolate the counts
+    pub fn sample(mut self, sample: SampleSpec) -> Self {
+        self.sample = Some(sample);
+        self
+    }
+
+    // Seed choosing the sampled files, to select the same files again
+    pub fn sample_seed(mut self, seed: u64) -> Self {
+        self.sample_seed = Some(seed);
+        self
+    }
+
+    // Only analyse the files git reports as changed since `since` (HEAD by default) and
+    // untracked files, restoring the others from the cache of earlier runs
+    pub fn changed(mut self, since: Option<&str>) -> Self {
+        self.changed_since = Some(since.unwrap_or("HEAD").to_string());
+        self
+    }
+
+    // Restore the files completed by an interrupted run from its checkpoint
+    pub fn resume(mut self, resume: bool) -> Self {
+        self.resume = resume;
+        self
+    }
+
+    // Give the let bindings without a type annotation the types rust-analyzer infers, instead
+    // of the types guessed from their initializers; needs the `semantic` feature
+    pub fn semantic(mut self, semantic: bool) -> Self {
+        self.semantic = semantic;
+        self
+    }
+
+    // Run a custom analysis on every parsed file, see `Collector`
+    pub fn collector(mut self, collector: impl Collector + 'static) -> Self {
+        self.source.collectors.push(Arc::new(collector));
+        self
+    }
+
+    pub(crate) fn policy(&self) -> &FailurePolicy {
+        &self.policy
+    }
+
+    pub(crate) fn source(&self) -> &SourceOptions {
+        &self.source
+    }
+
+    // The sample to analyse, with its seed
+    pub(crate) fn sampling(&self) -> Option<(SampleSpec, u64)> {
+        self.sample.map(|spec| {
+            (
+                spec,
+                self.sample_seed.unwrap_or_else(sampling::default_seed),
+            )
+        })
+    }
+
+    pub(crate) fn changed_since(&self) -> Option<&str> {
+        self.changed_since.as_deref()
+    }
+
+    pub(crate) fn resumes(&self) -> bool {
+        self.resume
+    }
+
+    pub(crate) fn resolves_types(&self) -> bool {
+        self.semantic
+    }
+
+    // Function to get which files of a project are walked, given what cargo knows about it
+    pub(crate) fn walk(&self, cargo_metadata: Option<&CargoMetadata>) -> WalkOptions {
+        WalkOptions {
+            max_depth: self.max_depth,
+            respect_ignore: self.respect_ignore,
+            follow_symlinks: self.follow_symlinks,
+            excludes: self.excludes.clone(),
+            target_dir: cargo_metadata.map(|cargo| cargo.target_directory.clone()),
+            targets: match (&self.targets, cargo_metadata) {
+                (Some(kinds), Some(cargo_metadata)) => {
+                    Some(targets::TargetSelection::new(cargo_metadata, kinds.clone()))
+                }
+                _ => None,
+            },
+        }
+    }
+}
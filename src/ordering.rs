@@ -0,0 +1,35 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Ordering of the variable listings (`--sort-by`).
+// Results are always ordered by file, line and name; `--sort-by` re-sorts the variables by
+// one or more keys, each later key breaking ties of the earlier ones. The sort is stable,
+// so variables equal in every key stay in file and line order.
+
+use crate::VarInfo;
+use std::cmp::Ordering;
+
+// Keys accepted by --sort-by
+pub const SORT_KEYS: &[&str] = &["name", "file", "line", "type", "scope", "mutability"];
+
+// Function to compare two variables by one key
+fn compare_by(a: &VarInfo, b: &VarInfo, key: &str) -> Ordering {
+    match key {
+        "name" => a.name.cmp(&b.name),
+        "file" => a.file_path.cmp(&b.file_path),
+        "line" => a.line_number.cmp(&b.line_number),
+        "type" => a.var_type.cmp(&b.var_type),
+        "scope" => a.scope.cmp(&b.scope),
+        // Mutable variables first
+        "mutability" => b.mutable.cmp(&a.mutable),
+        _ => Ordering::Equal,
+    }
+}
+
+// Function to sort variables by the given keys, in order of precedence
+pub fn sort_vars(vars: &mut [VarInfo], keys: &[String]) {
+    vars.sort_by(|a, b| {
+        keys.iter()
+            .map(|key| compare_by(a, b, key))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    });
+}
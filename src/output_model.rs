@@ -0,0 +1,724 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Versioned JSON output envelope and its schema (`forest schema`).
+// The JSON output is an object with a `schema_version`, the metadata, the variable and
+// data structure listings described by the models below, and one key per analysis
+// section. The JSON Schema printed by `forest schema` is generated from these models,
+// so their `///` doc comments become the descriptions in the schema. The sections are
+// serialized from the typed rows at the end of this file, and the schema describes them
+// as free-form objects.
+// SCHEMA_VERSION changes when a key is removed or renamed or its type changes; adding keys
+// or sections does not change it.
+
+use crate::{
+    allocations, doc_tests, error_handling, ffi, generics, globals, impl_blocks, line_stats,
+    macros, ownership, smart_pointers, targets, trait_objects, unsafe_code,
+};
+use crate::{AnalysisMetadata, AnalysisResults, DataStructureInfo, VarInfo};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+// Version of the JSON output layout
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// forest JSON output
+#[derive(Serialize, JsonSchema)]
+pub struct Output {
+    /// Version of the output layout; incompatible changes increase it
+    pub schema_version: u32,
+    pub metadata: Metadata,
+    pub mutable_variables: Vec<Variable>,
+    pub immutable_variables: Vec<Variable>,
+    pub data_structures: Vec<DataStructure>,
+    /// Analysis sections keyed by name, e.g. `errors`, `globals`, `recursion`
+    #[serde(flatten)]
+    pub sections: BTreeMap<&'static str, serde_json::Value>,
+}
+
+/// Project and run information with the main counts
+#[derive(Serialize, JsonSchema)]
+pub struct Metadata {
+    /// Version of the analysed package
+    pub version: String,
+    pub project_name: String,
+    /// Time of the analysis, e.g. `2025-04-01 12:00:00`
+    pub datetime: String,
+    pub mutable_variable_count: usize,
+    pub immutable_variable_count: usize,
+    pub data_structure_count: usize,
+    pub error_count: usize,
+    /// Files analysed with the fallback parser, which skips the items syn cannot parse
+    pub degraded_file_count: usize,
+    /// The workspace from `cargo metadata`; absent when a single file is analysed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cargo: Option<crate::cargo::CargoMetadata>,
+}
+
+/// A variable binding: `let`, parameter, pattern or closure binding
+#[derive(Serialize, JsonSchema)]
+pub struct Variable {
+    pub name: String,
+    pub file: String,
+    pub line: usize,
+    /// Column of the name, counting from 1
+    pub column: usize,
+    /// The source line of the declaration
+    pub context: String,
+    /// Lines around the declaration, with --context-lines
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context_window: Option<crate::context::ContextWindow>,
+    /// How the variable is declared, e.g. `let binding` or `function parameter`
+    pub kind: String,
+    /// Description of the type, e.g. `integer`
+    #[serde(rename = "type")]
+    pub var_type: String,
+    /// The type without type parameters, e.g. `Vec`
+    pub basic_type: String,
+    /// Logical path of the enclosing scope, e.g. `crate::parser::parse`
+    pub scope: String,
+    /// `production`, `test`, `example` or `bench`
+    pub code_class: String,
+    pub read_count: usize,
+    pub write_count: usize,
+    /// Line of the last read or write
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_use_line: Option<usize>,
+    /// Lines from the declaration to the last use
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub span: Option<usize>,
+    /// Name of the macro_rules! whose body declares the variable
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub macro_body: Option<String>,
+    /// Editor link to the source, for the editor chosen with --editor
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vscode_link: Option<String>,
+}
+
+impl Variable {
+    pub fn new(var: &VarInfo, link: bool) -> Self {
+        Variable {
+            name: var.name.clone(),
+            file: var.file_path.display().to_string(),
+            line: var.line_number,
+            column: var.column,
+            context: var.context.trim().to_string(),
+            context_window: var.context_window.clone(),
+            kind: var.var_kind.to_string(),
+            var_type: var.var_type.to_string(),
+            basic_type: var.basic_type.to_string(),
+            scope: var.scope.to_string(),
+            code_class: var.code_class.clone(),
+            read_count: var.read_count,
+            write_count: var.write_count,
+            last_use_line: var.last_use_line,
+            span: var.last_use_line.map(|_| var.span()),
+            macro_body: var.macro_body.clone(),
+            vscode_link: link.then(|| var.editor_link()),
+        }
+    }
+}
+
+/// An item: struct, enum, trait, function, module or type alias
+#[derive(Serialize, JsonSchema)]
+pub struct DataStructure {
+    pub name: String,
+    /// Kind of item, e.g. `struct` or `function`
+    #[serde(rename = "type")]
+    pub item_type: String,
+    /// Logical path, e.g. `crate::parser::Lexer`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// `pub`, `pub(crate)`, ... or `private`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub visibility: Option<String>,
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    pub code_class: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub macro_body: Option<String>,
+    /// For type aliases, the type they name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aliased_type: Option<String>,
+    /// For functions, the parameters
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parameters: Option<Vec<crate::signatures::Param>>,
+    /// For functions, the return type; absent for functions returning `()`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub return_type: Option<String>,
+    /// `newtype` or `builder`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub patterns: Vec<String>,
+    /// For traits, whether any method has a default body
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub has_default_methods: Option<bool>,
+    /// Editor link to the source, for the editor chosen with --editor
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vscode_link: Option<String>,
+    /// Set when --rustdoc-json matched the item
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rustdoc: Option<Rustdoc>,
+    /// For structs, the fields
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fields: Option<Vec<Field>>,
+    /// For enums, the variants
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variants: Option<Vec<Variant>>,
+}
+
+impl DataStructure {
+    pub fn new(item: &DataStructureInfo, link: bool) -> Self {
+        let non_empty = |text: &str| (!text.is_empty()).then(|| text.to_string());
+        DataStructure {
+            name: item.name.clone(),
+            item_type: item.data_structure_type.clone(),
+            path: non_empty(&item.item_path),
+            visibility: non_empty(&item.visibility),
+            file: item.file_path.display().to_string(),
+            line: item.line_number,
+            column: item.column,
+            code_class: item.code_class.clone(),
+            macro_body: item.macro_body.clone(),
+            aliased_type: item.aliased_type.clone(),
+            parameters: item
+                .signature
+                .as_ref()
+                .map(|signature| signature.params.clone()),
+            return_type: item
+                .signature
+                .as_ref()
+                .and_then(|signature| signature.return_type.clone()),
+            patterns: item.patterns.clone(),
+            has_default_methods: item.has_default_methods,
+            vscode_link: link.then(|| item.editor_link()),
+            rustdoc: item.rustdoc.as_ref().map(|rustdoc| Rustdoc {
+                id: rustdoc.id.clone(),
+                resolved_path: rustdoc.resolved_path.clone(),
+                docs: rustdoc.docs.clone(),
+                doc_link: rustdoc.doc_link.clone(),
+            }),
+            fields: (item.data_structure_type == "struct").then(|| {
+                item.fields
+                    .iter()
+                    .map(|field| Field {
+                        name: field.name.clone(),
+                        type_name: field.type_name.clone(),
+                        visibility: Some(field.visibility.clone()),
+                        shape: Some(field.shape.clone()),
+                    })
+                    .collect()
+            }),
+            variants: (item.data_structure_type == "enum").then(|| {
+                item.variants
+                    .iter()
+                    .map(|variant| Variant {
+                        name: variant.name.clone(),
+                        kind: variant.kind.clone(),
+                        payload: variant
+                            .payload
+                            .iter()
+                            .map(|field| Field {
+                                name: field.name.clone(),
+                                type_name: field.type_name.clone(),
+                                visibility: None,
+                                shape: None,
+                            })
+                            .collect(),
+                        discriminant: variant.discriminant.clone(),
+                    })
+                    .collect()
+            }),
+        }
+    }
+}
+
+/// Item information merged from rustdoc JSON
+#[derive(Serialize, JsonSchema)]
+pub struct Rustdoc {
+    pub id: String,
+    pub resolved_path: Option<String>,
+    pub docs: Option<String>,
+    /// Page of the item, relative to target/doc
+    pub doc_link: Option<String>,
+}
+
+/// A struct field or variant payload field
+#[derive(Serialize, JsonSchema)]
+pub struct Field {
+    /// Field name, or its index for tuple fields
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub visibility: Option<String>,
+    /// `reference`, `option`, `collection` or `value`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shape: Option<String>,
+}
+
+/// An enum variant
+#[derive(Serialize, JsonSchema)]
+pub struct Variant {
+    pub name: String,
+    /// `unit`, `tuple` or `struct`
+    pub kind: String,
+    pub payload: Vec<Field>,
+    /// Explicit `= value`, if any
+    pub discriminant: Option<String>,
+}
+
+impl Output {
+    pub fn new(
+        results: &AnalysisResults,
+        metadata: &AnalysisMetadata,
+        link: bool,
+        sections: BTreeMap<&'static str, serde_json::Value>,
+    ) -> Self {
+        Output {
+            schema_version: SCHEMA_VERSION,
+            metadata: Metadata {
+                version: metadata.version.clone(),
+                project_name: metadata.project_name.clone(),
+                datetime: metadata.datetime.clone(),
+                mutable_variable_count: results.mutable_vars.len(),
+                immutable_variable_count: results.immutable_vars.len(),
+                data_structure_count: results.data_structures.len(),
+                error_count: results.errors.len(),
+                degraded_file_count: results.degraded_files.len(),
+                cargo: metadata.cargo.clone(),
+            },
+            mutable_variables: results
+                .mutable_vars
+                .iter()
+                .map(|var| Variable::new(var, link))
+                .collect(),
+            immutable_variables: results
+                .immutable_vars
+                .iter()
+                .map(|var| Variable::new(var, link))
+                .collect(),
+            data_structures: results
+                .data_structures
+                .iter()
+                .map(|item| DataStructure::new(item, link))
+                .collect(),
+            sections,
+        }
+    }
+}
+
+// Function to generate the JSON Schema of the output
+// The schema describes the output as serialized, so keys skipped when empty are optional
+pub fn schema() -> schemars::Schema {
+    schemars::generate::SchemaSettings::draft2020_12()
+        .for_serialize()
+        .into_generator()
+        .into_root_schema_for::<Output>()
+}
+
+// Function to serialize an analysis section into the sections of the output
+// Sections are made of plain records and string-keyed maps, which always serialize
+pub fn section(section: impl Serialize) -> serde_json::Value {
+    serde_json::to_value(section).expect("analysis sections serialize to JSON")
+}
+
+// Rows of the analysis sections
+
+// Estimates of a sampled analysis
+#[derive(Serialize)]
+pub struct Sample {
+    pub estimated: bool,
+    pub total_files: usize,
+    pub sampled_files: usize,
+    pub seed: u64,
+    pub estimated_mutable_variable_count: usize,
+    pub estimated_immutable_variable_count: usize,
+    pub estimated_data_structure_count: usize,
+}
+
+// A group of functions with the same body
+#[derive(Serialize)]
+pub struct DuplicateGroup<'a> {
+    pub kind: &'a str,
+    pub token_count: usize,
+    pub functions: Vec<FunctionLocation<'a>>,
+}
+
+#[derive(Serialize)]
+pub struct FunctionLocation<'a> {
+    pub name: &'a str,
+    pub file: String,
+    pub line: usize,
+}
+
+// Where the types of variables and fields come from
+#[derive(Serialize)]
+pub struct TypeUsage<'a> {
+    pub std_uses: usize,
+    pub external_uses: usize,
+    pub local_uses: usize,
+    pub external_types: Vec<CrateTypes<'a>>,
+}
+
+#[derive(Serialize)]
+pub struct CrateTypes<'a> {
+    #[serde(rename = "crate")]
+    pub crate_name: &'a str,
+    pub uses: usize,
+    pub types: Vec<TypeCount<'a>>,
+}
+
+#[derive(Serialize)]
+pub struct TypeCount<'a> {
+    #[serde(rename = "type")]
+    pub type_name: &'a str,
+    pub count: usize,
+}
+
+// A variable that could be declared in a narrower scope
+#[derive(Serialize)]
+pub struct ScopeSuggestion<'a> {
+    pub name: &'a str,
+    pub function: &'a str,
+    pub file: String,
+    pub declaration_range: [usize; 2],
+    pub usage_range: [usize; 2],
+    pub narrower_scope: &'a str,
+}
+
+// How arguments are passed to calls
+#[derive(Serialize)]
+pub struct CallArguments<'a> {
+    pub moved: usize,
+    pub borrowed: usize,
+    pub mutably_borrowed: usize,
+    pub top_mut_callees: Vec<Callee<'a>>,
+}
+
+#[derive(Serialize)]
+pub struct Callee<'a> {
+    pub callee: &'a str,
+    pub calls: usize,
+    pub moved: usize,
+    pub borrowed: usize,
+    pub mutably_borrowed: usize,
+}
+
+// Mutable state per line of the functions
+#[derive(Serialize)]
+pub struct StateDensity<'a> {
+    pub overall: f64,
+    pub top_functions: Vec<StatefulFunction<'a>>,
+}
+
+#[derive(Serialize)]
+pub struct StatefulFunction<'a> {
+    pub name: &'a str,
+    pub file: String,
+    pub line: usize,
+    pub lines: usize,
+    pub mutable_locals: usize,
+    pub mut_params: usize,
+    pub interior_mutability_fields: usize,
+    pub density: f64,
+}
+
+// unsafe blocks, fns, traits and impls
+#[derive(Serialize)]
+pub struct UnsafeCode<'a> {
+    pub unsafe_blocks: usize,
+    pub unsafe_fns: usize,
+    pub unsafe_traits: usize,
+    pub unsafe_impls: Vec<UnsafeImpl<'a>>,
+    pub unsafe_sites: &'a [unsafe_code::UnsafeSite],
+}
+
+#[derive(Serialize)]
+pub struct UnsafeImpl<'a> {
+    #[serde(rename = "trait")]
+    pub trait_name: &'a str,
+    #[serde(rename = "type")]
+    pub self_type: &'a str,
+    pub send_or_sync: bool,
+    pub file: String,
+    pub line: usize,
+}
+
+// Items with code blocks in their documentation
+#[derive(Serialize)]
+pub struct DocTests<'a> {
+    pub per_crate: BTreeMap<&'a str, usize>,
+    pub items: &'a [doc_tests::DocTestItem],
+}
+
+#[derive(Serialize)]
+pub struct ImplBlocks<'a> {
+    pub blocks: &'a [impl_blocks::ImplBlock],
+    pub methods_per_type: BTreeMap<&'a str, usize>,
+}
+
+// A const or static item
+#[derive(Serialize)]
+pub struct Global<'a> {
+    #[serde(flatten)]
+    pub global: &'a globals::GlobalItem,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vscode_link: Option<String>,
+}
+
+// macro_rules! definitions and macro invocations
+#[derive(Serialize)]
+pub struct Macros<'a> {
+    pub definitions: &'a [macros::MacroDefinition],
+    pub invocations_per_file: Vec<MacroInvocations<'a>>,
+    pub top_invoked: Vec<NameCount<'a>>,
+}
+
+#[derive(Serialize)]
+pub struct MacroInvocations<'a> {
+    pub file: String,
+    pub total: usize,
+    pub counts: &'a BTreeMap<String, usize>,
+}
+
+#[derive(Serialize)]
+pub struct NameCount<'a> {
+    pub name: &'a str,
+    pub count: usize,
+}
+
+#[derive(Serialize)]
+pub struct Generics<'a> {
+    pub items: &'a [generics::GenericItem],
+    pub lifetime_heavy_modules: BTreeMap<&'a str, usize>,
+}
+
+// A cargo feature and the code it gates
+#[derive(Serialize)]
+pub struct Feature<'a> {
+    #[serde(rename = "crate")]
+    pub crate_name: &'a str,
+    pub feature: &'a str,
+    pub declared: bool,
+    pub gates: Vec<FeatureGate<'a>>,
+}
+
+#[derive(Serialize)]
+pub struct FeatureGate<'a> {
+    pub gated: &'a str,
+    pub file: String,
+    pub line: usize,
+}
+
+// Documentation coverage of the public items (--doc-coverage)
+#[derive(Serialize)]
+pub struct DocCoverage<'a> {
+    pub documented: usize,
+    pub total: usize,
+    pub coverage: f64,
+    pub modules: Vec<ModuleDocCoverage<'a>>,
+}
+
+#[derive(Serialize)]
+pub struct ModuleDocCoverage<'a> {
+    pub module: &'a str,
+    pub documented: usize,
+    pub total: usize,
+    pub coverage: f64,
+    pub undocumented: Vec<UndocumentedItem<'a>>,
+}
+
+#[derive(Serialize)]
+pub struct UndocumentedItem<'a> {
+    pub item: &'a str,
+    pub kind: &'a str,
+    pub file: String,
+    pub line: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vscode_link: Option<String>,
+}
+
+// A mutable variable that is never mutated
+#[derive(Serialize)]
+pub struct UnusedMut<'a> {
+    pub name: &'a str,
+    pub scope: &'a str,
+    pub file: String,
+    pub line: usize,
+    pub read_count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vscode_link: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct SmartPointers<'a> {
+    pub usages: &'a [smart_pointers::SmartPointer],
+    pub per_module: BTreeMap<&'a str, BTreeMap<&'a str, usize>>,
+}
+
+// unwrap, expect, panic! and ? per function
+#[derive(Serialize)]
+pub struct ErrorHandling<'a> {
+    pub sites: &'a [error_handling::ErrorSite],
+    pub functions: Vec<FunctionErrors<'a>>,
+    pub riskiest: Vec<&'a str>,
+}
+
+#[derive(Serialize)]
+pub struct FunctionErrors<'a> {
+    pub function: &'a str,
+    pub file: String,
+    pub line: usize,
+    pub lines: usize,
+    pub unwrap: usize,
+    pub expect: usize,
+    pub panics: usize,
+    #[serde(rename = "try")]
+    pub tries: usize,
+    pub unwrap_density: f64,
+}
+
+#[derive(Serialize)]
+pub struct Allocations<'a> {
+    pub calls: &'a [allocations::Allocation],
+    pub hotspots: Vec<AllocationHotspot<'a>>,
+}
+
+#[derive(Serialize)]
+pub struct AllocationHotspot<'a> {
+    pub function: &'a str,
+    pub file: String,
+    pub line: usize,
+    pub calls: usize,
+}
+
+// A local type with the places that mention it
+#[derive(Serialize)]
+pub struct TypeXref<'a> {
+    #[serde(rename = "type")]
+    pub name: &'a str,
+    pub kind: &'a str,
+    pub file: String,
+    pub line: usize,
+    pub count: usize,
+    pub contexts: BTreeMap<&'a str, usize>,
+    pub references: Vec<TypeReference<'a>>,
+}
+
+#[derive(Serialize)]
+pub struct TypeReference<'a> {
+    pub context: &'a str,
+    pub owner: &'a str,
+    pub file: String,
+    pub line: usize,
+}
+
+#[derive(Serialize)]
+pub struct Functions<'a> {
+    pub metrics: &'a [crate::complexity::FunctionMetrics],
+    pub most_complex: Vec<&'a str>,
+}
+
+#[derive(Serialize)]
+pub struct Lines<'a> {
+    pub totals: line_stats::LineCounts,
+    pub modules: BTreeMap<&'a str, line_stats::LineCounts>,
+    pub files: &'a [line_stats::FileLines],
+}
+
+#[derive(Serialize)]
+pub struct Targets<'a> {
+    pub counts: Vec<targets::TargetCounts>,
+    pub files: &'a [targets::FileTarget],
+}
+
+#[derive(Serialize)]
+pub struct TraitObjects<'a> {
+    pub uses: &'a [trait_objects::TraitUse],
+    pub by_trait: BTreeMap<&'a str, Dispatch>,
+}
+
+// How a trait is used: through dyn, impl or both
+#[derive(Serialize)]
+pub struct Dispatch {
+    pub dispatch: &'static str,
+    #[serde(rename = "dyn")]
+    pub dyn_uses: usize,
+    #[serde(rename = "impl")]
+    pub impl_uses: usize,
+}
+
+#[derive(Serialize)]
+pub struct Ffi<'a> {
+    pub extern_items: &'a [ffi::ForeignItem],
+    pub raw_pointer_params: &'a [ffi::RawPointerParam],
+    pub exported_symbols: usize,
+}
+
+// A literal value repeated in the code
+#[derive(Serialize)]
+pub struct LiteralGroup<'a> {
+    pub kind: &'a str,
+    pub value: &'a str,
+    pub count: usize,
+    pub locations: Vec<LiteralLocation<'a>>,
+}
+
+#[derive(Serialize)]
+pub struct LiteralLocation<'a> {
+    pub scope: &'a str,
+    pub file: String,
+    pub line: usize,
+}
+
+// Functions calling themselves, directly or through each other
+#[derive(Serialize)]
+pub struct Cycle<'a> {
+    pub kind: &'static str, // `direct` or `mutual`
+    pub members: Vec<CycleMember<'a>>,
+}
+
+#[derive(Serialize)]
+pub struct CycleMember<'a> {
+    pub function: &'a str,
+    pub file: String,
+    pub line: usize,
+}
+
+#[derive(Serialize)]
+pub struct Ownership<'a> {
+    pub total: ownership::ModuleOwnership,
+    pub modules: &'a BTreeMap<String, ownership::ModuleOwnership>,
+    pub params: Vec<ParamOwnership<'a>>,
+}
+
+#[derive(Serialize)]
+pub struct ParamOwnership<'a> {
+    pub function: &'a str,
+    pub param: &'a str,
+    #[serde(rename = "type")]
+    pub basic_type: &'a str,
+    pub ownership: &'static str,
+    pub file: String,
+    pub line: usize,
+}
+
+// The estimated memory layout of a struct
+#[derive(Serialize)]
+pub struct StructLayout<'a> {
+    #[serde(rename = "struct")]
+    pub name: &'a str,
+    pub estimated_size: usize,
+    pub estimated_align: usize,
+    pub heap_fields: usize,
+    pub heap_heavy: bool,
+    pub file: String,
+    pub line: usize,
+}
+
+// Counts of --group-by
+#[derive(Serialize)]
+pub struct Groups<'a> {
+    pub by: &'a str,
+    pub groups: Vec<crate::aggregate::Group>,
+}
@@ -0,0 +1,236 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Ownership of parameters and locals.
+// Every function parameter (including `self`) and every local variable is classified as
+// owned, a shared borrow (`&T`) or a mutable borrow (`&mut T`) from the type forest has
+// already extracted for it, and the counts are turned into ratios per module, such as
+// "23% of parameters take &mut". Locals without a written type are classified from their
+// initializer (`let r = &x;` is a shared borrow); anything else counts as owned.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use syn::visit::{self, Visit};
+
+// How a value is held
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Ownership {
+    Owned,
+    Shared,
+    Mutable,
+}
+
+impl Ownership {
+    // Function to classify a type as extracted by forest, e.g. `&mut Vec<u8>` or "Reference"
+    pub fn of_type(basic_type: &str) -> Self {
+        if basic_type.starts_with("&mut ") || basic_type == "Mutable reference" {
+            Ownership::Mutable
+        } else if basic_type.starts_with('&') || basic_type == "Reference" {
+            Ownership::Shared
+        } else {
+            Ownership::Owned
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Ownership::Owned => "owned",
+            Ownership::Shared => "&",
+            Ownership::Mutable => "&mut",
+        }
+    }
+}
+
+// A function parameter with how it is passed
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ParamOwnership {
+    pub function: String,
+    pub param: String,
+    pub basic_type: String,
+    pub ownership: Ownership,
+    pub module: String,
+    pub file_path: PathBuf,
+    pub line_number: usize,
+}
+
+// Numbers of owned, shared and mutably borrowed values
+#[derive(Clone, Copy, Default, Serialize)]
+pub struct OwnershipCounts {
+    pub owned: usize,
+    pub shared: usize,
+    pub mutable: usize,
+}
+
+impl OwnershipCounts {
+    fn add(&mut self, ownership: Ownership) {
+        match ownership {
+            Ownership::Owned => self.owned += 1,
+            Ownership::Shared => self.shared += 1,
+            Ownership::Mutable => self.mutable += 1,
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.owned + self.shared + self.mutable
+    }
+
+    // Percentages of owned, shared and mutable, e.g. "60% owned, 17% &, 23% &mut"
+    pub fn ratios_text(&self) -> String {
+        let percent = |count: usize| 100.0 * count as f64 / self.total().max(1) as f64;
+        format!(
+            "{:.0}% owned, {:.0}% &, {:.0}% &mut",
+            percent(self.owned),
+            percent(self.shared),
+            percent(self.mutable)
+        )
+    }
+}
+
+// Parameter and local counts of one module
+#[derive(Clone, Copy, Default, Serialize)]
+pub struct ModuleOwnership {
+    pub params: OwnershipCounts,
+    pub locals: OwnershipCounts,
+}
+
+// Visitor that classifies the parameters of the functions in a file
+struct ParamVisitor<'a> {
+    file_path: &'a Path,
+    module: String,
+    scope: String,
+    params: &'a mut Vec<ParamOwnership>,
+}
+
+impl ParamVisitor<'_> {
+    fn classify(&mut self, sig: &syn::Signature) {
+        let function = format!("{}::{}", self.scope, sig.ident);
+        for input in &sig.inputs {
+            let (param, basic_type, line_number) = match input {
+                syn::FnArg::Receiver(receiver) => {
+                    let basic_type = match (&receiver.reference, &receiver.mutability) {
+                        (Some(_), Some(_)) => "&mut Self",
+                        (Some(_), None) => "&Self",
+                        (None, _) => "Self",
+                    };
+                    let line = receiver.self_token.span.start().line;
+                    ("self".to_string(), basic_type.to_string(), line)
+                }
+                syn::FnArg::Typed(pat_type) => {
+                    let param = quote::ToTokens::to_token_stream(&pat_type.pat).to_string();
+                    let line = syn::spanned::Spanned::span(&pat_type.pat).start().line;
+                    (param, crate::extract_basic_type(&pat_type.ty), line)
+                }
+            };
+            self.params.push(ParamOwnership {
+                function: function.clone(),
+                param,
+                ownership: Ownership::of_type(&basic_type),
+                basic_type,
+                module: self.module.clone(),
+                file_path: self.file_path.to_path_buf(),
+                line_number,
+            });
+        }
+    }
+
+    fn visit_in_scope(&mut self, name: &impl std::fmt::Display, visit: impl FnOnce(&mut Self)) {
+        let inner_scope = format!("{}::{}", self.scope, name);
+        let outer_scope = std::mem::replace(&mut self.scope, inner_scope);
+        visit(self);
+        self.scope = outer_scope;
+    }
+}
+
+impl<'ast> Visit<'ast> for ParamVisitor<'_> {
+    fn visit_signature(&mut self, sig: &'ast syn::Signature) {
+        self.classify(sig);
+        visit::visit_signature(self, sig);
+    }
+
+    fn visit_item_fn(&mut self, item_fn: &'ast syn::ItemFn) {
+        self.visit_signature(&item_fn.sig);
+        self.visit_in_scope(&item_fn.sig.ident, |visitor| {
+            visitor.visit_block(&item_fn.block)
+        });
+    }
+
+    fn visit_item_impl(&mut self, item_impl: &'ast syn::ItemImpl) {
+        let name = crate::impl_blocks::type_name(&item_impl.self_ty);
+        self.visit_in_scope(&name, |visitor| visit::visit_item_impl(visitor, item_impl));
+    }
+
+    fn visit_item_trait(&mut self, item_trait: &'ast syn::ItemTrait) {
+        self.visit_in_scope(&item_trait.ident, |visitor| {
+            visit::visit_item_trait(visitor, item_trait)
+        });
+    }
+
+    fn visit_item_mod(&mut self, item_mod: &'ast syn::ItemMod) {
+        let inner_module = format!("{}::{}", self.module, item_mod.ident);
+        let outer_module = std::mem::replace(&mut self.module, inner_module);
+        self.visit_in_scope(&item_mod.ident, |visitor| {
+            visit::visit_item_mod(visitor, item_mod)
+        });
+        self.module = outer_module;
+    }
+}
+
+// Function to classify the parameters of the functions of a parsed file
+pub fn collect_params(
+    file_ast: &syn::File,
+    file_path: &Path,
+    module_path: &str,
+    params: &mut Vec<ParamOwnership>,
+) {
+    let mut visitor = ParamVisitor {
+        file_path,
+        module: module_path.to_string(),
+        scope: module_path.to_string(),
+        params,
+    };
+    visitor.visit_file(file_ast);
+}
+
+// Function to count owned, shared and mutable parameters and locals per module
+// Locals belong to the module that declares them, inline `mod` blocks included, like
+// parameters; parameters recorded as variables are skipped
+pub fn module_ownership<'a>(
+    params: &'a [ParamOwnership],
+    variables: impl Iterator<Item = &'a crate::VarInfo>,
+) -> BTreeMap<String, ModuleOwnership> {
+    let mut modules: BTreeMap<String, ModuleOwnership> = BTreeMap::new();
+    for param in params {
+        modules
+            .entry(param.module.clone())
+            .or_default()
+            .params
+            .add(param.ownership);
+    }
+    for var in variables.filter(|var| !var.var_kind.starts_with("function parameter")) {
+        // The inferred type is a description, e.g. "mutable reference to collection element"
+        let ownership = match Ownership::of_type(&var.basic_type) {
+            Ownership::Owned if var.var_type.contains("mutable reference") => Ownership::Mutable,
+            Ownership::Owned if var.var_type.contains("reference") => Ownership::Shared,
+            ownership => ownership,
+        };
+        modules
+            .entry(var.module.to_string())
+            .or_default()
+            .locals
+            .add(ownership);
+    }
+    modules
+}
+
+// Function to total the module counts over the project
+pub fn project_ownership(modules: &BTreeMap<String, ModuleOwnership>) -> ModuleOwnership {
+    let mut total = ModuleOwnership::default();
+    for module in modules.values() {
+        total.params.owned += module.params.owned;
+        total.params.shared += module.params.shared;
+        total.params.mutable += module.params.mutable;
+        total.locals.owned += module.locals.owned;
+        total.locals.shared += module.locals.shared;
+        total.locals.mutable += module.locals.mutable;
+    }
+    total
+}
@@ -0,0 +1,57 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// How file paths appear in the outputs and links (`--paths`).
+// `relative` paths are relative to the project directory, so reports made on different
+// machines or checkouts compare cleanly; their links are relative too. `absolute` paths
+// are joined to the current directory without resolving symlinks, and `canonical` paths
+// are resolved by the file system. Without `--paths`, paths are the project directory as
+// given joined with the path of the file inside it.
+// Canonical paths are resolved once per path and remembered for the rest of the run, since
+// the analysis and every record's editor link ask for them again and again.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+// Paths already resolved by the file system, or None when they could not be
+static CANONICAL_PATHS: OnceLock<Mutex<HashMap<PathBuf, Option<PathBuf>>>> = OnceLock::new();
+
+// Path modes accepted by --paths
+pub const PATH_MODES: &[&str] = &["relative", "absolute", "canonical"];
+
+// Function to absolutize a path without touching the file system
+fn absolute(path: &Path) -> PathBuf {
+    std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+// Function to canonicalize a path, asking the file system only the first time
+pub fn canonicalize(path: &Path) -> Option<PathBuf> {
+    let cache = CANONICAL_PATHS.get_or_init(Mutex::default);
+    if let Some(canonical) = cache.lock().unwrap().get(path) {
+        return canonical.clone();
+    }
+    // Resolved without holding the lock, so other threads are not kept waiting
+    let canonical = std::fs::canonicalize(path).ok();
+    cache
+        .lock()
+        .unwrap()
+        .insert(path.to_path_buf(), canonical.clone());
+    canonical
+}
+
+// Function to rewrite a file path for the given --paths mode
+// Files outside the project directory keep an absolute path in relative mode
+pub fn rewrite(path: &Path, project_dir: &Path, mode: &str) -> Option<PathBuf> {
+    let rewritten = match mode {
+        "relative" => {
+            let path = absolute(path);
+            match path.strip_prefix(absolute(project_dir)) {
+                Ok(relative) => relative.to_path_buf(),
+                Err(_) => path,
+            }
+        }
+        "absolute" => absolute(path),
+        "canonical" => canonicalize(path).unwrap_or_else(|| absolute(path)),
+        _ => return None,
+    };
+    (rewritten != path).then_some(rewritten)
+}
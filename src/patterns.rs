@@ -0,0 +1,60 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Newtype and builder pattern annotations.
+// A newtype is a tuple struct with a single field. A builder is a type with at least two
+// chainable methods, which take `self` by value and return `Self` (or take `&mut self` and
+// return `&mut Self`). Types are matched to their impl blocks by name, so both checks are
+// heuristics.
+
+use crate::impl_blocks::ImplBlock;
+use crate::DataStructureInfo;
+use std::collections::HashMap;
+
+pub const NEWTYPE: &str = "newtype";
+pub const BUILDER: &str = "builder";
+
+// Chainable methods a type needs to count as a builder
+const MIN_BUILDER_METHODS: usize = 2;
+
+// Function to annotate the structs of a project with the patterns they follow
+pub fn annotate(data_structures: &mut [DataStructureInfo], impl_blocks: &[ImplBlock]) {
+    let mut chainable: HashMap<&str, usize> = HashMap::new();
+    for block in impl_blocks
+        .iter()
+        .filter(|block| block.trait_name.is_none())
+    {
+        *chainable.entry(block.self_type.as_str()).or_insert(0) += block.builder_methods.len();
+    }
+    for data_structure in data_structures
+        .iter_mut()
+        .filter(|data_structure| data_structure.data_structure_type == "struct")
+    {
+        data_structure.patterns.clear();
+        if let [field] = data_structure.fields.as_slice() {
+            if field.name == "0" {
+                data_structure.patterns.push(NEWTYPE.to_string());
+            }
+        }
+        if chainable
+            .get(data_structure.name.as_str())
+            .is_some_and(|&count| count >= MIN_BUILDER_METHODS)
+        {
+            data_structure.patterns.push(BUILDER.to_string());
+        }
+    }
+}
+
+// Function to count the structs annotated with a pattern
+pub fn count(data_structures: &[DataStructureInfo], pattern: &str) -> usize {
+    data_structures
+        .iter()
+        .filter(|data_structure| data_structure.patterns.iter().any(|p| p == pattern))
+        .count()
+}
+
+// Marker appended to annotated structs, e.g. ` [newtype]`
+pub fn marker(patterns: &[String]) -> String {
+    patterns
+        .iter()
+        .map(|pattern| format!(" [{}]", pattern))
+        .collect()
+}
@@ -0,0 +1,345 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Public API surface (`--public-api`).
+// Starting at the library root, `pub` items are collected from every module reachable
+// through `pub mod`: functions with their signatures, structs with their public fields,
+// enums with their variants, traits with their items, constants, statics, type aliases,
+// re-exports and the methods and trait impls of the types. Each item becomes one line
+// keyed by its path, and the lines are sorted so two runs can be diffed to spot breaking
+// changes. Re-exports are listed as written rather than followed, and `#[cfg]` is ignored.
+
+use crate::ForestError;
+use quote::ToTokens;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// One line of the API listing
+struct ApiLine {
+    path: String, // Path the line sorts under, e.g. crate::parser::Parser::new
+    text: String,
+}
+
+// Function to format tokens without the spacing added by the token printer
+fn tidy(tokens: &impl ToTokens) -> String {
+    tokens
+        .to_token_stream()
+        .to_string()
+        .replace(" :: ", "::")
+        .replace(":: ", "::")
+        .replace(" :", ":")
+        .replace("' ", "'")
+        .replace(" <", "<")
+        .replace("< ", "<")
+        .replace(" >", ">")
+        .replace(" ,", ",")
+        .replace("& ", "&")
+        .replace(" (", "(")
+        .replace("( ", "(")
+        .replace(" )", ")")
+        .replace("[ ", "[")
+        .replace(" ]", "]")
+        .replace(" ;", ";")
+}
+
+// Function to format a function signature under its full path
+fn signature_text(sig: &syn::Signature, path: &str) -> String {
+    let mut qualifiers = String::new();
+    if sig.constness.is_some() {
+        qualifiers.push_str("const ");
+    }
+    if sig.asyncness.is_some() {
+        qualifiers.push_str("async ");
+    }
+    if sig.unsafety.is_some() {
+        qualifiers.push_str("unsafe ");
+    }
+    if let Some(ref abi) = sig.abi {
+        qualifiers.push_str(&format!("{} ", tidy(abi)));
+    }
+    let mut text = format!(
+        "{}fn {}{}({}){}",
+        qualifiers,
+        path,
+        tidy(&sig.generics),
+        tidy(&sig.inputs),
+        match sig.output {
+            syn::ReturnType::Default => String::new(),
+            ref output => format!(" {}", tidy(output)),
+        }
+    );
+    if let Some(ref where_clause) = sig.generics.where_clause {
+        text.push_str(&format!(" {}", tidy(where_clause)));
+    }
+    text
+}
+
+// Function to format the generics and where-clause of a type declaration
+fn generics_text(generics: &syn::Generics) -> String {
+    match generics.where_clause {
+        Some(ref where_clause) => format!("{} {}", tidy(generics), tidy(where_clause)),
+        None => tidy(generics),
+    }
+}
+
+// Walks the modules of a library, following `pub mod` declarations into their files
+struct ApiWalker {
+    lines: Vec<ApiLine>,
+}
+
+impl ApiWalker {
+    fn push(&mut self, path: String, text: String) {
+        self.lines.push(ApiLine { path, text });
+    }
+
+    // `dir` is where the files of child modules live
+    fn walk_items(&mut self, items: &[syn::Item], module: &str, dir: &Path) {
+        for item in items {
+            match item {
+                syn::Item::Fn(item_fn) if is_public(&item_fn.vis) => {
+                    let path = format!("{}::{}", module, item_fn.sig.ident);
+                    let text = format!("pub {}", signature_text(&item_fn.sig, &path));
+                    self.push(path, text);
+                }
+                syn::Item::Struct(item_struct) if is_public(&item_struct.vis) => {
+                    self.walk_struct(item_struct, module)
+                }
+                syn::Item::Enum(item_enum) if is_public(&item_enum.vis) => {
+                    self.walk_enum(item_enum, module)
+                }
+                syn::Item::Trait(item_trait) if is_public(&item_trait.vis) => {
+                    self.walk_trait(item_trait, module)
+                }
+                syn::Item::Const(item_const) if is_public(&item_const.vis) => {
+                    let path = format!("{}::{}", module, item_const.ident);
+                    let text = format!("pub const {}: {}", path, tidy(&item_const.ty));
+                    self.push(path, text);
+                }
+                syn::Item::Static(item_static) if is_public(&item_static.vis) => {
+                    let path = format!("{}::{}", module, item_static.ident);
+                    let mutability = match item_static.mutability {
+                        syn::StaticMutability::Mut(_) => "mut ",
+                        _ => "",
+                    };
+                    let text = format!(
+                        "pub static {}{}: {}",
+                        mutability,
+                        path,
+                        tidy(&item_static.ty)
+                    );
+                    self.push(path, text);
+                }
+                syn::Item::Type(item_type) if is_public(&item_type.vis) => {
+                    let path = format!("{}::{}", module, item_type.ident);
+                    let text = format!(
+                        "pub type {}{} = {}",
+                        path,
+                        generics_text(&item_type.generics),
+                        tidy(&item_type.ty)
+                    );
+                    self.push(path, text);
+                }
+                syn::Item::Use(item_use) if is_public(&item_use.vis) => {
+                    let tree = tidy(&item_use.tree);
+                    let text = format!("pub use {} in {}", tree, module);
+                    self.push(format!("{}::{}", module, tree), text);
+                }
+                syn::Item::Impl(item_impl) => self.walk_impl(item_impl, module),
+                syn::Item::Mod(item_mod) if is_public(&item_mod.vis) => {
+                    let path = format!("{}::{}", module, item_mod.ident);
+                    self.push(path.clone(), format!("pub mod {}", path));
+                    let child_dir = dir.join(item_mod.ident.to_string());
+                    match item_mod.content {
+                        Some((_, ref items)) => self.walk_items(items, &path, &child_dir),
+                        None => self.walk_file_module(&item_mod.ident.to_string(), &path, dir),
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // `mod name;` lives in dir/name.rs or dir/name/mod.rs
+    fn walk_file_module(&mut self, name: &str, path: &str, dir: &Path) {
+        let candidates = [
+            (dir.join(format!("{}.rs", name)), dir.join(name)),
+            (dir.join(name).join("mod.rs"), dir.join(name)),
+        ];
+        for (file, child_dir) in candidates {
+            if let Ok(content) = fs::read_to_string(&file) {
+                match syn::parse_file(&content) {
+                    Ok(file_ast) => self.walk_items(&file_ast.items, path, &child_dir),
+                    Err(e) => eprintln!("Failed to parse {}: {}", file.display(), e),
+                }
+                return;
+            }
+        }
+    }
+
+    fn walk_struct(&mut self, item_struct: &syn::ItemStruct, module: &str) {
+        let path = format!("{}::{}", module, item_struct.ident);
+        let text = format!(
+            "pub struct {}{}",
+            path,
+            generics_text(&item_struct.generics)
+        );
+        self.push(path.clone(), text);
+        for (index, field) in item_struct.fields.iter().enumerate() {
+            if !is_public(&field.vis) {
+                continue;
+            }
+            let name = match field.ident {
+                Some(ref ident) => ident.to_string(),
+                None => index.to_string(),
+            };
+            let field_path = format!("{}::{}", path, name);
+            let text = format!("pub field {}: {}", field_path, tidy(&field.ty));
+            self.push(field_path, text);
+        }
+    }
+
+    fn walk_enum(&mut self, item_enum: &syn::ItemEnum, module: &str) {
+        let path = format!("{}::{}", module, item_enum.ident);
+        let text = format!("pub enum {}{}", path, generics_text(&item_enum.generics));
+        self.push(path.clone(), text);
+        for variant in &item_enum.variants {
+            let variant_path = format!("{}::{}", path, variant.ident);
+            let fields = match variant.fields {
+                syn::Fields::Unit => String::new(),
+                ref fields => tidy(fields),
+            };
+            let text = format!("variant {}{}", variant_path, fields);
+            self.push(variant_path, text);
+        }
+    }
+
+    fn walk_trait(&mut self, item_trait: &syn::ItemTrait, module: &str) {
+        let path = format!("{}::{}", module, item_trait.ident);
+        let supertraits = if item_trait.supertraits.is_empty() {
+            String::new()
+        } else {
+            format!(": {}", tidy(&item_trait.supertraits))
+        };
+        let text = format!(
+            "pub {}trait {}{}{}",
+            if item_trait.unsafety.is_some() {
+                "unsafe "
+            } else {
+                ""
+            },
+            path,
+            generics_text(&item_trait.generics),
+            supertraits
+        );
+        self.push(path.clone(), text);
+        for item in &item_trait.items {
+            let (name, text) = match item {
+                syn::TraitItem::Fn(method) => {
+                    let item_path = format!("{}::{}", path, method.sig.ident);
+                    let text = format!("trait {}", signature_text(&method.sig, &item_path));
+                    (method.sig.ident.to_string(), text)
+                }
+                syn::TraitItem::Type(assoc) => (
+                    assoc.ident.to_string(),
+                    format!("trait type {}::{}", path, assoc.ident),
+                ),
+                syn::TraitItem::Const(assoc) => (
+                    assoc.ident.to_string(),
+                    format!("trait const {}::{}: {}", path, assoc.ident, tidy(&assoc.ty)),
+                ),
+                _ => continue,
+            };
+            self.push(format!("{}::{}", path, name), text);
+        }
+    }
+
+    // Inherent impls add their pub methods to the type; trait impls are listed as a whole
+    fn walk_impl(&mut self, item_impl: &syn::ItemImpl, module: &str) {
+        let type_path = format!(
+            "{}::{}",
+            module,
+            crate::impl_blocks::type_name(&item_impl.self_ty)
+        );
+        if let Some((ref negative, ref trait_path, _)) = item_impl.trait_ {
+            let text = format!(
+                "{}impl{} {}{} for {}",
+                if item_impl.unsafety.is_some() {
+                    "unsafe "
+                } else {
+                    ""
+                },
+                tidy(&item_impl.generics),
+                if negative.is_some() { "!" } else { "" },
+                tidy(trait_path),
+                tidy(&item_impl.self_ty)
+            );
+            // Sorted right after the type, ahead of its fields and methods
+            self.push(format!("{}::", type_path), text);
+            return;
+        }
+        for item in &item_impl.items {
+            if let syn::ImplItem::Fn(method) = item {
+                if is_public(&method.vis) {
+                    let path = format!("{}::{}", type_path, method.sig.ident);
+                    let text = format!("pub {}", signature_text(&method.sig, &path));
+                    self.push(path, text);
+                }
+            }
+        }
+    }
+}
+
+// Function to check for plain `pub`; `pub(crate)` and narrower are not part of the API
+fn is_public(visibility: &syn::Visibility) -> bool {
+    matches!(visibility, syn::Visibility::Public(_))
+}
+
+// Function to find the library root of a package, honouring `[lib] path` in Cargo.toml
+fn library_root(project_dir: &Path) -> Option<PathBuf> {
+    let manifest = crate::cargo::read_manifest(&project_dir.join("Cargo.toml"))?;
+    let root = match manifest
+        .get("lib")
+        .and_then(|lib| lib.get("path"))
+        .and_then(|path| path.as_str())
+    {
+        Some(path) => project_dir.join(path),
+        None => project_dir.join("src").join("lib.rs"),
+    };
+    root.is_file().then_some(root)
+}
+
+// Function to list the public API of the library in a project, one sorted line per item
+pub fn public_api(project_dir: &Path) -> Result<Vec<String>, ForestError> {
+    let root = library_root(project_dir).ok_or_else(|| {
+        ForestError::Config(format!(
+            "No library target found in {}; --public-api needs a crate with a lib.rs",
+            project_dir.display()
+        ))
+    })?;
+    let source = fs::read_to_string(&root).map_err(|e| ForestError::io(&root, e))?;
+    let file_ast = syn::parse_file(&source).map_err(|e| ForestError::parse(&root, &e))?;
+    let dir = root.parent().unwrap_or(project_dir);
+
+    let mut walker = ApiWalker { lines: Vec::new() };
+    walker.walk_items(&file_ast.items, "crate", dir);
+    // Parent modules are still borrowed while their children are walked, so the span map is
+    // cleared once the whole crate has been listed rather than after each file
+    drop(file_ast);
+    proc_macro2::extra::invalidate_current_thread_spans();
+    walker
+        .lines
+        .sort_by(|a, b| a.path.cmp(&b.path).then_with(|| a.text.cmp(&b.text)));
+    Ok(walker.lines.into_iter().map(|line| line.text).collect())
+}
+
+// Function to print the public API, or write it to a file
+pub fn write_public_api(project_dir: &Path, output_file: Option<&str>) -> Result<(), ForestError> {
+    let mut listing = public_api(project_dir)?.join("\n");
+    listing.push('\n');
+    match output_file {
+        Some(file) => {
+            fs::write(file, listing).map_err(|e| ForestError::output("public API", file, e))?;
+            eprintln!("Public API written to {}", file);
+        }
+        None => print!("{}", listing),
+    }
+    Ok(())
+}
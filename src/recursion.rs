@@ -0,0 +1,339 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Recursion and mutual recursion.
+// A call graph is built from the calls in each function body: plain and path calls
+// (`parse()`, `Parser::new()`, `Self::step()`) and method calls on `self`. Callees are
+// resolved by name, first against functions in the enclosing scopes of the caller and then
+// against a unique function of that name or path suffix anywhere in the project; calls that
+// stay ambiguous, such as methods on other values, are left out. Strongly connected
+// components of the graph are the recursion cycles: a function calling itself is directly
+// recursive, and small cycles of several functions are reported as mutual recursion.
+
+use crate::collector::macro_args;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use syn::visit::{self, Visit};
+
+// Largest mutual recursion cycle reported; bigger components are rarely true recursion
+const MAX_CYCLE_LEN: usize = 5;
+
+// The calls made by one function, as written
+#[derive(Clone, Serialize, Deserialize)]
+pub struct FunctionCalls {
+    pub function: String, // Path of the caller, e.g. crate::parser::Parser::parse
+    pub file_path: PathBuf,
+    pub line_number: usize,
+    pub callees: Vec<String>, // Called paths, e.g. `helper`, `Self::step` or `self.step`
+}
+
+// A recursion cycle; a single member calls itself directly
+pub struct Cycle<'a> {
+    pub members: Vec<&'a FunctionCalls>,
+}
+
+// Visitor that collects the calls of each function
+struct CallVisitor<'a> {
+    file_path: &'a Path,
+    scope: String,
+    current: Option<usize>, // Index of the function whose body is being visited
+    functions: &'a mut Vec<FunctionCalls>,
+}
+
+impl CallVisitor<'_> {
+    fn record(&mut self, callee: String) {
+        if let Some(function) = self.current.map(|index| &mut self.functions[index]) {
+            function.callees.push(callee);
+        }
+    }
+
+    fn visit_in_scope(&mut self, name: &impl std::fmt::Display, visit: impl FnOnce(&mut Self)) {
+        let inner_scope = format!("{}::{}", self.scope, name);
+        let outer_scope = std::mem::replace(&mut self.scope, inner_scope);
+        visit(self);
+        self.scope = outer_scope;
+    }
+
+    fn visit_function(&mut self, ident: &syn::Ident, visit: impl FnOnce(&mut Self)) {
+        self.visit_in_scope(ident, |visitor| {
+            visitor.functions.push(FunctionCalls {
+                function: visitor.scope.clone(),
+                file_path: visitor.file_path.to_path_buf(),
+                line_number: ident.span().start().line,
+                callees: Vec::new(),
+            });
+            let outer = visitor.current.replace(visitor.functions.len() - 1);
+            visit(visitor);
+            visitor.current = outer;
+        });
+    }
+}
+
+impl<'ast> Visit<'ast> for CallVisitor<'_> {
+    fn visit_item_fn(&mut self, item_fn: &'ast syn::ItemFn) {
+        self.visit_function(&item_fn.sig.ident, |visitor| {
+            visit::visit_item_fn(visitor, item_fn)
+        });
+    }
+
+    fn visit_impl_item_fn(&mut self, method: &'ast syn::ImplItemFn) {
+        self.visit_function(&method.sig.ident, |visitor| {
+            visit::visit_impl_item_fn(visitor, method)
+        });
+    }
+
+    fn visit_trait_item_fn(&mut self, method: &'ast syn::TraitItemFn) {
+        self.visit_function(&method.sig.ident, |visitor| {
+            visit::visit_trait_item_fn(visitor, method)
+        });
+    }
+
+    fn visit_item_impl(&mut self, item_impl: &'ast syn::ItemImpl) {
+        let name = crate::impl_blocks::type_name(&item_impl.self_ty);
+        self.visit_in_scope(&name, |visitor| visit::visit_item_impl(visitor, item_impl));
+    }
+
+    fn visit_item_trait(&mut self, item_trait: &'ast syn::ItemTrait) {
+        self.visit_in_scope(&item_trait.ident, |visitor| {
+            visit::visit_item_trait(visitor, item_trait)
+        });
+    }
+
+    fn visit_item_mod(&mut self, item_mod: &'ast syn::ItemMod) {
+        self.visit_in_scope(&item_mod.ident, |visitor| {
+            visit::visit_item_mod(visitor, item_mod)
+        });
+    }
+
+    fn visit_expr_call(&mut self, call: &'ast syn::ExprCall) {
+        if let syn::Expr::Path(ref expr_path) = *call.func {
+            let segments: Vec<String> = expr_path
+                .path
+                .segments
+                .iter()
+                .map(|segment| segment.ident.to_string())
+                .collect();
+            self.record(segments.join("::"));
+        }
+        visit::visit_expr_call(self, call);
+    }
+
+    fn visit_expr_method_call(&mut self, call: &'ast syn::ExprMethodCall) {
+        if let syn::Expr::Path(ref receiver) = *call.receiver {
+            if receiver.path.is_ident("self") {
+                self.record(format!("self.{}", call.method));
+            }
+        }
+        visit::visit_expr_method_call(self, call);
+    }
+
+    // Calls in macro arguments such as `println!("{}", count(x))` are only tokens to syn
+    fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+        if let Some(args) = macro_args(mac) {
+            for arg in &args {
+                self.visit_expr(arg);
+            }
+        }
+    }
+}
+
+// Function to collect the calls of the functions of a parsed file
+pub fn collect_calls(
+    file_ast: &syn::File,
+    file_path: &Path,
+    module_path: &str,
+    functions: &mut Vec<FunctionCalls>,
+) {
+    let mut visitor = CallVisitor {
+        file_path,
+        scope: module_path.to_string(),
+        current: None,
+        functions,
+    };
+    visitor.visit_file(file_ast);
+}
+
+// Function paths indexed for resolving calls
+struct FunctionIndex<'a> {
+    by_path: HashMap<&'a str, usize>,
+    by_name: HashMap<&'a str, Vec<usize>>,
+}
+
+impl<'a> FunctionIndex<'a> {
+    fn new(functions: &'a [FunctionCalls]) -> Self {
+        let mut by_path = HashMap::new();
+        let mut by_name: HashMap<&str, Vec<usize>> = HashMap::new();
+        for (index, function) in functions.iter().enumerate() {
+            by_path.entry(function.function.as_str()).or_insert(index);
+            let name = function.function.rsplit("::").next().unwrap_or_default();
+            by_name.entry(name).or_default().push(index);
+        }
+        FunctionIndex { by_path, by_name }
+    }
+
+    // Resolve a callee as written in the function at `caller`
+    fn resolve(&self, functions: &[FunctionCalls], caller: &str, callee: &str) -> Option<usize> {
+        // `self.step()` and `Self::step()` call a method of the caller's own impl
+        let callee = match callee.strip_prefix("self.") {
+            Some(method) => format!("Self::{}", method),
+            None => callee.to_string(),
+        };
+        let callee = callee
+            .trim_start_matches("crate::")
+            .trim_start_matches("self::")
+            .trim_start_matches("super::");
+        let impl_scope = caller.rsplit_once("::").map_or(caller, |(scope, _)| scope);
+        if let Some(method) = callee.strip_prefix("Self::") {
+            return self
+                .by_path
+                .get(format!("{}::{}", impl_scope, method).as_str())
+                .copied();
+        }
+
+        // Functions in the caller's enclosing scopes, innermost first
+        let mut scope = caller;
+        while let Some((outer, _)) = scope.rsplit_once("::") {
+            if let Some(&index) = self.by_path.get(format!("{}::{}", scope, callee).as_str()) {
+                return Some(index);
+            }
+            scope = outer;
+        }
+        if let Some(&index) = self.by_path.get(format!("{}::{}", scope, callee).as_str()) {
+            return Some(index);
+        }
+
+        // Otherwise a unique function whose path ends with the callee
+        let name = callee.rsplit("::").next().unwrap_or(callee);
+        let suffix = format!("::{}", callee);
+        let mut matches = self
+            .by_name
+            .get(name)?
+            .iter()
+            .filter(|&&index| functions[index].function.ends_with(&suffix));
+        match (matches.next(), matches.next()) {
+            (Some(&index), None) => Some(index),
+            _ => None,
+        }
+    }
+}
+
+// Tarjan's algorithm for the strongly connected components of the call graph
+struct Tarjan<'a> {
+    edges: &'a [Vec<usize>],
+    index: Vec<Option<usize>>,
+    low_link: Vec<usize>,
+    on_stack: Vec<bool>,
+    stack: Vec<usize>,
+    next_index: usize,
+    components: Vec<Vec<usize>>,
+}
+
+impl Tarjan<'_> {
+    fn connect(&mut self, node: usize) {
+        self.index[node] = Some(self.next_index);
+        self.low_link[node] = self.next_index;
+        self.next_index += 1;
+        self.stack.push(node);
+        self.on_stack[node] = true;
+
+        for &next in &self.edges[node] {
+            match self.index[next] {
+                None => {
+                    self.connect(next);
+                    self.low_link[node] = self.low_link[node].min(self.low_link[next]);
+                }
+                Some(next_index) if self.on_stack[next] => {
+                    self.low_link[node] = self.low_link[node].min(next_index);
+                }
+                Some(_) => {}
+            }
+        }
+
+        if Some(self.low_link[node]) == self.index[node] {
+            let mut component = Vec::new();
+            while let Some(member) = self.stack.pop() {
+                self.on_stack[member] = false;
+                component.push(member);
+                if member == node {
+                    break;
+                }
+            }
+            self.components.push(component);
+        }
+    }
+}
+
+// Function to resolve the call graph: the indices of the functions each function calls
+pub fn call_edges(functions: &[FunctionCalls]) -> Vec<Vec<usize>> {
+    let index = FunctionIndex::new(functions);
+    functions
+        .iter()
+        .map(|function| {
+            let mut targets: Vec<usize> = function
+                .callees
+                .iter()
+                .filter_map(|callee| index.resolve(functions, &function.function, callee))
+                .collect();
+            targets.sort_unstable();
+            targets.dedup();
+            targets
+        })
+        .collect()
+}
+
+// Function to find the recursion cycles: directly recursive functions and small mutual cycles
+pub fn find_cycles(functions: &[FunctionCalls]) -> Vec<Cycle<'_>> {
+    let edges = call_edges(functions);
+
+    let mut tarjan = Tarjan {
+        edges: &edges,
+        index: vec![None; functions.len()],
+        low_link: vec![0; functions.len()],
+        on_stack: vec![false; functions.len()],
+        stack: Vec::new(),
+        next_index: 0,
+        components: Vec::new(),
+    };
+    for node in 0..functions.len() {
+        if tarjan.index[node].is_none() {
+            tarjan.connect(node);
+        }
+    }
+
+    let mut cycles: Vec<Cycle> = tarjan
+        .components
+        .into_iter()
+        .filter(|component| match component.as_slice() {
+            [single] => edges[*single].contains(single),
+            members => members.len() <= MAX_CYCLE_LEN,
+        })
+        .map(|mut component| {
+            component.sort_unstable_by(|&a, &b| functions[a].function.cmp(&functions[b].function));
+            Cycle {
+                members: component
+                    .into_iter()
+                    .map(|member| &functions[member])
+                    .collect(),
+            }
+        })
+        .collect();
+    cycles.sort_by(|a, b| {
+        a.members
+            .len()
+            .cmp(&b.members.len())
+            .then_with(|| a.members[0].function.cmp(&b.members[0].function))
+    });
+    cycles
+}
+
+// Function to describe a cycle, e.g. "crate::a <-> crate::b"
+// Members are in path order; in cycles of three or more they need not call each other in turn
+pub fn cycle_text(cycle: &Cycle) -> String {
+    match cycle.members.as_slice() {
+        [single] => format!("{} calls itself", single.function),
+        members => members
+            .iter()
+            .map(|member| member.function.as_str())
+            .collect::<Vec<_>>()
+            .join(" <-> "),
+    }
+}
@@ -0,0 +1,195 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Redacted reports that can be shared outside the organisation (`--redact`).
+// The identifiers of the project (variables, items, fields, variants, modules, macros and
+// parameters) are replaced wherever they appear by a hash, the same hash for the same
+// identifier, so the structure and statistics of the report stay intact. Names from std
+// and the categories forest assigns are kept. Paths become relative to the project, the
+// source lines of declarations and doc comments are removed and string literals hashed.
+
+use crate::{AnalysisMetadata, AnalysisResults, ForestError};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+
+// Path segments that name no project item
+const KEEP: &[&str] = &[
+    "crate", "self", "super", "Self", "main", "std", "core", "alloc", "_",
+];
+
+// Keys whose values are categories chosen by forest, left as they are even when a project
+// identifier has the same name, e.g. a function called `test`
+const CATEGORY_KEYS: &[&str] = &[
+    "kind",
+    "code_class",
+    "visibility",
+    "data_structure_type",
+    "var_kind",
+    "shape",
+    "passing",
+    "storage",
+    "dispatch",
+    "context",
+    "edition",
+    "pointer",
+    "reason",
+];
+
+// Function to give the hashed stand-in of an identifier, keeping its initial case
+fn hashed(identifier: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    identifier.hash(&mut hasher);
+    let prefix = if identifier.starts_with(char::is_uppercase) {
+        "Id"
+    } else {
+        "id"
+    };
+    format!("{}_{:08x}", prefix, hasher.finish() as u32)
+}
+
+// Function to collect the identifiers the project defines
+fn project_identifiers(results: &AnalysisResults) -> HashSet<String> {
+    let mut identifiers = HashSet::new();
+    let mut add_path = |path: &str| {
+        for segment in path.split("::") {
+            let segment = segment.trim();
+            if !segment.is_empty() && !KEEP.contains(&segment) {
+                identifiers.insert(segment.to_string());
+            }
+        }
+    };
+    for var in results.mutable_vars.iter().chain(&results.immutable_vars) {
+        add_path(&var.name);
+        add_path(&var.scope);
+    }
+    for data_structure in &results.data_structures {
+        add_path(&data_structure.name);
+        add_path(&data_structure.item_path);
+        for field in &data_structure.fields {
+            add_path(&field.name);
+        }
+        for variant in &data_structure.variants {
+            add_path(&variant.name);
+            for field in &variant.payload {
+                add_path(&field.name);
+            }
+        }
+        if let Some(ref signature) = data_structure.signature {
+            for param in &signature.params {
+                add_path(&param.name);
+            }
+        }
+    }
+    for file_lines in &results.file_lines {
+        add_path(&file_lines.module);
+    }
+    for global in &results.globals {
+        add_path(&global.name);
+    }
+    for definition in &results.macro_definitions {
+        add_path(&definition.name);
+    }
+    // Tuple field indices are not identifiers
+    identifiers.retain(|identifier| !identifier.starts_with(|c: char| c.is_ascii_digit()));
+    identifiers
+}
+
+// Function to replace the project identifiers in a piece of text
+fn redact_text(text: &str, identifiers: &HashSet<String>) -> String {
+    let mut redacted = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(|c: char| c.is_alphabetic() || c == '_') {
+        redacted.push_str(&rest[..start]);
+        let token = &rest[start..];
+        let end = token
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(token.len());
+        let word = &token[..end];
+        if identifiers.contains(word) {
+            redacted.push_str(&hashed(word));
+        } else {
+            redacted.push_str(word);
+        }
+        rest = &token[end..];
+    }
+    redacted.push_str(rest);
+    redacted
+}
+
+// Function to redact every string of a serialized record
+fn redact_value(value: &mut serde_json::Value, key: &str, identifiers: &HashSet<String>) {
+    match value {
+        serde_json::Value::String(_) if CATEGORY_KEYS.contains(&key) => {}
+        serde_json::Value::String(text) => *text = redact_text(text, identifiers),
+        serde_json::Value::Array(values) => {
+            for value in values {
+                redact_value(value, key, identifiers);
+            }
+        }
+        serde_json::Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                redact_value(value, key, identifiers);
+            }
+        }
+        _ => {}
+    }
+}
+
+// Function to redact the results and metadata of a run in place
+pub fn redact(
+    results: &mut AnalysisResults,
+    metadata: &mut AnalysisMetadata,
+    project_dir: &Path,
+) -> Result<(), ForestError> {
+    results.rewrite_paths(|path| crate::paths::rewrite(path, project_dir, "relative"));
+    for var in results
+        .mutable_vars
+        .iter_mut()
+        .chain(results.immutable_vars.iter_mut())
+    {
+        var.context.clear();
+        var.context_window = None;
+    }
+    for data_structure in &mut results.data_structures {
+        if let Some(ref mut rustdoc) = data_structure.rustdoc {
+            rustdoc.docs = None;
+        }
+    }
+    // String literals may hold anything, so they are hashed as a whole; equal literals
+    // keep equal hashes, so repeated literals are still found
+    for literal in &mut results.literals {
+        if literal.kind == "string" {
+            literal.value = hashed(&literal.value);
+        }
+    }
+    for global in &mut results.globals {
+        if global.value.contains('"') {
+            global.value = format!("\"{}\"", hashed(&global.value));
+        }
+    }
+
+    let mut identifiers = project_identifiers(results);
+    identifiers.insert(metadata.project_name.clone());
+    identifiers.insert(metadata.project_name.replace('-', "_"));
+
+    // Error messages may quote the absolute project directory
+    let absolute_dir =
+        std::path::absolute(project_dir).map_err(|e| ForestError::io(project_dir, e))?;
+    for error in &mut results.errors {
+        error.message = error
+            .message
+            .replace(&format!("{}/", absolute_dir.display()), "");
+    }
+
+    // The sample is not serialized and holds no identifiers
+    let sample = results.sample.take();
+    let mut value =
+        serde_json::to_value(&*results).map_err(|e| ForestError::format("redacted", e))?;
+    redact_value(&mut value, "", &identifiers);
+    *results = serde_json::from_value(value).map_err(|e| ForestError::format("redacted", e))?;
+    results.sample = sample;
+    metadata.project_name = hashed(&metadata.project_name);
+    // The cargo metadata names packages, targets and dependencies
+    metadata.cargo = None;
+    Ok(())
+}
@@ -0,0 +1,150 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Ingestion of rustdoc JSON (`cargo +nightly rustdoc -- -Z unstable-options --output-format json`).
+// Items of the analysed crate are matched to forest's data structures by file, name
+// and kind, so they gain rustdoc's item ID, documentation and resolved path.
+
+use crate::{DataStructureInfo, ForestError};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+// Rustdoc information attached to a data structure
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RustdocItem {
+    pub id: String,                    // Item ID within the rustdoc JSON
+    pub resolved_path: Option<String>, // Fully qualified path, e.g. my_crate::module::Item
+    pub docs: Option<String>,          // Documentation comment as written
+    pub doc_link: Option<String>,      // Page of the item, relative to target/doc
+}
+
+// An item of the local crate with its location
+struct IndexedItem {
+    kind: String,
+    name: String,
+    filename: String,
+    line: usize,
+    item: RustdocItem,
+}
+
+// Function to read the local items of a rustdoc JSON file
+fn read_local_items(json_path: &Path) -> Result<Vec<IndexedItem>, ForestError> {
+    let content = fs::read_to_string(json_path).map_err(|e| ForestError::io(json_path, e))?;
+    let json: Value =
+        serde_json::from_str(&content).map_err(|e| ForestError::json(json_path, &e))?;
+
+    // Valid JSON without an item index is not what --rustdoc-json asks for
+    let index = json
+        .get("index")
+        .and_then(Value::as_object)
+        .ok_or_else(|| {
+            ForestError::Config(format!("{} has no rustdoc item index", json_path.display()))
+        })?;
+    let paths = json.get("paths").and_then(Value::as_object);
+
+    let mut items = Vec::new();
+    for (id, entry) in index {
+        // Only items of the documented crate itself have crate_id 0
+        if entry.get("crate_id").and_then(Value::as_u64) != Some(0) {
+            continue;
+        }
+        let (Some(name), Some(span)) = (
+            entry.get("name").and_then(Value::as_str),
+            entry.get("span").filter(|span| !span.is_null()),
+        ) else {
+            continue;
+        };
+        // The kind is the single key of "inner" (older formats also had a "kind" field)
+        let kind = entry
+            .get("inner")
+            .and_then(Value::as_object)
+            .and_then(|inner| inner.keys().next().cloned())
+            .or_else(|| {
+                entry
+                    .get("kind")
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+            })
+            .unwrap_or_default();
+
+        let path_entry = paths.and_then(|paths| paths.get(id));
+        let segments: Option<Vec<&str>> = path_entry
+            .and_then(|p| p.get("path"))
+            .and_then(Value::as_array)
+            .map(|path| path.iter().filter_map(Value::as_str).collect());
+
+        items.push(IndexedItem {
+            name: name.to_string(),
+            filename: span
+                .get("filename")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+            line: span
+                .get("begin")
+                .and_then(|begin| begin.get(0))
+                .and_then(Value::as_u64)
+                .unwrap_or(0) as usize,
+            item: RustdocItem {
+                id: id.clone(),
+                resolved_path: segments.as_ref().map(|s| s.join("::")),
+                docs: entry
+                    .get("docs")
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+                doc_link: segments.as_ref().and_then(|s| doc_link(&kind, s)),
+            },
+            kind,
+        });
+    }
+    Ok(items)
+}
+
+// Function to build the rustdoc page of an item, e.g. my_crate/module/struct.Item.html
+fn doc_link(kind: &str, segments: &[&str]) -> Option<String> {
+    let prefix = match kind {
+        "function" => "fn",
+        "struct" => "struct",
+        "enum" => "enum",
+        _ => return None,
+    };
+    let (name, modules) = segments.split_last()?;
+    Some(format!("{}/{}.{}.html", modules.join("/"), prefix, name))
+}
+
+// Function to merge rustdoc items into the data structures; returns the number matched
+pub fn merge_rustdoc_json(
+    json_path: &Path,
+    data_structures: &mut [DataStructureInfo],
+) -> Result<usize, ForestError> {
+    let items = read_local_items(json_path)?;
+
+    let mut matched = 0;
+    for data_structure in data_structures.iter_mut() {
+        // Rustdoc spans may start at attributes or doc comments, so take the nearest item
+        let best = items
+            .iter()
+            .filter(|item| {
+                item.name == data_structure.name
+                    && item.kind == data_structure.data_structure_type
+                    && data_structure.file_path.ends_with(&item.filename)
+            })
+            .min_by_key(|item| item.line.abs_diff(data_structure.line_number));
+        if let Some(item) = best {
+            data_structure.rustdoc = Some(item.item.clone());
+            matched += 1;
+        }
+    }
+    Ok(matched)
+}
+
+impl RustdocItem {
+    // One-line description: the resolved path and the first line of the docs
+    pub fn summary(&self) -> String {
+        let path = self.resolved_path.as_deref().unwrap_or("(private)");
+        match self.docs.as_deref().and_then(|docs| docs.lines().next()) {
+            Some(first_line) => format!("{} - {}", path, first_line),
+            None => path.to_string(),
+        }
+    }
+}
@@ -0,0 +1,184 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Sampling mode for enormous codebases.
+// A seedable random subset of the project's files is analysed and the summary
+// statistics are extrapolated to the whole project (and marked as estimates).
+
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// How many files to sample: a percentage of all files or a fixed number of files
+#[derive(Clone, Copy)]
+pub enum SampleSpec {
+    Percent(f64),
+    Files(usize),
+}
+
+// Function to parse `--sample` values such as "10%" or "200"
+pub fn parse_sample_spec(value: &str) -> Result<SampleSpec, String> {
+    let value = value.trim();
+    if let Some(percent) = value.strip_suffix('%') {
+        match percent.trim().parse::<f64>() {
+            Ok(p) if p > 0.0 && p <= 100.0 => Ok(SampleSpec::Percent(p)),
+            _ => Err(format!(
+                "invalid percentage '{}': expected a value in (0, 100]",
+                value
+            )),
+        }
+    } else {
+        match value.parse::<usize>() {
+            Ok(n) if n > 0 => Ok(SampleSpec::Files(n)),
+            _ => Err(format!(
+                "invalid sample '{}': expected a percentage (e.g. 10%) or a number of files",
+                value
+            )),
+        }
+    }
+}
+
+// Information about a sampled run, used to extrapolate counts
+#[derive(Clone)]
+pub struct SampleInfo {
+    pub total_files: usize,   // Number of Rust files found in the project
+    pub sampled_files: usize, // Number of Rust files actually analysed
+    pub seed: u64,            // Seed used to select the files (reuse to reproduce the sample)
+}
+
+impl SampleInfo {
+    // Extrapolate a count from the sampled files to the whole project
+    pub fn estimate(&self, count: usize) -> usize {
+        if self.sampled_files == 0 {
+            return 0;
+        }
+        (count as f64 * self.total_files as f64 / self.sampled_files as f64).round() as usize
+    }
+}
+
+// Small seedable PRNG (SplitMix64); good enough for picking files
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // Uniform value in 0..bound
+    fn next_below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+}
+
+// Function to pick a seed when none was given on the command line
+pub fn default_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0)
+}
+
+// Function to select a random subset of files
+pub fn sample_files(
+    mut files: Vec<PathBuf>,
+    spec: SampleSpec,
+    seed: u64,
+) -> (Vec<PathBuf>, SampleInfo) {
+    // Sort first so the same seed always selects the same files
+    files.sort();
+    let total_files = files.len();
+
+    let wanted = match spec {
+        SampleSpec::Percent(p) => ((total_files as f64 * p / 100.0).ceil() as usize).max(1),
+        SampleSpec::Files(n) => n,
+    }
+    .min(total_files);
+
+    // Partial Fisher-Yates shuffle: the first `wanted` entries become the sample
+    let mut rng = SplitMix64(seed);
+    for i in 0..wanted {
+        let j = i + rng.next_below(total_files - i);
+        files.swap(i, j);
+    }
+    files.truncate(wanted);
+    files.sort();
+
+    let info = SampleInfo {
+        total_files,
+        sampled_files: files.len(),
+        seed,
+    };
+    (files, info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn files(count: usize) -> Vec<PathBuf> {
+        (0..count)
+            .map(|i| PathBuf::from(format!("src/file{:02}.rs", i)))
+            .collect()
+    }
+
+    #[test]
+    fn parses_percentages_and_file_counts() {
+        assert!(matches!(parse_sample_spec("10%"), Ok(SampleSpec::Percent(p)) if p == 10.0));
+        assert!(matches!(
+            parse_sample_spec(" 200 "),
+            Ok(SampleSpec::Files(200))
+        ));
+        for invalid in ["0%", "101%", "0", "-3", "ten", ""] {
+            assert!(parse_sample_spec(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn same_seed_selects_the_same_files() {
+        let (first, info) = sample_files(files(20), SampleSpec::Files(5), 42);
+        let mut reversed = files(20);
+        reversed.reverse();
+        let (second, _) = sample_files(reversed, SampleSpec::Files(5), 42);
+
+        assert_eq!(first, second);
+        assert_eq!(first.len(), 5);
+        assert!(first.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(
+            (info.total_files, info.sampled_files, info.seed),
+            (20, 5, 42)
+        );
+        assert_ne!(first, sample_files(files(20), SampleSpec::Files(5), 43).0);
+    }
+
+    #[test]
+    fn sample_size_is_rounded_up_and_capped() {
+        let size = |spec| sample_files(files(20), spec, 1).1.sampled_files;
+        assert_eq!(size(SampleSpec::Percent(12.0)), 3);
+        assert_eq!(size(SampleSpec::Percent(0.1)), 1);
+        assert_eq!(size(SampleSpec::Files(50)), 20);
+        assert_eq!(
+            sample_files(Vec::new(), SampleSpec::Files(5), 1)
+                .1
+                .sampled_files,
+            0
+        );
+    }
+
+    #[test]
+    fn estimates_scale_to_all_files() {
+        let info = SampleInfo {
+            total_files: 30,
+            sampled_files: 4,
+            seed: 0,
+        };
+        assert_eq!(info.estimate(10), 75);
+        assert_eq!(info.estimate(0), 0);
+
+        let empty = SampleInfo {
+            sampled_files: 0,
+            ..info
+        };
+        assert_eq!(empty.estimate(10), 0);
+    }
+}
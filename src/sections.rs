@@ -0,0 +1,66 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Selection of the console report sections (`--sections`).
+// Section names are listed in the order the sections are printed. Without `--sections`
+// every section is printed; `--summary-only` prints none of them.
+
+// Names of the console sections accepted by --sections
+pub const SECTIONS: &[&str] = &[
+    "project",
+    "mutable",
+    "immutable",
+    "structures",
+    "duplicates",
+    "type-usage",
+    "narrower-scope",
+    "call-args",
+    "copy-refs",
+    "state-density",
+    "unsafe",
+    "ffi",
+    "doc-tests",
+    "impls",
+    "globals",
+    "closures",
+    "macros",
+    "async",
+    "generics",
+    "features",
+    "doc-coverage",
+    "unused-mut",
+    "long-lived",
+    "interior-mutability",
+    "smart-pointers",
+    "error-handling",
+    "allocations",
+    "type-xref",
+    "complexity",
+    "lines",
+    "targets",
+    "trait-objects",
+    "global-state",
+    "literals",
+    "recursion",
+    "ownership",
+    "struct-layout",
+    "parse-failures",
+    "errors",
+];
+
+// The console sections to print
+pub struct Sections {
+    selected: Option<Vec<String>>, // None prints every section
+}
+
+impl Sections {
+    pub fn new(selected: Option<Vec<String>>) -> Self {
+        Sections { selected }
+    }
+
+    // Whether the named section is printed
+    pub fn shows(&self, name: &str) -> bool {
+        debug_assert!(SECTIONS.contains(&name), "unknown section {}", name);
+        self.selected
+            .as_ref()
+            .is_none_or(|selected| selected.iter().any(|section| section == name))
+    }
+}
@@ -0,0 +1,175 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Types of let bindings resolved by rust-analyzer (--semantic).
+// The project is loaded into a rust-analyzer database, as an editor loads it, and the type
+// rust-analyzer infers for each binding of a `let` without a type annotation replaces the
+// type guessed from its initializer. Build scripts are not run and proc macros are not
+// expanded, so the project is never compiled; bindings rust-analyzer cannot type keep the
+// guess. rust-analyzer is only built into forest with the `semantic` cargo feature.
+
+#[cfg(feature = "semantic")]
+pub use loaded::Project;
+#[cfg(not(feature = "semantic"))]
+pub use unavailable::Project;
+
+#[cfg(not(feature = "semantic"))]
+mod unavailable {
+    use crate::{AnalysisResults, ForestError};
+    use std::path::Path;
+
+    // A project that cannot be loaded: forest was built without rust-analyzer
+    pub enum Project {}
+
+    impl Project {
+        pub fn load(_project_dir: &Path) -> Result<Project, ForestError> {
+            Err(ForestError::Config(
+                "--semantic needs forest built with the semantic feature (cargo install forest --features semantic)"
+                    .to_string(),
+            ))
+        }
+
+        pub fn resolve_let_types(&self, _results: &mut AnalysisResults) -> usize {
+            match *self {}
+        }
+    }
+}
+
+#[cfg(feature = "semantic")]
+mod loaded {
+    use crate::interning::intern;
+    use crate::line_index::LineIndex;
+    use crate::{extract_basic_type, AnalysisResults, ForestError};
+    use ra_ap_hir::{attach_db, HirDisplay, Semantics};
+    use ra_ap_ide_db::RootDatabase;
+    use ra_ap_load_cargo::{load_workspace_at, LoadCargoConfig, ProcMacroServerChoice};
+    use ra_ap_project_model::{CargoConfig, RustLibSource};
+    use ra_ap_syntax::ast::{self, HasName};
+    use ra_ap_syntax::AstNode;
+    use ra_ap_vfs::{AbsPathBuf, FileId, Vfs, VfsPath};
+    use std::collections::{BTreeSet, HashMap};
+    use std::path::Path;
+    use std::sync::Arc;
+
+    // The project loaded into rust-analyzer
+    pub struct Project {
+        db: RootDatabase,
+        vfs: Vfs,
+    }
+
+    impl Project {
+        // Function to load the cargo project (or workspace) of a directory with its
+        // dependencies and the standard library
+        pub fn load(project_dir: &Path) -> Result<Project, ForestError> {
+            let cargo_config = CargoConfig {
+                sysroot: Some(RustLibSource::Discover),
+                all_targets: true,
+                set_test: true,
+                ..Default::default()
+            };
+            let load_config = LoadCargoConfig {
+                load_out_dirs_from_check: false,
+                with_proc_macro_server: ProcMacroServerChoice::None,
+                prefill_caches: false,
+                num_worker_threads: rayon::current_num_threads(),
+                proc_macro_processes: 1,
+            };
+            let (db, vfs, _) = load_workspace_at(project_dir, &cargo_config, &load_config, &|_| {})
+                .map_err(|error| {
+                    ForestError::Config(format!(
+                        "rust-analyzer cannot load {}: {}",
+                        project_dir.display(),
+                        error
+                    ))
+                })?;
+            Ok(Project { db, vfs })
+        }
+
+        // Function to give the un-annotated let bindings of the results the types
+        // rust-analyzer infers, returning how many were resolved
+        pub fn resolve_let_types(&self, results: &mut AnalysisResults) -> usize {
+            let files: BTreeSet<Arc<Path>> = results
+                .mutable_vars
+                .iter()
+                .chain(&results.immutable_vars)
+                .map(|var| var.file_path.clone())
+                .collect();
+            let types: HashMap<(&Path, usize, usize), String> = files
+                .iter()
+                .flat_map(|file| {
+                    self.let_types(file)
+                        .into_iter()
+                        .map(|((line, column), ty)| ((file.as_ref(), line, column), ty))
+                })
+                .collect();
+
+            let mut resolved = 0;
+            for var in results
+                .mutable_vars
+                .iter_mut()
+                .chain(results.immutable_vars.iter_mut())
+            {
+                if let Some(ty) = types.get(&(var.file_path.as_ref(), var.line_number, var.column))
+                {
+                    var.var_type = intern(ty);
+                    var.basic_type = intern(&basic_type_of(ty));
+                    resolved += 1;
+                }
+            }
+            resolved
+        }
+
+        // Function to find the file rust-analyzer loaded for a path
+        fn file_id(&self, path: &Path) -> Option<FileId> {
+            let path = AbsPathBuf::try_from(path.canonicalize().ok()?.to_str()?).ok()?;
+            let (file_id, _) = self.vfs.file_id(&VfsPath::from(path))?;
+            Some(file_id)
+        }
+
+        // Function to infer the types of the bindings of the lets without a type annotation
+        // in a file, by the line and column of the binding's name
+        fn let_types(&self, path: &Path) -> HashMap<(usize, usize), String> {
+            let mut types = HashMap::new();
+            let Some(file_id) = self.file_id(path) else {
+                return types;
+            };
+            attach_db(&self.db, || {
+                let sema = Semantics::new(&self.db);
+                // Files outside the crate graph, e.g. examples of another package, are skipped
+                let Some(module) = sema.file_to_module_def(file_id) else {
+                    return;
+                };
+                let target = module.krate(&self.db).to_display_target(&self.db);
+                let source = sema.parse_guess_edition(file_id);
+                let text = source.syntax().to_string();
+                let lines = LineIndex::new(&text);
+
+                let lets = source.syntax().descendants().filter_map(ast::LetStmt::cast);
+                for pat in lets
+                    .filter(|let_stmt| let_stmt.ty().is_none())
+                    .filter_map(|let_stmt| let_stmt.pat())
+                {
+                    for binding in pat.syntax().descendants().filter_map(ast::IdentPat::cast) {
+                        let (Some(name), Some(ty)) =
+                            (binding.name(), sema.type_of_binding_in_pat(&binding))
+                        else {
+                            continue;
+                        };
+                        let ty = ty.display_truncated(&self.db, None, target).to_string();
+                        if ty.contains("{unknown}") {
+                            continue;
+                        }
+                        let offset = usize::from(name.syntax().text_range().start());
+                        types.insert((lines.line_of(offset), lines.column_of(offset)), ty);
+                    }
+                }
+            });
+            types
+        }
+    }
+
+    // Function to give the basic type of a type written by rust-analyzer, e.g. `Vec<i32>`
+    fn basic_type_of(type_text: &str) -> String {
+        syn::parse_str::<syn::Type>(type_text)
+            .map(|ty| extract_basic_type(&ty))
+            .unwrap_or_else(|_| type_text.to_string())
+    }
+}
@@ -0,0 +1,284 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// `forest serve`: a long-running analysis answering JSON-RPC 2.0 requests over stdio.
+// Requests are read one per line from stdin and each response is written as one line to
+// stdout, so editors and other tools can query forest without paying for a full analysis
+// per request. The merged results are kept in memory; before each request the files are
+// listed again, and only the records of new, removed and changed files (by modification time
+// or size) are replaced. Queries select records by reference instead of copying the results.
+// Methods: `summary` (counts of the current results), `query` (variables and data structures
+// matching the `name`, `type`, `scope`, `file` and `mutability` params, as the filter flags),
+// `stats` (rankings as `forest stats`, with an optional `top` param), `report` (the full
+// results, as --format json) and `shutdown`.
+
+use crate::cache::file_stamp;
+use crate::feature_gates::DeclaredFeatures;
+use crate::{
+    analyse_file, filters, output_model, project_files, results_model, stats, targets,
+    AnalysisMetadata, AnalysisOptions, AnalysisResults, ForestError, WalkOptions,
+};
+use rayon::prelude::*;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+// JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+
+// A failed request: JSON-RPC error code and message
+type RpcError = (i64, String);
+
+// A file whose records are in the results
+struct ServedFile {
+    stamp: Option<(u64, u64)>, // Modification time and size when analysed; None to retry
+    interior_mutable_fields: Vec<String>, // Records without a file path, kept per file so
+    declared_features: Vec<DeclaredFeatures>, // they can be rebuilt when files change
+}
+
+// The analysed project and the results kept between requests
+struct Server<'a> {
+    project_dir: String,
+    metadata: AnalysisMetadata,
+    walk: WalkOptions,
+    options: &'a AnalysisOptions,
+    files: BTreeMap<PathBuf, ServedFile>,
+    results: AnalysisResults, // Merged results of every file, updated in place
+    analysed: usize,          // Files analysed by the last refresh; the others were unchanged
+}
+
+impl<'a> Server<'a> {
+    fn new(project_dir: &str, metadata: AnalysisMetadata, options: &'a AnalysisOptions) -> Self {
+        Server {
+            project_dir: project_dir.to_string(),
+            walk: options.walk(metadata.cargo.as_ref()),
+            metadata,
+            files: BTreeMap::new(),
+            options,
+            results: AnalysisResults::new(),
+            analysed: 0,
+        }
+    }
+
+    // Function to bring the results up to date, re-analysing new and changed files only
+    fn refresh(&mut self) -> Result<(), ForestError> {
+        let project_dir = Path::new(&self.project_dir);
+        let mut walk_results = AnalysisResults::new();
+        let files = project_files(
+            &self.project_dir,
+            &self.walk,
+            self.options.source(),
+            &mut walk_results,
+            self.options.policy(),
+        )?;
+        // Files that failed have no stamp, so they are retried
+        let stale: Vec<(&PathBuf, Option<(u64, u64)>)> = files
+            .iter()
+            .map(|file| (file, file_stamp(file)))
+            .filter(|(file, stamp)| {
+                self.files
+                    .get(*file)
+                    .is_none_or(|served| served.stamp.is_none() || served.stamp != *stamp)
+            })
+            .collect();
+        let listed: BTreeSet<&PathBuf> = files.iter().collect();
+        let removed: Vec<PathBuf> = self
+            .files
+            .keys()
+            .filter(|file| !listed.contains(file))
+            .cloned()
+            .collect();
+        let walk_failed = self
+            .results
+            .errors
+            .iter()
+            .any(|error| error.file_path == project_dir);
+        self.analysed = stale.len();
+        if stale.is_empty() && removed.is_empty() && !walk_failed && walk_results.errors.is_empty()
+        {
+            return Ok(());
+        }
+
+        // Every file is analysed before the results change, so a failure leaves them intact
+        let (policy, options) = (self.options.policy(), self.options.source());
+        let analysed = stale
+            .par_iter()
+            .map(|(file, _)| {
+                let mut file_results = AnalysisResults::new();
+                analyse_file(file, &mut file_results, policy, options).map(|_| file_results)
+            })
+            .collect::<Result<Vec<AnalysisResults>, ForestError>>()?;
+        crate::interning::clear_all();
+
+        // Errors met while walking the directories are recorded against the project directory
+        let replaced: BTreeSet<&Path> = stale
+            .iter()
+            .map(|(file, _)| file.as_path())
+            .chain(removed.iter().map(PathBuf::as_path))
+            .collect();
+        self.results
+            .remove_files(|path| path == project_dir || replaced.contains(path));
+        for file in &removed {
+            self.files.remove(file);
+        }
+        let classifier = self.metadata.cargo.as_ref().map(targets::Targets::new);
+        for ((file, stamp), mut file_results) in stale.into_iter().zip(analysed) {
+            if let Some(ref classifier) = classifier {
+                file_results.file_targets = classifier.classify([file.as_path()]);
+            }
+            let served = ServedFile {
+                stamp: stamp.filter(|_| file_results.errors.is_empty()),
+                interior_mutable_fields: std::mem::take(&mut file_results.interior_mutable_fields),
+                declared_features: std::mem::take(&mut file_results.declared_features),
+            };
+            self.files.insert(file.clone(), served);
+            self.results.merge(file_results);
+        }
+        self.results.merge(walk_results);
+        self.results.interior_mutable_fields = self
+            .files
+            .values()
+            .flat_map(|served| served.interior_mutable_fields.iter().cloned())
+            .collect();
+        self.results.declared_features = self
+            .files
+            .values()
+            .flat_map(|served| served.declared_features.iter().cloned())
+            .collect();
+        self.results.sort_by_location();
+        Ok(())
+    }
+
+    // Function to answer one request
+    fn call(&self, method: &str, params: &Value) -> Result<Value, RpcError> {
+        let text = |name: &str| params.get(name).and_then(Value::as_str);
+        match method {
+            "summary" => Ok(json!({
+                "files": self.results.file_lines.len(),
+                "reanalysed_files": self.analysed,
+                "mutable_variables": self.results.mutable_vars.len(),
+                "immutable_variables": self.results.immutable_vars.len(),
+                "data_structures": self.results.data_structures.len(),
+                "errors": self.results.errors.len(),
+                "parse_failures": self.results.parse_failures.len(),
+            })),
+            "query" => {
+                let filters = filters::Filters::new(
+                    text("name"),
+                    text("type"),
+                    text("scope"),
+                    text("file"),
+                    text("mutability"),
+                    Path::new(&self.project_dir),
+                )
+                .map_err(|e| (INVALID_PARAMS, e.to_string()))?;
+                let matching = filters.select(&self.results);
+                let variables = |mutable: bool| -> Vec<output_model::Variable> {
+                    matching
+                        .variables()
+                        .iter()
+                        .filter(|var| var.mutable == mutable)
+                        .map(|var| output_model::Variable::new(var, false))
+                        .collect()
+                };
+                Ok(json!({
+                    "mutable_variables": variables(true),
+                    "immutable_variables": variables(false),
+                    "data_structures": matching
+                        .data_structures()
+                        .iter()
+                        .map(|item| output_model::DataStructure::new(item, false))
+                        .collect::<Vec<_>>(),
+                }))
+            }
+            "stats" => {
+                let top = params.get("top").and_then(Value::as_u64).unwrap_or(10) as usize;
+                serde_json::to_value(stats::stats(&self.results, top))
+                    .map_err(|e| (INTERNAL_ERROR, e.to_string()))
+            }
+            "report" => serde_json::to_value(results_model(&self.results, &self.metadata, false))
+                .map_err(|e| (INTERNAL_ERROR, e.to_string())),
+            _ => Err((METHOD_NOT_FOUND, format!("unknown method {}", method))),
+        }
+    }
+
+    // Function to answer a request line, and whether the server should stop
+    // Notifications (requests without an id) get no reply
+    fn handle(&mut self, line: &str) -> (Option<Value>, bool) {
+        let request: Value = match serde_json::from_str(line) {
+            Ok(request) => request,
+            Err(e) => {
+                let response = error_response(Value::Null, PARSE_ERROR, e.to_string());
+                return (Some(response), false);
+            }
+        };
+        let id = request.get("id").cloned();
+        let Some(method) = request.get("method").and_then(Value::as_str) else {
+            let response = error_response(
+                id.unwrap_or(Value::Null),
+                INVALID_REQUEST,
+                "missing method".to_string(),
+            );
+            return (Some(response), false);
+        };
+        let params = request.get("params").cloned().unwrap_or(Value::Null);
+
+        let stop = method == "shutdown";
+        let outcome = if stop {
+            Ok(Value::Null)
+        } else {
+            self.refresh()
+                .map_err(|e| (INTERNAL_ERROR, e.to_string()))
+                .and_then(|_| self.call(method, &params))
+        };
+        let response = id.map(|id| match outcome {
+            Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+            Err((code, message)) => error_response(id, code, message),
+        });
+        (response, stop)
+    }
+}
+
+// Function to build a JSON-RPC error response
+fn error_response(id: Value, code: i64, message: String) -> Value {
+    json!({"jsonrpc": "2.0", "id": id, "error": {"code": code, "message": message}})
+}
+
+// Function to serve requests on stdin until it closes or a shutdown request arrives
+// The files are selected and analysed with the options given on the command line
+pub fn serve(
+    project_dir: &str,
+    metadata: AnalysisMetadata,
+    options: &AnalysisOptions,
+) -> Result<(), ForestError> {
+    let mut server = Server::new(project_dir, metadata, options);
+    server.refresh()?;
+    eprintln!(
+        "forest serve: {} files of {} analysed; reading JSON-RPC requests from stdin",
+        server.results.file_lines.len(),
+        project_dir
+    );
+
+    let mut stdout = io::stdout().lock();
+    for line in io::stdin().lock().lines() {
+        let line = line.map_err(|e| ForestError::io("<stdin>", e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (response, stop) = server.handle(&line);
+        if let Some(response) = response {
+            serde_json::to_writer(&mut stdout, &response)
+                .map_err(io::Error::from)
+                .and_then(|_| writeln!(stdout))
+                .and_then(|_| stdout.flush())
+                .map_err(|e| ForestError::io("<stdout>", e))?;
+        }
+        if stop {
+            break;
+        }
+    }
+    Ok(())
+}
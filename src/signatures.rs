@@ -0,0 +1,102 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Function signatures.
+// Function records carry their parameter list and return type, so the data structures
+// section doubles as an inventory of the API. Each parameter records whether its binding
+// is `mut` and whether the value is passed by value, by reference or by mutable reference.
+
+use quote::ToTokens;
+use serde::{Deserialize, Serialize};
+
+// A parameter of a function
+#[derive(Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct Param {
+    pub name: String, // Binding name, or the pattern for destructured parameters
+    #[serde(rename = "type")]
+    pub type_name: String,
+    pub mutable: bool,   // `mut name: T`
+    pub passing: String, // "by value", "by reference" or "by mutable reference"
+}
+
+// Parameters and return type of a function
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Signature {
+    pub params: Vec<Param>,
+    pub return_type: Option<String>, // None for functions returning ()
+}
+
+impl Signature {
+    // The signature as written, e.g. `(mut count: usize, name: &str) -> bool`
+    pub fn text(&self) -> String {
+        let params: Vec<String> = self
+            .params
+            .iter()
+            .map(|param| {
+                let binding = if param.mutable { "mut " } else { "" };
+                if param.name == "self" {
+                    format!("{}{}", binding, param.type_name)
+                } else {
+                    format!("{}{}: {}", binding, param.name, param.type_name)
+                }
+            })
+            .collect();
+        match self.return_type {
+            Some(ref return_type) => format!("({}) -> {}", params.join(", "), return_type),
+            None => format!("({})", params.join(", ")),
+        }
+    }
+}
+
+// Function to describe how a value of a type is passed
+fn passing(ty: &syn::Type) -> &'static str {
+    match ty {
+        syn::Type::Reference(reference) if reference.mutability.is_some() => "by mutable reference",
+        syn::Type::Reference(_) => "by reference",
+        _ => "by value",
+    }
+}
+
+// Function to describe a parameter of a signature
+fn param_of(input: &syn::FnArg) -> Param {
+    match input {
+        syn::FnArg::Receiver(receiver) => Param {
+            name: "self".to_string(),
+            type_name: match receiver.reference {
+                Some(_) if receiver.mutability.is_some() => "&mut self".to_string(),
+                Some(_) => "&self".to_string(),
+                None => "self".to_string(),
+            },
+            mutable: receiver.reference.is_none() && receiver.mutability.is_some(),
+            passing: match receiver.reference {
+                Some(_) if receiver.mutability.is_some() => "by mutable reference",
+                Some(_) => "by reference",
+                None => "by value",
+            }
+            .to_string(),
+        },
+        syn::FnArg::Typed(pat_type) => {
+            let (name, mutable) = match *pat_type.pat {
+                syn::Pat::Ident(ref pat_ident) => {
+                    (pat_ident.ident.to_string(), pat_ident.mutability.is_some())
+                }
+                ref pattern => (pattern.to_token_stream().to_string(), false),
+            };
+            Param {
+                name,
+                type_name: crate::globals::type_text(&pat_type.ty),
+                mutable,
+                passing: passing(&pat_type.ty).to_string(),
+            }
+        }
+    }
+}
+
+// Function to describe the parameters and return type of a function
+pub fn signature_of(sig: &syn::Signature) -> Signature {
+    Signature {
+        params: sig.inputs.iter().map(param_of).collect(),
+        return_type: match sig.output {
+            syn::ReturnType::Default => None,
+            syn::ReturnType::Type(_, ref ty) => Some(crate::globals::type_text(ty)),
+        },
+    }
+}
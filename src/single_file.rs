@@ -0,0 +1,83 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Analysis of a single source file, or of source read from stdin (`forest file.rs`, `forest -`).
+// No Cargo.toml is needed: the project name is the file name, the version is unknown and
+// there are no dependencies. Paths are relative to the directory of the file, and source
+// from stdin is reported as `<stdin>`.
+
+use crate::{analyse_source, AnalysisResults, FailurePolicy, ForestError, SourceOptions};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+// The project_dir argument that reads source from stdin
+pub const STDIN: &str = "-";
+
+// Path reported for source read from stdin
+const STDIN_PATH: &str = "<stdin>";
+
+// Function to check whether the project_dir argument names a single source instead of a project
+pub fn is_single_source(project_dir: &str) -> bool {
+    project_dir == STDIN || Path::new(project_dir).is_file()
+}
+
+// Function to give the path records of the source are reported under
+fn source_path(project_dir: &str) -> PathBuf {
+    if project_dir == STDIN {
+        PathBuf::from(STDIN_PATH)
+    } else {
+        PathBuf::from(project_dir)
+    }
+}
+
+// Function to name the "project" of a single source after its file
+pub fn project_name(project_dir: &str) -> String {
+    if project_dir == STDIN {
+        return "stdin".to_string();
+    }
+    Path::new(project_dir)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| project_dir.to_string())
+}
+
+// Function to give the directory that paths of a single source are relative to
+pub fn root_dir(project_dir: &str) -> PathBuf {
+    match Path::new(project_dir).parent() {
+        Some(parent) if project_dir != STDIN && !parent.as_os_str().is_empty() => {
+            parent.to_path_buf()
+        }
+        _ => PathBuf::from("."),
+    }
+}
+
+// Function to analyse a single file, or the source read from stdin
+pub fn analyse(
+    project_dir: &str,
+    policy: &FailurePolicy,
+    options: &SourceOptions,
+) -> Result<AnalysisResults, ForestError> {
+    let content = if project_dir == STDIN {
+        let mut content = String::new();
+        std::io::stdin()
+            .read_to_string(&mut content)
+            .map_err(|e| ForestError::io(STDIN_PATH, e))?;
+        content
+    } else {
+        std::fs::read_to_string(project_dir).map_err(|e| ForestError::io(project_dir, e))?
+    };
+    let mut results = AnalysisResults::new();
+    analyse_source(
+        &source_path(project_dir),
+        &content,
+        &mut results,
+        policy,
+        options,
+    )?;
+    crate::interning::clear_all();
+    results
+        .warnings
+        .extend(crate::cargo::take_manifest_warnings());
+    results.sort_by_location();
+    results.collector_summaries =
+        crate::collector::summarize(&options.collectors, &results.collected);
+    Ok(results)
+}
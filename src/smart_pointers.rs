@@ -0,0 +1,107 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Smart pointers and ownership wrappers.
+// Every mention of `Box`, `Rc`, `Arc`, `Cow` or `Weak` in a type is recorded with the type
+// it wraps and its module, and the uses are tallied per module to show how each part of the
+// code shares and owns its data. Nested wrappers such as `Rc<RefCell<Box<T>>>` count once each.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use syn::visit::{self, Visit};
+
+use crate::globals::type_text;
+
+// Wrapper types that decide how a value is owned or shared
+const SMART_POINTERS: &[&str] = &["Box", "Rc", "Arc", "Cow", "Weak"];
+
+// A smart pointer in a type
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SmartPointer {
+    pub pointer: String, // "Box", "Rc", "Arc", "Cow" or "Weak"
+    #[serde(rename = "wraps")]
+    pub wrapped: String, // Type it wraps, e.g. RefCell<Node> for Rc<RefCell<Node>>
+    pub module: String,
+    #[serde(rename = "file")]
+    pub file_path: PathBuf,
+    #[serde(rename = "line")]
+    pub line_number: usize,
+}
+
+// Visitor that collects the smart pointers of the types in a file
+struct SmartPointerVisitor<'a> {
+    file_path: &'a Path,
+    module: String,
+    pointers: &'a mut Vec<SmartPointer>,
+}
+
+impl<'ast> Visit<'ast> for SmartPointerVisitor<'_> {
+    fn visit_type_path(&mut self, type_path: &'ast syn::TypePath) {
+        if let Some(segment) = type_path.path.segments.last() {
+            let pointer = segment.ident.to_string();
+            if SMART_POINTERS.contains(&pointer.as_str()) {
+                // Cow<'a, str> wraps str; the lifetime is not the wrapped type
+                let wrapped = match segment.arguments {
+                    syn::PathArguments::AngleBracketed(ref args) => {
+                        args.args.iter().find_map(|arg| match arg {
+                            syn::GenericArgument::Type(ty) => Some(type_text(ty)),
+                            _ => None,
+                        })
+                    }
+                    _ => None,
+                };
+                self.pointers.push(SmartPointer {
+                    pointer,
+                    wrapped: wrapped.unwrap_or_else(|| "_".to_string()),
+                    module: self.module.clone(),
+                    file_path: self.file_path.to_path_buf(),
+                    line_number: segment.ident.span().start().line,
+                });
+            }
+        }
+        visit::visit_type_path(self, type_path);
+    }
+
+    fn visit_item_mod(&mut self, item_mod: &'ast syn::ItemMod) {
+        let inner_module = format!("{}::{}", self.module, item_mod.ident);
+        let outer_module = std::mem::replace(&mut self.module, inner_module);
+        visit::visit_item_mod(self, item_mod);
+        self.module = outer_module;
+    }
+}
+
+// Function to collect the smart pointers of a parsed file
+pub fn collect_smart_pointers(
+    file_ast: &syn::File,
+    file_path: &Path,
+    module_path: &str,
+    pointers: &mut Vec<SmartPointer>,
+) {
+    let mut visitor = SmartPointerVisitor {
+        file_path,
+        module: module_path.to_string(),
+        pointers,
+    };
+    visitor.visit_file(file_ast);
+}
+
+// Function to tally the smart pointers of each module, e.g. crate::tree -> {Rc: 3, Weak: 1}
+pub fn module_tally(pointers: &[SmartPointer]) -> BTreeMap<&str, BTreeMap<&str, usize>> {
+    let mut tally: BTreeMap<&str, BTreeMap<&str, usize>> = BTreeMap::new();
+    for pointer in pointers {
+        *tally
+            .entry(pointer.module.as_str())
+            .or_default()
+            .entry(pointer.pointer.as_str())
+            .or_insert(0) += 1;
+    }
+    tally
+}
+
+// Function to format the tally of one module, e.g. "Arc: 2, Box: 1"
+pub fn tally_text(counts: &BTreeMap<&str, usize>) -> String {
+    counts
+        .iter()
+        .map(|(pointer, count)| format!("{}: {}", pointer, count))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,239 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Per-function mutable-state density.
+// The state of a function is its mutable locals, its `&mut` parameters and the
+// interior-mutability fields (Cell, RefCell, Mutex, atomics, ...) it touches. Divided
+// by the length of the function in lines, this gives a single "statefulness" number.
+
+use proc_macro2::TokenTree;
+use quote::ToTokens;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+
+// Number of functions listed in the ranking
+const TOP_FUNCTIONS: usize = 10;
+
+// Shorter functions are left out of the ranking, where a single `&mut self` would top it
+const MIN_RANKED_LINES: usize = 5;
+
+// Types that allow mutation through a shared reference
+const INTERIOR_MUTABILITY_TYPES: &[&str] = &[
+    "Cell",
+    "RefCell",
+    "UnsafeCell",
+    "OnceCell",
+    "Mutex",
+    "RwLock",
+];
+
+// Mutable state of a single function
+#[derive(Clone, Serialize, Deserialize)]
+pub struct FunctionState {
+    pub name: String,
+    pub file_path: PathBuf,
+    pub line_number: usize,
+    pub lines: usize,
+    pub mutable_locals: usize,
+    pub mut_params: usize,
+    pub fields_accessed: BTreeSet<String>, // Names of all fields accessed in the body
+}
+
+// A function with its statefulness score
+pub struct RankedFunction<'a> {
+    pub function: &'a FunctionState,
+    pub interior_fields: usize, // Interior-mutability fields touched
+    pub density: f64,           // State per line
+}
+
+// Function to check whether a field type wraps an interior-mutability type
+fn is_interior_mutable(tokens: proc_macro2::TokenStream) -> bool {
+    interior_wrapper(tokens).is_some()
+}
+
+// Function to find the first interior-mutability type in a type or expression, e.g. `RefCell`
+pub fn interior_wrapper(tokens: proc_macro2::TokenStream) -> Option<String> {
+    tokens.into_iter().find_map(|token| match token {
+        TokenTree::Ident(ident) => {
+            let name = ident.to_string();
+            (INTERIOR_MUTABILITY_TYPES.contains(&name.as_str()) || name.starts_with("Atomic"))
+                .then_some(name)
+        }
+        TokenTree::Group(group) => interior_wrapper(group.stream()),
+        _ => None,
+    })
+}
+
+// Visitor that counts the state of one function body
+#[derive(Default)]
+struct BodyVisitor {
+    mutable_locals: usize,
+    fields_accessed: BTreeSet<String>,
+}
+
+impl<'ast> Visit<'ast> for BodyVisitor {
+    // Covers `let mut`, `for mut`, mutable bindings in match arms and closure parameters
+    fn visit_pat_ident(&mut self, pat_ident: &'ast syn::PatIdent) {
+        if pat_ident.mutability.is_some() {
+            self.mutable_locals += 1;
+        }
+        visit::visit_pat_ident(self, pat_ident);
+    }
+
+    fn visit_expr_field(&mut self, expr_field: &'ast syn::ExprField) {
+        if let syn::Member::Named(ref ident) = expr_field.member {
+            self.fields_accessed.insert(ident.to_string());
+        }
+        visit::visit_expr_field(self, expr_field);
+    }
+
+    // Nested functions are measured on their own
+    fn visit_item_fn(&mut self, _item_fn: &'ast syn::ItemFn) {}
+}
+
+// Visitor that measures free functions and methods, and finds interior-mutability fields
+struct StateVisitor<'a> {
+    file_path: &'a Path,
+    impl_type: Option<String>,
+    functions: &'a mut Vec<FunctionState>,
+    interior_fields: &'a mut Vec<String>,
+}
+
+impl StateVisitor<'_> {
+    fn measure(&mut self, name: String, sig: &syn::Signature, body: &syn::Block) {
+        let mut body_visitor = BodyVisitor::default();
+        let mut mut_params = 0;
+        for input in &sig.inputs {
+            match input {
+                syn::FnArg::Receiver(receiver) => {
+                    if receiver.reference.is_some() && receiver.mutability.is_some() {
+                        mut_params += 1;
+                    }
+                }
+                syn::FnArg::Typed(pat_type) => {
+                    if let syn::Type::Reference(ref reference) = *pat_type.ty {
+                        if reference.mutability.is_some() {
+                            mut_params += 1;
+                        }
+                    }
+                    // `mut x: T` parameters are mutable locals
+                    body_visitor.visit_pat(&pat_type.pat);
+                }
+            }
+        }
+        body_visitor.visit_block(body);
+
+        let start = sig.span().start().line;
+        let end = body.brace_token.span.close().end().line;
+        self.functions.push(FunctionState {
+            name,
+            file_path: self.file_path.to_path_buf(),
+            line_number: start,
+            lines: end.saturating_sub(start) + 1,
+            mutable_locals: body_visitor.mutable_locals,
+            mut_params,
+            fields_accessed: body_visitor.fields_accessed,
+        });
+    }
+}
+
+impl<'ast> Visit<'ast> for StateVisitor<'_> {
+    fn visit_item_fn(&mut self, item_fn: &'ast syn::ItemFn) {
+        self.measure(item_fn.sig.ident.to_string(), &item_fn.sig, &item_fn.block);
+        visit::visit_item_fn(self, item_fn);
+    }
+
+    fn visit_item_impl(&mut self, item_impl: &'ast syn::ItemImpl) {
+        let previous = self.impl_type.replace(
+            item_impl
+                .self_ty
+                .to_token_stream()
+                .to_string()
+                .replace(' ', ""),
+        );
+        visit::visit_item_impl(self, item_impl);
+        self.impl_type = previous;
+    }
+
+    fn visit_impl_item_fn(&mut self, method: &'ast syn::ImplItemFn) {
+        let name = match self.impl_type {
+            Some(ref ty) => format!("{}::{}", ty, method.sig.ident),
+            None => method.sig.ident.to_string(),
+        };
+        self.measure(name, &method.sig, &method.block);
+        visit::visit_impl_item_fn(self, method);
+    }
+
+    fn visit_field(&mut self, field: &'ast syn::Field) {
+        if let Some(ref ident) = field.ident {
+            if is_interior_mutable(field.ty.to_token_stream()) {
+                self.interior_fields.push(ident.to_string());
+            }
+        }
+        visit::visit_field(self, field);
+    }
+}
+
+// Function to measure the functions of a parsed file
+pub fn collect_function_states(
+    file_ast: &syn::File,
+    file_path: &Path,
+    functions: &mut Vec<FunctionState>,
+    interior_fields: &mut Vec<String>,
+) {
+    let mut visitor = StateVisitor {
+        file_path,
+        impl_type: None,
+        functions,
+        interior_fields,
+    };
+    visitor.visit_file(file_ast);
+}
+
+// Mutable-state density of the whole project
+pub struct StateDensitySummary<'a> {
+    pub overall: f64, // Total state divided by the total length of all functions
+    pub top_functions: Vec<RankedFunction<'a>>, // Most stateful functions first
+}
+
+// Function to rank functions by mutable-state density, most stateful first
+// Fields are matched by name, as the type of the accessed value is not known
+pub fn summarize<'a>(
+    functions: &'a [FunctionState],
+    interior_fields: &[String],
+) -> StateDensitySummary<'a> {
+    let interior_fields: HashSet<&str> = interior_fields.iter().map(String::as_str).collect();
+    let mut total_state = 0;
+    let mut total_lines = 0;
+    let mut ranked: Vec<RankedFunction> = functions
+        .iter()
+        .map(|function| {
+            let touched = function
+                .fields_accessed
+                .iter()
+                .filter(|field| interior_fields.contains(field.as_str()))
+                .count();
+            let state = function.mutable_locals + function.mut_params + touched;
+            total_state += state;
+            total_lines += function.lines;
+            RankedFunction {
+                function,
+                interior_fields: touched,
+                density: state as f64 / function.lines.max(1) as f64,
+            }
+        })
+        .filter(|ranked| ranked.density > 0.0 && ranked.function.lines >= MIN_RANKED_LINES)
+        .collect();
+    ranked.sort_by(|a, b| {
+        b.density
+            .total_cmp(&a.density)
+            .then_with(|| a.function.name.cmp(&b.function.name))
+    });
+    ranked.truncate(TOP_FUNCTIONS);
+
+    StateDensitySummary {
+        overall: total_state as f64 / total_lines.max(1) as f64,
+        top_functions: ranked,
+    }
+}
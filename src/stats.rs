@@ -0,0 +1,132 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// `forest stats`: the worst offenders instead of the full inventory.
+// Ranks the files and functions with the most mutable variables, the files with the most
+// data structures, and the files and functions with the most lines, keeping the top N of
+// each. Variables are attributed to the scope they are declared in, so a function's count
+// includes the variables of its closures and blocks. Ties are broken by name.
+
+use crate::aggregate::{Count, GroupKey};
+use crate::AnalysisResults;
+use serde::Serialize;
+
+// A ranked file or function
+#[derive(Serialize)]
+pub struct Entry {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>, // file:line of functions, when known
+    pub count: usize,
+}
+
+// The rankings of a project
+#[derive(Serialize)]
+pub struct Stats {
+    pub files_by_mutable_variables: Vec<Entry>,
+    pub functions_by_mutable_variables: Vec<Entry>,
+    pub files_by_data_structures: Vec<Entry>,
+    pub files_by_lines: Vec<Entry>,
+    pub functions_by_lines: Vec<Entry>,
+}
+
+// Function to keep the top entries, largest first
+fn top(mut entries: Vec<Entry>, n: usize) -> Vec<Entry> {
+    entries.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+    entries.truncate(n);
+    entries
+}
+
+// Function to keep the top counts, which are already ranked largest first
+fn ranked(counts: Vec<Count>, n: usize) -> Vec<Entry> {
+    counts
+        .into_iter()
+        .take(n)
+        .map(|count| Entry {
+            name: count.key,
+            location: None,
+            count: count.count,
+        })
+        .collect()
+}
+
+// Function to rank the files and functions of the results
+pub fn stats(results: &AnalysisResults, n: usize) -> Stats {
+    let mutable = results.filter(|var| var.mutable);
+
+    Stats {
+        files_by_mutable_variables: ranked(mutable.counts_by(GroupKey::File), n),
+        functions_by_mutable_variables: ranked(
+            mutable
+                .clone()
+                .filter(|var| !var.scope.is_empty())
+                .counts_by(GroupKey::Scope),
+            n,
+        ),
+        files_by_data_structures: ranked(
+            results.select().data_structure_counts_by(GroupKey::File),
+            n,
+        ),
+        files_by_lines: top(
+            results
+                .file_lines
+                .iter()
+                .map(|file| Entry {
+                    name: file.file_path.display().to_string(),
+                    location: None,
+                    count: file.lines.total,
+                })
+                .collect(),
+            n,
+        ),
+        functions_by_lines: top(
+            results
+                .function_metrics
+                .iter()
+                .map(|function| Entry {
+                    name: function.function.clone(),
+                    location: Some(format!(
+                        "{}:{}",
+                        function.file_path.display(),
+                        function.line_number
+                    )),
+                    count: function.lines,
+                })
+                .collect(),
+            n,
+        ),
+    }
+}
+
+// Function to print one ranking
+fn print_ranking(title: &str, entries: &[Entry]) {
+    println!("\n\x1b[1m{}:\x1b[0m", title);
+    if entries.is_empty() {
+        println!("  none");
+    }
+    for entry in entries {
+        match entry.location {
+            Some(ref location) => println!("  {:>6}  {} ({})", entry.count, entry.name, location),
+            None => println!("  {:>6}  {}", entry.count, entry.name),
+        }
+    }
+}
+
+// Function to print the rankings
+pub fn print_stats(stats: &Stats, n: usize) {
+    print_ranking(
+        &format!("Top {} Files by Mutable Variables", n),
+        &stats.files_by_mutable_variables,
+    );
+    print_ranking(
+        &format!("Top {} Functions by Mutable Variables", n),
+        &stats.functions_by_mutable_variables,
+    );
+    print_ranking(
+        &format!("Top {} Files by Data Structures", n),
+        &stats.files_by_data_structures,
+    );
+    print_ranking(&format!("Top {} Files by Lines", n), &stats.files_by_lines);
+    print_ranking(
+        &format!("Top {} Functions by Lines", n),
+        &stats.functions_by_lines,
+    );
+}
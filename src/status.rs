@@ -0,0 +1,86 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Machine-readable run outcome for `--status-file`.
+// The status file is written even when the run fails, so CI steps can make decisions
+// without parsing the human-oriented output.
+
+use crate::thresholds::Check;
+use crate::{AnalysisResults, ForestError};
+use chrono::Local;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+// Information gathered during a run, filled in as far as the run progresses
+#[derive(Default)]
+pub struct RunStatus {
+    pub counts: Option<StatusCounts>, // None when the analysis did not complete
+    pub errors: Vec<(PathBuf, String)>, // Files that could not be analysed
+    pub thresholds: Vec<Check>,       // Thresholds evaluated after the analysis
+}
+
+pub struct StatusCounts {
+    pub mutable_vars: usize,
+    pub immutable_vars: usize,
+    pub data_structures: usize,
+    pub degraded_files: usize,
+}
+
+impl RunStatus {
+    // Record the counts and error summaries of a completed analysis
+    pub fn record_results(&mut self, results: &AnalysisResults) {
+        self.counts = Some(StatusCounts {
+            mutable_vars: results.mutable_vars.len(),
+            immutable_vars: results.immutable_vars.len(),
+            data_structures: results.data_structures.len(),
+            degraded_files: results.degraded_files.len(),
+        });
+        self.errors = results
+            .errors
+            .iter()
+            .map(|e| (e.file_path.clone(), e.message.clone()))
+            .collect();
+    }
+}
+
+// Function to write the status file for a finished (or failed) run
+pub fn write_status_file(
+    file: &str,
+    status: &RunStatus,
+    outcome: &Result<(), ForestError>,
+    duration: Duration,
+) -> io::Result<()> {
+    let counts = status.counts.as_ref().map(|c| {
+        serde_json::json!({
+            "mutable_variable_count": c.mutable_vars,
+            "immutable_variable_count": c.immutable_vars,
+            "data_structure_count": c.data_structures,
+            "degraded_file_count": c.degraded_files,
+            "error_count": status.errors.len()
+        })
+    });
+
+    let errors: Vec<serde_json::Value> = status
+        .errors
+        .iter()
+        .map(|(path, message)| {
+            serde_json::json!({
+                "file": path.display().to_string(),
+                "message": message
+            })
+        })
+        .collect();
+
+    let json = serde_json::json!({
+        "status": if outcome.is_ok() { "success" } else { "failure" },
+        "exit_code": outcome.as_ref().map_or_else(ForestError::exit_code, |_| 0),
+        "error": outcome.as_ref().err().map(|e| e.to_string()),
+        "datetime": Local::now().to_string(),
+        "duration_ms": duration.as_millis() as u64,
+        "counts": counts,
+        "thresholds": status.thresholds,
+        "errors": errors
+    });
+
+    fs::write(file, serde_json::to_string_pretty(&json)?)
+}
@@ -0,0 +1,268 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Streaming output (`--stream`).
+// Instead of collecting the whole project before writing anything, the variables, data
+// structures and errors of each file are written as JSON Lines as soon as the file is
+// analysed. Worker threads send the results of each file through a bounded channel to the
+// writer. The records of a file stay together and in source order, but files are written in
+// the order they finish. The first line describes the project and the last line holds the
+// totals. Sampling, changed-files mode and resuming select the files as for the report;
+// restored files are written first. Completed files are appended to the checkpoint log on
+// disk as they are written, so a plain stream holds only the files in flight, but the
+// restored files are loaded whole and changed-files mode keeps every file's results for the
+// cache it writes at the end. Project-wide analyses (duplicates, the call graph, ...) need
+// every file at once and are not streamed.
+
+use crate::args::Args;
+use crate::{
+    analyse_file, cache, filters, output_model, paths, plan_project, AnalysisMetadata,
+    AnalysisOptions, AnalysisResults, ForestError,
+};
+use rayon::prelude::*;
+use serde::Serialize;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::sync::mpsc;
+use std::time::Instant;
+
+// Analysed files waiting for the writer, per worker thread
+const FILES_IN_FLIGHT_PER_THREAD: usize = 2;
+
+// Counts of the records written, for the summary line
+#[derive(Default, Serialize)]
+struct Totals {
+    files: usize,
+    mutable_variables: usize,
+    immutable_variables: usize,
+    data_structures: usize,
+    errors: usize,
+    parse_failures: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sample: Option<output_model::Sample>, // Estimates for the whole project when sampling
+}
+
+// One line of the stream, tagged with its kind in the `record` field
+#[derive(Serialize)]
+#[serde(tag = "record", rename_all = "snake_case")]
+enum Record<'a> {
+    Metadata {
+        project_name: &'a str,
+        version: &'a str,
+        datetime: &'a str,
+    },
+    MutableVariable(output_model::Variable),
+    ImmutableVariable(output_model::Variable),
+    DataStructure(output_model::DataStructure),
+    Error {
+        file: String,
+        message: &'a str,
+    },
+    ParseFailure {
+        file: String,
+        line: usize,
+        column: usize,
+        message: &'a str,
+        fallback: bool,
+    },
+    Summary(&'a Totals),
+}
+
+// Function to write one record as a line of JSON
+fn write_record(out: &mut impl Write, record: &Record) -> io::Result<()> {
+    serde_json::to_writer(&mut *out, record)?;
+    writeln!(out)
+}
+
+// Function to print the warnings of results to stderr
+fn print_warnings(results: &AnalysisResults) {
+    for warning in results.warnings() {
+        eprintln!("Warning: {}", warning);
+    }
+}
+
+// Function to write the records of one analysed file
+fn write_results(
+    out: &mut impl Write,
+    results: &AnalysisResults,
+    link: bool,
+    totals: &mut Totals,
+) -> io::Result<()> {
+    totals.files += results.file_lines.len();
+    for var in &results.mutable_vars {
+        write_record(
+            out,
+            &Record::MutableVariable(output_model::Variable::new(var, link)),
+        )?;
+    }
+    for var in &results.immutable_vars {
+        write_record(
+            out,
+            &Record::ImmutableVariable(output_model::Variable::new(var, link)),
+        )?;
+    }
+    for item in &results.data_structures {
+        write_record(
+            out,
+            &Record::DataStructure(output_model::DataStructure::new(item, link)),
+        )?;
+    }
+    for failure in &results.parse_failures {
+        write_record(
+            out,
+            &Record::ParseFailure {
+                file: failure.file_path.display().to_string(),
+                line: failure.line_number,
+                column: failure.column,
+                message: &failure.message,
+                fallback: failure.fallback,
+            },
+        )?;
+    }
+    for error in &results.errors {
+        write_record(
+            out,
+            &Record::Error {
+                file: error.file_path.display().to_string(),
+                message: &error.message,
+            },
+        )?;
+    }
+    totals.mutable_variables += results.mutable_vars.len();
+    totals.immutable_variables += results.immutable_vars.len();
+    totals.data_structures += results.data_structures.len();
+    totals.parse_failures += results.parse_failures.len();
+    totals.errors += results.errors.len();
+    Ok(())
+}
+
+// Function to analyse the project and stream its records to --output or stdout
+pub fn stream(
+    args: &Args,
+    project_dir: &str,
+    metadata: &AnalysisMetadata,
+) -> Result<(), ForestError> {
+    let root_dir = Path::new(project_dir);
+    let filters = filters::Filters::new(
+        args.name_filter.as_deref(),
+        args.type_filter.as_deref(),
+        args.scope_filter.as_deref(),
+        args.file_filter.as_deref(),
+        args.mutability.as_deref(),
+        root_dir,
+    )?;
+    let analysis_options = AnalysisOptions::from_args(args);
+    let (policy, options) = (analysis_options.policy(), analysis_options.source());
+    let mut run = plan_project(project_dir, &analysis_options, metadata.cargo.as_ref())?;
+    // Stdout may hold the stream, so notes and warnings go to stderr
+    for note in &run.notes {
+        eprintln!("{}", note);
+    }
+    print_warnings(&run.results);
+
+    // Function to filter a file's results and rewrite their paths before they are written
+    let prepare = |file_results: &mut AnalysisResults| {
+        if !filters.is_empty() {
+            filters.apply(file_results);
+        }
+        if let Some(ref mode) = args.paths {
+            file_results.rewrite_paths(|path| paths::rewrite(path, root_dir, mode));
+        }
+        file_results.sort_by_location();
+    };
+
+    let output = args.output_file.as_deref().unwrap_or("<stdout>");
+    let output_error = |e: io::Error| ForestError::output("stream", output, e);
+    let out: Box<dyn Write> = match args.output_file {
+        Some(ref file) => Box::new(File::create(file).map_err(output_error)?),
+        None => Box::new(io::stdout().lock()),
+    };
+    let mut out = BufWriter::new(out);
+    write_record(
+        &mut out,
+        &Record::Metadata {
+            project_name: &metadata.project_name,
+            version: &metadata.version,
+            datetime: &metadata.datetime,
+        },
+    )
+    .map_err(output_error)?;
+    // Errors met while walking the directories are written first, then the restored files
+    let mut totals = Totals::default();
+    write_results(&mut out, &run.results, args.link, &mut totals).map_err(output_error)?;
+    for mut file_results in std::mem::take(&mut run.restored) {
+        prepare(&mut file_results);
+        write_results(&mut out, &file_results, args.link, &mut totals).map_err(output_error)?;
+    }
+
+    // The channel is bounded, so workers wait while the writer catches up
+    let (sender, receiver) =
+        mpsc::sync_channel(rayon::current_num_threads() * FILES_IN_FLIGHT_PER_THREAD);
+    let files = std::mem::take(&mut run.files);
+    let files = &files;
+    std::thread::scope(|scope| -> Result<(), ForestError> {
+        let workers = scope.spawn(move || {
+            files.par_iter().try_for_each_with(sender, |sender, file| {
+                let mut file_results = AnalysisResults::new();
+                analyse_file(file, &mut file_results, policy, options)?;
+                // Sending fails once the writer stopped, e.g. because the pipe was closed
+                sender
+                    .send((file, file_results))
+                    .map_err(|_| output_error(io::Error::other("the stream output was closed")))
+            })
+        });
+        // The cache and checkpoint log store the results before they are filtered
+        let mut last_checkpoint = Instant::now();
+        for (file, mut file_results) in receiver {
+            print_warnings(&file_results);
+            if let Some(warning) = run.update(root_dir, file, &file_results) {
+                eprintln!("Warning: {}", warning);
+            }
+            prepare(&mut file_results);
+            write_results(&mut out, &file_results, args.link, &mut totals).map_err(output_error)?;
+            if last_checkpoint.elapsed() >= cache::CHECKPOINT_INTERVAL {
+                if let Some(warning) = run.save_checkpoint() {
+                    eprintln!("Warning: {}", warning);
+                }
+                last_checkpoint = Instant::now();
+            }
+        }
+        workers
+            .join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))?;
+        Ok(())
+    })?;
+
+    totals.sample = run
+        .results
+        .sample
+        .as_ref()
+        .map(|sample| output_model::Sample {
+            estimated: true,
+            total_files: sample.total_files,
+            sampled_files: sample.sampled_files,
+            seed: sample.seed,
+            estimated_mutable_variable_count: sample.estimate(totals.mutable_variables),
+            estimated_immutable_variable_count: sample.estimate(totals.immutable_variables),
+            estimated_data_structure_count: sample.estimate(totals.data_structures),
+        });
+    if let Some(warning) = run.finish(root_dir) {
+        eprintln!("Warning: {}", warning);
+    }
+    write_record(&mut out, &Record::Summary(&totals))
+        .and_then(|_| out.flush())
+        .map_err(output_error)?;
+
+    if args.strict && totals.parse_failures > 0 {
+        return Err(ForestError::Check(format!(
+            "strict: {} files could not be parsed by syn",
+            totals.parse_failures
+        )));
+    }
+    if totals.errors > 0 {
+        return Err(ForestError::Check(format!(
+            "analysis incomplete: {} files could not be analysed",
+            totals.errors
+        )));
+    }
+    Ok(())
+}
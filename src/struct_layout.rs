@@ -0,0 +1,300 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Estimated memory layout of structs.
+// Sizes and alignments are estimates for a 64-bit target: primitives, references, smart
+// pointers, the common std containers, arrays and tuples have known sizes, and structs and
+// fieldless enums of the project are resolved by name. rustc is free to reorder fields, so
+// a struct is assumed to need only the padding that rounds its size up to its alignment.
+// Structs with a field of any other type (generics, foreign types) are left out.
+
+use crate::DataStructureInfo;
+use std::collections::{HashMap, HashSet};
+
+// A struct with this many String, Vec or other heap-owning collection fields is flagged
+pub const HEAP_FIELD_THRESHOLD: usize = 3;
+
+// Size and alignment of a type, in bytes
+#[derive(Clone, Copy)]
+struct Layout {
+    size: usize,
+    align: usize,
+}
+
+impl Layout {
+    const fn new(size: usize, align: usize) -> Self {
+        Layout { size, align }
+    }
+}
+
+// Pointer-sized and fat-pointer layouts
+const THIN: Layout = Layout::new(8, 8);
+const FAT: Layout = Layout::new(16, 8);
+
+// Types with invalid bit patterns, which `Option` uses to mark `None`
+const NICHE_TYPES: &[&str] = &[
+    "bool",
+    "char",
+    "Box",
+    "Rc",
+    "Arc",
+    "NonNull",
+    "Weak",
+    "String",
+    "Vec",
+    "PathBuf",
+    "OsString",
+    "BTreeMap",
+    "BTreeSet",
+    "BinaryHeap",
+    "VecDeque",
+    "LinkedList",
+    "HashMap",
+    "HashSet",
+    "Duration",
+];
+
+// Estimated layout of one struct
+pub struct StructLayout<'a> {
+    pub structure: &'a DataStructureInfo,
+    pub size: usize,
+    pub align: usize,
+    pub heap_fields: usize, // String, Vec and other heap-owning collection fields
+}
+
+impl StructLayout<'_> {
+    pub fn heap_heavy(&self) -> bool {
+        self.heap_fields >= HEAP_FIELD_THRESHOLD
+    }
+}
+
+// Function to round a size up to a multiple of an alignment
+fn round_up(size: usize, align: usize) -> usize {
+    size.div_ceil(align.max(1)) * align.max(1)
+}
+
+// Function to give the layout of a primitive or std type by name
+fn known_layout(name: &str) -> Option<Layout> {
+    Some(match name {
+        "u8" | "i8" | "bool" => Layout::new(1, 1),
+        "u16" | "i16" => Layout::new(2, 2),
+        "u32" | "i32" | "f32" | "char" => Layout::new(4, 4),
+        "u64" | "i64" | "f64" | "usize" | "isize" => Layout::new(8, 8),
+        "u128" | "i128" => Layout::new(16, 16),
+        "Box" | "Rc" | "Arc" | "NonNull" | "Weak" => THIN,
+        "String" | "Vec" | "PathBuf" | "OsString" | "BTreeMap" | "BTreeSet" | "BinaryHeap" => {
+            Layout::new(24, 8)
+        }
+        "VecDeque" | "LinkedList" => Layout::new(32, 8),
+        "HashMap" | "HashSet" => Layout::new(48, 8),
+        "Duration" => Layout::new(16, 8),
+        "PhantomData" | "PhantomPinned" => Layout::new(0, 1),
+        _ => return None,
+    })
+}
+
+// Function to check whether a type is a string or collection that owns heap memory
+fn is_heap_owning(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Path(type_path) => type_path.path.segments.last().is_some_and(|segment| {
+            segment.ident == "String"
+                || segment.ident == "PathBuf"
+                || crate::fields::is_collection(&segment.ident.to_string())
+        }),
+        _ => false,
+    }
+}
+
+// Function to check whether a pointer target is unsized, making the pointer fat
+fn is_unsized(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Slice(_) | syn::Type::TraitObject(_) => true,
+        syn::Type::Path(type_path) => type_path.path.is_ident("str"),
+        _ => false,
+    }
+}
+
+// Function to lay out a sequence of fields, allowing rustc to reorder them
+fn combine(layouts: impl IntoIterator<Item = Layout>) -> Layout {
+    let (size, align) = layouts.into_iter().fold((0, 1), |(size, align), layout| {
+        (size + layout.size, align.max(layout.align))
+    });
+    Layout::new(round_up(size, align), align)
+}
+
+// Resolves field types to layouts, including the structs and enums of the project
+struct Resolver<'a> {
+    structs: HashMap<&'a str, Vec<&'a DataStructureInfo>>,
+    fieldless_enums: HashSet<&'a str>,
+    resolved: HashMap<&'a str, Option<Layout>>, // By display name
+    in_progress: HashSet<&'a str>,
+}
+
+impl<'a> Resolver<'a> {
+    fn new(data_structures: &'a [DataStructureInfo]) -> Self {
+        let mut structs: HashMap<&str, Vec<&DataStructureInfo>> = HashMap::new();
+        let mut fieldless_enums = HashSet::new();
+        for data_structure in data_structures {
+            match data_structure.data_structure_type.as_str() {
+                "struct" => structs
+                    .entry(data_structure.name.as_str())
+                    .or_default()
+                    .push(data_structure),
+                "enum"
+                    if !data_structure.variants.is_empty()
+                        && data_structure.variants.len() <= 256
+                        && data_structure.variants.iter().all(|v| v.payload.is_empty()) =>
+                {
+                    fieldless_enums.insert(data_structure.name.as_str());
+                }
+                _ => {}
+            }
+        }
+        Resolver {
+            structs,
+            fieldless_enums,
+            resolved: HashMap::new(),
+            in_progress: HashSet::new(),
+        }
+    }
+
+    // Layout of a struct of the project, or None if any field is unknown
+    fn struct_layout(&mut self, structure: &'a DataStructureInfo) -> Option<Layout> {
+        let name = structure.display_name();
+        if let Some(layout) = self.resolved.get(name) {
+            return *layout;
+        }
+        // A struct containing itself by value has no size; behind a pointer it is not visited
+        if !self.in_progress.insert(name) {
+            return None;
+        }
+        let layouts: Option<Vec<Layout>> = structure
+            .fields
+            .iter()
+            .map(|field| {
+                syn::parse_str::<syn::Type>(&field.type_name)
+                    .ok()
+                    .and_then(|ty| self.type_layout(&ty))
+            })
+            .collect();
+        let layout = layouts.map(combine);
+        self.in_progress.remove(name);
+        self.resolved.insert(name, layout);
+        layout
+    }
+
+    fn type_layout(&mut self, ty: &syn::Type) -> Option<Layout> {
+        match ty {
+            syn::Type::Reference(reference) if is_unsized(&reference.elem) => Some(FAT),
+            syn::Type::Ptr(pointer) if is_unsized(&pointer.elem) => Some(FAT),
+            syn::Type::Reference(_) | syn::Type::Ptr(_) | syn::Type::BareFn(_) => Some(THIN),
+            syn::Type::Paren(paren) => self.type_layout(&paren.elem),
+            syn::Type::Group(group) => self.type_layout(&group.elem),
+            syn::Type::Tuple(tuple) => {
+                let layouts: Option<Vec<Layout>> =
+                    tuple.elems.iter().map(|ty| self.type_layout(ty)).collect();
+                layouts.map(combine)
+            }
+            syn::Type::Array(array) => {
+                let element = self.type_layout(&array.elem)?;
+                let syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Int(ref length),
+                    ..
+                }) = array.len
+                else {
+                    return None;
+                };
+                let length: usize = length.base10_parse().ok()?;
+                Some(Layout::new(element.size * length, element.align))
+            }
+            syn::Type::Path(type_path) if type_path.qself.is_none() => {
+                let segment = type_path.path.segments.last()?;
+                let name = segment.ident.to_string();
+                let first_argument = match segment.arguments {
+                    syn::PathArguments::AngleBracketed(ref args) => {
+                        args.args.iter().find_map(|arg| match arg {
+                            syn::GenericArgument::Type(ty) => Some(ty),
+                            _ => None,
+                        })
+                    }
+                    _ => None,
+                };
+                match (name.as_str(), first_argument) {
+                    ("Option", Some(inner)) => self.option_layout(inner),
+                    ("Box" | "Rc" | "Arc", Some(inner)) if is_unsized(inner) => Some(FAT),
+                    ("Cell" | "UnsafeCell" | "ManuallyDrop", Some(inner)) => {
+                        self.type_layout(inner)
+                    }
+                    ("RefCell", Some(inner)) => {
+                        self.type_layout(inner).map(|inner| combine([inner, THIN]))
+                    }
+                    _ => {
+                        if let Some(layout) = known_layout(&name) {
+                            return Some(layout);
+                        }
+                        if self.fieldless_enums.contains(name.as_str()) {
+                            return Some(Layout::new(1, 1));
+                        }
+                        // Names shared by several structs cannot be resolved reliably
+                        match self.structs.get(name.as_str()).map(Vec::as_slice) {
+                            Some([structure]) => self.struct_layout(structure),
+                            _ => None,
+                        }
+                    }
+                }
+            }
+            _ => None,
+        }
+    }
+
+    // Option costs nothing for types with a niche, and a tag plus padding otherwise
+    fn option_layout(&mut self, inner: &syn::Type) -> Option<Layout> {
+        let layout = self.type_layout(inner)?;
+        let has_niche = match inner {
+            syn::Type::Reference(_) | syn::Type::BareFn(_) => true,
+            syn::Type::Path(type_path) => type_path.path.segments.last().is_some_and(|segment| {
+                let name = segment.ident.to_string();
+                NICHE_TYPES.contains(&name.as_str()) || self.fieldless_enums.contains(name.as_str())
+            }),
+            _ => false,
+        };
+        if has_niche {
+            Some(layout)
+        } else {
+            Some(combine([layout, Layout::new(1, 1)]))
+        }
+    }
+}
+
+// Function to estimate the layout of every struct whose field types are all known
+// The largest structs come first
+pub fn estimate_layouts(data_structures: &[DataStructureInfo]) -> Vec<StructLayout<'_>> {
+    let mut resolver = Resolver::new(data_structures);
+    let mut layouts: Vec<StructLayout> = data_structures
+        .iter()
+        .filter(|data_structure| {
+            data_structure.data_structure_type == "struct" && !data_structure.fields.is_empty()
+        })
+        .filter_map(|structure| {
+            let layout = resolver.struct_layout(structure)?;
+            let heap_fields = structure
+                .fields
+                .iter()
+                .filter(|field| {
+                    syn::parse_str::<syn::Type>(&field.type_name)
+                        .is_ok_and(|ty| is_heap_owning(&ty))
+                })
+                .count();
+            Some(StructLayout {
+                structure,
+                size: layout.size,
+                align: layout.align,
+                heap_fields,
+            })
+        })
+        .collect();
+    layouts.sort_by(|a, b| {
+        b.size
+            .cmp(&a.size)
+            .then_with(|| a.structure.display_name().cmp(b.structure.display_name()))
+    });
+    layouts
+}
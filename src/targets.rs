@@ -0,0 +1,222 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Cargo targets of the analysed files (`--targets`).
+// Each file is assigned to the lib, bin, example, test, bench or build script target of
+// `cargo metadata` whose module tree it sits in: the target's root file itself, or the
+// deepest target whose module directory contains the file (`src/` for `src/lib.rs` and
+// `src/main.rs`, `src/bin/tool/` for `src/bin/tool.rs`). When a lib and a bin share `src/`,
+// the lib wins. Files outside every module directory, e.g. `tests/common/mod.rs`, belong to
+// no target. The assignment follows directories, not `mod` declarations.
+
+use crate::cargo::CargoMetadata;
+use crate::AnalysisResults;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+// Target kinds accepted by --targets, named as cargo's --lib, --bins, ... options
+pub const TARGET_KINDS: &[&str] = &["lib", "bins", "examples", "tests", "benches", "build"];
+
+// The target a file belongs to
+#[derive(Clone, Serialize, Deserialize)]
+pub struct FileTarget {
+    #[serde(rename = "file")]
+    pub file_path: PathBuf,
+    pub package: String,
+    pub kind: String, // lib, bin, example, test, bench or build
+    pub name: String,
+}
+
+// A target with the directory its modules live in
+struct Root {
+    package: String,
+    kind: &'static str,
+    name: String,
+    src_path: PathBuf,   // Canonical path of the root file
+    module_dir: PathBuf, // Canonical directory of the modules of the root file
+}
+
+// The targets of a workspace
+pub struct Targets {
+    roots: Vec<Root>,
+}
+
+// Function to reduce cargo's target kinds to one kind per target
+// lib, rlib, cdylib, proc-macro and the other library kinds are all `lib`
+fn target_kind(kinds: &[String]) -> &'static str {
+    match kinds.first().map(String::as_str) {
+        Some("bin") => "bin",
+        Some("example") => "example",
+        Some("test") => "test",
+        Some("bench") => "bench",
+        Some("custom-build") => "build",
+        _ => "lib",
+    }
+}
+
+// Function to give the --targets kind of a target kind, e.g. `bins` for `bin`
+fn plural(kind: &str) -> &'static str {
+    match kind {
+        "bin" => "bins",
+        "example" => "examples",
+        "test" => "tests",
+        "bench" => "benches",
+        "build" => "build",
+        _ => "lib",
+    }
+}
+
+impl Targets {
+    pub fn new(metadata: &CargoMetadata) -> Self {
+        let mut roots = Vec::new();
+        for package in &metadata.packages {
+            for target in &package.targets {
+                let Some(src_path) = crate::paths::canonicalize(&target.src_path) else {
+                    continue;
+                };
+                let parent = src_path.parent().unwrap_or(Path::new("")).to_path_buf();
+                let module_dir = match src_path.file_stem().and_then(|stem| stem.to_str()) {
+                    Some("lib" | "main" | "mod") | None => parent,
+                    Some(stem) => parent.join(stem),
+                };
+                roots.push(Root {
+                    package: package.name.clone(),
+                    kind: target_kind(&target.kind),
+                    name: target.name.clone(),
+                    src_path,
+                    module_dir,
+                });
+            }
+        }
+        // Libs first, so they win ties with bins sharing their directory
+        roots.sort_by_key(|root| root.kind != "lib");
+        Targets { roots }
+    }
+
+    // Function to find the target of a file, if any
+    fn root_of(&self, file: &Path) -> Option<&Root> {
+        let file = crate::paths::canonicalize(file)?;
+        if let Some(root) = self.roots.iter().find(|root| root.src_path == file) {
+            return Some(root);
+        }
+        self.roots
+            .iter()
+            .filter(|root| file.starts_with(&root.module_dir))
+            .fold(None, |deepest: Option<&Root>, root| match deepest {
+                Some(deepest)
+                    if deepest.module_dir.components().count()
+                        >= root.module_dir.components().count() =>
+                {
+                    Some(deepest)
+                }
+                _ => Some(root),
+            })
+    }
+
+    // Function to tag files with their targets; files of no target are left out
+    pub fn classify<'a>(&self, files: impl IntoIterator<Item = &'a Path>) -> Vec<FileTarget> {
+        files
+            .into_iter()
+            .filter_map(|file| {
+                let root = self.root_of(file)?;
+                Some(FileTarget {
+                    file_path: file.to_path_buf(),
+                    package: root.package.clone(),
+                    kind: root.kind.to_string(),
+                    name: root.name.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
+// The target kinds chosen with --targets
+pub struct TargetSelection {
+    targets: Targets,
+    kinds: Vec<String>,
+}
+
+impl TargetSelection {
+    pub fn new(metadata: &CargoMetadata, kinds: Vec<String>) -> Self {
+        TargetSelection {
+            targets: Targets::new(metadata),
+            kinds,
+        }
+    }
+
+    // Function to check whether a file belongs to a target of a chosen kind
+    pub fn includes(&self, file: &Path) -> bool {
+        self.targets
+            .root_of(file)
+            .is_some_and(|root| self.kinds.iter().any(|kind| kind == plural(root.kind)))
+    }
+}
+
+// Counts of a target
+#[derive(Default, Serialize)]
+pub struct TargetCounts {
+    pub package: String,
+    pub kind: String,
+    pub name: String,
+    pub files: usize,
+    pub mutable_variables: usize,
+    pub immutable_variables: usize,
+    pub data_structures: usize,
+}
+
+impl TargetCounts {
+    // Label of the target, e.g. `bin tool`
+    pub fn label(&self) -> String {
+        format!("{} {}", self.kind, self.name)
+    }
+
+    // Function to describe the counts in one line
+    pub fn describe(&self) -> String {
+        format!(
+            "{}: {} files, {} mutable and {} immutable variables, {} data structures",
+            self.label(),
+            self.files,
+            self.mutable_variables,
+            self.immutable_variables,
+            self.data_structures
+        )
+    }
+}
+
+// Function to count the files, variables and data structures of each target
+// Targets are listed by package, kind and name
+pub fn counts(results: &AnalysisResults) -> Vec<TargetCounts> {
+    let file_targets = &results.file_targets;
+    let mut by_target: BTreeMap<(&str, &str, &str), TargetCounts> = BTreeMap::new();
+    let mut target_of: BTreeMap<&Path, (&str, &str, &str)> = BTreeMap::new();
+    for file_target in file_targets {
+        let key = (
+            file_target.package.as_str(),
+            file_target.kind.as_str(),
+            file_target.name.as_str(),
+        );
+        target_of.insert(&file_target.file_path, key);
+        let counts = by_target.entry(key).or_insert_with(|| TargetCounts {
+            package: file_target.package.clone(),
+            kind: file_target.kind.clone(),
+            name: file_target.name.clone(),
+            ..TargetCounts::default()
+        });
+        counts.files += 1;
+    }
+    for var in &results.mutable_vars {
+        if let Some(key) = target_of.get(&*var.file_path) {
+            by_target.entry(*key).or_default().mutable_variables += 1;
+        }
+    }
+    for var in &results.immutable_vars {
+        if let Some(key) = target_of.get(&*var.file_path) {
+            by_target.entry(*key).or_default().immutable_variables += 1;
+        }
+    }
+    for data_structure in &results.data_structures {
+        if let Some(key) = target_of.get(data_structure.file_path.as_path()) {
+            by_target.entry(*key).or_default().data_structures += 1;
+        }
+    }
+    by_target.into_values().collect()
+}
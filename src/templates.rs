@@ -0,0 +1,39 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// User templates (`--template FILE`).
+// The template is rendered with Tera (Jinja2-like syntax) against the same results model
+// that `--format json` writes, so every top-level JSON key is a template variable, e.g.
+// `{% for var in mutable_variables %}{{ var.name }}{% endfor %}`. Output is not
+// HTML-escaped; use the `escape` filter where it matters.
+
+use crate::ForestError;
+use serde::Serialize;
+use std::error::Error;
+use std::path::Path;
+
+// Function to describe a template error with its causes, which say where rendering failed
+fn error_chain(error: &tera::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    message
+}
+
+// Function to render a template file against the results model
+// A template that cannot be read is an I/O error, one that does not compile is a wrong option
+pub fn render(template: &Path, model: &impl Serialize) -> Result<String, ForestError> {
+    let source = std::fs::read_to_string(template).map_err(|e| ForestError::io(template, e))?;
+    let name = template.display().to_string();
+    let mut tera = tera::Tera::default();
+    tera.add_raw_template(&name, &source)
+        .map_err(|e| ForestError::Config(format!("invalid template {}", error_chain(&e))))?;
+    let context = tera::Context::from_serialize(model)
+        .map_err(|e| ForestError::format("template", error_chain(&e)))?;
+    let rendered = tera
+        .render(&name, &context)
+        .map_err(|e| ForestError::format("template", error_chain(&e)))?;
+    Ok(rendered)
+}
@@ -0,0 +1,1105 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Text output: the plain-text report written with --format text, and the one-line listings
+// of variables and data structures shared with the console report.
+
+use crate::{
+    allocations, async_code, call_args, code_class_marker, columns, complexity, doc_coverage,
+    doc_tests, duplicates, error_handling, feature_gates, generics, grouping, impl_blocks,
+    line_stats, literals, macro_body_marker, ownership, patterns, recursion, smart_pointers,
+    span_marker, state_density, struct_layout, targets, trait_objects, type_usage, type_xref,
+    unsafe_code, usage, AnalysisMetadata, AnalysisResults, DataStructureInfo, ForestError, VarInfo,
+};
+use std::fs::File;
+use std::io::{self, Write};
+
+// New display with link
+pub fn format_var_with_link(var: &VarInfo) -> String {
+    format!(
+        "{} ({}): {} at [{}:{}:{}]({}) - kind: {}, type: {}, basic type: {}, scope: {}, reads: {}, writes: {}",
+        var.name,
+        if var.mutable { "mutable" } else { "immutable" },
+        var.context.trim(),
+        var.file_path.display(),
+        var.line_number,
+        var.column,
+        var.editor_link(),
+        var.var_kind,
+        var.var_type,
+        var.basic_type,
+        var.scope,
+        var.read_count,
+        var.write_count
+    ) + &span_marker(var)
+        + &macro_body_marker(&var.macro_body)
+        + &code_class_marker(&var.code_class)
+}
+
+// New display with link
+pub fn format_structure_with_link(structure: &DataStructureInfo) -> String {
+    format!(
+        "{} ({}): at [{}:{}:{}]({})",
+        structure.display_name(),
+        structure.kind_label(),
+        structure.file_path.display(),
+        structure.line_number,
+        structure.column,
+        structure.editor_link()
+    ) + &macro_body_marker(&structure.macro_body)
+        + if structure.has_default_methods == Some(true) {
+            " [has default methods]"
+        } else {
+            ""
+        }
+        + &structure
+            .aliased_type
+            .as_ref()
+            .map(|aliased_type| format!(" = {}", aliased_type))
+            .unwrap_or_default()
+        + &structure
+            .signature
+            .as_ref()
+            .map(|signature| format!(" fn{}", signature.text()))
+            .unwrap_or_default()
+        + &patterns::marker(&structure.patterns)
+        + &code_class_marker(&structure.code_class)
+}
+
+// Function to output results in text format
+pub fn output_text(
+    results: &AnalysisResults,
+    metadata: &AnalysisMetadata,
+    file: &str,
+    columns: Option<&[String]>,
+    group_by: Option<&str>,
+    link: bool,
+) -> Result<(), ForestError> {
+    File::create(file)
+        .and_then(|mut out| write_text(&mut out, results, metadata, columns, group_by, link))
+        .map_err(|e| ForestError::output("text", file, e))
+}
+
+// Function to write the text report
+fn write_text(
+    file: &mut File,
+    results: &AnalysisResults,
+    metadata: &AnalysisMetadata,
+    columns: Option<&[String]>,
+    group_by: Option<&str>,
+    link: bool,
+) -> io::Result<()> {
+    writeln!(file, "Project Information")?;
+    writeln!(file, "-------------------")?;
+    writeln!(file, "Project Name: {}", metadata.project_name)?;
+    writeln!(file, "Version: {}", metadata.version)?;
+    writeln!(file, "Analysis Run At: {}", metadata.datetime)?;
+    if let Some(ref cargo_metadata) = metadata.cargo {
+        for line in cargo_metadata.describe() {
+            writeln!(file, "{}", line)?;
+        }
+    }
+    writeln!(file)?;
+
+    if let Some(ref sample) = results.sample {
+        writeln!(file, "Sample Estimates")?;
+        writeln!(file, "----------------")?;
+        writeln!(
+            file,
+            "Sampled {} of {} files (seed {})",
+            sample.sampled_files, sample.total_files, sample.seed
+        )?;
+        writeln!(
+            file,
+            "Estimated mutable variables: ~{}",
+            sample.estimate(results.mutable_vars.len())
+        )?;
+        writeln!(
+            file,
+            "Estimated immutable variables: ~{}",
+            sample.estimate(results.immutable_vars.len())
+        )?;
+        writeln!(
+            file,
+            "Estimated data structures: ~{}",
+            sample.estimate(results.data_structures.len())
+        )?;
+        writeln!(file)?;
+    }
+
+    if let Some(by) = group_by {
+        let groups = grouping::group(results, by);
+        writeln!(file, "Grouped by {} ({} groups)", by, groups.len())?;
+        writeln!(file, "{}", "-".repeat("Grouped by ".len() + by.len()))?;
+        grouping::write_table(file, "", by, &groups)?;
+    } else {
+        writeln!(file, "Mutable Variables ({})", results.mutable_vars.len())?;
+        writeln!(file, "-------------------")?;
+        if let Some(columns) = columns {
+            columns::write_table(file, "", &results.mutable_vars, columns, link)?;
+        } else {
+            for var in &results.mutable_vars {
+                if link {
+                    writeln!(file, "{}", format_var_with_link(var))?;
+                } else {
+                    writeln!(file, "{}", var)?;
+                }
+                if let Some(ref window) = var.context_window {
+                    for line in window.numbered(var.line_number) {
+                        writeln!(file, "  {}", line)?;
+                    }
+                }
+            }
+        }
+
+        writeln!(
+            file,
+            "\nImmutable Variables ({})",
+            results.immutable_vars.len()
+        )?;
+        writeln!(file, "---------------------")?;
+        if let Some(columns) = columns {
+            columns::write_table(file, "", &results.immutable_vars, columns, link)?;
+        } else {
+            for var in &results.immutable_vars {
+                if link {
+                    writeln!(file, "{}", format_var_with_link(var))?;
+                } else {
+                    writeln!(file, "{}", var)?;
+                }
+                if let Some(ref window) = var.context_window {
+                    for line in window.numbered(var.line_number) {
+                        writeln!(file, "  {}", line)?;
+                    }
+                }
+            }
+        }
+
+        writeln!(
+            file,
+            "\ndata_structures ({})",
+            results.data_structures.len()
+        )?;
+        writeln!(file, "----------------")?;
+        for data_structure in &results.data_structures {
+            if link {
+                writeln!(file, "{}", format_structure_with_link(data_structure))?;
+            } else {
+                writeln!(file, "{}", data_structure)?;
+            }
+            if let Some(ref item) = data_structure.rustdoc {
+                writeln!(file, "  {}", item.summary())?;
+            }
+            for field in &data_structure.fields {
+                writeln!(file, "  {}", field.describe())?;
+            }
+            for variant in &data_structure.variants {
+                writeln!(file, "  {}", variant.describe())?;
+            }
+        }
+    }
+
+    let duplicate_groups = duplicates::find_duplicates(&results.function_fingerprints);
+    if !duplicate_groups.is_empty() {
+        writeln!(
+            file,
+            "\nDuplicate Functions ({} groups)",
+            duplicate_groups.len()
+        )?;
+        writeln!(file, "-------------------")?;
+        for group in &duplicate_groups {
+            writeln!(
+                file,
+                "{} duplicate ({} tokens):",
+                group.kind, group.token_count
+            )?;
+            for function in &group.functions {
+                writeln!(
+                    file,
+                    "  {} at {}:{}",
+                    function.name,
+                    function.file_path.display(),
+                    function.line_number
+                )?;
+            }
+        }
+    }
+
+    let type_summary = type_usage::summarize(
+        &results.type_uses,
+        &metadata.dependencies,
+        &results.data_structures,
+    );
+    writeln!(
+        file,
+        "\nType Usage ({} std, {} external, {} project-local)",
+        type_summary.std_uses, type_summary.external_uses, type_summary.local_uses
+    )?;
+    writeln!(file, "----------")?;
+    for usage in &type_summary.by_crate {
+        writeln!(file, "{} ({} uses):", usage.crate_name, usage.uses)?;
+        for (type_name, count) in &usage.types {
+            writeln!(file, "  {:>5}  {}", count, type_name)?;
+        }
+    }
+
+    if !results.scope_suggestions.is_empty() {
+        writeln!(
+            file,
+            "\nNarrower Scope Suggestions ({})",
+            results.scope_suggestions.len()
+        )?;
+        writeln!(file, "--------------------------")?;
+        for s in &results.scope_suggestions {
+            writeln!(
+                file,
+                "{} in {} at {}:{} (in scope for lines {}-{}) is only used in one {} (lines {}-{})",
+                s.name,
+                s.function,
+                s.file_path.display(),
+                s.declaration_line,
+                s.declaration_line,
+                s.scope_end_line,
+                s.narrower_scope,
+                s.usage_start_line,
+                s.usage_end_line
+            )?;
+        }
+    }
+
+    let call_summary = call_args::summarize(&results.call_args);
+    writeln!(
+        file,
+        "\nCall Arguments ({} moved, {} borrowed, {} mutably borrowed)",
+        call_summary.moved, call_summary.borrowed, call_summary.mutably_borrowed
+    )?;
+    writeln!(file, "--------------")?;
+    for callee in &call_summary.top_mut_callees {
+        writeln!(
+            file,
+            "{:>5} &mut  {} ({} calls, {} moved, {} borrowed)",
+            callee.mutably_borrowed, callee.callee, callee.calls, callee.moved, callee.borrowed
+        )?;
+    }
+
+    if !results.copy_refs.is_empty() {
+        writeln!(
+            file,
+            "\nCopy Types Passed by Reference ({})",
+            results.copy_refs.len()
+        )?;
+        writeln!(file, "------------------------------")?;
+        for finding in &results.copy_refs {
+            writeln!(
+                file,
+                "{} {}: {} in {} at {}:{} (pass by value)",
+                finding.kind,
+                finding.name,
+                finding.type_name,
+                finding.function,
+                finding.file_path.display(),
+                finding.line_number
+            )?;
+        }
+    }
+
+    let density =
+        state_density::summarize(&results.function_states, &results.interior_mutable_fields);
+    writeln!(
+        file,
+        "\nMutable State Density (overall {:.3} per line)",
+        density.overall
+    )?;
+    writeln!(file, "---------------------")?;
+    for ranked in &density.top_functions {
+        let function = ranked.function;
+        writeln!(
+            file,
+            "{:.3}  {} at {}:{} ({} lines, {} mutable locals, {} &mut params, {} interior-mutability fields)",
+            ranked.density,
+            function.name,
+            function.file_path.display(),
+            function.line_number,
+            function.lines,
+            function.mutable_locals,
+            function.mut_params,
+            ranked.interior_fields
+        )?;
+    }
+
+    if !results.unsafe_impls.is_empty() || !results.unsafe_sites.is_empty() {
+        writeln!(
+            file,
+            "\nUnsafe Code ({} unsafe trait impls, review each; {} other unsafe sites)",
+            results.unsafe_impls.len(),
+            results.unsafe_sites.len()
+        )?;
+        writeln!(file, "-----------")?;
+        for unsafe_impl in unsafe_code::sorted_for_review(&results.unsafe_impls) {
+            writeln!(
+                file,
+                "{}unsafe impl {} for {} at {}:{}",
+                if unsafe_impl.is_send_or_sync() {
+                    "! "
+                } else {
+                    "  "
+                },
+                unsafe_impl.trait_name,
+                unsafe_impl.self_type,
+                unsafe_impl.file_path.display(),
+                unsafe_impl.line_number
+            )?;
+        }
+        for site in &results.unsafe_sites {
+            writeln!(
+                file,
+                "    {} in {} at {}:{}",
+                site.label(),
+                site.scope,
+                site.file_path.display(),
+                site.line_number
+            )?;
+        }
+    }
+
+    if !results.foreign_items.is_empty()
+        || !results.exported_symbols.is_empty()
+        || !results.pointer_params.is_empty()
+    {
+        writeln!(file, "\nFFI")?;
+        writeln!(file, "---")?;
+    }
+    if !results.foreign_items.is_empty() {
+        writeln!(
+            file,
+            "Extern blocks ({} items):",
+            results.foreign_items.len()
+        )?;
+        for item in &results.foreign_items {
+            writeln!(
+                file,
+                "  extern \"{}\" {}{} at {}:{}",
+                item.abi,
+                item.declaration,
+                item.link
+                    .as_ref()
+                    .map(|link| format!(" [link: {}]", link))
+                    .unwrap_or_default(),
+                item.file_path.display(),
+                item.line_number
+            )?;
+        }
+    }
+    if !results.exported_symbols.is_empty() {
+        writeln!(
+            file,
+            "Exported symbols ({}):",
+            results.exported_symbols.len()
+        )?;
+        writeln!(
+            file,
+            "  {:<30} {:<6} {:<11} {:<6} {:<24} location",
+            "symbol", "kind", "reason", "abi", "item"
+        )?;
+        for symbol in &results.exported_symbols {
+            writeln!(
+                file,
+                "  {:<30} {:<6} {:<11} {:<6} {:<24} {}:{}",
+                symbol.symbol,
+                symbol.kind,
+                symbol.reason,
+                symbol.abi.as_deref().unwrap_or("-"),
+                symbol.item_name,
+                symbol.file_path.display(),
+                symbol.line_number
+            )?;
+        }
+    }
+
+    if !results.pointer_params.is_empty() {
+        writeln!(
+            file,
+            "Raw pointer parameters ({}):",
+            results.pointer_params.len()
+        )?;
+        for param in &results.pointer_params {
+            writeln!(
+                file,
+                "  {}: {} in {} at {}:{}",
+                param.param,
+                param.type_name,
+                param.function,
+                param.file_path.display(),
+                param.line_number
+            )?;
+        }
+    }
+
+    if !results.doc_tests.is_empty() {
+        let per_crate = doc_tests::tests_per_crate(&results.doc_tests);
+        writeln!(file, "\nDoc Tests ({} crates)", per_crate.len())?;
+        writeln!(file, "---------")?;
+        for (crate_name, count) in &per_crate {
+            writeln!(file, "{}: {} doc tests", crate_name, count)?;
+        }
+        for item in &results.doc_tests {
+            let mutable = match item.mutable_vars {
+                Some(count) if count > 0 => {
+                    format!(", examples declare {} mutable variables", count)
+                }
+                _ => String::new(),
+            };
+            writeln!(
+                file,
+                "  {} {} at {}:{} ({} doc tests{})",
+                item.kind,
+                item.item,
+                item.file_path.display(),
+                item.line_number,
+                item.doc_tests,
+                mutable
+            )?;
+        }
+    }
+
+    if !results.impl_blocks.is_empty() {
+        writeln!(file, "\nImpl Blocks ({})", results.impl_blocks.len())?;
+        writeln!(file, "-----------")?;
+        writeln!(file, "Types with the most methods:")?;
+        for (type_name, methods) in impl_blocks::methods_per_type(&results.impl_blocks) {
+            writeln!(file, "  {}: {} methods", type_name, methods)?;
+        }
+        for block in &results.impl_blocks {
+            writeln!(
+                file,
+                "{} at {}:{}: {}",
+                block.header(),
+                block.file_path.display(),
+                block.line_number,
+                block.method_list()
+            )?;
+        }
+    }
+
+    if !results.globals.is_empty() {
+        writeln!(file, "\nGlobals ({})", results.globals.len())?;
+        writeln!(file, "-------")?;
+        for global in &results.globals {
+            writeln!(
+                file,
+                "{} at {}:{}",
+                global.declaration(),
+                global.file_path.display(),
+                global.line_number
+            )?;
+        }
+    }
+
+    if !results.closures.is_empty() {
+        writeln!(file, "\nClosures ({})", results.closures.len())?;
+        writeln!(file, "--------")?;
+        for closure in &results.closures {
+            writeln!(
+                file,
+                "{} in {} at {}:{}, captures: {}",
+                closure.signature(),
+                closure.scope,
+                closure.file_path.display(),
+                closure.line_number,
+                if closure.captures.is_empty() {
+                    "none".to_string()
+                } else {
+                    closure.captures.join(", ")
+                }
+            )?;
+        }
+    }
+
+    if !results.macro_definitions.is_empty() || !results.macro_invocations.is_empty() {
+        writeln!(
+            file,
+            "\nMacros ({} definitions)",
+            results.macro_definitions.len()
+        )?;
+        writeln!(file, "------")?;
+        for definition in &results.macro_definitions {
+            writeln!(
+                file,
+                "macro_rules! {} ({} rules{}) at {}:{}",
+                definition.name,
+                definition.rules,
+                if definition.exported {
+                    ", exported"
+                } else {
+                    ""
+                },
+                definition.file_path.display(),
+                definition.line_number
+            )?;
+        }
+        writeln!(file, "Invocations per file:")?;
+        for invocations in &results.macro_invocations {
+            writeln!(
+                file,
+                "  {}: {}",
+                invocations.file_path.display(),
+                invocations.total()
+            )?;
+        }
+    }
+
+    if !results.async_functions.is_empty() {
+        writeln!(
+            file,
+            "\nAsync Functions ({})",
+            results.async_functions.len()
+        )?;
+        writeln!(file, "---------------")?;
+        for function in async_code::by_awaits(&results.async_functions) {
+            writeln!(
+                file,
+                "{:>4} awaits  {}{} at {}:{}",
+                function.awaits,
+                if function.is_async { "async " } else { "" },
+                function.function,
+                function.file_path.display(),
+                function.line_number
+            )?;
+        }
+    }
+
+    if !results.generic_items.is_empty() {
+        writeln!(file, "\nGenerics ({} items)", results.generic_items.len())?;
+        writeln!(file, "--------")?;
+        let heavy = generics::lifetime_heavy_modules(&results.generic_items);
+        if !heavy.is_empty() {
+            writeln!(file, "Lifetime-heavy modules:")?;
+            for (module, lifetimes) in heavy {
+                writeln!(file, "  {}: {} lifetime parameters", module, lifetimes)?;
+            }
+        }
+        for item in &results.generic_items {
+            writeln!(
+                file,
+                "{} {}{} at {}:{}",
+                item.kind,
+                item.item,
+                item.signature(),
+                item.file_path.display(),
+                item.line_number
+            )?;
+        }
+    }
+
+    let feature_report =
+        feature_gates::feature_report(&results.feature_gates, &results.declared_features);
+    if !feature_report.is_empty() {
+        writeln!(file, "\nFeature Gates ({} features)", feature_report.len())?;
+        writeln!(file, "-------------")?;
+        for feature in &feature_report {
+            let note = match (feature.declared, feature.gates.is_empty()) {
+                (false, _) => " [not declared in Cargo.toml]",
+                (true, true) => " [declared but never used]",
+                (true, false) => "",
+            };
+            writeln!(
+                file,
+                "{}/{}: {} gated{}",
+                feature.crate_name,
+                feature.feature,
+                feature.gates.len(),
+                note
+            )?;
+            for gate in &feature.gates {
+                writeln!(
+                    file,
+                    "  {} at {}:{}",
+                    gate.gated,
+                    gate.file_path.display(),
+                    gate.line_number
+                )?;
+            }
+        }
+    }
+
+    if !results.doc_items.is_empty() {
+        writeln!(file, "\nDocumentation Coverage")?;
+        writeln!(file, "----------------------")?;
+        for module in doc_coverage::module_coverage(&results.doc_items) {
+            writeln!(
+                file,
+                "{}: {:.1}% ({} of {} public items)",
+                module.module,
+                module.percentage(),
+                module.documented,
+                module.total
+            )?;
+            for item in &module.undocumented {
+                writeln!(
+                    file,
+                    "  undocumented {} {} at {}:{}",
+                    item.kind,
+                    item.item,
+                    item.file_path.display(),
+                    item.line_number
+                )?;
+            }
+        }
+    }
+
+    let unused_mut = usage::unused_mut(&results.mutable_vars, &results.degraded_files);
+    if !unused_mut.is_empty() {
+        writeln!(
+            file,
+            "\nUnused mut ({} variables never mutated)",
+            unused_mut.len()
+        )?;
+        writeln!(file, "----------")?;
+        for var in &unused_mut {
+            writeln!(
+                file,
+                "{} in {} at {}:{}",
+                var.name,
+                var.scope,
+                var.file_path.display(),
+                var.line_number
+            )?;
+        }
+    }
+
+    let long_lived = usage::longest_lived(&results.mutable_vars);
+    if !long_lived.is_empty() {
+        writeln!(file, "\nLongest-Lived Mutable Variables")?;
+        writeln!(file, "-------------------------------")?;
+        for var in &long_lived {
+            writeln!(
+                file,
+                "{} in {}: {} lines, declared at {}:{}",
+                var.name,
+                var.scope,
+                var.span(),
+                var.file_path.display(),
+                var.line_number
+            )?;
+        }
+    }
+
+    if !results.interior_mutability.is_empty() {
+        writeln!(
+            file,
+            "\nInterior Mutability ({} usages)",
+            results.interior_mutability.len()
+        )?;
+        writeln!(file, "-------------------")?;
+        for used in &results.interior_mutability {
+            writeln!(
+                file,
+                "{} {} {}: {} at {}:{}",
+                used.wrapper,
+                used.storage,
+                used.owner,
+                used.type_name,
+                used.file_path.display(),
+                used.line_number
+            )?;
+        }
+    }
+
+    if !results.smart_pointers.is_empty() {
+        writeln!(
+            file,
+            "\nSmart Pointers ({} usages)",
+            results.smart_pointers.len()
+        )?;
+        writeln!(file, "--------------")?;
+        for (module, counts) in smart_pointers::module_tally(&results.smart_pointers) {
+            writeln!(file, "{}: {}", module, smart_pointers::tally_text(&counts))?;
+        }
+        for pointer in &results.smart_pointers {
+            writeln!(
+                file,
+                "  {}<{}> in {} at {}:{}",
+                pointer.pointer,
+                pointer.wrapped,
+                pointer.module,
+                pointer.file_path.display(),
+                pointer.line_number
+            )?;
+        }
+    }
+
+    if !results.error_sites.is_empty() {
+        writeln!(
+            file,
+            "\nError Handling ({} sites)",
+            results.error_sites.len()
+        )?;
+        writeln!(file, "--------------")?;
+        let riskiest = error_handling::riskiest(&results.function_errors);
+        if !riskiest.is_empty() {
+            writeln!(file, "Highest unwrap density:")?;
+            for function in riskiest {
+                writeln!(
+                    file,
+                    "  {:.3}  {} at {}:{} ({} lines, {} unwrap, {} expect, {} panics, {} ?)",
+                    function.unwrap_density(),
+                    function.function,
+                    function.file_path.display(),
+                    function.line_number,
+                    function.lines,
+                    function.unwraps,
+                    function.expects,
+                    function.panics,
+                    function.tries
+                )?;
+            }
+        }
+        for site in &results.error_sites {
+            writeln!(
+                file,
+                "{} in {} at {}:{}",
+                site.kind,
+                site.function,
+                site.file_path.display(),
+                site.line_number
+            )?;
+        }
+    }
+
+    if !results.allocations.is_empty() {
+        writeln!(
+            file,
+            "\nClone and Allocation Hotspots ({} calls)",
+            results.allocations.len()
+        )?;
+        writeln!(file, "-----------------------------")?;
+        for hotspot in allocations::hotspots(&results.allocations) {
+            writeln!(
+                file,
+                "{:>4}  {} at {}:{}",
+                hotspot.count,
+                hotspot.function,
+                hotspot.file_path.display(),
+                hotspot.first_line
+            )?;
+        }
+        for allocation in &results.allocations {
+            writeln!(
+                file,
+                "  {} in {} at {}:{}",
+                allocation.kind,
+                allocation.function,
+                allocation.file_path.display(),
+                allocation.line_number
+            )?;
+        }
+    }
+
+    let xrefs = type_xref::cross_reference(&results.data_structures, &results.type_references);
+    if !xrefs.is_empty() {
+        writeln!(
+            file,
+            "\nType Cross-Reference ({} types, least referenced first)",
+            xrefs.len()
+        )?;
+        writeln!(file, "--------------------")?;
+        for xref in &xrefs {
+            if xref.references.is_empty() {
+                writeln!(file, "{} ({}): never referenced", xref.name(), xref.kind())?;
+                continue;
+            }
+            writeln!(
+                file,
+                "{} ({}): {} references ({})",
+                xref.name(),
+                xref.kind(),
+                xref.references.len(),
+                xref.contexts_text()
+            )?;
+            for reference in &xref.references {
+                writeln!(
+                    file,
+                    "  {} in {} at {}:{}",
+                    reference.context,
+                    reference.owner,
+                    reference.file_path.display(),
+                    reference.line_number
+                )?;
+            }
+        }
+    }
+
+    if !results.function_metrics.is_empty() {
+        writeln!(file, "\nFunctions ({})", results.function_metrics.len())?;
+        writeln!(file, "---------")?;
+        writeln!(file, "Most complex:")?;
+        for function in complexity::most_complex(&results.function_metrics) {
+            writeln!(
+                file,
+                "  {:>3}  {} at {}:{}",
+                function.cyclomatic,
+                function.function,
+                function.file_path.display(),
+                function.line_number
+            )?;
+        }
+        for function in &results.function_metrics {
+            writeln!(
+                file,
+                "{} at {}:{} - {} lines, {} params, nesting {}, {} branches, cyclomatic {}",
+                function.function,
+                function.file_path.display(),
+                function.line_number,
+                function.lines,
+                function.params,
+                function.nesting_depth,
+                function.branches,
+                function.cyclomatic
+            )?;
+        }
+    }
+
+    if !results.file_lines.is_empty() {
+        let totals = line_stats::project_totals(&results.file_lines);
+        writeln!(file, "\nLines of Code ({} lines)", totals.total)?;
+        writeln!(file, "-------------")?;
+        writeln!(
+            file,
+            "{:>8} {:>8} {:>8} {:>8}  module",
+            "total", "code", "comment", "blank"
+        )?;
+        for (module, lines) in line_stats::module_totals(&results.file_lines) {
+            writeln!(
+                file,
+                "{:>8} {:>8} {:>8} {:>8}  {}",
+                lines.total, lines.code, lines.comment, lines.blank, module
+            )?;
+        }
+        writeln!(
+            file,
+            "{:>8} {:>8} {:>8} {:>8}  file",
+            "total", "code", "comment", "blank"
+        )?;
+        for file_lines in &results.file_lines {
+            let lines = file_lines.lines;
+            writeln!(
+                file,
+                "{:>8} {:>8} {:>8} {:>8}  {}",
+                lines.total,
+                lines.code,
+                lines.comment,
+                lines.blank,
+                file_lines.file_path.display()
+            )?;
+        }
+    }
+
+    if !results.file_targets.is_empty() {
+        writeln!(file, "\nCargo Targets")?;
+        writeln!(file, "-------------")?;
+        for target in targets::counts(results) {
+            writeln!(file, "{}", target.describe())?;
+        }
+        for file_target in &results.file_targets {
+            writeln!(
+                file,
+                "  {} {}: {}",
+                file_target.kind,
+                file_target.name,
+                file_target.file_path.display()
+            )?;
+        }
+    }
+
+    if !results.trait_uses.is_empty() {
+        writeln!(
+            file,
+            "\nTrait Objects and impl Trait ({} uses)",
+            results.trait_uses.len()
+        )?;
+        writeln!(file, "----------------------------")?;
+        for (trait_name, counts) in trait_objects::dispatch_by_trait(&results.trait_uses) {
+            writeln!(
+                file,
+                "{}: {} ({} dyn, {} impl)",
+                trait_name,
+                counts.label(),
+                counts.dyn_uses,
+                counts.impl_uses
+            )?;
+        }
+        for trait_use in &results.trait_uses {
+            writeln!(
+                file,
+                "  {} {} in {} of {} at {}:{}",
+                trait_use.dispatch,
+                trait_use.trait_name,
+                trait_use.context,
+                trait_use.owner,
+                trait_use.file_path.display(),
+                trait_use.line_number
+            )?;
+        }
+    }
+
+    if !results.global_state.is_empty() {
+        writeln!(
+            file,
+            "\nGlobal Mutable State ({})",
+            results.global_state.len()
+        )?;
+        writeln!(file, "--------------------")?;
+        for state in &results.global_state {
+            writeln!(
+                file,
+                "{} {}: {} at {}:{}",
+                state.kind,
+                state.name,
+                state.type_name,
+                state.file_path.display(),
+                state.line_number
+            )?;
+        }
+    }
+
+    if !results.literals.is_empty() {
+        let groups = literals::group_literals(&results.literals);
+        writeln!(
+            file,
+            "\nMagic Numbers and String Literals ({} values)",
+            groups.len()
+        )?;
+        writeln!(file, "---------------------------------")?;
+        for group in &groups {
+            let value = match group.kind {
+                "string" => format!("{:?}", group.value),
+                _ => group.value.to_string(),
+            };
+            writeln!(
+                file,
+                "{} ({}, {} uses):",
+                value,
+                group.kind,
+                group.uses.len()
+            )?;
+            for literal in &group.uses {
+                writeln!(
+                    file,
+                    "  in {} at {}:{}",
+                    literal.scope,
+                    literal.file_path.display(),
+                    literal.line_number
+                )?;
+            }
+        }
+    }
+
+    let cycles = recursion::find_cycles(&results.function_calls);
+    if !cycles.is_empty() {
+        writeln!(file, "\nRecursion ({} cycles)", cycles.len())?;
+        writeln!(file, "---------")?;
+        for cycle in &cycles {
+            let kind = if cycle.members.len() == 1 {
+                "direct"
+            } else {
+                "mutual"
+            };
+            writeln!(file, "{}: {}", kind, recursion::cycle_text(cycle))?;
+            for member in &cycle.members {
+                writeln!(
+                    file,
+                    "  {} at {}:{}",
+                    member.function,
+                    member.file_path.display(),
+                    member.line_number
+                )?;
+            }
+        }
+    }
+
+    let ownership_by_module = ownership::module_ownership(
+        &results.param_ownership,
+        results.mutable_vars.iter().chain(&results.immutable_vars),
+    );
+    if !ownership_by_module.is_empty() {
+        writeln!(file, "\nOwnership by Module")?;
+        writeln!(file, "-------------------")?;
+        for (module, counts) in &ownership_by_module {
+            writeln!(file, "{}:", module)?;
+            writeln!(
+                file,
+                "  {} parameters: {}",
+                counts.params.total(),
+                counts.params.ratios_text()
+            )?;
+            writeln!(
+                file,
+                "  {} locals: {}",
+                counts.locals.total(),
+                counts.locals.ratios_text()
+            )?;
+        }
+        for param in &results.param_ownership {
+            writeln!(
+                file,
+                "{} {}: {} in {} at {}:{}",
+                param.ownership.label(),
+                param.param,
+                param.basic_type,
+                param.function,
+                param.file_path.display(),
+                param.line_number
+            )?;
+        }
+    }
+
+    let layouts = struct_layout::estimate_layouts(&results.data_structures);
+    if !layouts.is_empty() {
+        writeln!(file, "\nStruct Layout (estimate)")?;
+        writeln!(file, "------------------------")?;
+        writeln!(
+            file,
+            "Sizes assume a 64-bit target and rustc field reordering"
+        )?;
+        writeln!(file, "{:>6} {:>5} {:>4}  struct", "bytes", "align", "heap")?;
+        for layout in &layouts {
+            writeln!(
+                file,
+                "{:>6} {:>5} {:>4}  {}{} ({}:{})",
+                layout.size,
+                layout.align,
+                layout.heap_fields,
+                layout.structure.display_name(),
+                if layout.heap_heavy() {
+                    " [heap-heavy]"
+                } else {
+                    ""
+                },
+                layout.structure.file_path.display(),
+                layout.structure.line_number
+            )?;
+        }
+    }
+
+    if !results.parse_failures.is_empty() {
+        writeln!(file, "\nParse Failures ({})", results.parse_failures.len())?;
+        writeln!(file, "----------------")?;
+        for failure in &results.parse_failures {
+            writeln!(
+                file,
+                "{}: {} ({})",
+                failure.file_path.display(),
+                failure.describe(),
+                failure.outcome()
+            )?;
+        }
+    }
+
+    if !results.errors.is_empty() {
+        writeln!(file, "\nErrors ({})", results.errors.len())?;
+        writeln!(file, "----------")?;
+        for error in &results.errors {
+            writeln!(file, "{}: {}", error.file_path.display(), error.message)?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,240 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// CI thresholds (`--baseline`, `--max-mutable-vars`, `--max-unsafe-blocks`,
+// `--max-unused-mut`, `--fail-on-increase`), for the analysis and for `forest precommit`.
+// Metrics are read from the JSON results model, so the current run and a baseline report
+// written with `--format json` are measured the same way. A run that exceeds a maximum, or
+// whose metric grew past the baseline with --fail-on-increase, fails with a non-zero exit
+// code after its results have been written.
+
+use crate::{results_model, AnalysisMetadata, AnalysisResults, ForestError};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+// Metrics and where the JSON report holds them; arrays count their entries
+const METRICS: &[(&str, &str)] = &[
+    ("mutable", "/metadata/mutable_variable_count"),
+    ("immutable", "/metadata/immutable_variable_count"),
+    ("structures", "/metadata/data_structure_count"),
+    ("unsafe", "/unsafe_code/unsafe_blocks"),
+    ("unused-mut", "/unused_mut"),
+    ("global-state", "/global_mutable_state"),
+    ("errors", "/metadata/error_count"),
+];
+
+// Names of the metrics accepted by --fail-on-increase
+pub fn metric_names() -> Vec<&'static str> {
+    METRICS.iter().map(|&(name, _)| name).collect()
+}
+
+// Values of the metrics of a run
+pub type Metrics = BTreeMap<&'static str, usize>;
+
+// Function to measure a JSON report; metrics missing from it are 0
+pub fn measure(report: &serde_json::Value) -> Metrics {
+    METRICS
+        .iter()
+        .map(|&(name, pointer)| {
+            let value = match report.pointer(pointer) {
+                Some(serde_json::Value::Array(entries)) => entries.len(),
+                Some(value) => value.as_u64().unwrap_or(0) as usize,
+                None => 0,
+            };
+            (name, value)
+        })
+        .collect()
+}
+
+// Function to measure a baseline report written by --format json
+pub fn load_baseline(path: &str) -> Result<Metrics, ForestError> {
+    let content = std::fs::read_to_string(path).map_err(|e| ForestError::io(path, e))?;
+    let report: serde_json::Value =
+        serde_json::from_str(&content).map_err(|e| ForestError::json(path, &e))?;
+    Ok(measure(&report))
+}
+
+// The outcome of one threshold
+#[derive(Serialize)]
+pub struct Check {
+    pub rule: String, // e.g. `max-mutable-vars` or `fail-on-increase mutable`
+    pub passed: bool,
+    pub message: String,
+}
+
+// The limits a run must stay within
+pub struct Thresholds {
+    pub max_mutable_vars: Option<usize>,
+    pub max_unsafe_blocks: Option<usize>,
+    pub max_unused_mut: Option<usize>,
+    pub fail_on_increase: Vec<String>, // Metrics that may not grow past the baseline
+}
+
+impl Thresholds {
+    // Function to check whether any limit was given
+    pub fn is_empty(&self) -> bool {
+        self.max_mutable_vars.is_none()
+            && self.max_unsafe_blocks.is_none()
+            && self.max_unused_mut.is_none()
+            && self.fail_on_increase.is_empty()
+    }
+
+    // Function to measure results as their JSON report would be and check them against every
+    // limit, returning the metrics and the checks
+    pub fn evaluate(
+        &self,
+        results: &AnalysisResults,
+        metadata: &AnalysisMetadata,
+        baseline: Option<&Metrics>,
+    ) -> Result<(Metrics, Vec<Check>), ForestError> {
+        let model = serde_json::to_value(results_model(results, metadata, false))
+            .map_err(|e| ForestError::format("json", e))?;
+        let current = measure(&model);
+        let checks = self.checks(&current, baseline);
+        Ok((current, checks))
+    }
+
+    // Function to check the run against every limit
+    pub fn checks(&self, current: &Metrics, baseline: Option<&Metrics>) -> Vec<Check> {
+        let mut checks = Vec::new();
+        for (limit, metric, rule) in [
+            (self.max_mutable_vars, "mutable", "max-mutable-vars"),
+            (self.max_unsafe_blocks, "unsafe", "max-unsafe-blocks"),
+            (self.max_unused_mut, "unused-mut", "max-unused-mut"),
+        ] {
+            if let Some(limit) = limit {
+                let passed = current[metric] <= limit;
+                checks.push(Check {
+                    rule: rule.to_string(),
+                    passed,
+                    message: format!(
+                        "{} {} {} --{} {}",
+                        metric,
+                        current[metric],
+                        if passed { "is within" } else { "exceeds" },
+                        rule,
+                        limit
+                    ),
+                });
+            }
+        }
+        if let Some(baseline) = baseline {
+            for metric in &self.fail_on_increase {
+                let metric = metric.as_str();
+                let passed = current[metric] <= baseline[metric];
+                checks.push(Check {
+                    rule: format!("fail-on-increase {}", metric),
+                    passed,
+                    message: format!(
+                        "{} {} from {} to {} since the baseline",
+                        metric,
+                        if passed {
+                            "did not increase"
+                        } else {
+                            "increased"
+                        },
+                        baseline[metric],
+                        current[metric]
+                    ),
+                });
+            }
+        }
+        checks
+    }
+}
+
+// Function to print the metrics of the run next to the baseline
+pub fn print_comparison(baseline: &Metrics, current: &Metrics) {
+    println!("\n\x1b[1mBaseline Comparison:\x1b[0m");
+    for (name, &value) in current {
+        let before = baseline[name];
+        println!(
+            "  {:<14} {:>6} -> {:>6} ({:+})",
+            name,
+            before,
+            value,
+            value as isize - before as isize
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn thresholds() -> Thresholds {
+        Thresholds {
+            max_mutable_vars: None,
+            max_unsafe_blocks: None,
+            max_unused_mut: None,
+            fail_on_increase: Vec::new(),
+        }
+    }
+
+    fn metrics(mutable: usize, unsafe_blocks: usize) -> Metrics {
+        let report = json!({
+            "metadata": { "mutable_variable_count": mutable },
+            "unsafe_code": { "unsafe_blocks": unsafe_blocks },
+        });
+        measure(&report)
+    }
+
+    #[test]
+    fn measures_counts_and_arrays() {
+        let report = json!({
+            "metadata": { "mutable_variable_count": 4, "error_count": 1 },
+            "unused_mut": [{}, {}],
+        });
+        let metrics = measure(&report);
+        assert_eq!(metrics["mutable"], 4);
+        assert_eq!(metrics["errors"], 1);
+        assert_eq!(metrics["unused-mut"], 2);
+        assert_eq!(metrics["unsafe"], 0);
+        assert_eq!(metrics.len(), metric_names().len());
+    }
+
+    #[test]
+    fn maximums_pass_up_to_the_limit() {
+        let limits = Thresholds {
+            max_mutable_vars: Some(3),
+            max_unsafe_blocks: Some(0),
+            ..thresholds()
+        };
+        assert!(!limits.is_empty());
+
+        let checks = limits.checks(&metrics(3, 1), None);
+        assert_eq!(checks.len(), 2);
+        assert!(checks[0].passed);
+        assert_eq!(
+            checks[0].message,
+            "mutable 3 is within --max-mutable-vars 3"
+        );
+        assert!(!checks[1].passed);
+        assert_eq!(checks[1].rule, "max-unsafe-blocks");
+        assert_eq!(checks[1].message, "unsafe 1 exceeds --max-unsafe-blocks 0");
+    }
+
+    #[test]
+    fn increases_fail_only_against_a_baseline() {
+        let limits = Thresholds {
+            fail_on_increase: vec!["mutable".to_string(), "unsafe".to_string()],
+            ..thresholds()
+        };
+        assert!(!limits.is_empty());
+        assert!(limits.checks(&metrics(5, 0), None).is_empty());
+
+        let checks = limits.checks(&metrics(5, 0), Some(&metrics(4, 1)));
+        assert_eq!(checks[0].rule, "fail-on-increase mutable");
+        assert!(!checks[0].passed);
+        assert_eq!(
+            checks[0].message,
+            "mutable increased from 4 to 5 since the baseline"
+        );
+        assert!(checks[1].passed);
+    }
+
+    #[test]
+    fn no_limits_no_checks() {
+        assert!(thresholds().is_empty());
+        assert!(thresholds().checks(&metrics(100, 100), None).is_empty());
+    }
+}
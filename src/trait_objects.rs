@@ -0,0 +1,210 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Trait objects and `impl Trait`.
+// Each `dyn Trait` and `impl Trait` type is recorded with the position it appears in: a
+// parameter, a return type, a struct field, a local variable or elsewhere. `dyn` means
+// dynamic dispatch through a vtable, which needs an object-safe trait; `impl` is resolved
+// at compile time and monomorphized like a generic. Every trait in a bound list counts,
+// so `Box<dyn Error + Send>` uses both Error and Send dynamically.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use syn::visit::{self, Visit};
+
+// A trait named in a `dyn` or `impl` type
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TraitUse {
+    #[serde(rename = "trait")]
+    pub trait_name: String, // As written, without generic arguments, e.g. std::fmt::Debug or Fn
+    pub dispatch: String, // "dyn" or "impl"
+    pub context: String,  // "parameter", "return", "field", "local" or "other"
+    pub owner: String,    // Path of the enclosing item
+    #[serde(rename = "file")]
+    pub file_path: PathBuf,
+    #[serde(rename = "line")]
+    pub line_number: usize,
+}
+
+// How often a trait is used dynamically and statically
+#[derive(Clone, Copy, Default, Serialize)]
+pub struct DispatchCounts {
+    pub dyn_uses: usize,
+    pub impl_uses: usize,
+}
+
+impl DispatchCounts {
+    // "dynamic", "static" or "both"
+    pub fn label(&self) -> &'static str {
+        match (self.dyn_uses > 0, self.impl_uses > 0) {
+            (true, true) => "both",
+            (true, false) => "dynamic",
+            _ => "static",
+        }
+    }
+}
+
+// Visitor that collects the dyn and impl trait types of a file
+struct TraitObjectVisitor<'a> {
+    file_path: &'a Path,
+    scope: String,
+    context: &'static str,
+    uses: &'a mut Vec<TraitUse>,
+}
+
+impl TraitObjectVisitor<'_> {
+    fn record(
+        &mut self,
+        bounds: &syn::punctuated::Punctuated<syn::TypeParamBound, syn::Token![+]>,
+        dispatch: &str,
+    ) {
+        for bound in bounds {
+            if let syn::TypeParamBound::Trait(trait_bound) = bound {
+                let trait_name = trait_bound
+                    .path
+                    .segments
+                    .iter()
+                    .map(|segment| segment.ident.to_string())
+                    .collect::<Vec<_>>()
+                    .join("::");
+                let line_number = trait_bound
+                    .path
+                    .segments
+                    .last()
+                    .map_or(0, |segment| segment.ident.span().start().line);
+                self.uses.push(TraitUse {
+                    trait_name,
+                    dispatch: dispatch.to_string(),
+                    context: self.context.to_string(),
+                    owner: self.scope.clone(),
+                    file_path: self.file_path.to_path_buf(),
+                    line_number,
+                });
+            }
+        }
+    }
+
+    fn visit_in_context(&mut self, context: &'static str, visit: impl FnOnce(&mut Self)) {
+        let outer_context = std::mem::replace(&mut self.context, context);
+        visit(self);
+        self.context = outer_context;
+    }
+
+    fn visit_in_scope(&mut self, name: &impl std::fmt::Display, visit: impl FnOnce(&mut Self)) {
+        let inner_scope = format!("{}::{}", self.scope, name);
+        let outer_scope = std::mem::replace(&mut self.scope, inner_scope);
+        visit(self);
+        self.scope = outer_scope;
+    }
+}
+
+impl<'ast> Visit<'ast> for TraitObjectVisitor<'_> {
+    fn visit_type_trait_object(&mut self, object: &'ast syn::TypeTraitObject) {
+        self.record(&object.bounds, "dyn");
+        visit::visit_type_trait_object(self, object);
+    }
+
+    fn visit_type_impl_trait(&mut self, impl_trait: &'ast syn::TypeImplTrait) {
+        self.record(&impl_trait.bounds, "impl");
+        visit::visit_type_impl_trait(self, impl_trait);
+    }
+
+    fn visit_fn_arg(&mut self, arg: &'ast syn::FnArg) {
+        self.visit_in_context("parameter", |visitor| visit::visit_fn_arg(visitor, arg));
+    }
+
+    fn visit_return_type(&mut self, output: &'ast syn::ReturnType) {
+        self.visit_in_context("return", |visitor| {
+            visit::visit_return_type(visitor, output)
+        });
+    }
+
+    fn visit_field(&mut self, field: &'ast syn::Field) {
+        self.visit_in_context("field", |visitor| visit::visit_field(visitor, field));
+    }
+
+    fn visit_local(&mut self, local: &'ast syn::Local) {
+        self.visit_in_context("local", |visitor| visit::visit_local(visitor, local));
+    }
+
+    // Closures in a signature's body are not part of it
+    fn visit_block(&mut self, block: &'ast syn::Block) {
+        self.visit_in_context("other", |visitor| visit::visit_block(visitor, block));
+    }
+
+    fn visit_item_fn(&mut self, item_fn: &'ast syn::ItemFn) {
+        self.visit_in_scope(&item_fn.sig.ident, |visitor| {
+            visit::visit_item_fn(visitor, item_fn)
+        });
+    }
+
+    fn visit_impl_item_fn(&mut self, method: &'ast syn::ImplItemFn) {
+        self.visit_in_scope(&method.sig.ident, |visitor| {
+            visit::visit_impl_item_fn(visitor, method)
+        });
+    }
+
+    fn visit_trait_item_fn(&mut self, method: &'ast syn::TraitItemFn) {
+        self.visit_in_scope(&method.sig.ident, |visitor| {
+            visit::visit_trait_item_fn(visitor, method)
+        });
+    }
+
+    fn visit_item_struct(&mut self, item_struct: &'ast syn::ItemStruct) {
+        self.visit_in_scope(&item_struct.ident, |visitor| {
+            visit::visit_item_struct(visitor, item_struct)
+        });
+    }
+
+    fn visit_item_enum(&mut self, item_enum: &'ast syn::ItemEnum) {
+        self.visit_in_scope(&item_enum.ident, |visitor| {
+            visit::visit_item_enum(visitor, item_enum)
+        });
+    }
+
+    fn visit_item_impl(&mut self, item_impl: &'ast syn::ItemImpl) {
+        let name = crate::impl_blocks::type_name(&item_impl.self_ty);
+        self.visit_in_scope(&name, |visitor| visit::visit_item_impl(visitor, item_impl));
+    }
+
+    fn visit_item_trait(&mut self, item_trait: &'ast syn::ItemTrait) {
+        self.visit_in_scope(&item_trait.ident, |visitor| {
+            visit::visit_item_trait(visitor, item_trait)
+        });
+    }
+
+    fn visit_item_mod(&mut self, item_mod: &'ast syn::ItemMod) {
+        self.visit_in_scope(&item_mod.ident, |visitor| {
+            visit::visit_item_mod(visitor, item_mod)
+        });
+    }
+}
+
+// Function to collect the dyn and impl trait types of a parsed file
+pub fn collect_trait_objects(
+    file_ast: &syn::File,
+    file_path: &Path,
+    module_path: &str,
+    uses: &mut Vec<TraitUse>,
+) {
+    let mut visitor = TraitObjectVisitor {
+        file_path,
+        scope: module_path.to_string(),
+        context: "other",
+        uses,
+    };
+    visitor.visit_file(file_ast);
+}
+
+// Function to count the dyn and impl uses of each trait
+pub fn dispatch_by_trait(uses: &[TraitUse]) -> BTreeMap<&str, DispatchCounts> {
+    let mut by_trait: BTreeMap<&str, DispatchCounts> = BTreeMap::new();
+    for trait_use in uses {
+        let counts = by_trait.entry(trait_use.trait_name.as_str()).or_default();
+        if trait_use.dispatch == "dyn" {
+            counts.dyn_uses += 1;
+        } else {
+            counts.impl_uses += 1;
+        }
+    }
+    by_trait
+}
@@ -0,0 +1,246 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Ranking of the types used by variables and struct fields.
+// Every use is classified as a std, external (a crate dependency) or project-local
+// type, so the external types can be ranked per crate to show which dependencies
+// permeate the codebase.
+
+use crate::{DataStructureInfo, VarInfo};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use syn::visit::{self, Visit};
+
+// Types in scope without an import (primitives and the std prelude)
+const STD_PRELUDE: &[&str] = &[
+    "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize", "f32",
+    "f64", "bool", "char", "str", "String", "Vec", "Option", "Result", "Box", "ToString",
+];
+
+// Crate roots that belong to the standard library
+const STD_CRATES: &[&str] = &["std", "core", "alloc"];
+
+// Number of external types listed per crate
+const TOP_TYPES_PER_CRATE: usize = 10;
+
+// A single use of a type name in a variable or field declaration
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TypeUse {
+    pub type_name: String,
+    pub path_root: Option<String>, // First path segment, as written or resolved through `use`
+    pub file_path: PathBuf,
+}
+
+// Where a type comes from
+enum TypeOrigin {
+    Std,
+    External(String), // Name of the dependency crate
+    Local,
+    Unresolved, // Generic parameters, glob imports and the like
+}
+
+// Usage counts of the types of a whole project
+pub struct TypeUsageSummary {
+    pub std_uses: usize,
+    pub external_uses: usize,
+    pub local_uses: usize,
+    pub by_crate: Vec<CrateUsage>, // Most used dependencies first
+}
+
+// External types of one dependency crate
+pub struct CrateUsage {
+    pub crate_name: String,
+    pub uses: usize,
+    pub types: Vec<(String, usize)>, // Most used types first, with their use counts
+}
+
+// Visitor that records the `use` imports and the struct and enum field types of a file
+struct TypeVisitor<'a> {
+    file_path: &'a Path,
+    imports: HashMap<String, String>, // Imported name -> root of its path
+    field_uses: Vec<(String, Option<String>)>,
+    in_field: bool,
+}
+
+impl TypeVisitor<'_> {
+    fn collect_imports(&mut self, tree: &syn::UseTree, root: Option<&str>) {
+        match tree {
+            syn::UseTree::Path(path) => {
+                let segment = path.ident.to_string();
+                let root = root.unwrap_or(&segment).to_string();
+                self.collect_imports(&path.tree, Some(&root));
+            }
+            syn::UseTree::Name(name) => {
+                let ident = name.ident.to_string();
+                let root = root.unwrap_or(&ident).to_string();
+                self.imports.insert(ident, root);
+            }
+            syn::UseTree::Rename(rename) => {
+                let root = root
+                    .map(str::to_string)
+                    .unwrap_or_else(|| rename.ident.to_string());
+                self.imports.insert(rename.rename.to_string(), root);
+            }
+            syn::UseTree::Group(group) => {
+                for item in &group.items {
+                    self.collect_imports(item, root);
+                }
+            }
+            syn::UseTree::Glob(_) => {}
+        }
+    }
+
+    // Resolve the root of a path through the imports of the file
+    fn resolve_root(&self, first_segment: &str) -> String {
+        match first_segment {
+            "crate" | "self" | "super" => "crate".to_string(),
+            _ => self
+                .imports
+                .get(first_segment)
+                .cloned()
+                .unwrap_or_else(|| first_segment.to_string()),
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for TypeVisitor<'_> {
+    fn visit_item_use(&mut self, item_use: &'ast syn::ItemUse) {
+        self.collect_imports(&item_use.tree, None);
+    }
+
+    fn visit_field(&mut self, field: &'ast syn::Field) {
+        self.in_field = true;
+        visit::visit_field(self, field);
+        self.in_field = false;
+    }
+
+    fn visit_type_path(&mut self, type_path: &'ast syn::TypePath) {
+        if self.in_field {
+            let segments = &type_path.path.segments;
+            if let Some(last) = segments.last() {
+                let root = if segments.len() > 1 {
+                    Some(self.resolve_root(&segments[0].ident.to_string()))
+                } else {
+                    self.imports.get(&last.ident.to_string()).cloned()
+                };
+                self.field_uses.push((last.ident.to_string(), root));
+            }
+        }
+        // Generic arguments are type uses as well
+        visit::visit_type_path(self, type_path);
+    }
+}
+
+// Function to record the type uses of the variables and fields of one file
+pub fn collect_type_uses<'a>(
+    file_ast: &syn::File,
+    file_path: &Path,
+    vars: impl Iterator<Item = &'a VarInfo>,
+    uses: &mut Vec<TypeUse>,
+) {
+    let mut visitor = TypeVisitor {
+        file_path,
+        imports: HashMap::new(),
+        field_uses: Vec::new(),
+        in_field: false,
+    };
+    visitor.visit_file(file_ast);
+
+    // Variables only keep their basic type, so their names are resolved through the imports
+    for var in vars.filter(|var| *var.file_path == *file_path) {
+        for type_name in var
+            .basic_type
+            .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .filter(|name| !name.is_empty() && *name != "mut" && *name != "N")
+        {
+            uses.push(TypeUse {
+                type_name: type_name.to_string(),
+                path_root: visitor.imports.get(type_name).cloned(),
+                file_path: visitor.file_path.to_path_buf(),
+            });
+        }
+    }
+
+    for (type_name, path_root) in visitor.field_uses {
+        uses.push(TypeUse {
+            type_name,
+            path_root,
+            file_path: file_path.to_path_buf(),
+        });
+    }
+}
+
+// Function to classify a type use
+fn classify(
+    type_use: &TypeUse,
+    dependencies: &HashSet<String>,
+    local_types: &HashSet<&str>,
+) -> TypeOrigin {
+    match type_use.path_root.as_deref() {
+        Some(root) if STD_CRATES.contains(&root) => TypeOrigin::Std,
+        Some(root) if dependencies.contains(root) => TypeOrigin::External(root.to_string()),
+        // Any other root is `crate` or a module of the project
+        Some(_) => TypeOrigin::Local,
+        None if local_types.contains(type_use.type_name.as_str()) => TypeOrigin::Local,
+        None if STD_PRELUDE.contains(&type_use.type_name.as_str()) => TypeOrigin::Std,
+        None => TypeOrigin::Unresolved,
+    }
+}
+
+// Function to count type uses by origin and rank the external types of each crate
+pub fn summarize(
+    uses: &[TypeUse],
+    dependencies: &[String],
+    data_structures: &[DataStructureInfo],
+) -> TypeUsageSummary {
+    // Cargo.toml names use dashes, paths use underscores
+    let dependencies: HashSet<String> = dependencies.iter().map(|d| d.replace('-', "_")).collect();
+    let local_types: HashSet<&str> = data_structures
+        .iter()
+        .filter(|d| d.data_structure_type != "function")
+        .map(|d| d.name.as_str())
+        .collect();
+
+    let mut summary = TypeUsageSummary {
+        std_uses: 0,
+        external_uses: 0,
+        local_uses: 0,
+        by_crate: Vec::new(),
+    };
+    let mut crate_types: HashMap<String, HashMap<&str, usize>> = HashMap::new();
+    for type_use in uses {
+        match classify(type_use, &dependencies, &local_types) {
+            TypeOrigin::Std => summary.std_uses += 1,
+            TypeOrigin::Local => summary.local_uses += 1,
+            TypeOrigin::External(crate_name) => {
+                summary.external_uses += 1;
+                *crate_types
+                    .entry(crate_name)
+                    .or_default()
+                    .entry(&type_use.type_name)
+                    .or_default() += 1;
+            }
+            TypeOrigin::Unresolved => {}
+        }
+    }
+
+    for (crate_name, types) in crate_types {
+        let total = types.values().sum();
+        let mut ranked: Vec<(String, usize)> = types
+            .into_iter()
+            .map(|(name, count)| (name.to_string(), count))
+            .collect();
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        ranked.truncate(TOP_TYPES_PER_CRATE);
+        summary.by_crate.push(CrateUsage {
+            crate_name,
+            uses: total,
+            types: ranked,
+        });
+    }
+    summary.by_crate.sort_by(|a, b| {
+        b.uses
+            .cmp(&a.uses)
+            .then_with(|| a.crate_name.cmp(&b.crate_name))
+    });
+    summary
+}
@@ -0,0 +1,238 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Cross-reference of the project's structs, enums and traits.
+// Every type path and trait bound is recorded with where it appears: a struct field, a
+// function signature, a local variable, an impl header or elsewhere (casts, turbofish,
+// aliases). References are matched to the declared types by name, so types that share a
+// name across modules share their references. Types with few or no references are listed
+// first, as candidates for removal.
+
+use crate::DataStructureInfo;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use syn::visit::{self, Visit};
+
+// Kinds of declared items that are cross-referenced
+const REFERENCED_KINDS: &[&str] = &["struct", "enum", "trait"];
+
+// A single mention of a type or trait name
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TypeReference {
+    pub type_name: String, // Last path segment, e.g. Lexer for crate::parser::Lexer
+    pub context: String,   // "field", "signature", "local", "impl" or "other"
+    pub owner: String,     // Path of the enclosing item, e.g. crate::parser::parse
+    pub file_path: PathBuf,
+    pub line_number: usize,
+}
+
+// A declared type with the references to it
+pub struct TypeXref<'a> {
+    pub declaration: &'a DataStructureInfo,
+    pub references: Vec<&'a TypeReference>,
+}
+
+impl TypeXref<'_> {
+    // Path of the declared type
+    pub fn name(&self) -> &str {
+        self.declaration.display_name()
+    }
+
+    // The kind of item, e.g. "struct"
+    pub fn kind(&self) -> &str {
+        &self.declaration.data_structure_type
+    }
+
+    // Number of references in each context, e.g. {"field": 2, "signature": 1}
+    pub fn contexts(&self) -> BTreeMap<&str, usize> {
+        let mut contexts = BTreeMap::new();
+        for reference in &self.references {
+            *contexts.entry(reference.context.as_str()).or_insert(0) += 1;
+        }
+        contexts
+    }
+
+    // The contexts as text, e.g. "2 field, 1 signature"
+    pub fn contexts_text(&self) -> String {
+        self.contexts()
+            .iter()
+            .map(|(context, count)| format!("{} {}", count, context))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+// Visitor that collects the type references of a file
+struct ReferenceVisitor<'a> {
+    file_path: &'a Path,
+    scope: String,
+    context: &'static str,
+    references: &'a mut Vec<TypeReference>,
+}
+
+impl ReferenceVisitor<'_> {
+    fn record(&mut self, path: &syn::Path) {
+        if let Some(segment) = path.segments.last() {
+            if segment.ident == "Self" {
+                return;
+            }
+            self.references.push(TypeReference {
+                type_name: segment.ident.to_string(),
+                context: self.context.to_string(),
+                owner: self.scope.clone(),
+                file_path: self.file_path.to_path_buf(),
+                line_number: segment.ident.span().start().line,
+            });
+        }
+    }
+
+    fn visit_in_context(&mut self, context: &'static str, visit: impl FnOnce(&mut Self)) {
+        let outer_context = std::mem::replace(&mut self.context, context);
+        visit(self);
+        self.context = outer_context;
+    }
+
+    fn visit_in_scope(&mut self, name: &impl std::fmt::Display, visit: impl FnOnce(&mut Self)) {
+        let inner_scope = format!("{}::{}", self.scope, name);
+        let outer_scope = std::mem::replace(&mut self.scope, inner_scope);
+        visit(self);
+        self.scope = outer_scope;
+    }
+}
+
+impl<'ast> Visit<'ast> for ReferenceVisitor<'_> {
+    fn visit_type_path(&mut self, type_path: &'ast syn::TypePath) {
+        self.record(&type_path.path);
+        visit::visit_type_path(self, type_path);
+    }
+
+    fn visit_trait_bound(&mut self, bound: &'ast syn::TraitBound) {
+        self.record(&bound.path);
+        visit::visit_trait_bound(self, bound);
+    }
+
+    fn visit_field(&mut self, field: &'ast syn::Field) {
+        self.visit_in_context("field", |visitor| visit::visit_field(visitor, field));
+    }
+
+    fn visit_signature(&mut self, sig: &'ast syn::Signature) {
+        self.visit_in_context("signature", |visitor| visit::visit_signature(visitor, sig));
+    }
+
+    fn visit_local(&mut self, local: &'ast syn::Local) {
+        self.visit_in_context("local", |visitor| visit::visit_local(visitor, local));
+    }
+
+    fn visit_item_fn(&mut self, item_fn: &'ast syn::ItemFn) {
+        self.visit_in_scope(&item_fn.sig.ident, |visitor| {
+            visit::visit_item_fn(visitor, item_fn)
+        });
+    }
+
+    fn visit_impl_item_fn(&mut self, method: &'ast syn::ImplItemFn) {
+        self.visit_in_scope(&method.sig.ident, |visitor| {
+            visit::visit_impl_item_fn(visitor, method)
+        });
+    }
+
+    fn visit_trait_item_fn(&mut self, method: &'ast syn::TraitItemFn) {
+        self.visit_in_scope(&method.sig.ident, |visitor| {
+            visit::visit_trait_item_fn(visitor, method)
+        });
+    }
+
+    fn visit_item_struct(&mut self, item_struct: &'ast syn::ItemStruct) {
+        self.visit_in_scope(&item_struct.ident, |visitor| {
+            visit::visit_item_struct(visitor, item_struct)
+        });
+    }
+
+    fn visit_item_enum(&mut self, item_enum: &'ast syn::ItemEnum) {
+        self.visit_in_scope(&item_enum.ident, |visitor| {
+            visit::visit_item_enum(visitor, item_enum)
+        });
+    }
+
+    fn visit_item_trait(&mut self, item_trait: &'ast syn::ItemTrait) {
+        self.visit_in_scope(&item_trait.ident, |visitor| {
+            visit::visit_item_trait(visitor, item_trait)
+        });
+    }
+
+    // The implemented trait and the self type are impl references; the items are not
+    fn visit_item_impl(&mut self, item_impl: &'ast syn::ItemImpl) {
+        let name = crate::impl_blocks::type_name(&item_impl.self_ty);
+        self.visit_in_context("impl", |visitor| {
+            visitor.visit_generics(&item_impl.generics);
+            if let Some((_, ref path, _)) = item_impl.trait_ {
+                visitor.record(path);
+                visitor.visit_path(path);
+            }
+            visitor.visit_type(&item_impl.self_ty);
+        });
+        self.visit_in_scope(&name, |visitor| {
+            visitor.visit_in_context("other", |visitor| {
+                for item in &item_impl.items {
+                    visitor.visit_impl_item(item);
+                }
+            })
+        });
+    }
+
+    fn visit_item_mod(&mut self, item_mod: &'ast syn::ItemMod) {
+        self.visit_in_scope(&item_mod.ident, |visitor| {
+            visit::visit_item_mod(visitor, item_mod)
+        });
+    }
+}
+
+// Function to collect the type references of a parsed file
+pub fn collect_type_references(
+    file_ast: &syn::File,
+    file_path: &Path,
+    module_path: &str,
+    references: &mut Vec<TypeReference>,
+) {
+    let mut visitor = ReferenceVisitor {
+        file_path,
+        scope: module_path.to_string(),
+        context: "other",
+        references,
+    };
+    visitor.visit_file(file_ast);
+}
+
+// Function to match the references to the declared structs, enums and traits
+// Types are listed by number of references, least referenced first
+pub fn cross_reference<'a>(
+    data_structures: &'a [DataStructureInfo],
+    references: &'a [TypeReference],
+) -> Vec<TypeXref<'a>> {
+    let mut by_name: HashMap<&str, Vec<&TypeReference>> = HashMap::new();
+    for reference in references {
+        by_name
+            .entry(reference.type_name.as_str())
+            .or_default()
+            .push(reference);
+    }
+    let mut xrefs: Vec<TypeXref> = data_structures
+        .iter()
+        .filter(|item| {
+            REFERENCED_KINDS.contains(&item.data_structure_type.as_str())
+                && item.macro_body.is_none()
+        })
+        .map(|declaration| TypeXref {
+            declaration,
+            references: by_name
+                .get(declaration.name.as_str())
+                .cloned()
+                .unwrap_or_default(),
+        })
+        .collect();
+    xrefs.sort_by(|a, b| {
+        a.references
+            .len()
+            .cmp(&b.references.len())
+            .then_with(|| a.name().cmp(b.name()))
+    });
+    xrefs
+}
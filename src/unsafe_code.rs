@@ -0,0 +1,188 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Inventory of unsafe code.
+// Unsafe trait impls are listed individually, manual `Send` and `Sync` impls first,
+// since each one asserts a guarantee the compiler cannot check and deserves review.
+// Unsafe blocks, unsafe fns and unsafe traits are listed with their enclosing scope.
+
+use quote::ToTokens;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use syn::visit::{self, Visit};
+
+// An `unsafe impl Trait for Type`
+#[derive(Clone, Serialize, Deserialize)]
+pub struct UnsafeImpl {
+    pub trait_name: String,
+    pub self_type: String,
+    pub file_path: PathBuf,
+    pub line_number: usize,
+}
+
+// An unsafe block, unsafe fn or unsafe trait
+#[derive(Clone, Serialize, Deserialize)]
+pub struct UnsafeSite {
+    pub kind: String,  // "block", "fn" or "trait"
+    pub name: String,  // Name of the fn or trait, empty for blocks
+    pub scope: String, // Enclosing scope, e.g. crate::ffi::Buffer::read
+    #[serde(rename = "file")]
+    pub file_path: PathBuf,
+    #[serde(rename = "line")]
+    pub line_number: usize,
+}
+
+impl UnsafeSite {
+    // Short description, e.g. `unsafe fn read` or `unsafe block`
+    pub fn label(&self) -> String {
+        if self.name.is_empty() {
+            format!("unsafe {}", self.kind)
+        } else {
+            format!("unsafe {} {}", self.kind, self.name)
+        }
+    }
+}
+
+impl UnsafeImpl {
+    // Manual Send/Sync impls override the compiler's thread-safety reasoning
+    pub fn is_send_or_sync(&self) -> bool {
+        let last_segment = self.trait_name.rsplit("::").next().unwrap_or_default();
+        last_segment == "Send" || last_segment == "Sync"
+    }
+}
+
+// Visitor that finds unsafe code
+struct UnsafeVisitor<'a> {
+    file_path: &'a Path,
+    scope: String,
+    impls: &'a mut Vec<UnsafeImpl>,
+    sites: &'a mut Vec<UnsafeSite>,
+}
+
+impl UnsafeVisitor<'_> {
+    fn push_site(&mut self, kind: &str, name: String, line_number: usize) {
+        self.sites.push(UnsafeSite {
+            kind: kind.to_string(),
+            name,
+            scope: self.scope.clone(),
+            file_path: self.file_path.to_path_buf(),
+            line_number,
+        });
+    }
+
+    // Visit a nested item with its name appended to the scope
+    fn in_scope(&mut self, name: &impl std::fmt::Display, visit: impl FnOnce(&mut Self)) {
+        let inner = format!("{}::{}", self.scope, name);
+        let outer = std::mem::replace(&mut self.scope, inner);
+        visit(self);
+        self.scope = outer;
+    }
+
+    // Record an unsafe fn, then visit its body in its own scope
+    fn visit_fn(&mut self, sig: &syn::Signature, visit: impl FnOnce(&mut Self)) {
+        if let Some(unsafety) = sig.unsafety {
+            self.push_site("fn", sig.ident.to_string(), unsafety.span.start().line);
+        }
+        self.in_scope(&sig.ident, visit);
+    }
+}
+
+impl<'ast> Visit<'ast> for UnsafeVisitor<'_> {
+    fn visit_expr_unsafe(&mut self, expr_unsafe: &'ast syn::ExprUnsafe) {
+        self.push_site(
+            "block",
+            String::new(),
+            expr_unsafe.unsafe_token.span.start().line,
+        );
+        visit::visit_expr_unsafe(self, expr_unsafe);
+    }
+
+    fn visit_item_fn(&mut self, item_fn: &'ast syn::ItemFn) {
+        self.visit_fn(&item_fn.sig, |visitor| {
+            visit::visit_item_fn(visitor, item_fn)
+        });
+    }
+
+    fn visit_impl_item_fn(&mut self, method: &'ast syn::ImplItemFn) {
+        self.visit_fn(&method.sig, |visitor| {
+            visit::visit_impl_item_fn(visitor, method)
+        });
+    }
+
+    fn visit_trait_item_fn(&mut self, method: &'ast syn::TraitItemFn) {
+        self.visit_fn(&method.sig, |visitor| {
+            visit::visit_trait_item_fn(visitor, method)
+        });
+    }
+
+    fn visit_item_trait(&mut self, item_trait: &'ast syn::ItemTrait) {
+        if let Some(unsafety) = item_trait.unsafety {
+            self.push_site(
+                "trait",
+                item_trait.ident.to_string(),
+                unsafety.span.start().line,
+            );
+        }
+        self.in_scope(&item_trait.ident, |visitor| {
+            visit::visit_item_trait(visitor, item_trait)
+        });
+    }
+
+    fn visit_item_mod(&mut self, item_mod: &'ast syn::ItemMod) {
+        self.in_scope(&item_mod.ident, |visitor| {
+            visit::visit_item_mod(visitor, item_mod)
+        });
+    }
+
+    fn visit_item_impl(&mut self, item_impl: &'ast syn::ItemImpl) {
+        if let (Some(unsafety), Some((_, ref trait_path, _))) =
+            (item_impl.unsafety, &item_impl.trait_)
+        {
+            self.impls.push(UnsafeImpl {
+                trait_name: trait_path.to_token_stream().to_string().replace(' ', ""),
+                self_type: item_impl
+                    .self_ty
+                    .to_token_stream()
+                    .to_string()
+                    .replace(' ', ""),
+                file_path: self.file_path.to_path_buf(),
+                line_number: unsafety.span.start().line,
+            });
+        }
+        let self_type = crate::impl_blocks::type_name(&item_impl.self_ty);
+        self.in_scope(&self_type, |visitor| {
+            visit::visit_item_impl(visitor, item_impl)
+        });
+    }
+}
+
+// Function to collect the unsafe code of a parsed file
+pub fn collect_unsafe(
+    file_ast: &syn::File,
+    file_path: &Path,
+    module_path: &str,
+    impls: &mut Vec<UnsafeImpl>,
+    sites: &mut Vec<UnsafeSite>,
+) {
+    let mut visitor = UnsafeVisitor {
+        file_path,
+        scope: module_path.to_string(),
+        impls,
+        sites,
+    };
+    visitor.visit_file(file_ast);
+}
+
+// Function to count the unsafe sites of one kind
+pub fn count_kind(sites: &[UnsafeSite], kind: &str) -> usize {
+    sites.iter().filter(|site| site.kind == kind).count()
+}
+
+// Function to order unsafe impls for review: Send/Sync first, then by location
+pub fn sorted_for_review(impls: &[UnsafeImpl]) -> Vec<&UnsafeImpl> {
+    let mut sorted: Vec<&UnsafeImpl> = impls.iter().collect();
+    sorted.sort_by(|a, b| {
+        b.is_send_or_sync()
+            .cmp(&a.is_send_or_sync())
+            .then_with(|| (&a.file_path, a.line_number).cmp(&(&b.file_path, b.line_number)))
+    });
+    sorted
+}
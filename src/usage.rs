@@ -0,0 +1,420 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Variable reads and writes.
+// Every use of a name is resolved to the binding it refers to: the nearest preceding
+// declaration of that name in an enclosing block, so a shadowing `let` starts counts of its
+// own and `let x = x + 1` reads the earlier `x`. A use inside a closure resolves to the
+// binding the closure captures. Bindings are identified like VarInfo, by the line of their
+// pattern and the column of their name. Assignments, compound assignments and `&mut` borrows
+// of a variable or of a place inside it (`v.len = 0`, `v[i] += 1`) are writes; so are calls of
+// well-known `&mut self` methods such as `push` and the target of `write!`, since they borrow
+// the receiver mutably. Any other mention is a read.
+
+use crate::collector::macro_args;
+use crate::line_index::LineIndex;
+use crate::variables::names_constant;
+use std::collections::HashMap;
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+
+// Maximum number of variables listed as longest-lived
+const TOP_LONG_LIVED: usize = 10;
+
+// Methods of std types that take `&mut self`, so calling one mutates the receiver
+const MUTATING_METHODS: &[&str] = &[
+    "append",
+    "as_mut",
+    "as_mut_slice",
+    "clear",
+    "dedup",
+    "dedup_by_key",
+    "drain",
+    "entry",
+    "extend",
+    "extend_from_slice",
+    "fill",
+    "get_mut",
+    "get_or_insert",
+    "get_or_insert_with",
+    "insert",
+    "iter_mut",
+    "last_mut",
+    "first_mut",
+    "next_back",
+    "pop",
+    "pop_back",
+    "pop_front",
+    "push",
+    "push_back",
+    "push_front",
+    "push_str",
+    "read_line",
+    "read_to_end",
+    "read_to_string",
+    "remove",
+    "reserve",
+    "resize",
+    "retain",
+    "reverse",
+    "rotate_left",
+    "rotate_right",
+    "set_len",
+    "shrink_to_fit",
+    "sort",
+    "sort_by",
+    "sort_by_key",
+    "sort_unstable",
+    "sort_unstable_by",
+    "sort_unstable_by_key",
+    "split_off",
+    "swap",
+    "swap_remove",
+    "truncate",
+    "write",
+    "write_all",
+    "write_fmt",
+    "flush",
+];
+
+// How often a variable is read and written after its declaration
+#[derive(Clone, Copy, Default)]
+pub struct VarUsage {
+    pub reads: usize,
+    pub writes: usize,
+    pub last_line: Option<usize>, // Line of the last read or write
+}
+
+// Where a binding is declared: the line of its pattern and the column of its name
+type Declaration = (usize, usize);
+
+// Uses of the variables of a file, by name and declaration
+#[derive(Default)]
+pub struct UsageMap {
+    usage: HashMap<(String, Declaration), VarUsage>,
+}
+
+impl UsageMap {
+    // The usage of the binding declared at a line and column; unknown bindings were never used
+    pub fn usage_of(&self, name: &str, line_number: usize, column: usize) -> VarUsage {
+        self.usage
+            .get(&(name.to_string(), (line_number, column)))
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+// Visitor that collects the names a pattern binds
+#[derive(Default)]
+struct BindingNames(Vec<syn::Ident>);
+
+impl<'ast> Visit<'ast> for BindingNames {
+    fn visit_pat_ident(&mut self, pat_ident: &'ast syn::PatIdent) {
+        // `None` and other unit variants or constants in a pattern bind nothing
+        if !names_constant(pat_ident) {
+            self.0.push(pat_ident.ident.clone());
+        }
+        visit::visit_pat_ident(self, pat_ident);
+    }
+}
+
+// Visitor that resolves the uses of names to their declarations and counts them
+struct UsageVisitor<'a> {
+    lines: &'a LineIndex<'a>,
+    blocks: Vec<Vec<(String, Declaration)>>, // Bindings in scope, innermost block last
+    usage: UsageMap,
+}
+
+impl UsageVisitor<'_> {
+    // Function to find the declaration a name refers to where the walk is
+    fn resolve(&self, name: &str) -> Option<Declaration> {
+        self.blocks
+            .iter()
+            .rev()
+            .flat_map(|bindings| bindings.iter().rev())
+            .find(|(bound, _)| bound == name)
+            .map(|(_, declaration)| *declaration)
+    }
+
+    fn record(&mut self, ident: &syn::Ident, write: bool) {
+        let name = ident.to_string();
+        let Some(declaration) = self.resolve(&name) else {
+            return;
+        };
+        let line = self.lines.line_of(ident.span().byte_range().start);
+        let usage = self.usage.usage.entry((name, declaration)).or_default();
+        if write {
+            usage.writes += 1;
+        } else {
+            usage.reads += 1;
+        }
+        usage.last_line = usage.last_line.max(Some(line));
+    }
+
+    // Function to bring the bindings of a pattern into scope in the innermost block
+    fn declare(&mut self, pat: &syn::Pat) {
+        let line = self.lines.line_of(pat.span().byte_range().start);
+        let mut names = BindingNames::default();
+        names.visit_pat(pat);
+        if let Some(bindings) = self.blocks.last_mut() {
+            for ident in names.0 {
+                let column = self.lines.column_of(ident.span().byte_range().start);
+                bindings.push((ident.to_string(), (line, column)));
+            }
+        }
+    }
+
+    // Visit a block of bindings, e.g. the arm of a match, that ends when `visit` returns
+    fn visit_nested(&mut self, visit: impl FnOnce(&mut Self)) {
+        self.blocks.push(Vec::new());
+        visit(self);
+        self.blocks.pop();
+    }
+
+    // Visit a function; items do not see the bindings of their surroundings
+    fn visit_function(&mut self, sig: &syn::Signature, visit: impl FnOnce(&mut Self)) {
+        let outer_blocks = std::mem::replace(&mut self.blocks, vec![Vec::new()]);
+        for arg in &sig.inputs {
+            if let syn::FnArg::Typed(pat_type) = arg {
+                self.declare(&pat_type.pat);
+            }
+        }
+        visit(self);
+        self.blocks = outer_blocks;
+    }
+
+    // Visit the target of a write: the variable it belongs to is written, indices are read
+    fn visit_place(&mut self, place: &syn::Expr) {
+        match place {
+            syn::Expr::Path(expr_path) if expr_path.path.get_ident().is_some() => {
+                if let Some(ident) = expr_path.path.get_ident() {
+                    self.record(ident, true);
+                }
+            }
+            syn::Expr::Field(field) => self.visit_place(&field.base),
+            syn::Expr::Index(index) => {
+                self.visit_place(&index.expr);
+                self.visit_expr(&index.index);
+            }
+            syn::Expr::Paren(paren) => self.visit_place(&paren.expr),
+            // Writes through a reference, e.g. `*r = 1`, only read the reference itself
+            other => self.visit_expr(other),
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for UsageVisitor<'_> {
+    fn visit_item_fn(&mut self, item_fn: &'ast syn::ItemFn) {
+        self.visit_function(&item_fn.sig, |visitor| {
+            visit::visit_item_fn(visitor, item_fn)
+        });
+    }
+
+    fn visit_impl_item_fn(&mut self, method: &'ast syn::ImplItemFn) {
+        self.visit_function(&method.sig, |visitor| {
+            visit::visit_impl_item_fn(visitor, method)
+        });
+    }
+
+    fn visit_trait_item_fn(&mut self, method: &'ast syn::TraitItemFn) {
+        self.visit_function(&method.sig, |visitor| {
+            visit::visit_trait_item_fn(visitor, method)
+        });
+    }
+
+    fn visit_block(&mut self, block: &'ast syn::Block) {
+        self.visit_nested(|visitor| visit::visit_block(visitor, block));
+    }
+
+    // The initializer is read before the pattern's bindings shadow anything
+    fn visit_local(&mut self, local: &'ast syn::Local) {
+        if let Some(init) = &local.init {
+            self.visit_expr(&init.expr);
+            if let Some((_, diverge)) = &init.diverge {
+                self.visit_expr(diverge);
+            }
+        }
+        self.declare(&local.pat);
+    }
+
+    fn visit_expr_closure(&mut self, closure: &'ast syn::ExprClosure) {
+        self.visit_nested(|visitor| {
+            for input in &closure.inputs {
+                visitor.declare(input);
+            }
+            visitor.visit_expr(&closure.body);
+        });
+    }
+
+    fn visit_expr_for_loop(&mut self, for_loop: &'ast syn::ExprForLoop) {
+        self.visit_expr(&for_loop.expr);
+        self.visit_nested(|visitor| {
+            visitor.declare(&for_loop.pat);
+            visitor.visit_block(&for_loop.body);
+        });
+    }
+
+    // The bindings of `if let` are in scope in the then branch only
+    fn visit_expr_if(&mut self, expr_if: &'ast syn::ExprIf) {
+        self.visit_nested(|visitor| {
+            visitor.visit_expr(&expr_if.cond);
+            visitor.visit_block(&expr_if.then_branch);
+        });
+        if let Some((_, else_branch)) = &expr_if.else_branch {
+            self.visit_expr(else_branch);
+        }
+    }
+
+    fn visit_expr_while(&mut self, expr_while: &'ast syn::ExprWhile) {
+        self.visit_nested(|visitor| {
+            visitor.visit_expr(&expr_while.cond);
+            visitor.visit_block(&expr_while.body);
+        });
+    }
+
+    fn visit_expr_let(&mut self, expr_let: &'ast syn::ExprLet) {
+        self.visit_expr(&expr_let.expr);
+        self.declare(&expr_let.pat);
+    }
+
+    fn visit_expr_match(&mut self, expr_match: &'ast syn::ExprMatch) {
+        self.visit_expr(&expr_match.expr);
+        for arm in &expr_match.arms {
+            self.visit_nested(|visitor| {
+                visitor.declare(&arm.pat);
+                if let Some((_, guard)) = &arm.guard {
+                    visitor.visit_expr(guard);
+                }
+                visitor.visit_expr(&arm.body);
+            });
+        }
+    }
+
+    fn visit_expr_assign(&mut self, assign: &'ast syn::ExprAssign) {
+        self.visit_place(&assign.left);
+        self.visit_expr(&assign.right);
+    }
+
+    fn visit_expr_binary(&mut self, binary: &'ast syn::ExprBinary) {
+        use syn::BinOp::*;
+        let compound = matches!(
+            binary.op,
+            AddAssign(_)
+                | SubAssign(_)
+                | MulAssign(_)
+                | DivAssign(_)
+                | RemAssign(_)
+                | BitXorAssign(_)
+                | BitAndAssign(_)
+                | BitOrAssign(_)
+                | ShlAssign(_)
+                | ShrAssign(_)
+        );
+        if compound {
+            self.visit_place(&binary.left);
+            self.visit_expr(&binary.right);
+        } else {
+            visit::visit_expr_binary(self, binary);
+        }
+    }
+
+    fn visit_expr_reference(&mut self, reference: &'ast syn::ExprReference) {
+        if reference.mutability.is_some() {
+            self.visit_place(&reference.expr);
+        } else {
+            visit::visit_expr_reference(self, reference);
+        }
+    }
+
+    fn visit_expr_method_call(&mut self, call: &'ast syn::ExprMethodCall) {
+        if MUTATING_METHODS.contains(&call.method.to_string().as_str()) {
+            self.visit_place(&call.receiver);
+            for arg in &call.args {
+                self.visit_expr(arg);
+            }
+        } else {
+            visit::visit_expr_method_call(self, call);
+        }
+    }
+
+    fn visit_expr_path(&mut self, expr_path: &'ast syn::ExprPath) {
+        if let Some(ident) = expr_path.path.get_ident() {
+            self.record(ident, false);
+        }
+        visit::visit_expr_path(self, expr_path);
+    }
+
+    // Macro arguments such as `println!("{}", count)` are only tokens to syn
+    fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+        if let Some(args) = macro_args(mac) {
+            // `write!(out, ...)` writes to its first argument
+            let writes_first = mac.path.is_ident("write") || mac.path.is_ident("writeln");
+            for (index, arg) in args.iter().enumerate() {
+                if index == 0 && writes_first {
+                    self.visit_place(arg);
+                } else {
+                    self.visit_expr(arg);
+                }
+            }
+        }
+    }
+}
+
+// Function to find the mutable variables that are never written, so need not be `mut`
+// Variables of degraded files and macro bodies are skipped, as their uses are not counted
+pub fn unused_mut<'a>(
+    mutable_vars: &'a [crate::VarInfo],
+    degraded_files: &[std::path::PathBuf],
+) -> Vec<&'a crate::VarInfo> {
+    mutable_vars
+        .iter()
+        .filter(|var| {
+            var.write_count == 0
+                && var.macro_body.is_none()
+                && !degraded_files.iter().any(|path| **path == *var.file_path)
+        })
+        .collect()
+}
+
+// Function to rank the mutable variables by how many lines they stay in use
+pub fn longest_lived(mutable_vars: &[crate::VarInfo]) -> Vec<&crate::VarInfo> {
+    let mut ranked: Vec<&crate::VarInfo> =
+        mutable_vars.iter().filter(|var| var.span() > 0).collect();
+    ranked.sort_by_key(|var| std::cmp::Reverse(var.span()));
+    ranked.truncate(TOP_LONG_LIVED);
+    ranked
+}
+
+// Function to count the reads and writes of the variables of a parsed file
+pub fn collect_usage(file_ast: &syn::File, lines: &LineIndex) -> UsageMap {
+    let mut visitor = UsageVisitor {
+        lines,
+        blocks: Vec::new(),
+        usage: UsageMap::default(),
+    };
+    visitor.visit_file(file_ast);
+    visitor.usage
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_shadowed_bindings_separately() {
+        let source = "fn main() {\n    let mut x = 1;\n    x += 1;\n    let x = x * 2;\n    let f = || x + 1;\n    let mut it = [1].iter();\n    it.next();\n    println!(\"{} {}\", f(), x);\n}\n";
+        let file_ast = syn::parse_file(source).unwrap();
+        let lines = LineIndex::new(source);
+        let usage = collect_usage(&file_ast, &lines);
+
+        // The first `x` is written once and read by the initializer that shadows it
+        let first = usage.usage_of("x", 2, 13);
+        assert_eq!((first.reads, first.writes), (1, 1));
+        assert_eq!(first.last_line, Some(4));
+        // The second is read by the closure and by println!
+        let second = usage.usage_of("x", 4, 9);
+        assert_eq!((second.reads, second.writes), (2, 0));
+        assert_eq!(second.last_line, Some(8));
+        // `next` reads the iterator rather than counting as a write
+        let it = usage.usage_of("it", 6, 13);
+        assert_eq!((it.reads, it.writes), (1, 0));
+    }
+}
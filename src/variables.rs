@@ -0,0 +1,682 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// The built-in collector of variables.
+// Bindings of `let` statements, mutable function parameters, `for` loops, `if let`,
+// `while let` and match arms are recorded as mutable or immutable variables, with the line
+// and column of their name, the source line, how they were declared and their type. Types
+// that are not written out are inferred from the value the pattern matches, e.g. `vector` for
+// `Vec::new()`, and sub-patterns from the part of the value they match. With --scope-depth,
+// bindings in deeper blocks are not recorded.
+
+use crate::collector::{Collector, Context, Pattern};
+use crate::interning::{intern, intern_path};
+use crate::{extract_basic_type, format_type, VarInfo};
+use quote::ToTokens;
+use syn::spanned::Spanned;
+use syn::{Expr, Pat, Type};
+
+pub struct Variables {
+    pub scope_depth: Option<usize>, // Deepest block whose bindings are recorded
+}
+
+impl Variables {
+    // Whether bindings are recorded where the walk is
+    fn records(&self, context: &Context) -> bool {
+        self.scope_depth
+            .is_none_or(|max| context.block_depth() <= max)
+    }
+}
+
+impl Collector for Variables {
+    fn name(&self) -> &str {
+        "variables"
+    }
+
+    fn local(&self, local: &syn::Local, context: &mut Context) {
+        if !self.records(context) {
+            return;
+        }
+        let site = Site::of(local.pat.span(), context);
+        let first_records = recorded(context);
+
+        match &local.pat {
+            Pat::Ident(pat_ident) => {
+                let source = BindingSource::of_local(local);
+                let var_type = match source {
+                    BindingSource::Unknown => "inferred".to_string(),
+                    source => source.var_type(),
+                };
+                let var = VarInfo {
+                    var_kind: intern("inferred from initialization"),
+                    var_type: intern(&var_type),
+                    basic_type: intern(&source.basic_type()),
+                    ..declared(context, &site, pat_ident, pat_ident.mutability.is_some())
+                };
+                push(context, var);
+            }
+            // Handle pattern with explicit type annotation
+            Pat::Type(pat_type) => extract_variables_from_pattern(
+                context,
+                &pat_type.pat,
+                Some(&pat_type.ty),
+                BindingSource::of_local(local),
+                &site,
+            ),
+            // Handle other pattern types (destructuring, etc.)
+            pat => extract_variables_from_pattern(
+                context,
+                pat,
+                None,
+                BindingSource::of_local(local),
+                &site,
+            ),
+        }
+
+        // `let ... else` bindings only exist when the pattern matches
+        if local
+            .init
+            .as_ref()
+            .is_some_and(|init| init.diverge.is_some())
+        {
+            qualify_kinds(context, first_records, "let-else binding");
+        }
+    }
+
+    // Mutable parameters are recorded; immutable ones are listed with the function's signature
+    fn fn_param(&self, param: &syn::FnArg, context: &mut Context) {
+        let syn::FnArg::Typed(pat_type) = param else {
+            return;
+        };
+        let Pat::Ident(pat_ident) = &*pat_type.pat else {
+            return;
+        };
+        if pat_ident.mutability.is_none() || !self.records(context) {
+            return;
+        }
+        let site = Site::of(pat_type.pat.span(), context);
+        let var = VarInfo {
+            var_kind: intern(&format!(
+                "function parameter: {}",
+                format_type(&pat_type.ty)
+            )),
+            var_type: intern(&format_type(&pat_type.ty)),
+            basic_type: intern(&extract_basic_type(&pat_type.ty)),
+            ..declared(context, &site, pat_ident, true)
+        };
+        push(context, var);
+    }
+
+    fn pattern(&self, pattern: Pattern, context: &mut Context) {
+        if !self.records(context) {
+            return;
+        }
+        match pattern {
+            // Mutable loop variables, and the bindings of other loop patterns
+            Pattern::ForLoop(for_loop) => {
+                let site = Site::of(for_loop.pat.span(), context);
+                match &*for_loop.pat {
+                    Pat::Ident(pat_ident) if pat_ident.mutability.is_some() => {
+                        let var = VarInfo {
+                            var_kind: intern("for loop variable"),
+                            var_type: intern(&infer_type_from_loop_expr(&for_loop.expr)),
+                            basic_type: intern(&infer_basic_type_from_expr(&for_loop.expr)),
+                            ..declared(context, &site, pat_ident, true)
+                        };
+                        push(context, var);
+                    }
+                    Pat::Ident(_) => {}
+                    pat => extract_variables_from_pattern(
+                        context,
+                        pat,
+                        None,
+                        BindingSource::Iteration(&for_loop.expr),
+                        &site,
+                    ),
+                }
+            }
+            Pattern::IfLet(expr_let) => {
+                extract_bindings_of(context, &expr_let.pat, &expr_let.expr, "if-let pattern")
+            }
+            Pattern::WhileLet(expr_let) => {
+                extract_bindings_of(context, &expr_let.pat, &expr_let.expr, "while-let pattern")
+            }
+            // Closure parameters are listed by closures.rs
+            Pattern::ClosureParam(_) => {}
+        }
+    }
+
+    fn arm(&self, arm: &syn::Arm, scrutinee: &Expr, context: &mut Context) {
+        if self.records(context) {
+            extract_bindings_of(context, &arm.pat, scrutinee, "match arm pattern");
+        }
+    }
+}
+
+// Where a pattern is: its line and the text of that line, shown as the context of its bindings
+struct Site {
+    line_number: usize,
+    line: String,
+}
+
+impl Site {
+    fn of(span: proc_macro2::Span, context: &Context) -> Self {
+        let line_number = context.line_of(span);
+        let line = match context.source_line(line_number) {
+            Some(line) => line.to_string(),
+            None => format!("Unknown context at line {}", line_number),
+        };
+        Site { line_number, line }
+    }
+}
+
+// Function to describe a binding declared at a site; the caller sets its kind and types
+fn declared(context: &Context, site: &Site, pat_ident: &syn::PatIdent, mutable: bool) -> VarInfo {
+    VarInfo {
+        name: pat_ident.ident.to_string(),
+        mutable,
+        file_path: intern_path(context.file_path()),
+        line_number: site.line_number,
+        column: context.column_of(pat_ident.ident.span()),
+        context: site.line.clone(),
+        var_kind: intern(""),
+        var_type: intern(""),
+        basic_type: intern("unknown"),
+        scope: intern(context.scope()),
+        module: intern(context.module_path()),
+        macro_body: None,
+        code_class: context.code_class().to_string(),
+        read_count: 0,
+        write_count: 0,
+        last_use_line: None,
+        context_window: None,
+    }
+}
+
+// Function to add a variable to the mutable or immutable variables of the results
+fn push(context: &mut Context, var: VarInfo) {
+    let results = context.results();
+    if var.mutable {
+        results.mutable_vars.push(var);
+    } else {
+        results.immutable_vars.push(var);
+    }
+}
+
+// Numbers of mutable and immutable variables recorded so far
+fn recorded(context: &mut Context) -> (usize, usize) {
+    let results = context.results();
+    (results.mutable_vars.len(), results.immutable_vars.len())
+}
+
+// Function to collect the bindings of a pattern, qualifying their kind with where the pattern
+// appears
+fn extract_bindings_of(context: &mut Context, pat: &Pat, matched: &Expr, kind: &str) {
+    let site = Site::of(pat.span(), context);
+    let first_records = recorded(context);
+    extract_variables_from_pattern(context, pat, None, BindingSource::Exact(matched), &site);
+    qualify_kinds(context, first_records, kind);
+}
+
+// Function to qualify the kind of the variables recorded since `first_records` with a
+// pattern's context
+fn qualify_kinds(
+    context: &mut Context,
+    (first_mutable, first_immutable): (usize, usize),
+    kind: &str,
+) {
+    let results = context.results();
+    for var in results.mutable_vars[first_mutable..]
+        .iter_mut()
+        .chain(results.immutable_vars[first_immutable..].iter_mut())
+    {
+        var.var_kind = if &*var.var_kind == "pattern match" {
+            intern(kind)
+        } else {
+            intern(&format!("{}, {}", kind, var.var_kind))
+        };
+    }
+}
+
+// Function to record the bindings of a pattern, with the type written for it if any
+fn extract_variables_from_pattern(
+    context: &mut Context,
+    pat: &Pat,
+    ty: Option<&Type>,
+    source: BindingSource,
+    site: &Site,
+) {
+    match pat {
+        // `None` and other unit variants or constants in a pattern bind nothing
+        Pat::Ident(pat_ident) if names_constant(pat_ident) => {}
+        Pat::Ident(pat_ident) => {
+            // Determine the type - either from explicit annotation or by inference
+            let (var_type, basic_type) = match ty {
+                Some(ty) => (format_type(ty), extract_basic_type(ty)),
+                None => (source.var_type(), source.basic_type()),
+            };
+            let var = VarInfo {
+                var_kind: intern(if ty.is_some() {
+                    "explicitly typed pattern"
+                } else {
+                    "pattern match"
+                }),
+                var_type: intern(&var_type),
+                basic_type: intern(&basic_type),
+                ..declared(context, site, pat_ident, pat_ident.mutability.is_some())
+            };
+            push(context, var);
+        }
+        Pat::Tuple(tuple) => {
+            // For tuple destructuring, try to extract element types
+            for (i, elem) in tuple.elems.iter().enumerate() {
+                let elem_type = match ty {
+                    Some(Type::Tuple(tuple_type)) => tuple_type.elems.get(i),
+                    _ => None,
+                };
+                extract_variables_from_pattern(context, elem, elem_type, source.element(i), site);
+            }
+        }
+        Pat::TupleStruct(tuple_struct) => {
+            // For tuple struct patterns like Some(x), try to determine wrapped type
+            let struct_name = tuple_struct
+                .path
+                .segments
+                .last()
+                .map(|seg| seg.ident.to_string())
+                .unwrap_or_default();
+
+            // Handle special cases like Option and Result
+            let elem_type_hint = match struct_name.as_str() {
+                "Some" => "optional value",
+                "Ok" => "success value",
+                "Err" => "error value",
+                _ => "",
+            };
+
+            for elem in &tuple_struct.elems {
+                match elem {
+                    Pat::Ident(pat_ident) if names_constant(pat_ident) => {}
+                    Pat::Ident(pat_ident) => {
+                        // Improve the type inference for known wrappers
+                        let var_type = if !elem_type_hint.is_empty() {
+                            elem_type_hint.to_string()
+                        } else {
+                            source.part().var_type()
+                        };
+                        let var = VarInfo {
+                            var_kind: intern(&format!("destructured from {}", struct_name)),
+                            var_type: intern(&var_type),
+                            ..declared(context, site, pat_ident, pat_ident.mutability.is_some())
+                        };
+                        push(context, var);
+                    }
+                    // For more complex nested patterns
+                    elem => {
+                        extract_variables_from_pattern(context, elem, None, source.part(), site)
+                    }
+                }
+            }
+        }
+        Pat::Struct(struct_pat) => {
+            // For struct patterns like Point { x, y }, try to link fields to their types
+            let struct_name = struct_pat
+                .path
+                .segments
+                .last()
+                .map(|seg| seg.ident.to_string())
+                .unwrap_or_default();
+
+            for field in &struct_pat.fields {
+                if let Pat::Ident(pat_ident) = &*field.pat {
+                    let field_name = field.member.to_token_stream().to_string();
+                    let var = VarInfo {
+                        var_kind: intern(&format!("destructured from struct {}", struct_name)),
+                        var_type: intern(&format!("field '{}' of {}", field_name, struct_name)),
+                        ..declared(context, site, pat_ident, pat_ident.mutability.is_some())
+                    };
+                    push(context, var);
+                } else {
+                    // For nested patterns
+                    extract_variables_from_pattern(context, &field.pat, None, source.part(), site);
+                }
+            }
+        }
+        Pat::Reference(ref_pat) => {
+            // Process reference patterns like &x or &mut x
+            if let Pat::Ident(pat_ident) = &*ref_pat.pat {
+                let mutable = pat_ident.mutability.is_some() || ref_pat.mutability.is_some();
+                let ref_type = if ref_pat.mutability.is_some() {
+                    "mutable reference to"
+                } else {
+                    "reference to"
+                };
+                let var = VarInfo {
+                    var_kind: intern("reference pattern"),
+                    var_type: intern(&format!("{} {}", ref_type, source.part().var_type())),
+                    ..declared(context, site, pat_ident, mutable)
+                };
+                push(context, var);
+            } else {
+                // For nested patterns within the reference
+                extract_variables_from_pattern(context, &ref_pat.pat, None, source.part(), site);
+            }
+        }
+        Pat::Slice(slice_pat) => {
+            // For slice patterns like [a, b, rest @ ..]
+            for elem in &slice_pat.elems {
+                if let Pat::Ident(pat_ident) = elem {
+                    let var_type = if pat_ident.subpat.is_some() {
+                        "remaining slice elements"
+                    } else {
+                        "slice element"
+                    };
+                    let var = VarInfo {
+                        var_kind: intern("slice pattern"),
+                        var_type: intern(var_type),
+                        ..declared(context, site, pat_ident, pat_ident.mutability.is_some())
+                    };
+                    push(context, var);
+                } else {
+                    // For nested patterns
+                    extract_variables_from_pattern(context, elem, None, source.part(), site);
+                }
+            }
+        }
+        // For or-patterns like `A | B`, every case binds the same names
+        Pat::Or(or_pat) => {
+            if let Some(first) = or_pat.cases.first() {
+                extract_variables_from_pattern(context, first, ty, source, site);
+            }
+        }
+        // For patterns with explicit type annotations
+        Pat::Type(type_pat) => {
+            extract_variables_from_pattern(context, &type_pat.pat, Some(&type_pat.ty), source, site)
+        }
+        _ => {}
+    }
+}
+
+// Where the value a pattern matches comes from, to infer the types of bindings without a type
+#[derive(Clone, Copy)]
+enum BindingSource<'a> {
+    Exact(&'a Expr),     // The binding holds the value of the expression
+    PartOf(&'a Expr),    // The binding holds part of the value, e.g. an element of a tuple
+    Iteration(&'a Expr), // The binding holds the items of a `for` loop's iterator
+    Unknown,             // e.g. `let` without an initializer
+}
+
+impl<'a> BindingSource<'a> {
+    // The source of the value a `let` statement matches
+    fn of_local(local: &'a syn::Local) -> Self {
+        local.init.as_ref().map_or(BindingSource::Unknown, |init| {
+            BindingSource::Exact(&init.expr)
+        })
+    }
+
+    // The source of a sub-pattern, which matches part of the value
+    fn part(self) -> Self {
+        match self {
+            BindingSource::Exact(expr) => BindingSource::PartOf(expr),
+            source => source,
+        }
+    }
+
+    // The source of the element of a tuple pattern at an index
+    fn element(self, index: usize) -> Self {
+        match self {
+            BindingSource::Exact(Expr::Tuple(tuple)) => tuple
+                .elems
+                .iter()
+                .nth(index)
+                .map_or(self.part(), BindingSource::Exact),
+            source => source.part(),
+        }
+    }
+
+    // Function to infer the type of a binding from the value its pattern matches
+    fn var_type(self) -> String {
+        match self {
+            BindingSource::Exact(expr) => infer_type_from_expr(expr),
+            BindingSource::PartOf(expr) => match expr {
+                Expr::Array(_) | Expr::Repeat(_) => "array element".to_string(),
+                Expr::Macro(mac) if mac.mac.path.is_ident("vec") => "vector element".to_string(),
+                Expr::Call(call) => match &*call.func {
+                    Expr::Path(path) if path.path.is_ident("Some") => {
+                        "value inside Option".to_string()
+                    }
+                    Expr::Path(path) if path.path.is_ident("Ok") => "success value".to_string(),
+                    Expr::Path(path) if path.path.is_ident("Err") => "error value".to_string(),
+                    _ => "inferred from context".to_string(),
+                },
+                Expr::MethodCall(call) if call.method == "iter" => {
+                    "reference to collection element".to_string()
+                }
+                Expr::MethodCall(call) if call.method == "iter_mut" => {
+                    "mutable reference to collection element".to_string()
+                }
+                Expr::MethodCall(call) if call.method == "into_iter" => {
+                    "owned collection element".to_string()
+                }
+                _ => "inferred from context".to_string(),
+            },
+            BindingSource::Iteration(expr) => infer_type_from_loop_expr(expr),
+            BindingSource::Unknown => "inferred from context".to_string(),
+        }
+    }
+
+    // Function to infer the basic type of a binding, known when it holds the whole value
+    fn basic_type(self) -> String {
+        match self {
+            BindingSource::Exact(expr) => infer_basic_type_from_expr(expr),
+            _ => "unknown".to_string(),
+        }
+    }
+}
+
+// Function to tell a constant or unit variant in a pattern, e.g. `None`, from a binding
+pub(crate) fn names_constant(pat_ident: &syn::PatIdent) -> bool {
+    pat_ident.by_ref.is_none()
+        && pat_ident.mutability.is_none()
+        && pat_ident.subpat.is_none()
+        && pat_ident
+            .ident
+            .to_string()
+            .starts_with(|c: char| c.is_ascii_uppercase())
+}
+
+// Function to infer basic type from an expression
+fn infer_basic_type_from_expr(expr: &Expr) -> String {
+    match expr {
+        Expr::Lit(lit_expr) => match &lit_expr.lit {
+            syn::Lit::Str(_) => "String".to_string(),
+            syn::Lit::ByteStr(_) => "Vec<u8>".to_string(),
+            syn::Lit::Byte(_) => "u8".to_string(),
+            syn::Lit::Char(_) => "char".to_string(),
+            syn::Lit::Int(int_lit) => {
+                if let Some(suffix) = int_lit.suffix().chars().next() {
+                    match suffix {
+                        'i' => "integer".to_string(),
+                        'u' => "unsigned integer".to_string(),
+                        _ => "integer".to_string(),
+                    }
+                } else {
+                    "integer".to_string()
+                }
+            }
+            syn::Lit::Float(_) => "f64".to_string(),
+            syn::Lit::Bool(_) => "bool".to_string(),
+            _ => "unknown".to_string(),
+        },
+        Expr::Array(_) => "Array".to_string(),
+        Expr::Call(call_expr) => {
+            if let Expr::Path(path_expr) = &*call_expr.func {
+                let path_string = quote::quote!(#path_expr).to_string();
+                if path_string.ends_with("::new") {
+                    format!("Instance of {}", path_string.trim_end_matches("::new"))
+                } else {
+                    "Function call result".to_string()
+                }
+            } else {
+                "Function call result".to_string()
+            }
+        }
+        Expr::MethodCall(method_call) => {
+            let method_name = method_call.method.to_string();
+            match method_name.as_str() {
+                "iter" => "Iterator".to_string(),
+                "iter_mut" => "Mutable Iterator".to_string(),
+                "into_iter" => "Owned Iterator".to_string(),
+                "collect" => "Collection".to_string(),
+                _ => "Method call result".to_string(),
+            }
+        }
+        Expr::Struct(_) => "Struct instance".to_string(),
+        Expr::Reference(ref_expr) => {
+            let mutability = if ref_expr.mutability.is_some() {
+                "Mutable reference"
+            } else {
+                "Reference"
+            };
+            mutability.to_string()
+        }
+        Expr::Binary(_) => "Binary expression result".to_string(),
+        Expr::Match(_) => "Match result".to_string(),
+        Expr::If(_) => "Conditional result".to_string(),
+        _ => "Unknown expression".to_string(),
+    }
+}
+
+// Function to infer type from an expression
+fn infer_type_from_expr(expr: &Expr) -> String {
+    match expr {
+        Expr::Lit(lit_expr) => match &lit_expr.lit {
+            syn::Lit::Str(_) => "string".to_string(),
+            syn::Lit::ByteStr(_) => "byte string".to_string(),
+            syn::Lit::Byte(_) => "byte".to_string(),
+            syn::Lit::Char(_) => "character".to_string(),
+            syn::Lit::Int(int_lit) => {
+                // Fix suffix access - it returns &str directly, not Option<&str>
+                let suffix = int_lit.suffix();
+                if !suffix.is_empty() {
+                    match suffix {
+                        "i8" | "i16" | "i32" | "i64" | "i128" | "isize" => {
+                            format!("integer ({})", suffix)
+                        }
+                        "u8" | "u16" | "u32" | "u64" | "u128" | "usize" => {
+                            format!("unsigned integer ({})", suffix)
+                        }
+                        _ => "integer".to_string(),
+                    }
+                } else {
+                    "integer".to_string()
+                }
+            }
+            syn::Lit::Float(float_lit) => {
+                // Fix suffix access for float literal
+                let suffix = float_lit.suffix();
+                match suffix {
+                    "f32" => "floating-point (f32)".to_string(),
+                    "f64" => "floating-point (f64)".to_string(),
+                    _ => "floating-point".to_string(),
+                }
+            }
+            syn::Lit::Bool(_) => "boolean".to_string(),
+            _ => "literal".to_string(),
+        },
+        Expr::Array(_) => "array".to_string(),
+        Expr::Call(call_expr) => {
+            if let Expr::Path(path_expr) = &*call_expr.func {
+                let path_string = quote::quote!(#path_expr).to_string();
+                if path_string.ends_with("::new") {
+                    let type_name = path_string.trim_end_matches("::new");
+                    match type_name {
+                        "Vec" => "vector".to_string(),
+                        "String" => "string".to_string(),
+                        "HashMap" => "hash map".to_string(),
+                        "BTreeMap" => "tree map".to_string(),
+                        _ => format!("{} instance", type_name),
+                    }
+                } else {
+                    "function result".to_string()
+                }
+            } else {
+                "function result".to_string()
+            }
+        }
+        Expr::MethodCall(method_call) => {
+            let method_name = method_call.method.to_string();
+            match method_name.as_str() {
+                "iter" => "iterator".to_string(),
+                "iter_mut" => "mutable iterator".to_string(),
+                "into_iter" => "owned iterator".to_string(),
+                "collect" => "collection".to_string(),
+                "map" => "mapped iterator".to_string(),
+                "filter" => "filtered iterator".to_string(),
+                "unwrap" => "unwrapped value".to_string(),
+                "expect" => "unwrapped value".to_string(),
+                "clone" => "cloned value".to_string(),
+                "to_string" => "string".to_string(),
+                _ => "method result".to_string(),
+            }
+        }
+        Expr::Struct(struct_expr) => {
+            let struct_name = if let Some(path) = &struct_expr.path.get_ident() {
+                path.to_string()
+            } else {
+                quote::quote!(#struct_expr.path).to_string()
+            };
+            struct_name
+        }
+        Expr::Reference(ref_expr) => {
+            let mutability = if ref_expr.mutability.is_some() {
+                "mutable "
+            } else {
+                ""
+            };
+            format!("{}reference", mutability)
+        }
+        Expr::Binary(bin_expr) => match bin_expr.op {
+            syn::BinOp::Add(_)
+            | syn::BinOp::Sub(_)
+            | syn::BinOp::Mul(_)
+            | syn::BinOp::Div(_)
+            | syn::BinOp::Rem(_) => "numeric".to_string(),
+
+            syn::BinOp::And(_) | syn::BinOp::Or(_) => "boolean".to_string(),
+
+            syn::BinOp::BitAnd(_)
+            | syn::BinOp::BitOr(_)
+            | syn::BinOp::BitXor(_)
+            | syn::BinOp::Shl(_)
+            | syn::BinOp::Shr(_) => "integer".to_string(),
+
+            syn::BinOp::Eq(_)
+            | syn::BinOp::Lt(_)
+            | syn::BinOp::Le(_)
+            | syn::BinOp::Ne(_)
+            | syn::BinOp::Ge(_)
+            | syn::BinOp::Gt(_) => "boolean".to_string(),
+
+            _ => "expression result".to_string(),
+        },
+        Expr::Match(_) => "match result".to_string(),
+        Expr::If(_) => "conditional result".to_string(),
+        _ => "expression result".to_string(),
+    }
+}
+
+// Function to infer type from a loop iterator expression
+fn infer_type_from_loop_expr(expr: &Expr) -> String {
+    match expr {
+        Expr::Range(_) => "integer (range)".to_string(),
+        Expr::MethodCall(method_call) => {
+            let method_name = method_call.method.to_string();
+            match method_name.as_str() {
+                "iter" => "reference to collection element".to_string(),
+                "iter_mut" => "mutable reference to collection element".to_string(),
+                "into_iter" => "owned collection element".to_string(),
+                _ => "collection element".to_string(),
+            }
+        }
+        _ => "collection element".to_string(),
+    }
+}
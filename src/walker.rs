@@ -0,0 +1,250 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// The walk of a parsed file that calls the collectors.
+// The walker keeps track of where it is (module, scope, closure numbering, code class and
+// block depth) and calls every collector for each event: the built-in variable and data
+// structure collectors first, then the ones registered with `AnalysisOptions::collector`.
+// Functions, methods, impls, traits and closures open a scope, e.g.
+// `crate::parser::Parser::next::closure#1`; inline modules extend the module path.
+
+use crate::collector::{Collector, Context, Pattern, Position};
+use crate::data_structures::DataStructures;
+use crate::line_index::LineIndex;
+use crate::variables::Variables;
+use crate::{code_class, impl_blocks, AnalysisResults};
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+use syn::visit::{self, Visit};
+use syn::Expr;
+
+pub struct Walker<'a> {
+    file_path: &'a Path,
+    lines: &'a LineIndex<'a>, // Line table of the source the AST was parsed from
+    module_path: String,      // Logical path of the module being walked, e.g. crate::parser
+    scope: String, // Path of the innermost scope, e.g. crate::parser::Parser::next::closure#1
+    closure_count: usize, // Closures seen so far in the current scope, for numbering them
+    code_class: &'static str, // Class of the code being walked; empty to classify by line later
+    block_depth: usize, // Blocks around the code being walked; function bodies are 1
+    variables: Variables,
+    collectors: &'a [Arc<dyn Collector>], // Registered collectors, called after the built-ins
+    results: &'a mut AnalysisResults,
+}
+
+impl<'a> Walker<'a> {
+    pub fn new(
+        file_path: &'a Path,
+        lines: &'a LineIndex<'a>,
+        module_path: &str,
+        results: &'a mut AnalysisResults,
+    ) -> Self {
+        Walker {
+            file_path,
+            lines,
+            module_path: module_path.to_string(),
+            scope: String::new(),
+            closure_count: 0,
+            code_class: "",
+            block_depth: 0,
+            variables: Variables { scope_depth: None },
+            collectors: &[],
+            results,
+        }
+    }
+
+    // Walk code that sits in a scope of its own, e.g. `macro_rules! name`
+    pub fn in_scope(mut self, scope: String) -> Self {
+        self.scope = scope;
+        self
+    }
+
+    pub fn code_class(mut self, code_class: &'static str) -> Self {
+        self.code_class = code_class;
+        self
+    }
+
+    // Variables in deeper blocks are not recorded (--scope-depth)
+    pub fn scope_depth(mut self, scope_depth: Option<usize>) -> Self {
+        self.variables.scope_depth = scope_depth;
+        self
+    }
+
+    pub fn collectors(mut self, collectors: &'a [Arc<dyn Collector>]) -> Self {
+        self.collectors = collectors;
+        self
+    }
+
+    // Call each collector with where the walk is
+    fn dispatch(&mut self, event: impl Fn(&dyn Collector, &mut Context)) {
+        let position = Position {
+            file_path: self.file_path,
+            lines: self.lines,
+            module_path: &self.module_path,
+            scope: if self.scope.is_empty() {
+                &self.module_path
+            } else {
+                &self.scope
+            },
+            code_class: self.code_class,
+            block_depth: self.block_depth,
+        };
+        let built_in: [&dyn Collector; 2] = [&self.variables, &DataStructures];
+        let registered = self.collectors.iter().map(|collector| collector.as_ref());
+        for collector in built_in.into_iter().chain(registered) {
+            let mut context = Context::new(collector.name(), position, self.results);
+            event(collector, &mut context);
+        }
+    }
+
+    // Walk a scope nested in the current one, e.g. a method in its impl or a closure in its function
+    fn walk_scope(&mut self, segment: &impl fmt::Display, walk: impl FnOnce(&mut Self)) {
+        let inner_scope = if self.scope.is_empty() {
+            self.item_path(segment)
+        } else {
+            format!("{}::{}", self.scope, segment)
+        };
+        let outer_scope = std::mem::replace(&mut self.scope, inner_scope);
+        let outer_count = std::mem::replace(&mut self.closure_count, 0);
+        walk(self);
+        self.scope = outer_scope;
+        self.closure_count = outer_count;
+    }
+
+    // Full path of an item declared in the module being walked
+    fn item_path(&self, name: &impl fmt::Display) -> String {
+        if self.module_path.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}::{}", self.module_path, name)
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for Walker<'_> {
+    // Items such as `#[cfg(test)] mod tests` set the class of everything inside them
+    fn visit_item(&mut self, item: &'ast syn::Item) {
+        let outer_class = self.code_class;
+        if let Some(class) = code_class::item_class(item) {
+            self.code_class = class;
+        }
+        self.dispatch(|collector, context| collector.item(item, context));
+        visit::visit_item(self, item);
+        self.code_class = outer_class;
+    }
+
+    fn visit_impl_item(&mut self, item: &'ast syn::ImplItem) {
+        self.dispatch(|collector, context| collector.impl_item(item, context));
+        visit::visit_impl_item(self, item);
+    }
+
+    fn visit_trait_item(&mut self, item: &'ast syn::TraitItem) {
+        self.dispatch(|collector, context| collector.trait_item(item, context));
+        visit::visit_trait_item(self, item);
+    }
+
+    fn visit_fn_arg(&mut self, param: &'ast syn::FnArg) {
+        self.dispatch(|collector, context| collector.fn_param(param, context));
+        visit::visit_fn_arg(self, param);
+    }
+
+    fn visit_local(&mut self, local: &'ast syn::Local) {
+        self.dispatch(|collector, context| collector.local(local, context));
+        visit::visit_local(self, local);
+    }
+
+    fn visit_expr(&mut self, expr: &'ast Expr) {
+        self.dispatch(|collector, context| collector.expr(expr, context));
+        visit::visit_expr(self, expr);
+    }
+
+    fn visit_expr_for_loop(&mut self, for_loop: &'ast syn::ExprForLoop) {
+        self.dispatch(|collector, context| collector.pattern(Pattern::ForLoop(for_loop), context));
+        visit::visit_expr_for_loop(self, for_loop);
+    }
+
+    fn visit_expr_if(&mut self, if_expr: &'ast syn::ExprIf) {
+        if let Expr::Let(expr_let) = &*if_expr.cond {
+            self.dispatch(|collector, context| {
+                collector.pattern(Pattern::IfLet(expr_let), context)
+            });
+        }
+        visit::visit_expr_if(self, if_expr);
+    }
+
+    fn visit_expr_while(&mut self, while_expr: &'ast syn::ExprWhile) {
+        if let Expr::Let(expr_let) = &*while_expr.cond {
+            self.dispatch(|collector, context| {
+                collector.pattern(Pattern::WhileLet(expr_let), context)
+            });
+        }
+        visit::visit_expr_while(self, while_expr);
+    }
+
+    // Every arm matches the scrutinee, so the arms are called before anything inside them
+    fn visit_expr_match(&mut self, match_expr: &'ast syn::ExprMatch) {
+        for arm in &match_expr.arms {
+            self.dispatch(|collector, context| collector.arm(arm, &match_expr.expr, context));
+        }
+        visit::visit_expr_match(self, match_expr);
+    }
+
+    // Closures are numbered within their enclosing scope, e.g. `run::closure#2`
+    fn visit_expr_closure(&mut self, closure: &'ast syn::ExprClosure) {
+        self.dispatch(|collector, context| collector.closure(closure, context));
+        self.closure_count += 1;
+        let segment = format!("closure#{}", self.closure_count);
+        self.walk_scope(&segment, |walker| {
+            for param in &closure.inputs {
+                walker.dispatch(|collector, context| {
+                    collector.pattern(Pattern::ClosureParam(param), context)
+                });
+            }
+            visit::visit_expr_closure(walker, closure)
+        });
+    }
+
+    fn visit_item_fn(&mut self, item_fn: &'ast syn::ItemFn) {
+        self.walk_scope(&item_fn.sig.ident, |walker| {
+            visit::visit_item_fn(walker, item_fn)
+        });
+    }
+
+    // Methods are scoped by their impl's self type, e.g. `Parser::next_token`
+    fn visit_item_impl(&mut self, item_impl: &'ast syn::ItemImpl) {
+        self.walk_scope(&impl_blocks::type_name(&item_impl.self_ty), |walker| {
+            visit::visit_item_impl(walker, item_impl)
+        });
+    }
+
+    fn visit_impl_item_fn(&mut self, method: &'ast syn::ImplItemFn) {
+        self.walk_scope(&method.sig.ident, |walker| {
+            visit::visit_impl_item_fn(walker, method)
+        });
+    }
+
+    // Default methods of traits are scoped by their trait, e.g. `Render::draw`
+    fn visit_item_trait(&mut self, item_trait: &'ast syn::ItemTrait) {
+        self.walk_scope(&item_trait.ident, |walker| {
+            visit::visit_item_trait(walker, item_trait)
+        });
+    }
+
+    fn visit_trait_item_fn(&mut self, method: &'ast syn::TraitItemFn) {
+        self.walk_scope(&method.sig.ident, |walker| {
+            visit::visit_trait_item_fn(walker, method)
+        });
+    }
+
+    // Inline modules extend the path of their items
+    fn visit_item_mod(&mut self, item_mod: &'ast syn::ItemMod) {
+        let module_path = self.item_path(&item_mod.ident);
+        let outer_module = std::mem::replace(&mut self.module_path, module_path);
+        visit::visit_item_mod(self, item_mod);
+        self.module_path = outer_module;
+    }
+
+    fn visit_block(&mut self, block: &'ast syn::Block) {
+        self.block_depth += 1;
+        visit::visit_block(self, block);
+        self.block_depth -= 1;
+    }
+}
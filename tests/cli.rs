@@ -0,0 +1,106 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// The command line: exit codes of thresholds and errors, and where notes and warnings
+// are printed.
+
+mod common;
+
+use common::{fixture, Scratch};
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+// Function to run forest on the sample crate with the given arguments
+fn forest(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_forest"))
+        .arg(fixture("sample"))
+        .arg("--summary-only")
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+fn analysis_succeeds() {
+    let output = forest(&[]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout(&output).contains("Found 3 mutable variables"));
+}
+
+#[test]
+fn exceeded_threshold_exits_with_1() {
+    let output = forest(&["--max-mutable-vars", "2"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout(&output).contains("error: mutable 3 exceeds --max-mutable-vars 2"));
+    assert!(stderr(&output).contains("1 thresholds exceeded"));
+
+    assert_eq!(forest(&["--max-mutable-vars", "3"]).status.code(), Some(0));
+}
+
+#[test]
+fn invalid_glob_exits_with_78() {
+    let output = forest(&["--exclude", "["]);
+    assert_eq!(output.status.code(), Some(78));
+}
+
+#[test]
+fn unwritable_output_exits_with_73() {
+    let scratch = Scratch::new("cli-output");
+    let file = scratch.join("missing").join("report.json");
+    let output = forest(&["--format", "json", "--output", file.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(73));
+}
+
+#[test]
+fn parse_error_with_fail_fast_exits_with_65() {
+    let output = Command::new(env!("CARGO_BIN_EXE_forest"))
+        .arg(fixture("broken.rs"))
+        .args(["--allow-degraded", "false", "--fail-fast"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(65));
+    assert!(stderr(&output).contains("broken.rs"));
+}
+
+#[test]
+fn unknown_format_is_a_usage_error() {
+    assert_eq!(forest(&["--format", "yaml"]).status.code(), Some(2));
+}
+
+#[test]
+fn sampling_note_is_printed_to_stdout() {
+    let output = forest(&["--sample", "1", "--sample-seed", "7"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout(&output).contains("Sampling 1 of 3 files (seed 7)"));
+    assert!(!stderr(&output).contains("Sampling"));
+}
+
+#[test]
+fn serve_selects_files_with_its_options() {
+    let summary = |args: &[&str]| {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_forest"))
+            .arg("serve")
+            .arg(fixture("sample"))
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(b"{\"jsonrpc\": \"2.0\", \"id\": 1, \"method\": \"summary\"}\n")
+            .unwrap();
+        stdout(&child.wait_with_output().unwrap())
+    };
+    assert!(summary(&[]).contains("\"files\":3"));
+    assert!(summary(&["--exclude", "src/shapes.rs"]).contains("\"files\":2"));
+}
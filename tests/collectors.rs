@@ -0,0 +1,172 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Collector dispatch: a registered collector is called for each event of the walk with
+// where the walk is, and its records and summary are returned with the results.
+
+mod common;
+
+use common::fixture;
+use forest::collector::{Context, Pattern, Record};
+use forest::{AnalysisOptions, Analyzer, Collector};
+use serde_json::json;
+use std::collections::BTreeMap;
+use syn::spanned::Spanned;
+
+// Records every event with the scope it was called in
+struct Events;
+
+impl Events {
+    fn event(context: &mut Context, name: &str, span: proc_macro2::Span) {
+        let line = context.line_of(span);
+        let data = json!({
+            "scope": context.scope(),
+            "code_class": context.code_class(),
+            "column": context.column_of(span),
+            "source": context.source_line(line).unwrap_or_default().trim(),
+        });
+        context.record(name, line, data);
+    }
+}
+
+impl Collector for Events {
+    fn name(&self) -> &str {
+        "events"
+    }
+
+    // Named items are recorded at their name, as the data structures are
+    fn item(&self, item: &syn::Item, context: &mut Context) {
+        let span = match item {
+            syn::Item::Fn(item_fn) => item_fn.sig.ident.span(),
+            syn::Item::Mod(item_mod) => item_mod.ident.span(),
+            _ => item.span(),
+        };
+        Events::event(context, "item", span);
+    }
+
+    fn impl_item(&self, item: &syn::ImplItem, context: &mut Context) {
+        Events::event(context, "impl item", item.span());
+    }
+
+    fn trait_item(&self, item: &syn::TraitItem, context: &mut Context) {
+        Events::event(context, "trait item", item.span());
+    }
+
+    fn fn_param(&self, param: &syn::FnArg, context: &mut Context) {
+        Events::event(context, "param", param.span());
+    }
+
+    fn local(&self, local: &syn::Local, context: &mut Context) {
+        Events::event(context, "local", local.pat.span());
+    }
+
+    fn pattern(&self, pattern: Pattern, context: &mut Context) {
+        let name = match pattern {
+            Pattern::ForLoop(_) => "for",
+            Pattern::IfLet(_) => "if let",
+            Pattern::WhileLet(_) => "while let",
+            Pattern::ClosureParam(_) => "closure param",
+        };
+        Events::event(context, name, pattern.pat().span());
+    }
+
+    fn arm(&self, arm: &syn::Arm, _scrutinee: &syn::Expr, context: &mut Context) {
+        Events::event(context, "arm", arm.pat.span());
+    }
+
+    fn closure(&self, closure: &syn::ExprClosure, context: &mut Context) {
+        Events::event(context, "closure", closure.span());
+    }
+
+    // Number of records of each event
+    fn summary(&self, records: &[Record]) -> Option<serde_json::Value> {
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for record in records {
+            *counts.entry(&record.name).or_default() += 1;
+        }
+        Some(json!(counts))
+    }
+}
+
+fn analyse() -> forest::AnalysisResults {
+    let options = AnalysisOptions::new().collector(Events);
+    Analyzer::new(options).analyze(fixture("sample")).unwrap()
+}
+
+fn records<'a>(results: &'a forest::AnalysisResults, name: &str) -> Vec<&'a Record> {
+    results
+        .collected()
+        .iter()
+        .filter(|record| record.name == name)
+        .collect()
+}
+
+#[test]
+fn every_event_is_dispatched() {
+    let results = analyse();
+    let summary = results.collector_summary("events").unwrap();
+
+    assert_eq!(summary["param"], 8);
+    assert_eq!(summary["local"], 5);
+    assert_eq!(summary["arm"], 4);
+    assert_eq!(summary["impl item"], 2);
+    assert_eq!(summary["trait item"], 2);
+    assert_eq!(summary["closure"], 1);
+    assert_eq!(summary["for"], 1);
+    assert_eq!(summary["if let"], 1);
+    assert_eq!(summary["while let"], 1);
+    assert_eq!(summary["closure param"], 1);
+    assert!(results
+        .collected()
+        .iter()
+        .all(|record| record.collector == "events"));
+}
+
+#[test]
+fn events_know_their_scope() {
+    let results = analyse();
+    let scope_of = |name: &str| -> Vec<String> {
+        records(&results, name)
+            .iter()
+            .map(|record| record.data["scope"].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    assert_eq!(scope_of("closure"), ["crate::scale"]);
+    assert_eq!(scope_of("closure param"), ["crate::scale::closure#1"]);
+    assert_eq!(scope_of("for"), ["crate::total"]);
+    assert_eq!(
+        scope_of("impl item"),
+        ["crate::shapes::Shape", "crate::shapes::Point"]
+    );
+    assert_eq!(scope_of("trait item"), ["crate::shapes::Area"; 2]);
+}
+
+#[test]
+fn events_know_their_position_and_class() {
+    let results = analyse();
+    let local = |source: &str| {
+        records(&results, "local")
+            .into_iter()
+            .find(|record| record.data["source"] == source)
+            .unwrap()
+    };
+
+    let sum = local("let mut sum = 0;");
+    assert!(sum.file_path.ends_with("src/lib.rs"));
+    assert_eq!(sum.line_number, 7);
+    assert_eq!(sum.data["column"], 9);
+    assert_eq!(sum.data["code_class"], "production");
+    assert_eq!(local("let values = vec![1, 2];").data["code_class"], "test");
+}
+
+#[test]
+fn records_match_the_built_in_listings() {
+    let results = analyse();
+
+    // The built-in collectors see the same lines as a registered one
+    for structure in results.data_structures() {
+        assert!(records(&results, "item")
+            .iter()
+            .any(|record| record.file_path == structure.file_path()
+                && record.line_number == structure.line_number()));
+    }
+}
@@ -0,0 +1,41 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// Helpers shared by the integration tests: the fixtures under tests/fixtures and scratch
+// directories that are removed when the test ends.
+#![allow(dead_code)] // Each test crate uses some of the helpers
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Path of a fixture, e.g. `fixture("sample")` for the sample crate
+pub fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join(name)
+}
+
+// A directory under the system temporary directory, removed on drop
+pub struct Scratch(PathBuf);
+
+impl Scratch {
+    pub fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("forest-test-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        Scratch(dir)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+
+    pub fn join(&self, name: &str) -> PathBuf {
+        self.0.join(name)
+    }
+}
+
+impl Drop for Scratch {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
@@ -0,0 +1,8 @@
+pub fn fine() -> u32 {
+    let answer = 42;
+    answer
+}
+
+pub fn broken() -> u32 {
+    let = 1;
+}
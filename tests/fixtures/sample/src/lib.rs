@@ -0,0 +1,36 @@
+//! A small crate analysed by forest's tests.
+
+pub mod shapes;
+
+/// Adds up the values.
+pub fn total(values: &[i32]) -> i32 {
+    let mut sum = 0;
+    for value in values {
+        sum += value;
+    }
+    sum
+}
+
+pub fn describe(value: Option<i32>) -> String {
+    let label = match value {
+        Some(n) => format!("{}", n),
+        None => "none".to_string(),
+    };
+    label
+}
+
+pub fn scale(mut factor: i32, values: Vec<i32>) -> Vec<i32> {
+    factor *= 2;
+    values.iter().map(|value| value * factor).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn totals() {
+        let values = vec![1, 2];
+        assert_eq!(total(&values), 3);
+    }
+}
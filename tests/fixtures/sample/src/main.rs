@@ -0,0 +1,13 @@
+fn main() {
+    let mut count = 0;
+    while let Some(next) = step(count) {
+        count = next;
+    }
+    if let Some(last) = step(0) {
+        println!("{} {}", count, last);
+    }
+}
+
+fn step(count: u32) -> Option<u32> {
+    (count < 3).then_some(count + 1)
+}
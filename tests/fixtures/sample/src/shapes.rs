@@ -0,0 +1,35 @@
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+pub enum Shape {
+    Circle(f64),
+    Square(f64),
+}
+
+pub type Points = Vec<Point>;
+
+pub trait Area {
+    fn area(&self) -> f64;
+
+    fn describe(&self) -> String {
+        format!("area {}", self.area())
+    }
+}
+
+impl Area for Shape {
+    fn area(&self) -> f64 {
+        match self {
+            Shape::Circle(radius) => 3.0 * radius * radius,
+            Shape::Square(side) => side * side,
+        }
+    }
+}
+
+impl Point {
+    pub fn origin() -> Self {
+        let (x, y) = (0.0, 0.0);
+        Point { x, y }
+    }
+}
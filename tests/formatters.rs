@@ -0,0 +1,124 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// The formatter registry: the built-in formats, registering and replacing formats, and
+// the errors of unknown formats and unwritable files.
+
+mod common;
+
+use common::{fixture, Scratch};
+use forest::formatter::{Formatters, Report};
+use forest::{AnalysisMetadata, AnalysisOptions, AnalysisResults, Analyzer};
+use forest::{ForestError, OutputFormatter};
+use std::fs;
+
+// Writes the number of mutable variables
+struct Count(&'static str);
+
+impl OutputFormatter for Count {
+    fn name(&self) -> &str {
+        self.0
+    }
+
+    fn write(&self, report: &Report, file: &str) -> Result<(), ForestError> {
+        let count = report.results().mutable_variables().len();
+        fs::write(file, format!("{} mutable\n", count))
+            .map_err(|error| ForestError::io(file, error))
+    }
+}
+
+fn analyse() -> (AnalysisResults, AnalysisMetadata) {
+    Analyzer::new(AnalysisOptions::new())
+        .analyze_with_metadata(fixture("sample"))
+        .unwrap()
+}
+
+#[test]
+fn builtin_formats_are_registered_in_order() {
+    let formatters = Formatters::builtin();
+    assert_eq!(formatters.names(), ["text", "json", "csv", "html", "dot"]);
+    assert_eq!(formatters.get("csv").unwrap().name(), "csv");
+    assert!(formatters.get("yaml").is_none());
+}
+
+#[test]
+fn registering_appends_or_replaces() {
+    let mut formatters = Formatters::builtin();
+    formatters.register(Count("count"));
+    assert_eq!(
+        formatters.names(),
+        ["text", "json", "csv", "html", "dot", "count"]
+    );
+
+    // A format registered under a built-in name replaces it
+    formatters.register(Count("json"));
+    assert_eq!(
+        formatters.names(),
+        ["text", "csv", "html", "dot", "count", "json"]
+    );
+}
+
+#[test]
+fn custom_formatter_writes_the_report() {
+    let (results, metadata) = analyse();
+    let scratch = Scratch::new("custom-format");
+    let file = scratch.join("count.txt");
+    let mut formatters = Formatters::builtin();
+    formatters.register(Count("count"));
+
+    formatters
+        .write(
+            "count",
+            &Report::new(&results, &metadata),
+            file.to_str().unwrap(),
+        )
+        .unwrap();
+    assert_eq!(fs::read_to_string(file).unwrap(), "3 mutable\n");
+}
+
+#[test]
+fn json_report_lists_the_results() {
+    let (results, metadata) = analyse();
+    let scratch = Scratch::new("json-format");
+    let file = scratch.join("report.json");
+
+    Formatters::builtin()
+        .write(
+            "json",
+            &Report::new(&results, &metadata),
+            file.to_str().unwrap(),
+        )
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(file).unwrap()).unwrap();
+    assert_eq!(json["metadata"]["project_name"], "sample");
+    assert_eq!(json["mutable_variables"].as_array().unwrap().len(), 3);
+    assert_eq!(json["data_structures"].as_array().unwrap().len(), 12);
+    assert_eq!(json, Report::new(&results, &metadata).json().unwrap());
+}
+
+#[test]
+fn unknown_format_is_a_configuration_error() {
+    let (results, metadata) = analyse();
+    let error = Formatters::builtin()
+        .write("yaml", &Report::new(&results, &metadata), "report.yaml")
+        .unwrap_err();
+
+    assert!(matches!(error, ForestError::Config(_)));
+    assert!(error.to_string().contains("text, json, csv, html, dot"));
+    assert_eq!(error.exit_code(), 78);
+}
+
+#[test]
+fn unwritable_output_is_a_format_error() {
+    let (results, metadata) = analyse();
+    let scratch = Scratch::new("unwritable-format");
+    let file = scratch.join("missing").join("report.txt");
+    let error = Formatters::builtin()
+        .write(
+            "text",
+            &Report::new(&results, &metadata),
+            file.to_str().unwrap(),
+        )
+        .unwrap_err();
+
+    assert!(matches!(error, ForestError::Format { ref format, .. } if format == "text"));
+    assert_eq!(error.exit_code(), 73);
+}
@@ -0,0 +1,191 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// The library API on the sample crate and on single files: the records, the options that
+// change what is analysed, sampling and the errors of a failed analysis.
+
+mod common;
+
+use common::fixture;
+use forest::{AnalysisOptions, Analyzer, ForestError, SampleSpec};
+
+fn names<'a>(records: impl IntoIterator<Item = &'a str>) -> Vec<&'a str> {
+    let mut names: Vec<&str> = records.into_iter().collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn analyses_the_sample_crate() {
+    let (results, metadata) = Analyzer::default()
+        .analyze_with_metadata(fixture("sample"))
+        .unwrap();
+
+    assert_eq!(metadata.project_name(), "sample");
+    assert_eq!(metadata.version(), "0.2.0");
+    assert_eq!(results.file_count(), 3);
+    assert!(results.errors().is_empty());
+    assert!(results.warnings().is_empty());
+    assert_eq!(
+        names(results.mutable_variables().iter().map(|var| var.name())),
+        ["count", "factor", "sum"]
+    );
+    assert_eq!(
+        names(results.immutable_variables().iter().map(|var| var.name())),
+        ["label", "last", "n", "next", "radius", "side", "values", "x", "y"]
+    );
+    assert_eq!(results.data_structures().len(), 12);
+}
+
+#[test]
+fn variables_know_where_and_how_they_are_declared() {
+    let results = Analyzer::default().analyze(fixture("sample")).unwrap();
+    let mutable = |name: &str| {
+        results
+            .mutable_variables()
+            .iter()
+            .find(|var| var.name() == name)
+            .unwrap()
+    };
+
+    let sum = mutable("sum");
+    assert!(sum.file_path().ends_with("src/lib.rs"));
+    assert_eq!((sum.line_number(), sum.column()), (7, 13));
+    assert_eq!(sum.scope(), "crate::total");
+    assert_eq!(sum.kind(), "inferred from initialization");
+    assert_eq!(sum.code_class(), "production");
+    assert!(sum.write_count() > 0);
+
+    let factor = mutable("factor");
+    assert_eq!(factor.kind(), "function parameter: i32");
+    assert_eq!(factor.var_type(), "i32");
+
+    let side = results
+        .immutable_variables()
+        .iter()
+        .find(|var| var.name() == "side")
+        .unwrap();
+    assert_eq!(side.scope(), "crate::shapes::Shape::area");
+    assert_eq!(side.kind(), "match arm pattern, destructured from Square");
+}
+
+#[test]
+fn data_structures_have_paths_kinds_and_classes() {
+    let results = Analyzer::default().analyze(fixture("sample")).unwrap();
+    let structure = |name: &str| {
+        results
+            .data_structures()
+            .iter()
+            .find(|structure| structure.name() == name)
+            .unwrap()
+    };
+
+    let point = structure("Point");
+    assert_eq!(point.kind(), "struct");
+    assert_eq!(point.item_path(), "crate::shapes::Point");
+    assert_eq!(point.visibility(), "pub");
+    assert_eq!((point.line_number(), point.column()), (1, 12));
+    assert_eq!(structure("Area").kind(), "trait");
+    assert_eq!(structure("Points").kind(), "type alias");
+    assert_eq!(structure("tests").code_class(), "test");
+    assert_eq!(structure("totals").code_class(), "test");
+}
+
+#[test]
+fn test_code_can_be_left_out() {
+    let options = AnalysisOptions::new().include_tests(false);
+    let results = Analyzer::new(options).analyze(fixture("sample")).unwrap();
+
+    assert!(results
+        .immutable_variables()
+        .iter()
+        .all(|var| var.name() != "values"));
+    assert!(results
+        .data_structures()
+        .iter()
+        .all(|structure| structure.code_class() != "test"));
+}
+
+#[test]
+fn excluded_files_are_not_analysed() {
+    let options = AnalysisOptions::new().excludes(["src/shapes.rs"]);
+    let results = Analyzer::new(options).analyze(fixture("sample")).unwrap();
+
+    assert_eq!(results.file_count(), 2);
+    assert!(results
+        .data_structures()
+        .iter()
+        .all(|structure| structure.name() != "Point"));
+}
+
+#[test]
+fn a_sample_of_the_files_is_analysed() {
+    let analyse = |seed| {
+        let options = AnalysisOptions::new()
+            .sample(SampleSpec::Files(1))
+            .sample_seed(seed);
+        Analyzer::new(options).analyze(fixture("sample")).unwrap()
+    };
+
+    let results = analyse(7);
+    assert_eq!(results.file_count(), 1);
+    assert_eq!(results.notes(), ["Sampling 1 of 3 files (seed 7)"]);
+
+    // The same seed selects the same file
+    let again = analyse(7);
+    let files = |results: &forest::AnalysisResults| {
+        let mut files: Vec<_> = results
+            .data_structures()
+            .iter()
+            .map(|structure| structure.file_path().to_path_buf())
+            .collect();
+        files.dedup();
+        files
+    };
+    assert_eq!(files(&results), files(&again));
+}
+
+#[test]
+fn a_single_file_falls_back_to_the_items_that_parse() {
+    let results = Analyzer::default().analyze(fixture("broken.rs")).unwrap();
+
+    assert!(results.errors().is_empty());
+    assert_eq!(
+        names(results.immutable_variables().iter().map(|var| var.name())),
+        ["answer"]
+    );
+}
+
+#[test]
+fn unparsable_files_are_recorded_without_the_fallback() {
+    let options = AnalysisOptions::new().allow_degraded(false);
+    let results = Analyzer::new(options)
+        .analyze(fixture("broken.rs"))
+        .unwrap();
+
+    assert_eq!(results.errors().len(), 1);
+    assert!(results.errors()[0].file_path().ends_with("broken.rs"));
+    assert!(results.errors()[0].message().starts_with("parse error"));
+    assert_eq!(results.warnings().len(), 1);
+    assert!(results.warnings()[0].starts_with("skipping "));
+}
+
+#[test]
+fn failing_fast_returns_the_parse_error() {
+    let options = AnalysisOptions::new().allow_degraded(false).fail_fast(true);
+    let error = Analyzer::new(options)
+        .analyze(fixture("broken.rs"))
+        .err()
+        .unwrap();
+
+    assert!(matches!(error, ForestError::Parse { line: 7, .. }));
+    assert!(error.path().unwrap().ends_with("broken.rs"));
+    assert_eq!(error.exit_code(), forest::error::EXIT_DATAERR);
+}
+
+#[test]
+fn a_directory_without_a_manifest_is_a_config_error() {
+    let scratch = common::Scratch::new("no-manifest");
+    let error = Analyzer::default().analyze(scratch.path()).err().unwrap();
+
+    assert!(matches!(error, ForestError::Config(_)));
+    assert_eq!(error.exit_code(), forest::error::EXIT_CONFIG);
+}
@@ -0,0 +1,59 @@
+// Copyright (c) 2025 Nicholas D. Crosbie
+// --semantic: the let bindings of the sample crate typed by rust-analyzer, or the error of a
+// forest built without the semantic feature.
+
+mod common;
+
+use common::fixture;
+use forest::{AnalysisOptions, Analyzer, ForestError};
+
+fn analyse(path: std::path::PathBuf) -> Result<forest::AnalysisResults, ForestError> {
+    Analyzer::new(AnalysisOptions::new().semantic(true)).analyze(path)
+}
+
+#[cfg(feature = "semantic")]
+#[test]
+fn let_bindings_get_inferred_types() {
+    let results = analyse(fixture("sample")).unwrap();
+    let var = |name: &str| {
+        results
+            .mutable_variables()
+            .iter()
+            .chain(results.immutable_variables())
+            .find(|var| var.name() == name)
+            .unwrap()
+    };
+
+    assert_eq!(var("sum").var_type(), "i32");
+    assert_eq!(var("count").var_type(), "u32");
+    assert_eq!(var("label").var_type(), "String");
+    assert_eq!(var("values").var_type(), "Vec<i32>");
+    assert_eq!(var("values").basic_type(), "Vec<i32>");
+    assert_eq!(var("x").var_type(), "f64");
+    assert_eq!(var("sum").kind(), "inferred from initialization");
+
+    // Bindings of other patterns keep their guessed types
+    assert_eq!(var("next").var_type(), "optional value");
+    assert_eq!(
+        results.notes(),
+        ["Resolved the types of 6 let bindings with rust-analyzer"]
+    );
+}
+
+#[cfg(not(feature = "semantic"))]
+#[test]
+fn semantic_needs_the_feature() {
+    let error = analyse(fixture("sample")).err().unwrap();
+    assert!(
+        matches!(error, ForestError::Config(ref message) if message.contains("--features semantic"))
+    );
+    assert_eq!(error.exit_code(), 78);
+}
+
+#[test]
+fn semantic_needs_a_project() {
+    let error = analyse(fixture("broken.rs")).err().unwrap();
+    assert!(
+        matches!(error, ForestError::Config(ref message) if message.contains("project directory"))
+    );
+}